@@ -12,6 +12,7 @@ use crate::pwg::OpcodeResolutionError;
 /// - When it encounters a bigint operation opcode, it performs the operation on the stored values
 ///   and store the result using the provided ID.
 /// - When it gets a to_bytes opcode, it simply looks up the value and resolves the output witness accordingly.
+#[derive(Clone)]
 pub(crate) struct AcvmBigIntSolver {
     bigint_solver: BigIntSolver,
 }
@@ -41,6 +41,14 @@ pub struct BrilligSolver<'b, F, B: BlackBoxFunctionSolver<F>> {
     pub function_id: BrilligFunctionId,
 }
 
+// Implemented by hand so that the solver can be cloned without requiring the black box solver
+// it borrows to be `Clone`.
+impl<B: BlackBoxFunctionSolver<F>, F: Clone> Clone for BrilligSolver<'_, F, B> {
+    fn clone(&self) -> Self {
+        Self { vm: self.vm.clone(), acir_index: self.acir_index, function_id: self.function_id }
+    }
+}
+
 impl<'b, B: BlackBoxFunctionSolver<F>, F: AcirField> BrilligSolver<'b, F, B> {
     /// Assigns the zero value to all outputs of a given [brillig call][acir::circuit::opcodes::Opcode::BrilligCall].
     pub(super) fn zero_out_brillig_outputs(
@@ -164,6 +172,14 @@ impl<'b, B: BlackBoxFunctionSolver<F>, F: AcirField> BrilligSolver<'b, F, B> {
         self.vm.write_memory_at(ptr, value);
     }
 
+    pub fn watch_memory_writes(&mut self, addresses: &[usize]) {
+        self.vm.watch_memory_writes(addresses);
+    }
+
+    pub fn get_watched_memory_writes(&self) -> &[usize] {
+        self.vm.get_watched_memory_writes()
+    }
+
     pub fn get_call_stack(&self) -> Vec<usize> {
         self.vm.get_call_stack()
     }
@@ -193,7 +193,7 @@ impl<F> From<InvalidInputBitSize> for OpcodeResolutionError<F> {
 
 pub type ProfilingSamples = Vec<ProfilingSample>;
 
-#[derive(Default)]
+#[derive(Default, Clone)]
 pub struct ProfilingSample {
     pub call_stack: Vec<OpcodeLocation>,
     pub brillig_function_id: Option<BrilligFunctionId>,
@@ -245,6 +245,32 @@ pub struct ACVM<'a, F: AcirField, B: BlackBoxFunctionSolver<F>> {
     brillig_fuzzing_trace: Option<Vec<u32>>,
 }
 
+// Implemented by hand so that the ACVM can be cloned without requiring the black box solver
+// it borrows to be `Clone`.
+impl<F: AcirField, B: BlackBoxFunctionSolver<F>> Clone for ACVM<'_, F, B> {
+    fn clone(&self) -> Self {
+        Self {
+            status: self.status.clone(),
+            backend: self.backend,
+            block_solvers: self.block_solvers.clone(),
+            bigint_solver: self.bigint_solver.clone(),
+            opcodes: self.opcodes,
+            instruction_pointer: self.instruction_pointer,
+            witness_map: self.witness_map.clone(),
+            brillig_solver: self.brillig_solver.clone(),
+            acir_call_counter: self.acir_call_counter,
+            acir_call_results: self.acir_call_results.clone(),
+            unconstrained_functions: self.unconstrained_functions,
+            assertion_payloads: self.assertion_payloads,
+            profiling_active: self.profiling_active,
+            profiling_samples: self.profiling_samples.clone(),
+            brillig_fuzzing_active: self.brillig_fuzzing_active,
+            brillig_branch_to_feature_map: self.brillig_branch_to_feature_map,
+            brillig_fuzzing_trace: self.brillig_fuzzing_trace.clone(),
+        }
+    }
+}
+
 impl<'a, F: AcirField, B: BlackBoxFunctionSolver<F>> ACVM<'a, F, B> {
     pub fn new(
         backend: &'a B,
@@ -359,6 +385,23 @@ impl<'a, F: AcirField, B: BlackBoxFunctionSolver<F>> ACVM<'a, F, B> {
         }
     }
 
+    /// Return the call stack of the Brillig function which is currently being executed, if one exists,
+    /// together with the id of that function.
+    ///
+    /// This can be used to locate the source of a pending foreign call.
+    pub fn get_brillig_call_stack(&self) -> Option<(BrilligFunctionId, Vec<OpcodeLocation>)> {
+        let brillig_solver = self.brillig_solver.as_ref()?;
+        let call_stack = brillig_solver
+            .get_call_stack()
+            .into_iter()
+            .map(|brillig_index| OpcodeLocation::Brillig {
+                acir_index: self.instruction_pointer,
+                brillig_index,
+            })
+            .collect();
+        Some((brillig_solver.function_id, call_stack))
+    }
+
     /// Resolves a foreign call's [result][brillig_vm::brillig::ForeignCallResult] using a result calculated outside of the ACVM.
     ///
     /// The ACVM can then be restarted to solve the remaining Brillig VM process as well as the remaining ACIR opcodes.
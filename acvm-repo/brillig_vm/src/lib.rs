@@ -99,7 +99,7 @@ pub struct BrilligProfilingSample {
     pub call_stack: Vec<usize>,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq)]
 /// VM encapsulates the state of the Brillig VM during execution.
 pub struct VM<'a, F, B: BlackBoxFunctionSolver<F>> {
     /// Calldata to the brillig function
@@ -138,6 +138,29 @@ pub struct VM<'a, F, B: BlackBoxFunctionSolver<F>> {
     branch_to_feature_map: BranchToFeatureMap,
 }
 
+// Implemented by hand so that the VM can be cloned without requiring the solver it borrows to be `Clone`.
+impl<F: Clone, B: BlackBoxFunctionSolver<F>> Clone for VM<'_, F, B> {
+    fn clone(&self) -> Self {
+        Self {
+            calldata: self.calldata.clone(),
+            program_counter: self.program_counter,
+            foreign_call_counter: self.foreign_call_counter,
+            foreign_call_results: self.foreign_call_results.clone(),
+            bytecode: self.bytecode,
+            status: self.status.clone(),
+            memory: self.memory.clone(),
+            call_stack: self.call_stack.clone(),
+            black_box_solver: self.black_box_solver,
+            bigint_solver: self.bigint_solver.clone(),
+            profiling_active: self.profiling_active,
+            profiling_samples: self.profiling_samples.clone(),
+            fuzzing_active: self.fuzzing_active,
+            fuzzer_trace: self.fuzzer_trace.clone(),
+            branch_to_feature_map: self.branch_to_feature_map.clone(),
+        }
+    }
+}
+
 impl<'a, F: AcirField, B: BlackBoxFunctionSolver<F>> VM<'a, F, B> {
     /// Constructs a new VM instance
     pub fn new(
@@ -265,6 +288,18 @@ impl<'a, F: AcirField, B: BlackBoxFunctionSolver<F>> VM<'a, F, B> {
         self.memory.write(MemoryAddress::direct(ptr), value);
     }
 
+    /// Watches the given memory addresses for writes by the opcodes processed from now on,
+    /// so debuggers can tell when a memory address is written even if its value stays the same.
+    /// Watching no addresses stops watching.
+    pub fn watch_memory_writes(&mut self, addresses: &[usize]) {
+        self.memory.watch_writes(addresses);
+    }
+
+    /// Returns the watched memory addresses written since watching started
+    pub fn get_watched_memory_writes(&self) -> &[usize] {
+        self.memory.watched_writes()
+    }
+
     /// Returns the VM's current call stack, including the actual program
     /// counter in the last position of the returned vector.
     pub fn get_call_stack(&self) -> Vec<usize> {
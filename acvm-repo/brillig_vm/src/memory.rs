@@ -1,3 +1,5 @@
+use std::{collections::BTreeSet, ops::Range};
+
 use acir::{
     AcirField,
     brillig::{BitSize, IntegerBitSize, MemoryAddress},
@@ -290,6 +292,16 @@ pub struct Memory<F> {
     // Memory is a vector of values.
     // We grow the memory when values past the end are set, extending with 0s.
     inner: Vec<MemoryValue<F>>,
+    // The addresses watched by a debugger, if any. Boxed so that writes only pay for a null check
+    // when nothing is watched.
+    watched: Option<Box<WatchedAddresses>>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct WatchedAddresses {
+    addresses: BTreeSet<usize>,
+    // The watched addresses written since watching started, in the order they were written.
+    written: Vec<usize>,
 }
 
 impl<F: AcirField> Memory<F> {
@@ -330,6 +342,9 @@ impl<F: AcirField> Memory<F> {
         let resolved_ptr = self.resolve(address);
         self.resize_to_fit(resolved_ptr + 1);
         self.inner[resolved_ptr] = value;
+        if self.watched.is_some() {
+            self.record_watched_write(resolved_ptr..resolved_ptr + 1);
+        }
     }
 
     fn resize_to_fit(&mut self, size: usize) {
@@ -344,6 +359,33 @@ impl<F: AcirField> Memory<F> {
         let resolved_address = self.resolve(address);
         self.resize_to_fit(resolved_address + values.len());
         self.inner[resolved_address..(resolved_address + values.len())].copy_from_slice(values);
+        if self.watched.is_some() {
+            self.record_watched_write(resolved_address..(resolved_address + values.len()));
+        }
+    }
+
+    #[cold]
+    fn record_watched_write(&mut self, addresses: Range<usize>) {
+        if let Some(watched) = &mut self.watched {
+            let WatchedAddresses { addresses: watched_addresses, written } = &mut **watched;
+            written.extend(watched_addresses.range(addresses));
+        }
+    }
+
+    /// Watches the given addresses for writes, replacing the addresses watched so far
+    /// and dropping their writes. Watching no addresses stops watching.
+    pub fn watch_writes(&mut self, addresses: &[usize]) {
+        self.watched = (!addresses.is_empty()).then(|| {
+            Box::new(WatchedAddresses {
+                addresses: addresses.iter().copied().collect(),
+                written: Vec::new(),
+            })
+        });
+    }
+
+    /// Returns the watched addresses written since watching started
+    pub fn watched_writes(&self) -> &[usize] {
+        self.watched.as_ref().map_or(&[], |watched| &watched.written)
     }
 
     /// Returns the values of the memory
@@ -351,3 +393,28 @@ impl<F: AcirField> Memory<F> {
         &self.inner
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use acir::{FieldElement, brillig::MemoryAddress};
+
+    use super::{Memory, MemoryValue};
+
+    #[test]
+    fn records_writes_to_watched_addresses_only() {
+        let mut memory = Memory::<FieldElement>::default();
+        let value = MemoryValue::new_field(FieldElement::from(1_u128));
+        memory.write(MemoryAddress::direct(3), value);
+        assert!(memory.watched_writes().is_empty());
+
+        memory.watch_writes(&[3, 5]);
+        memory.write(MemoryAddress::direct(3), value);
+        memory.write_slice(MemoryAddress::direct(4), &[value, value]);
+        memory.write(MemoryAddress::direct(6), value);
+        assert_eq!(memory.watched_writes(), &[3, 5]);
+
+        memory.watch_writes(&[]);
+        memory.write(MemoryAddress::direct(3), value);
+        assert!(memory.watched_writes().is_empty());
+    }
+}
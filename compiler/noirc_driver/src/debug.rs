@@ -1,8 +1,11 @@
 use fm::{FileId, FileManager};
-use noirc_errors::debug_info::DebugInfo;
+use noirc_errors::{Location, debug_info::DebugInfo};
+use noirc_frontend::hir::Context;
+use noirc_frontend::hir::def_map::{ModuleId, fully_qualified_module_path};
+use noirc_frontend::node_interner::FuncId;
 use serde::{Deserialize, Serialize};
 use std::{
-    collections::{BTreeMap, BTreeSet},
+    collections::{BTreeMap, BTreeSet, HashMap},
     path::PathBuf,
 };
 
@@ -61,3 +64,67 @@ pub(crate) fn filter_relevant_files(
     }
     file_map
 }
+
+/// Records the name of the function each opcode was generated from, that is the function
+/// the innermost location of the opcode's call stack is in, so that tools such as the profiler
+/// can attribute opcodes to functions, even once they've been inlined.
+///
+/// Functions are named by their fully qualified path, including the type or trait of methods,
+/// so that functions with the same name in different modules or impls are told apart.
+pub(crate) fn record_function_names(debug_symbols: &mut [DebugInfo], context: &Context) {
+    let interner = &context.def_interner;
+    let mut function_bodies: HashMap<FileId, Vec<(Location, FuncId)>> = HashMap::new();
+    for func_id in interner.function_ids() {
+        if let Some(body) = interner.function(&func_id).try_as_expr() {
+            let location = interner.expr_location(&body);
+            function_bodies.entry(location.file).or_default().push((location, func_id));
+        }
+    }
+
+    // Functions generated at comptime have the locations of the code that quoted them, which can be
+    // in the body of another function, so the innermost body containing the location wins.
+    let function_name = |location: Location| {
+        let (_, func_id) = function_bodies
+            .get(&location.file)?
+            .iter()
+            .filter(|(body, _)| body.contains(&location))
+            .min_by_key(|(body, _)| body.span.end() - body.span.start())?;
+        Some(qualified_function_name(context, *func_id))
+    };
+
+    for debug_info in debug_symbols {
+        let brillig_call_stacks =
+            debug_info.brillig_locations.values().flat_map(|locations| locations.values());
+        let call_stacks: BTreeSet<_> =
+            debug_info.acir_locations.values().chain(brillig_call_stacks).copied().collect();
+        for call_stack_id in call_stacks {
+            let location = debug_info.location_tree.locations[call_stack_id.index()].value;
+            if let Some(name) = function_name(location) {
+                debug_info.function_names.insert(call_stack_id, name);
+            }
+        }
+    }
+}
+
+/// The path to a function from the root crate, such as `foo::Bar::new` or `std::hash::Hash::hash`.
+fn qualified_function_name(context: &Context, func_id: FuncId) -> String {
+    let interner = &context.def_interner;
+    let meta = interner.function_meta(&func_id);
+    let module_id = ModuleId { krate: meta.source_crate, local_id: meta.source_module };
+    let root_crate_id = context.root_crate_id();
+    let module_path = fully_qualified_module_path(
+        &context.def_maps,
+        &context.crate_graph,
+        root_crate_id,
+        module_id,
+    );
+
+    let owner = match (&meta.self_type, meta.trait_id) {
+        (Some(self_type), _) => Some(self_type.to_string()),
+        (None, Some(trait_id)) => Some(interner.get_trait(trait_id).name.to_string()),
+        (None, None) => None,
+    };
+    let path = module_path.split("::").filter(|segment| !segment.is_empty());
+    let name = interner.function_name(&func_id);
+    path.chain(owner.as_deref()).chain(std::iter::once(name)).collect::<Vec<_>>().join("::")
+}
@@ -33,14 +33,17 @@ use tracing::info;
 mod abi_gen;
 mod contract;
 mod debug;
+mod opcode_budget;
 mod program;
 mod stdlib;
 
-use debug::filter_relevant_files;
+use debug::{filter_relevant_files, record_function_names};
+use opcode_budget::check_opcode_budgets;
 
 pub use contract::{CompiledContract, CompiledContractOutputs, ContractFunction};
 pub use debug::DebugFile;
 pub use noirc_frontend::graph::{CrateId, CrateName};
+pub use opcode_budget::{OpcodeBudgetExceeded, OpcodeBudgetKind};
 pub use program::CompiledProgram;
 
 const STD_CRATE_NAME: &str = "std";
@@ -161,6 +164,11 @@ pub struct CompileOptions {
     #[arg(long)]
     pub count_array_copies: bool,
 
+    /// Record the function each opcode was generated from in the debug info,
+    /// so that the profiler's `execution-trace` command can report the cost of each function
+    #[arg(long)]
+    pub record_function_names: bool,
+
     /// Flag to turn on the lookback feature of the Brillig call constraints
     /// check, allowing tracking argument values before the call happens preventing
     /// certain rare false positives (leads to a slowdown on large rollout functions)
@@ -238,6 +246,7 @@ impl CompileOptions {
 pub enum CompileError {
     MonomorphizationError(MonomorphizationError),
     RuntimeError(RuntimeError),
+    OpcodeBudgetExceeded(OpcodeBudgetExceeded),
 }
 
 impl From<MonomorphizationError> for CompileError {
@@ -252,11 +261,18 @@ impl From<RuntimeError> for CompileError {
     }
 }
 
+impl From<OpcodeBudgetExceeded> for CompileError {
+    fn from(error: OpcodeBudgetExceeded) -> Self {
+        Self::OpcodeBudgetExceeded(error)
+    }
+}
+
 impl From<CompileError> for CustomDiagnostic {
     fn from(error: CompileError) -> CustomDiagnostic {
         match error {
             CompileError::RuntimeError(err) => err.into(),
             CompileError::MonomorphizationError(err) => err.into(),
+            CompileError::OpcodeBudgetExceeded(err) => err.into(),
         }
     }
 }
@@ -750,6 +766,9 @@ pub fn compile_no_check(
     if let Some(cached_program) = cached_program {
         if !force_compile && cached_program.hash == hash {
             info!("Program matches existing artifact, returning early");
+            // Budgets aren't part of the hash, so they might have changed since it was compiled.
+            // The cached artifact has been transformed for the backend already.
+            check_opcode_budgets(context, main_function, &cached_program, None)?;
             return Ok(cached_program);
         }
     }
@@ -786,17 +805,21 @@ pub fn compile_no_check(
         skip_passes: options.skip_ssa_pass.clone(),
     };
 
-    let SsaProgramArtifact { program, debug, warnings, names, brillig_names, error_types, .. } =
-        if options.minimal_ssa {
-            create_program_with_minimal_passes(program, &ssa_evaluator_options)?
-        } else {
-            create_program(program, &ssa_evaluator_options)?
-        };
+    let SsaProgramArtifact {
+        program, mut debug, warnings, names, brillig_names, error_types, ..
+    } = if options.minimal_ssa {
+        create_program_with_minimal_passes(program, &ssa_evaluator_options)?
+    } else {
+        create_program(program, &ssa_evaluator_options)?
+    };
 
     let abi = abi_gen::gen_abi(context, &main_function, return_visibility, error_types);
     let file_map = filter_relevant_files(&debug, &context.file_manager);
+    if options.record_function_names {
+        record_function_names(&mut debug, context);
+    }
 
-    Ok(CompiledProgram {
+    let compiled_program = CompiledProgram {
         hash,
         program,
         debug,
@@ -806,7 +829,11 @@ pub fn compile_no_check(
         warnings,
         names,
         brillig_names,
-    })
+    };
+    let expression_width = options.expression_width.unwrap_or(DEFAULT_EXPRESSION_WIDTH);
+    check_opcode_budgets(context, main_function, &compiled_program, Some(expression_width))?;
+
+    Ok(compiled_program)
 }
 
 /// Specifies a contract function and extra metadata that
@@ -0,0 +1,197 @@
+//! Checks the opcode budgets set on functions with `#[max_acir_opcodes(N)]` and
+//! `#[max_brillig_bytecode(N)]` against the program they were compiled into.
+
+use std::collections::BTreeSet;
+use std::fmt;
+
+use acvm::acir::circuit::ExpressionWidth;
+use noirc_errors::{CustomDiagnostic, Location, call_stack::CallStackId, debug_info::DebugInfo};
+use noirc_frontend::hir::Context;
+use noirc_frontend::node_interner::FuncId;
+use noirc_frontend::token::SecondaryAttributeKind;
+
+use crate::CompiledProgram;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OpcodeBudgetKind {
+    Acir,
+    Brillig,
+}
+
+impl fmt::Display for OpcodeBudgetKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OpcodeBudgetKind::Acir => write!(f, "ACIR opcodes"),
+            OpcodeBudgetKind::Brillig => write!(f, "Brillig opcodes"),
+        }
+    }
+}
+
+/// A function compiled to more opcodes than its budget allows.
+#[derive(Debug, Clone)]
+pub struct OpcodeBudgetExceeded {
+    pub function_name: String,
+    pub kind: OpcodeBudgetKind,
+    pub budget: u64,
+    pub actual: usize,
+    /// The location of the attribute that set the budget.
+    pub location: Location,
+}
+
+impl From<OpcodeBudgetExceeded> for CustomDiagnostic {
+    fn from(error: OpcodeBudgetExceeded) -> CustomDiagnostic {
+        let OpcodeBudgetExceeded { function_name, kind, budget, actual, location } = error;
+        CustomDiagnostic::simple_error(
+            format!(
+                "`{function_name}` compiles to {actual} {kind}, exceeding its budget of {budget}"
+            ),
+            format!("{kind} budget set here"),
+            location,
+        )
+    }
+}
+
+struct OpcodeBudget {
+    function: FuncId,
+    kind: OpcodeBudgetKind,
+    budget: u64,
+    location: Location,
+}
+
+/// Checks every opcode budget set on a function of the program.
+///
+/// The budget of the entry point covers the whole program. The budget of any other function covers
+/// the opcodes generated for its body, wherever it ended up: an opcode counts towards a function if
+/// its call stack goes through the function's body, so every call to an inlined function counts.
+///
+/// Budgets are checked against the circuit the backend receives: unless `expression_width` is `None`
+/// because the program has been transformed already, a copy of the program goes through the same
+/// ACVM transformation first.
+pub(crate) fn check_opcode_budgets(
+    context: &Context,
+    main_function: FuncId,
+    compiled_program: &CompiledProgram,
+    expression_width: Option<ExpressionWidth>,
+) -> Result<(), OpcodeBudgetExceeded> {
+    let interner = &context.def_interner;
+
+    let mut budgets: Vec<OpcodeBudget> = interner
+        .function_ids()
+        .flat_map(|function| {
+            interner.function_attributes(&function).secondary.iter().filter_map(move |attribute| {
+                let (kind, budget) = match attribute.kind {
+                    SecondaryAttributeKind::MaxAcirOpcodes(budget) => {
+                        (OpcodeBudgetKind::Acir, budget)
+                    }
+                    SecondaryAttributeKind::MaxBrilligBytecode(budget) => {
+                        (OpcodeBudgetKind::Brillig, budget)
+                    }
+                    _ => return None,
+                };
+                Some(OpcodeBudget { function, kind, budget, location: attribute.location })
+            })
+        })
+        .collect();
+    if budgets.is_empty() {
+        return Ok(());
+    }
+
+    // Report the first budget in the source code, regardless of the order functions were interned in.
+    budgets.sort_by_key(|budget| (budget.location.file.as_usize(), budget.location.span.start()));
+
+    let transformed_program;
+    let compiled_program = match expression_width {
+        Some(expression_width) => {
+            transformed_program = transform_program(compiled_program, expression_width);
+            &transformed_program
+        }
+        None => compiled_program,
+    };
+
+    for OpcodeBudget { function, kind, budget, location } in budgets {
+        let actual = if function == main_function {
+            let program = &compiled_program.program;
+            match kind {
+                OpcodeBudgetKind::Acir => {
+                    program.functions.iter().map(|function| function.opcodes.len()).sum()
+                }
+                OpcodeBudgetKind::Brillig => program
+                    .unconstrained_functions
+                    .iter()
+                    .map(|function| function.bytecode.len())
+                    .sum(),
+            }
+        } else {
+            let Some(body) = interner.function(&function).try_as_expr() else {
+                continue;
+            };
+            let body_location = interner.expr_location(&body);
+            count_opcodes_in(&compiled_program.debug, kind, body_location)
+        };
+
+        if actual as u64 > budget {
+            let function_name = interner.function_name(&function).to_string();
+            return Err(OpcodeBudgetExceeded { function_name, kind, budget, actual, location });
+        }
+    }
+
+    Ok(())
+}
+
+/// Applies the ACVM transformation `nargo` applies before handing the program to a backend.
+fn transform_program(
+    compiled_program: &CompiledProgram,
+    expression_width: ExpressionWidth,
+) -> CompiledProgram {
+    let mut compiled_program = compiled_program.clone();
+    let functions = std::mem::take(&mut compiled_program.program.functions);
+    compiled_program.program.functions = functions
+        .into_iter()
+        .zip(&mut compiled_program.debug)
+        .map(|(function, debug_info)| {
+            let (circuit, location_map) = acvm::compiler::compile(function, expression_width);
+            debug_info.update_acir(location_map);
+            circuit
+        })
+        .collect();
+    compiled_program
+}
+
+/// Counts the opcodes of the given kind whose call stack goes through `body`.
+fn count_opcodes_in(debug: &[DebugInfo], kind: OpcodeBudgetKind, body: Location) -> usize {
+    let in_body = |debug_info: &DebugInfo, call_stack_id: CallStackId| {
+        debug_info
+            .location_tree
+            .get_call_stack(call_stack_id)
+            .iter()
+            .any(|location| body.contains(location))
+    };
+
+    match kind {
+        OpcodeBudgetKind::Acir => debug
+            .iter()
+            .map(|debug_info| {
+                debug_info
+                    .acir_locations
+                    .values()
+                    .filter(|call_stack_id| in_body(debug_info, **call_stack_id))
+                    .count()
+            })
+            .sum(),
+        OpcodeBudgetKind::Brillig => {
+            // Each ACIR function has the locations of the Brillig functions it calls,
+            // so a Brillig function called from more than one of them shows up more than once.
+            let mut opcodes = BTreeSet::new();
+            for debug_info in debug {
+                for (brillig_function_id, locations) in &debug_info.brillig_locations {
+                    for (location, call_stack_id) in locations {
+                        if in_body(debug_info, *call_stack_id) {
+                            opcodes.insert((*brillig_function_id, *location));
+                        }
+                    }
+                }
+            }
+            opcodes.len()
+        }
+    }
+}
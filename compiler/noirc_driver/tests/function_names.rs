@@ -0,0 +1,61 @@
+use std::collections::BTreeSet;
+use std::path::Path;
+
+use noirc_driver::{CompileOptions, compile_main, file_manager_with_stdlib, prepare_crate};
+use noirc_frontend::hir::{Context, def_map::parse_file};
+
+/// Compiles `source` and returns the function names recorded in the debug info of `main`.
+fn function_names(source: &str, options: &CompileOptions) -> BTreeSet<String> {
+    let root = Path::new("");
+    let file_name = Path::new("main.nr");
+    let mut file_manager = file_manager_with_stdlib(root);
+    file_manager.add_file_with_source(file_name, source.to_owned()).expect(
+        "Adding source buffer to file manager should never fail when file manager is empty",
+    );
+    let parsed_files = file_manager
+        .as_file_map()
+        .all_file_ids()
+        .map(|&file_id| (file_id, parse_file(&file_manager, file_id)))
+        .collect();
+
+    let mut context = Context::new(file_manager, parsed_files);
+    let root_crate_id = prepare_crate(&mut context, file_name);
+
+    let (program, _) =
+        compile_main(&mut context, root_crate_id, options, None).expect("program should compile");
+    program.debug[0].function_names.values().cloned().collect()
+}
+
+const SOURCE: &str = "
+struct Foo {}
+
+impl Foo {
+    fn new(x: Field) -> Field {
+        x * x
+    }
+}
+
+struct Bar {}
+
+impl Bar {
+    fn new(x: Field) -> Field {
+        x * x * x
+    }
+}
+
+fn main(x: Field, y: pub Field) {
+    assert(Foo::new(x) + Bar::new(x) == y);
+}";
+
+#[test]
+fn records_qualified_names_of_methods() {
+    let options = CompileOptions { record_function_names: true, ..CompileOptions::default() };
+    let names = function_names(SOURCE, &options);
+    assert!(names.contains("Foo::new"));
+    assert!(names.contains("Bar::new"));
+}
+
+#[test]
+fn does_not_record_function_names_by_default() {
+    assert!(function_names(SOURCE, &CompileOptions::default()).is_empty());
+}
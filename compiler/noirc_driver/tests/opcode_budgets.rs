@@ -0,0 +1,83 @@
+use std::path::Path;
+
+use noirc_driver::{
+    CompileOptions, ErrorsAndWarnings, compile_main, file_manager_with_stdlib, prepare_crate,
+};
+use noirc_frontend::hir::{Context, def_map::parse_file};
+
+fn compile(source: &str) -> Result<(), ErrorsAndWarnings> {
+    let root = Path::new("");
+    let file_name = Path::new("main.nr");
+    let mut file_manager = file_manager_with_stdlib(root);
+    file_manager.add_file_with_source(file_name, source.to_owned()).expect(
+        "Adding source buffer to file manager should never fail when file manager is empty",
+    );
+    let parsed_files = file_manager
+        .as_file_map()
+        .all_file_ids()
+        .map(|&file_id| (file_id, parse_file(&file_manager, file_id)))
+        .collect();
+
+    let mut context = Context::new(file_manager, parsed_files);
+    let root_crate_id = prepare_crate(&mut context, file_name);
+
+    compile_main(&mut context, root_crate_id, &CompileOptions::default(), None).map(|_| ())
+}
+
+#[test]
+fn accepts_program_within_acir_opcodes_budget() {
+    let source = "
+#[max_acir_opcodes(100)]
+fn main(x: Field, y: pub Field) {
+    assert(x * x == y);
+}";
+    assert!(compile(source).is_ok());
+}
+
+#[test]
+fn rejects_program_over_acir_opcodes_budget() {
+    let source = "
+#[max_acir_opcodes(0)]
+fn main(x: Field, y: pub Field) {
+    assert(x * x == y);
+}";
+    let errors = compile(source).unwrap_err();
+    assert_eq!(errors.len(), 1);
+    assert!(errors[0].message.starts_with("`main` compiles to"));
+    assert!(errors[0].message.ends_with("ACIR opcodes, exceeding its budget of 0"));
+}
+
+#[test]
+fn rejects_inlined_function_over_acir_opcodes_budget() {
+    let source = "
+fn main(x: Field, y: pub Field) {
+    assert(square(x) == y);
+}
+
+#[max_acir_opcodes(0)]
+fn square(x: Field) -> Field {
+    assert(x != 0);
+    x * x
+}";
+    let errors = compile(source).unwrap_err();
+    assert_eq!(errors.len(), 1);
+    assert!(errors[0].message.starts_with("`square` compiles to"));
+}
+
+#[test]
+fn rejects_unconstrained_function_over_brillig_bytecode_budget() {
+    let source = "
+fn main(x: Field, y: pub Field) {
+    // Safety: the result is constrained below
+    let square = unsafe { square(x) };
+    assert(square == y);
+}
+
+#[max_brillig_bytecode(0)]
+unconstrained fn square(x: Field) -> Field {
+    x * x
+}";
+    let errors = compile(source).unwrap_err();
+    assert_eq!(errors.len(), 1);
+    assert!(errors[0].message.ends_with("Brillig opcodes, exceeding its budget of 0"));
+}
@@ -4,7 +4,9 @@ use serde::{Deserialize, Serialize};
 use crate::Location;
 
 pub type CallStack = Vec<Location>;
-#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(
+    Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
 pub struct CallStackId(u32);
 
 impl CallStackId {
@@ -109,6 +109,10 @@ pub struct DebugInfo {
     /// This a map per brillig function representing the range of opcodes where a procedure is activated.
     pub brillig_procedure_locs:
         BTreeMap<BrilligFunctionId, BTreeMap<ProcedureDebugId, (usize, usize)>>,
+    /// The name of the function the innermost location of each opcode's call stack is in.
+    /// Only recorded when compiling with `--record-function-names`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub function_names: BTreeMap<CallStackId, String>,
 }
 
 impl DebugInfo {
@@ -135,6 +139,7 @@ impl DebugInfo {
             functions,
             types,
             brillig_procedure_locs,
+            function_names: BTreeMap::new(),
         }
     }
 
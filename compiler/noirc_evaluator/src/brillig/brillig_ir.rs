@@ -77,6 +77,13 @@ impl ReservedRegisters {
     }
 }
 
+/// The address of the free memory pointer, which is bumped whenever memory is allocated on the heap.
+///
+/// Exposed so tools can measure the heap allocations of a running Brillig program.
+pub fn free_memory_pointer_address() -> MemoryAddress {
+    ReservedRegisters::free_memory_pointer()
+}
+
 /// Brillig context object that is used while constructing the
 /// Brillig bytecode.
 pub(crate) struct BrilligContext<F, Registers> {
@@ -26,6 +26,7 @@ use crate::ssa::{
 use fxhash::{FxHashMap as HashMap, FxHashSet as HashSet};
 use std::{borrow::Cow, collections::BTreeSet};
 
+pub use self::brillig_ir::free_memory_pointer_address;
 pub use self::brillig_ir::procedures::ProcedureId;
 
 /// Options that affect Brillig code generation.
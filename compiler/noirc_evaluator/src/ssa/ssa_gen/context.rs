@@ -47,6 +47,9 @@ pub(super) struct FunctionContext<'a> {
     /// These are ordered such that an inner loop is at the end of the vector and
     /// outer loops are at the beginning. When a loop is finished, it is popped.
     loops: Vec<Loop>,
+
+    /// How `return` expressions are lowered in the current function, if it contains any.
+    early_return: Option<EarlyReturn>,
 }
 
 /// Shared context for all functions during ssa codegen. This is the only
@@ -96,6 +99,16 @@ pub(super) struct Loop {
     pub(super) loop_end: BasicBlockId,
 }
 
+/// How `return` expressions are lowered in a function which contains any.
+#[derive(Clone)]
+enum EarlyReturn {
+    /// Unconstrained functions jump from each `return` to a single block which returns its parameters.
+    Jump { exit_block: BasicBlockId },
+    /// Constrained functions can't jump out of a branch. Instead each `return` stores the returned
+    /// values into `return_slots` and sets the `returned` flag, which guards any code after it.
+    Flag { returned: Value, return_slots: Values },
+}
+
 /// The queue of functions remaining to compile
 type FunctionQueue = Vec<(FuncId, IrFunctionId)>;
 
@@ -126,7 +139,8 @@ impl<'a> FunctionContext<'a> {
         builder.set_runtime(runtime);
 
         let definitions = HashMap::default();
-        let mut this = Self { definitions, builder, shared_context, loops: Vec::new() };
+        let mut this =
+            Self { definitions, builder, shared_context, loops: Vec::new(), early_return: None };
         this.add_parameters_to_scope(parameters);
         this
     }
@@ -138,6 +152,7 @@ impl<'a> FunctionContext<'a> {
     /// avoid calling new_function until the previous function is completely finished with ssa-gen.
     pub(super) fn new_function(&mut self, id: IrFunctionId, func: &ast::Function) {
         self.definitions.clear();
+        self.early_return = None;
 
         if func.unconstrained {
             self.builder.new_brillig_function(func.name.clone(), id, func.inline_type);
@@ -1102,6 +1117,163 @@ impl<'a> FunctionContext<'a> {
         }
     }
 
+    /// Codegen the body of a function which contains `return` expressions, and return its value.
+    ///
+    /// In unconstrained functions each `return` jumps to a single exit block:
+    ///
+    /// ```text
+    ///   ... codegen body, where `return v0` is `jmp exit(v0)` ...
+    ///   jmp exit(v1)
+    /// exit(v2: ?):
+    ///   return v2
+    /// ```
+    ///
+    /// In constrained functions each `return v0` stores `v0` into a return slot and sets a
+    /// `returned` flag, so that the code which follows it is only executed if it is unset:
+    ///
+    /// ```text
+    ///   v0 = allocate  // returned flag, initially false
+    ///   v1 = allocate  // return slot, initially zeroed
+    ///   ... codegen body, where any code after a `return` is guarded by `v0` ...
+    ///   v3 = load v0
+    ///   jmpif v3, then: end, else: not_returned
+    /// not_returned():
+    ///   store v2 at v1  // the value of the body
+    ///   jmp end()
+    /// end():
+    ///   v4 = load v1
+    ///   return v4
+    /// ```
+    pub(super) fn codegen_function_body_with_early_returns(
+        &mut self,
+        body: &ast::Expression,
+        return_type: &ast::Type,
+    ) -> Result<(), RuntimeError> {
+        if self.builder.current_function.runtime().is_brillig() {
+            let exit_block = self.builder.insert_block();
+            let results = Self::map_type(return_type, |typ| {
+                Value::from(self.builder.add_block_parameter(exit_block, typ))
+            });
+            self.early_return = Some(EarlyReturn::Jump { exit_block });
+
+            let body_values = self.codegen_expression(body)?.into_value_list(self);
+            self.builder.terminate_with_jmp(exit_block, body_values);
+
+            self.builder.switch_to_block(exit_block);
+            let results = results.into_value_list(self);
+            self.builder.terminate_with_return(results);
+        } else {
+            let not_returned =
+                self.builder.numeric_constant(FieldElement::zero(), NumericType::bool());
+            let returned = self.new_mutable_variable(not_returned);
+            let return_slots = Self::map_type(return_type, |typ| {
+                let zeroed = self.zeroed_value(&typ);
+                self.new_mutable_variable(zeroed)
+            });
+            let early_return = EarlyReturn::Flag { returned, return_slots: return_slots.clone() };
+            self.early_return = Some(early_return);
+
+            // The value of the body is only returned if no `return` was reached before its end
+            let body_values = self.codegen_expression(body)?;
+            self.codegen_unless_returned(|this| {
+                this.assign(return_slots.clone(), body_values);
+                Ok(Self::unit_value())
+            })?;
+
+            let results = return_slots.into_value_list(self);
+            self.builder.terminate_with_return(results);
+        }
+        Ok(())
+    }
+
+    /// Return the given values from the current function, before the end of its body.
+    pub(super) fn codegen_early_return(&mut self, values: Values) {
+        match self.early_return.clone() {
+            Some(EarlyReturn::Jump { exit_block }) => {
+                let values = values.into_value_list(self);
+                self.builder.terminate_with_jmp(exit_block, values);
+            }
+            Some(EarlyReturn::Flag { returned, return_slots }) => {
+                self.assign(return_slots, values);
+                let has_returned =
+                    self.builder.numeric_constant(FieldElement::one(), NumericType::bool());
+                self.builder.insert_store(returned.eval_reference(), has_returned);
+            }
+            None => unreachable!("ICE: `return` in a function which was not expected to return"),
+        }
+    }
+
+    /// True if `return` expressions in the current function set a `returned` flag,
+    /// in which case any code which may run after one has to be guarded
+    /// with [`FunctionContext::codegen_unless_returned`].
+    pub(super) fn returns_through_flag(&self) -> bool {
+        matches!(self.early_return, Some(EarlyReturn::Flag { .. }))
+    }
+
+    /// Codegen `f` only if no `return` was reached yet. This is the equivalent of
+    /// `if !returned { f() } else { zeroed }`, where the zeroed values are never used
+    /// since the function returns the values stored by the `return` instead.
+    pub(super) fn codegen_unless_returned(
+        &mut self,
+        f: impl FnOnce(&mut Self) -> Result<Values, RuntimeError>,
+    ) -> Result<Values, RuntimeError> {
+        let Some(EarlyReturn::Flag { returned, .. }) = self.early_return.clone() else {
+            unreachable!("ICE: expected `return` to be lowered through a flag")
+        };
+        let returned = returned.eval(self);
+
+        let not_returned_block = self.builder.insert_block();
+        let returned_block = self.builder.insert_block();
+        let end_block = self.builder.insert_block();
+        self.builder.terminate_with_jmpif(returned, returned_block, not_returned_block);
+
+        self.builder.switch_to_block(not_returned_block);
+        let values = f(self)?.map(|value| Values::from(value.eval(self)));
+        let arguments = values.clone().into_value_list(self);
+        self.builder.terminate_with_jmp(end_block, arguments.clone());
+
+        self.builder.switch_to_block(returned_block);
+        let zeroed = vecmap(&arguments, |argument| {
+            let typ = self.builder.type_of_value(*argument);
+            self.zeroed_value(&typ)
+        });
+        self.builder.terminate_with_jmp(end_block, zeroed);
+
+        self.builder.switch_to_block(end_block);
+        Ok(values.map(|value| {
+            let typ = self.builder.type_of_value(value.eval_reference());
+            Values::from(self.builder.add_block_parameter(end_block, typ))
+        }))
+    }
+
+    /// Creates a value of the given type, for code paths where its value is never used.
+    fn zeroed_value(&mut self, typ: &Type) -> ValueId {
+        match typ {
+            Type::Numeric(numeric_type) => {
+                self.builder.numeric_constant(FieldElement::zero(), *numeric_type)
+            }
+            Type::Array(element_types, length) => {
+                let element_types = element_types.clone();
+                let elements = (0..*length)
+                    .flat_map(|_| element_types.iter())
+                    .map(|element_type| self.zeroed_value(element_type))
+                    .collect();
+                self.builder.insert_make_array(elements, typ.clone())
+            }
+            Type::Slice(_) => self.builder.insert_make_array(im::Vector::new(), typ.clone()),
+            Type::Reference(element_type) => {
+                let value = self.zeroed_value(element_type);
+                let reference = self.builder.insert_allocate(element_type.as_ref().clone());
+                self.builder.insert_store(reference, value);
+                reference
+            }
+            Type::Function => {
+                let id = self.builder.current_function.id();
+                self.builder.import_function(id)
+            }
+        }
+    }
+
     pub(crate) fn enter_loop(&mut self, loop_: Loop) {
         self.loops.push(loop_);
     }
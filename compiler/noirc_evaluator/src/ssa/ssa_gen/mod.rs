@@ -9,7 +9,7 @@ use noirc_frontend::token::FmtStrFragment;
 pub use program::Ssa;
 
 use context::{Loop, SharedContext};
-use iter_extended::{try_vecmap, vecmap};
+use iter_extended::vecmap;
 use noirc_errors::Location;
 use noirc_frontend::ast::UnaryOp;
 use noirc_frontend::hir_def::types::Type as HirType;
@@ -73,7 +73,7 @@ pub fn generate_ssa(program: Program) -> Result<Ssa, RuntimeError> {
     // Generate the call_data bus from the relevant parameters. We create it *before* processing the function body
     let call_data = function_context.builder.call_data_bus(is_databus);
 
-    function_context.codegen_function_body(&main.body)?;
+    function_context.codegen_function_body(main)?;
 
     let mut return_data = DataBusBuilder::new();
     if let Some(return_location) = return_location {
@@ -126,7 +126,7 @@ pub fn generate_ssa(program: Program) -> Result<Ssa, RuntimeError> {
     while let Some((src_function_id, dest_id)) = context.pop_next_function_in_queue() {
         let function = &context.program[src_function_id];
         function_context.new_function(dest_id, function);
-        function_context.codegen_function_body(&function.body)?;
+        function_context.codegen_function_body(function)?;
     }
 
     let ssa = function_context.builder.finish();
@@ -136,8 +136,13 @@ pub fn generate_ssa(program: Program) -> Result<Ssa, RuntimeError> {
 impl FunctionContext<'_> {
     /// Codegen a function's body and set its return value to that of its last parameter.
     /// For functions returning nothing, this will be an empty list.
-    fn codegen_function_body(&mut self, body: &Expression) -> Result<(), RuntimeError> {
-        let return_value = self.codegen_expression(body)?;
+    fn codegen_function_body(&mut self, function: &ast::Function) -> Result<(), RuntimeError> {
+        if function.body.contains_return() {
+            return self
+                .codegen_function_body_with_early_returns(&function.body, &function.return_type);
+        }
+
+        let return_value = self.codegen_expression(&function.body)?;
         let results = return_value.into_value_list(self);
 
         self.builder.terminate_with_return(results);
@@ -171,6 +176,7 @@ impl FunctionContext<'_> {
             Expression::Semi(semi) => self.codegen_semi(semi),
             Expression::Break => Ok(self.codegen_break()),
             Expression::Continue => Ok(self.codegen_continue()),
+            Expression::Return(expr) => self.codegen_return(expr),
             Expression::Clone(expr) => self.codegen_clone(expr),
             Expression::Drop(expr) => self.codegen_drop(expr),
         }
@@ -211,29 +217,31 @@ impl FunctionContext<'_> {
     fn codegen_literal(&mut self, literal: &ast::Literal) -> Result<Values, RuntimeError> {
         match literal {
             ast::Literal::Array(array) => {
-                let elements = self.codegen_array_elements(&array.contents)?;
-
-                let typ = Self::convert_type(&array.typ).flatten();
-                Ok(match array.typ {
-                    ast::Type::Array(_, _) => {
-                        self.codegen_array_checked(elements, typ[0].clone())?
-                    }
-                    _ => unreachable!("ICE: unexpected array literal type, got {}", array.typ),
+                let elements = array.contents.iter().collect::<Vec<_>>();
+                self.codegen_operands_then(&elements, |this, elements| {
+                    let typ = Self::convert_type(&array.typ).flatten();
+                    Ok(match array.typ {
+                        ast::Type::Array(_, _) => {
+                            this.codegen_array_checked(elements, typ[0].clone())?
+                        }
+                        _ => unreachable!("ICE: unexpected array literal type, got {}", array.typ),
+                    })
                 })
             }
             ast::Literal::Slice(array) => {
-                let elements = self.codegen_array_elements(&array.contents)?;
-
-                let typ = Self::convert_type(&array.typ).flatten();
-                Ok(match array.typ {
-                    ast::Type::Slice(_) => {
-                        let slice_length =
-                            self.builder.length_constant(array.contents.len() as u128);
-                        let slice_contents =
-                            self.codegen_array_checked(elements, typ[1].clone())?;
-                        Tree::Branch(vec![slice_length.into(), slice_contents])
-                    }
-                    _ => unreachable!("ICE: unexpected slice literal type, got {}", array.typ),
+                let elements = array.contents.iter().collect::<Vec<_>>();
+                self.codegen_operands_then(&elements, |this, elements| {
+                    let typ = Self::convert_type(&array.typ).flatten();
+                    Ok(match array.typ {
+                        ast::Type::Slice(_) => {
+                            let slice_length =
+                                this.builder.length_constant(array.contents.len() as u128);
+                            let slice_contents =
+                                this.codegen_array_checked(elements, typ[1].clone())?;
+                            Tree::Branch(vec![slice_length.into(), slice_contents])
+                        }
+                        _ => unreachable!("ICE: unexpected slice literal type, got {}", array.typ),
+                    })
                 })
             }
             ast::Literal::Integer(value, typ, location) => {
@@ -275,11 +283,34 @@ impl FunctionContext<'_> {
         }
     }
 
-    fn codegen_array_elements(
+    /// Codegen the operands of an expression in order, then the operation on their values.
+    ///
+    /// Once an operand may have returned through the `returned` flag, whatever is evaluated after
+    /// it is guarded the same as the rest of a block after a `return`: the remaining operands and
+    /// the operation itself could otherwise fail on the zeroed values the `return` leaves behind,
+    /// as in `x / y?` or `assert_eq(x?, 1)`.
+    fn codegen_operands_then(
         &mut self,
-        elements: &[Expression],
-    ) -> Result<Vec<Values>, RuntimeError> {
-        try_vecmap(elements, |element| self.codegen_expression(element))
+        operands: &[&Expression],
+        operation: impl FnOnce(&mut Self, Vec<Values>) -> Result<Values, RuntimeError>,
+    ) -> Result<Values, RuntimeError> {
+        let mut may_have_returned = false;
+        let mut values = Vec::with_capacity(operands.len());
+        for operand in operands {
+            let value = if may_have_returned {
+                self.codegen_unless_returned(|this| this.codegen_expression(operand))?
+            } else {
+                self.codegen_expression(operand)?
+            };
+            values.push(value);
+            may_have_returned |= self.returns_through_flag() && operand.contains_return();
+        }
+
+        if may_have_returned {
+            self.codegen_unless_returned(|this| operation(this, values))
+        } else {
+            operation(self, values)
+        }
     }
 
     fn codegen_string(&mut self, string: &str) -> Values {
@@ -334,8 +365,14 @@ impl FunctionContext<'_> {
 
     fn codegen_block(&mut self, block: &[Expression]) -> Result<Values, RuntimeError> {
         let mut result = Self::unit_value();
-        for expr in block {
+        for (i, expr) in block.iter().enumerate() {
             result = self.codegen_expression(expr)?;
+
+            // If this expression may have returned, the rest of the block must only run if it didn't
+            let rest = &block[i + 1..];
+            if !rest.is_empty() && self.returns_through_flag() && expr.contains_return() {
+                return self.codegen_unless_returned(|this| this.codegen_block(rest));
+            }
         }
         Ok(result)
     }
@@ -401,29 +438,36 @@ impl FunctionContext<'_> {
     }
 
     fn codegen_binary(&mut self, binary: &ast::Binary) -> Result<Values, RuntimeError> {
-        let lhs = self.codegen_non_tuple_expression(&binary.lhs)?;
-        let rhs = self.codegen_non_tuple_expression(&binary.rhs)?;
-        Ok(self.insert_binary(lhs, binary.operator, rhs, binary.location))
+        self.codegen_operands_then(&[&binary.lhs, &binary.rhs], |this, operands| {
+            let [lhs, rhs] = operands.try_into().expect("binary expressions have two operands");
+            let lhs = lhs.into_leaf().eval(this);
+            let rhs = rhs.into_leaf().eval(this);
+            Ok(this.insert_binary(lhs, binary.operator, rhs, binary.location))
+        })
     }
 
     fn codegen_index(&mut self, index: &ast::Index) -> Result<Values, RuntimeError> {
-        let array_or_slice = self.codegen_expression(&index.collection)?.into_value_list(self);
-        let index_value = self.codegen_non_tuple_expression(&index.index)?;
-        // Slices are represented as a tuple in the form: (length, slice contents).
-        // Thus, slices require two value ids for their representation.
-        let (array, slice_length) = if array_or_slice.len() > 1 {
-            (array_or_slice[1], Some(array_or_slice[0]))
-        } else {
-            (array_or_slice[0], None)
-        };
-
-        self.codegen_array_index(
-            array,
-            index_value,
-            &index.element_type,
-            index.location,
-            slice_length,
-        )
+        self.codegen_operands_then(&[&index.collection, &index.index], |this, operands| {
+            let [array_or_slice, index_value] =
+                operands.try_into().expect("index expressions have two operands");
+            let array_or_slice = array_or_slice.into_value_list(this);
+            let index_value = index_value.into_leaf().eval(this);
+            // Slices are represented as a tuple in the form: (length, slice contents).
+            // Thus, slices require two value ids for their representation.
+            let (array, slice_length) = if array_or_slice.len() > 1 {
+                (array_or_slice[1], Some(array_or_slice[0]))
+            } else {
+                (array_or_slice[0], None)
+            };
+
+            this.codegen_array_index(
+                array,
+                index_value,
+                &index.element_type,
+                index.location,
+                slice_length,
+            )
+        })
     }
 
     /// This is broken off from codegen_index so that it can also be
@@ -576,7 +620,12 @@ impl FunctionContext<'_> {
         // Compile the loop body
         self.builder.switch_to_block(loop_body);
         self.define(for_expr.index_variable, loop_index.into());
-        self.codegen_expression(&for_expr.block)?;
+        if self.returns_through_flag() && for_expr.block.contains_return() {
+            // Once the loop body returned, none of the remaining iterations may run
+            self.codegen_unless_returned(|this| this.codegen_expression(&for_expr.block))?;
+        } else {
+            self.codegen_expression(&for_expr.block)?;
+        }
         let new_loop_index = self.make_offset(loop_index, 1);
         self.builder.terminate_with_jmp(loop_entry, vec![new_loop_index]);
 
@@ -688,7 +737,19 @@ impl FunctionContext<'_> {
     ///   ... This is the current insert point after codegen_if finishes ...
     /// ```
     fn codegen_if(&mut self, if_expr: &ast::If) -> Result<Values, RuntimeError> {
-        let condition = self.codegen_non_tuple_expression(&if_expr.condition)?;
+        self.codegen_operands_then(&[&if_expr.condition], |this, condition| {
+            let [condition] = condition.try_into().expect("if expressions have a condition");
+            let condition = condition.into_leaf().eval(this);
+            this.codegen_if_branches(condition, if_expr)
+        })
+    }
+
+    /// Codegen the branches of an if expression, once its condition is known.
+    fn codegen_if_branches(
+        &mut self,
+        condition: ValueId,
+        if_expr: &ast::If,
+    ) -> Result<Values, RuntimeError> {
         if let Some(result) = self.try_codegen_constant_if(condition, if_expr) {
             return result;
         }
@@ -951,7 +1012,8 @@ impl FunctionContext<'_> {
     }
 
     fn codegen_tuple(&mut self, tuple: &[Expression]) -> Result<Values, RuntimeError> {
-        Ok(Tree::Branch(try_vecmap(tuple, |expr| self.codegen_expression(expr))?))
+        let elements = tuple.iter().collect::<Vec<_>>();
+        self.codegen_operands_then(&elements, |_, elements| Ok(Tree::Branch(elements)))
     }
 
     fn codegen_extract_tuple_field(
@@ -966,19 +1028,24 @@ impl FunctionContext<'_> {
     /// Generate SSA for a function call. Note that calls to built-in functions
     /// and intrinsics are also represented by the function call instruction.
     fn codegen_call(&mut self, call: &ast::Call) -> Result<Values, RuntimeError> {
-        let function = self.codegen_non_tuple_expression(&call.func)?;
-        let mut arguments = Vec::with_capacity(call.arguments.len());
-
-        for argument in &call.arguments {
-            let mut values = self.codegen_expression(argument)?.into_value_list(self);
-            arguments.append(&mut values);
-        }
+        let operands =
+            std::iter::once(call.func.as_ref()).chain(&call.arguments).collect::<Vec<_>>();
+        self.codegen_operands_then(&operands, |this, operands| {
+            let mut operands = operands.into_iter();
+            let function = operands.next().expect("calls have a function").into_leaf().eval(this);
+            let mut arguments = Vec::with_capacity(call.arguments.len());
+
+            for argument in operands {
+                let mut values = argument.into_value_list(this);
+                arguments.append(&mut values);
+            }
 
-        // Don't need to increment array reference counts when passed in as arguments
-        // since it is done within the function to each parameter already.
+            // Don't need to increment array reference counts when passed in as arguments
+            // since it is done within the function to each parameter already.
 
-        self.codegen_intrinsic_call_checks(function, &arguments, call.location);
-        Ok(self.insert_call(function, arguments, &call.return_type, call.location))
+            this.codegen_intrinsic_call_checks(function, &arguments, call.location);
+            Ok(this.insert_call(function, arguments, &call.return_type, call.location))
+        })
     }
 
     fn codegen_intrinsic_call_checks(
@@ -1042,17 +1109,20 @@ impl FunctionContext<'_> {
         location: Location,
         assert_payload: &Option<Box<(Expression, HirType)>>,
     ) -> Result<Values, RuntimeError> {
-        let expr = self.codegen_non_tuple_expression(expr)?;
-        let true_literal = self.builder.numeric_constant(true, NumericType::bool());
+        self.codegen_operands_then(&[expr], |this, expr| {
+            let [expr] = expr.try_into().expect("constrain expressions have a condition");
+            let expr = expr.into_leaf().eval(this);
+            let true_literal = this.builder.numeric_constant(true, NumericType::bool());
 
-        // Set the location here for any errors that may occur when we codegen the assert message
-        self.builder.set_location(location);
+            // Set the location here for any errors that may occur when we codegen the assert message
+            this.builder.set_location(location);
 
-        let assert_payload = self.codegen_constrain_error(assert_payload)?;
+            let assert_payload = this.codegen_constrain_error(assert_payload)?;
 
-        self.builder.insert_constrain(expr, true_literal, assert_payload);
+            this.builder.insert_constrain(expr, true_literal, assert_payload);
 
-        Ok(Self::unit_value())
+            Ok(Self::unit_value())
+        })
     }
 
     // This method does not necessary codegen the full assert message expression, thus it does not
@@ -1086,11 +1156,13 @@ impl FunctionContext<'_> {
     fn codegen_assign(&mut self, assign: &ast::Assign) -> Result<Values, RuntimeError> {
         // Evaluate the rhs first - when we load the expression in the lvalue we want that
         // to reflect any mutations from evaluating the rhs.
-        let rhs = self.codegen_expression(&assign.expression)?;
-        let lhs = self.extract_current_value(&assign.lvalue)?;
+        self.codegen_operands_then(&[&assign.expression], |this, rhs| {
+            let [rhs] = rhs.try_into().expect("assignments have a value");
+            let lhs = this.extract_current_value(&assign.lvalue)?;
 
-        self.assign_new_value(lhs, rhs);
-        Ok(Self::unit_value())
+            this.assign_new_value(lhs, rhs);
+            Ok(Self::unit_value())
+        })
     }
 
     fn codegen_semi(&mut self, expr: &Expression) -> Result<Values, RuntimeError> {
@@ -1104,6 +1176,12 @@ impl FunctionContext<'_> {
         Self::unit_value()
     }
 
+    fn codegen_return(&mut self, expr: &Expression) -> Result<Values, RuntimeError> {
+        let values = self.codegen_expression(expr)?;
+        self.codegen_early_return(values);
+        Ok(Self::unit_value())
+    }
+
     fn codegen_continue(&mut self) -> Values {
         let loop_ = self.current_loop();
 
@@ -44,6 +44,9 @@ pub enum ExpressionKind {
     Unsafe(UnsafeExpression),
     AsTraitPath(Box<AsTraitPath>),
     TypePath(Box<TypePath>),
+    /// `expr?`: evaluates to the output of `expr`, or returns early from the enclosing
+    /// function if `expr` holds a residual (such as `Option::none()`).
+    Try(Box<Expression>),
 
     // This variant is only emitted when inlining the result of comptime
     // code. It is used to translate function values back into the AST while
@@ -258,6 +261,7 @@ impl Expression {
             | ExpressionKind::Unquote(..)
             | ExpressionKind::AsTraitPath(..)
             | ExpressionKind::TypePath(..)
+            | ExpressionKind::Try(..)
             | ExpressionKind::Resolved(..)
             | ExpressionKind::Interned(..)
             | ExpressionKind::InternedStatement(..)
@@ -611,6 +615,7 @@ impl Display for ExpressionKind {
             }
             AsTraitPath(path) => write!(f, "{path}"),
             TypePath(path) => write!(f, "{path}"),
+            Try(expr) => write!(f, "{expr}?"),
             InternedStatement(_) => write!(f, "?InternedStatement"),
         }
     }
@@ -86,6 +86,7 @@ impl From<FunctionDefinition> for NoirFunction {
             Some(FunctionAttributeKind::Foreign(_)) => FunctionKind::LowLevel,
             Some(FunctionAttributeKind::Test { .. }) => FunctionKind::Normal,
             Some(FunctionAttributeKind::FuzzingHarness { .. }) => FunctionKind::Normal,
+            Some(FunctionAttributeKind::Bench) => FunctionKind::Normal,
             Some(FunctionAttributeKind::Oracle(_)) => FunctionKind::Oracle,
             Some(FunctionAttributeKind::Fold) => FunctionKind::Normal,
             Some(FunctionAttributeKind::NoPredicates) => FunctionKind::Normal,
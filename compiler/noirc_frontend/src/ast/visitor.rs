@@ -244,6 +244,10 @@ pub trait Visitor {
         true
     }
 
+    fn visit_try(&mut self, _: &Expression, _: Span) -> bool {
+        true
+    }
+
     fn visit_comptime_expression(&mut self, _: &BlockExpression, _: Span) -> bool {
         true
     }
@@ -329,6 +333,10 @@ pub trait Visitor {
 
     fn visit_continue(&mut self) {}
 
+    fn visit_return_statement(&mut self, _: Option<&Expression>) -> bool {
+        true
+    }
+
     fn visit_interned_statement(&mut self, _: InternedStatementKind) {}
 
     fn visit_error_statement(&mut self) {}
@@ -964,6 +972,11 @@ impl Expression {
                 as_trait_path.accept(span, visitor);
             }
             ExpressionKind::TypePath(path) => path.accept(span, visitor),
+            ExpressionKind::Try(expression) => {
+                if visitor.visit_try(expression, span) {
+                    expression.accept(visitor);
+                }
+            }
             ExpressionKind::Quote(tokens) => visitor.visit_quote(tokens),
             ExpressionKind::Resolved(expr_id) => visitor.visit_resolved_expression(*expr_id),
             ExpressionKind::Interned(id) => visitor.visit_interned_expression(*id),
@@ -1238,6 +1251,13 @@ impl Statement {
             }
             StatementKind::Break => visitor.visit_break(),
             StatementKind::Continue => visitor.visit_continue(),
+            StatementKind::Return(expression) => {
+                if visitor.visit_return_statement(expression.as_ref()) {
+                    if let Some(expression) = expression {
+                        expression.accept(visitor);
+                    }
+                }
+            }
             StatementKind::Interned(id) => visitor.visit_interned_statement(*id),
             StatementKind::Error => visitor.visit_error_statement(),
         }
@@ -444,7 +444,7 @@ impl DebugInstrumenter {
             ast::ExpressionKind::Lambda(lambda) => {
                 self.walk_expr(&mut lambda.body);
             }
-            ast::ExpressionKind::Parenthesized(expr) => {
+            ast::ExpressionKind::Parenthesized(expr) | ast::ExpressionKind::Try(expr) => {
                 self.walk_expr(expr);
             }
             _ => {}
@@ -499,6 +499,9 @@ impl DebugInstrumenter {
             ast::StatementKind::Semi(expr) => {
                 self.walk_expr(expr);
             }
+            ast::StatementKind::Return(Some(expr)) => {
+                self.walk_expr(expr);
+            }
             ast::StatementKind::For(for_stmt) => {
                 self.walk_for(for_stmt);
             }
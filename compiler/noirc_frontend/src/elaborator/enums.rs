@@ -513,6 +513,7 @@ impl Elaborator<'_> {
             | ExpressionKind::Unsafe(_)
             | ExpressionKind::AsTraitPath(_)
             | ExpressionKind::TypePath(_)
+            | ExpressionKind::Try(_)
             | ExpressionKind::Resolved(_)
             | ExpressionKind::Error => syntax_error(self),
         }
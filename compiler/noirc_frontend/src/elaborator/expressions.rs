@@ -9,9 +9,9 @@ use crate::{
         ArrayLiteral, AsTraitPath, BinaryOpKind, BlockExpression, CallExpression, CastExpression,
         ConstrainExpression, ConstrainKind, ConstructorExpression, Expression, ExpressionKind,
         Ident, IfExpression, IndexExpression, InfixExpression, ItemVisibility, Lambda, Literal,
-        MatchExpression, MemberAccessExpression, MethodCallExpression, PrefixExpression,
-        StatementKind, TraitBound, UnaryOp, UnresolvedTraitConstraint, UnresolvedTypeData,
-        UnresolvedTypeExpression, UnsafeExpression,
+        MatchExpression, MemberAccessExpression, MethodCallExpression, Path, Pattern,
+        PrefixExpression, Statement, StatementKind, TraitBound, UnaryOp, UnresolvedTraitConstraint,
+        UnresolvedTypeData, UnresolvedTypeExpression, UnsafeExpression,
     },
     hir::{
         comptime::{self, InterpreterError},
@@ -33,7 +33,8 @@ use crate::{
         traits::{ResolvedTraitBound, TraitConstraint},
     },
     node_interner::{
-        DefinitionId, DefinitionKind, ExprId, FuncId, InternedStatementKind, StmtId, TraitMethodId,
+        DefinitionId, DefinitionKind, ExprId, FuncId, InternedStatementKind, StmtId, TraitId,
+        TraitMethodId,
     },
     token::{FmtStrFragment, Tokens},
 };
@@ -102,6 +103,7 @@ impl Elaborator<'_> {
                 return self.elaborate_as_trait_path(*path);
             }
             ExpressionKind::TypePath(path) => return self.elaborate_type_path(*path),
+            ExpressionKind::Try(value) => return self.elaborate_try(*value, expr.location),
         };
         let id = self.interner.push_expr(hir_expr);
         self.interner.push_expr_location(id, expr.location);
@@ -154,10 +156,10 @@ impl Elaborator<'_> {
         let statements_len = block.statements.len();
         let mut statements = Vec::with_capacity(statements_len);
 
-        // If we found a break or continue statement, this holds its location (only for the first one)
-        let mut break_or_continue_location = None;
-        // When encountering a statement after a break or continue we'll error saying it's unreachable,
-        // but we only want to error for the first statement.
+        // If we found a break, continue or return statement, this holds its location (only for the first one)
+        let mut jump_location = None;
+        // When encountering a statement after a break, continue or return we'll error saying it's
+        // unreachable, but we only want to error for the first statement.
         let mut errored_unreachable = false;
 
         for (i, statement) in block.statements.into_iter().enumerate() {
@@ -166,7 +168,7 @@ impl Elaborator<'_> {
             let (id, stmt_type) =
                 self.elaborate_statement_with_target_type(statement, statement_target_type);
 
-            if break_or_continue_location.is_none() {
+            if jump_location.is_none() {
                 statements.push(id);
             }
 
@@ -182,16 +184,16 @@ impl Elaborator<'_> {
                 });
             }
 
-            if let Some(break_or_continue_location) = break_or_continue_location {
+            if let Some(jump_location) = jump_location {
                 if !errored_unreachable {
-                    self.push_err(ResolverError::UnreachableStatement {
-                        location,
-                        break_or_continue_location,
-                    });
+                    self.push_err(ResolverError::UnreachableStatement { location, jump_location });
                     errored_unreachable = true;
                 }
-            } else if matches!(stmt, HirStatement::Break | HirStatement::Continue) {
-                break_or_continue_location = Some(location);
+            } else if matches!(
+                stmt,
+                HirStatement::Break | HirStatement::Continue | HirStatement::Return(_)
+            ) {
+                jump_location = Some(location);
                 block_type = stmt_type;
             } else if i + 1 == statements.len() {
                 block_type = stmt_type;
@@ -1233,7 +1235,11 @@ impl Elaborator<'_> {
 
         let return_type = self.resolve_inferred_type(lambda.return_type);
         let body_location = lambda.body.location;
+
+        // A `return` within the lambda returns from the lambda, not from the enclosing function
+        let old_return_type = self.replace_return_type(Some(return_type.clone()));
         let (body, body_type) = self.elaborate_expression(lambda.body);
+        self.replace_return_type(old_return_type);
 
         let lambda_context = self.lambda_stack.pop().unwrap();
         self.pop_scope();
@@ -1447,4 +1453,101 @@ impl Elaborator<'_> {
         self.interner.push_expr_type(id, typ.clone());
         (id, typ)
     }
+
+    /// Elaborates `expr?` by desugaring it through the `Try` and `FromResidual` stdlib traits:
+    ///
+    /// ```noir
+    /// {
+    ///     let $try = expr;
+    ///     if Try::is_break($try) {
+    ///         return FromResidual::from_residual(Try::residual($try));
+    ///     }
+    ///     Try::output($try)
+    /// }
+    /// ```
+    fn elaborate_try(&mut self, expr: Expression, location: Location) -> (ExprId, Type) {
+        let Some((try_trait, from_residual_trait)) = self.interner.get_try_operator_traits() else {
+            self.push_err(ResolverError::TryOperatorWithoutStdlib { location });
+            let error = self.interner.push_expr_full(HirExpression::Error, location, Type::Error);
+            return (error, Type::Error);
+        };
+
+        let expr_location = expr.location;
+        let try_ident = Ident::new("$try".to_string(), expr_location);
+        let try_value = || {
+            let path = Path::from_ident(try_ident.clone());
+            Expression::new(ExpressionKind::Variable(path), expr_location)
+        };
+
+        let let_try = Statement {
+            kind: StatementKind::new_let(
+                Pattern::Identifier(try_ident.clone()),
+                UnresolvedTypeData::Unspecified.with_dummy_location(),
+                expr,
+                Vec::new(),
+            ),
+            location: expr_location,
+        };
+
+        let is_break = self.trait_method_call(try_trait, "is_break", vec![try_value()], location);
+        let residual = self.trait_method_call(try_trait, "residual", vec![try_value()], location);
+        let from_residual =
+            self.trait_method_call(from_residual_trait, "from_residual", vec![residual], location);
+        let return_residual =
+            Statement { kind: StatementKind::Return(Some(from_residual)), location };
+        let if_break = Statement {
+            kind: StatementKind::Expression(Expression::new(
+                ExpressionKind::If(Box::new(IfExpression {
+                    condition: is_break,
+                    consequence: Expression::new(
+                        ExpressionKind::Block(BlockExpression {
+                            statements: vec![return_residual],
+                        }),
+                        location,
+                    ),
+                    alternative: None,
+                })),
+                location,
+            )),
+            location,
+        };
+
+        let output = self.trait_method_call(try_trait, "output", vec![try_value()], location);
+        let output = Statement { kind: StatementKind::Expression(output), location };
+
+        let block = BlockExpression { statements: vec![let_try, if_break, output] };
+        self.elaborate_expression(Expression::new(ExpressionKind::Block(block), location))
+    }
+
+    /// Creates a call to the given method of a stdlib trait, for use in desugared code.
+    fn trait_method_call(
+        &mut self,
+        trait_id: TraitId,
+        method_name: &str,
+        arguments: Vec<Expression>,
+        location: Location,
+    ) -> Expression {
+        let the_trait = self.interner.get_trait(trait_id);
+        let method_id = the_trait.find_method(method_name).unwrap_or_else(|| {
+            panic!("ICE: expected `{}` to have a `{method_name}` method", the_trait.name)
+        });
+        let constraint = the_trait.as_constraint(location);
+        let trait_method = TraitMethod { method_id, constraint, assumed: false };
+
+        let definition_id = self.interner.trait_method_id(method_id);
+        let ident = HirIdent {
+            location,
+            id: definition_id,
+            impl_kind: ImplKind::TraitMethod(trait_method),
+        };
+
+        let id = self.interner.push_expr(HirExpression::Ident(ident.clone(), None));
+        self.interner.push_expr_location(id, location);
+        let typ = self.type_check_variable(ident, id, None);
+        self.interner.push_expr_type(id, typ);
+
+        let func = Box::new(Expression::new(ExpressionKind::Resolved(id), location));
+        let call = CallExpression { func, arguments, is_macro_call: false };
+        Expression::new(ExpressionKind::Call(Box::new(call)), location)
+    }
 }
@@ -15,7 +15,7 @@ use crate::{
         DefinitionId, DefinitionKind, ExprId, FuncId, FunctionModifiers, NodeInterner,
     },
     shared::{Signedness, Visibility},
-    token::FunctionAttributeKind,
+    token::{FunctionAttributeKind, SecondaryAttributeKind},
 };
 
 use noirc_errors::Location;
@@ -67,10 +67,33 @@ pub(super) fn inlining_attributes(
         | FunctionAttributeKind::Oracle(_)
         | FunctionAttributeKind::Test(_)
         | FunctionAttributeKind::InlineAlways
-        | FunctionAttributeKind::FuzzingHarness(_) => None,
+        | FunctionAttributeKind::FuzzingHarness(_)
+        | FunctionAttributeKind::Bench => None,
     }
 }
 
+/// `#[max_acir_opcodes]` only applies to constrained functions and
+/// `#[max_brillig_bytecode]` only to unconstrained ones.
+pub(super) fn opcode_budget_attributes(
+    func: &FuncMeta,
+    modifiers: &FunctionModifiers,
+) -> Option<ResolverError> {
+    modifiers.attributes.secondary.iter().find_map(|attribute| {
+        let location = attribute.location;
+        match attribute.kind {
+            SecondaryAttributeKind::MaxAcirOpcodes(_) if modifiers.is_unconstrained => {
+                let ident = func_meta_name_ident(func, modifiers);
+                Some(ResolverError::MaxAcirOpcodesAttributeOnUnconstrained { ident, location })
+            }
+            SecondaryAttributeKind::MaxBrilligBytecode(_) if !modifiers.is_unconstrained => {
+                let ident = func_meta_name_ident(func, modifiers);
+                Some(ResolverError::MaxBrilligBytecodeAttributeOnConstrained { ident, location })
+            }
+            _ => None,
+        }
+    })
+}
+
 /// Attempting to define new low level (`#[builtin]` or `#[foreign]`) functions outside of the stdlib is disallowed.
 pub(super) fn low_level_function_outside_stdlib(
     modifiers: &FunctionModifiers,
@@ -305,11 +328,12 @@ fn can_return_without_recursing(interner: &NodeInterner, func_id: FuncId, expr_i
             HirStatement::For(e) => check(e.start_range) && check(e.end_range),
             HirStatement::Loop(e) => check(e),
             HirStatement::While(condition, block) => check(condition) && check(block),
+            HirStatement::Return(e) => check(e),
             HirStatement::Comptime(_)
             | HirStatement::Break
             | HirStatement::Continue
             | HirStatement::Error => true,
-        })
+        }) || can_return_early_without_recursing(interner, func_id, &block)
     };
 
     match interner.expression(&expr_id) {
@@ -345,6 +369,65 @@ fn can_return_without_recursing(interner: &NodeInterner, func_id: FuncId, expr_i
     }
 }
 
+/// Check if a block can reach an early `return` without calling a specific function.
+fn can_return_early_without_recursing(
+    interner: &NodeInterner,
+    func_id: FuncId,
+    block: &HirBlockExpression,
+) -> bool {
+    let check = |e| can_return_without_recursing(interner, func_id, e);
+    let check_early = |e| expression_can_return_early_without_recursing(interner, func_id, e);
+
+    for stmt_id in block.statements.iter() {
+        let (returns_early, completes) = match interner.statement(stmt_id) {
+            HirStatement::Return(e) => return check(e),
+            HirStatement::Expression(e) | HirStatement::Semi(e) => (check_early(e), check(e)),
+            HirStatement::Let(s) => (false, check(s.expression)),
+            HirStatement::Assign(s) => (false, check(s.expression)),
+            HirStatement::For(e) => {
+                let range = check(e.start_range) && check(e.end_range);
+                (range && check_early(e.block), range)
+            }
+            HirStatement::Loop(e) => (check_early(e), check(e)),
+            HirStatement::While(condition, block) => {
+                (check(condition) && check_early(block), check(condition) && check(block))
+            }
+            HirStatement::Comptime(_)
+            | HirStatement::Break
+            | HirStatement::Continue
+            | HirStatement::Error => (false, true),
+        };
+        if returns_early {
+            return true;
+        }
+        if !completes {
+            return false;
+        }
+    }
+    false
+}
+
+/// Check if an expression can reach an early `return` without calling a specific function.
+fn expression_can_return_early_without_recursing(
+    interner: &NodeInterner,
+    func_id: FuncId,
+    expr_id: ExprId,
+) -> bool {
+    let check = |e| can_return_without_recursing(interner, func_id, e);
+    let check_early = |e| expression_can_return_early_without_recursing(interner, func_id, e);
+
+    match interner.expression(&expr_id) {
+        HirExpression::Block(b) | HirExpression::Unsafe(b) => {
+            can_return_early_without_recursing(interner, func_id, &b)
+        }
+        HirExpression::If(e) => {
+            check(e.condition)
+                && (check_early(e.consequence) || e.alternative.is_some_and(check_early))
+        }
+        _ => false,
+    }
+}
+
 fn can_return_without_recursing_match(
     interner: &NodeInterner,
     func_id: FuncId,
@@ -265,6 +265,10 @@ struct FunctionContext {
     /// into an error, because doing that involves a completely different approach
     /// (just unifying indexes with u32).
     indexes_to_check: Vec<ExprId>,
+
+    /// The type that a `return` statement in the current function or lambda must return.
+    /// This is `None` outside of functions, such as in `comptime` blocks and globals.
+    return_type: Option<Type>,
 }
 
 impl<'context> Elaborator<'context> {
@@ -494,7 +498,8 @@ impl<'context> Elaborator<'context> {
         let old_item = self.current_item.replace(DependencyId::Function(id));
 
         self.trait_bounds = func_meta.all_trait_constraints().cloned().collect();
-        self.function_context.push(FunctionContext::default());
+        let return_type = Some(func_meta.return_type().clone());
+        self.function_context.push(FunctionContext { return_type, ..Default::default() });
 
         let modifiers = self.interner.function_modifiers(&id).clone();
 
@@ -1205,6 +1210,7 @@ impl<'context> Elaborator<'context> {
 
     fn run_function_lints(&mut self, func: &FuncMeta, modifiers: &FunctionModifiers) {
         self.run_lint(|_| lints::inlining_attributes(func, modifiers).map(Into::into));
+        self.run_lint(|_| lints::opcode_budget_attributes(func, modifiers).map(Into::into));
         self.run_lint(|_| lints::missing_pub(func, modifiers).map(Into::into));
         self.run_lint(|_| {
             let pub_allowed = func.is_entry_point || modifiers.attributes.is_foldable();
@@ -14,7 +14,7 @@ use crate::{
         type_check::{Source, TypeCheckError},
     },
     hir_def::{
-        expr::{HirBlockExpression, HirExpression, HirIdent},
+        expr::{HirBlockExpression, HirExpression, HirIdent, HirLiteral},
         stmt::{
             HirAssignStatement, HirForStatement, HirLValue, HirLetStatement, HirPattern,
             HirStatement,
@@ -43,6 +43,7 @@ impl Elaborator<'_> {
             StatementKind::While(while_) => self.elaborate_while(while_),
             StatementKind::Break => self.elaborate_jump(true, statement.location),
             StatementKind::Continue => self.elaborate_jump(false, statement.location),
+            StatementKind::Return(expr) => self.elaborate_return(expr, statement.location),
             StatementKind::Comptime(statement) => self.elaborate_comptime_statement(*statement),
             StatementKind::Expression(expr) => {
                 let (expr, typ) = self.elaborate_expression_with_target_type(expr, target_type);
@@ -353,6 +354,73 @@ impl Elaborator<'_> {
         (expr, self.interner.next_type_variable())
     }
 
+    fn elaborate_return(
+        &mut self,
+        expression: Option<Expression>,
+        location: Location,
+    ) -> (HirStatement, Type) {
+        let return_type =
+            self.function_context.last().and_then(|context| context.return_type.clone());
+
+        let (expr, expr_type, expr_location) = match expression {
+            Some(expression) => {
+                let expr_location = expression.location;
+                let (expr, expr_type) =
+                    self.elaborate_expression_with_target_type(expression, return_type.as_ref());
+                (expr, expr_type, expr_location)
+            }
+            None => {
+                let expr = self.intern_expr(HirExpression::Literal(HirLiteral::Unit), location);
+                self.interner.push_expr_type(expr, Type::Unit);
+                (expr, Type::Unit, location)
+            }
+        };
+
+        match return_type {
+            Some(Type::TraitAsType(trait_id, trait_name, generics)) => {
+                if self
+                    .interner
+                    .lookup_trait_implementation(
+                        &expr_type,
+                        trait_id,
+                        &generics.ordered,
+                        &generics.named,
+                    )
+                    .is_err()
+                {
+                    let return_type = Type::TraitAsType(trait_id, trait_name, generics);
+                    self.push_err(TypeCheckError::TypeMismatch {
+                        expected_typ: return_type.to_string(),
+                        expr_typ: expr_type.to_string(),
+                        expr_location,
+                    });
+                }
+            }
+            Some(return_type) => {
+                self.unify_with_coercions(&expr_type, &return_type, expr, expr_location, || {
+                    TypeCheckError::TypeMismatch {
+                        expected_typ: return_type.to_string(),
+                        expr_typ: expr_type.to_string(),
+                        expr_location,
+                    }
+                });
+            }
+            None => self.push_err(ResolverError::ReturnOutsideFunction { location }),
+        }
+
+        // Like `break` and `continue`, a `return` never produces a value where it appears,
+        // so it can be used wherever any type is expected.
+        (HirStatement::Return(expr), self.interner.next_type_variable())
+    }
+
+    /// Sets the type `return` statements must return from now on, returning the previous one.
+    pub(super) fn replace_return_type(&mut self, return_type: Option<Type>) -> Option<Type> {
+        match self.function_context.last_mut() {
+            Some(context) => std::mem::replace(&mut context.return_type, return_type),
+            None => None,
+        }
+    }
+
     fn get_lvalue_error_info(&self, lvalue: &HirLValue) -> (DefinitionId, String, Location) {
         match lvalue {
             HirLValue::Ident(name, _) => {
@@ -113,6 +113,7 @@ impl Elaborator<'_> {
             if self.crate_id.is_stdlib() {
                 self.interner.try_add_infix_operator_trait(*trait_id);
                 self.interner.try_add_prefix_operator_trait(*trait_id);
+                self.interner.try_add_try_operator_trait(*trait_id);
             }
         }
 
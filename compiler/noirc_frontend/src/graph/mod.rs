@@ -4,7 +4,7 @@
 // This version is also simpler due to not having macro_defs or proc_macros
 // XXX: Edition may be reintroduced or some sort of versioning
 
-use std::{fmt::Display, str::FromStr};
+use std::{collections::BTreeSet, fmt::Display, str::FromStr};
 
 use fm::FileId;
 use rustc_hash::{FxHashMap, FxHashSet};
@@ -157,6 +157,10 @@ pub const CHARACTER_BLACK_LIST: [char; 1] = ['-'];
 pub struct CrateData {
     pub root_file_id: FileId,
     pub dependencies: Vec<Dependency>,
+    /// Items annotated with `#[cfg(feature = "...")]` are only compiled if the feature is in this set.
+    pub features: BTreeSet<String>,
+    /// Whether items annotated with `#[cfg(test)]` are compiled.
+    pub cfg_test: bool,
 }
 
 /// A dependency is a crate name and a crate_id
@@ -199,7 +203,12 @@ impl CrateGraph {
             }
         }
 
-        let data = CrateData { root_file_id: file_id, dependencies: Vec::new() };
+        let data = CrateData {
+            root_file_id: file_id,
+            dependencies: Vec::new(),
+            features: BTreeSet::new(),
+            cfg_test: false,
+        };
         let crate_id = CrateId::Root(self.arena.len());
         let prev = self.arena.insert(crate_id, data);
         assert!(prev.is_none());
@@ -230,7 +239,12 @@ impl CrateGraph {
                 panic!("ICE: A dummy CrateId should not exist in the CrateGraph")
             }
             None => {
-                let data = CrateData { root_file_id: file_id, dependencies: Vec::new() };
+                let data = CrateData {
+                    root_file_id: file_id,
+                    dependencies: Vec::new(),
+                    features: BTreeSet::new(),
+                    cfg_test: false,
+                };
                 let crate_id = CrateId::Crate(self.arena.len());
                 let prev = self.arena.insert(crate_id, data);
                 assert!(prev.is_none());
@@ -250,7 +264,12 @@ impl CrateGraph {
             }
         }
 
-        let data = CrateData { root_file_id: file_id, dependencies: Vec::new() };
+        let data = CrateData {
+            root_file_id: file_id,
+            dependencies: Vec::new(),
+            features: BTreeSet::new(),
+            cfg_test: false,
+        };
         let crate_id = CrateId::Stdlib(self.arena.len());
         let prev = self.arena.insert(crate_id, data);
         assert!(prev.is_none());
@@ -272,7 +291,12 @@ impl CrateGraph {
             }
         }
 
-        let data = CrateData { root_file_id: file_id, dependencies: Vec::new() };
+        let data = CrateData {
+            root_file_id: file_id,
+            dependencies: Vec::new(),
+            features: BTreeSet::new(),
+            cfg_test: false,
+        };
         let crate_id = CrateId::RootAndStdlib(self.arena.len());
         let prev = self.arena.insert(crate_id, data);
         assert!(prev.is_none());
@@ -340,6 +364,21 @@ impl CrateGraph {
         false
     }
 
+    /// Enables the given features of a crate in addition to the ones already enabled,
+    /// so that a crate used by several packages gets the union of the features they request.
+    pub fn enable_features(
+        &mut self,
+        crate_id: CrateId,
+        features: impl IntoIterator<Item = String>,
+    ) {
+        self.arena.get_mut(&crate_id).expect("ICE: crate should exist").features.extend(features);
+    }
+
+    /// Compiles the items of a crate annotated with `#[cfg(test)]`, so that its tests can be run.
+    pub fn enable_cfg_test(&mut self, crate_id: CrateId) {
+        self.arena.get_mut(&crate_id).expect("ICE: crate should exist").cfg_test = true;
+    }
+
     pub fn number_of_crates(&self) -> usize {
         self.arena.len()
     }
@@ -285,6 +285,7 @@ impl<'interner> TokenPrettyPrinter<'interner> {
             | Token::Pound
             | Token::Pipe
             | Token::Bang
+            | Token::Question
             | Token::DollarSign => {
                 write!(f, "{token}")
             }
@@ -683,6 +684,9 @@ fn remove_interned_in_expression_kind(
         ExpressionKind::Unquote(expr) => {
             ExpressionKind::Unquote(Box::new(remove_interned_in_expression(interner, *expr)))
         }
+        ExpressionKind::Try(expr) => {
+            ExpressionKind::Try(Box::new(remove_interned_in_expression(interner, *expr)))
+        }
         ExpressionKind::Comptime(block, span) => {
             let statements =
                 vecmap(block.statements, |stmt| remove_interned_in_statement(interner, stmt));
@@ -829,6 +833,9 @@ fn remove_interned_in_statement_kind(
             let statement = interner.get_statement_kind(id).clone();
             remove_interned_in_statement_kind(interner, statement)
         }
+        StatementKind::Return(expr) => {
+            StatementKind::Return(expr.map(|expr| remove_interned_in_expression(interner, expr)))
+        }
         StatementKind::Break | StatementKind::Continue | StatementKind::Error => statement,
     }
 }
@@ -5,6 +5,7 @@ use crate::{
     Type,
     ast::TraitBound,
     hir::{
+        comptime::Value,
         def_collector::dc_crate::CompilationError,
         type_check::{NoMatchingImplFoundError, TypeCheckError},
     },
@@ -185,6 +186,9 @@ pub enum InterpreterError {
     ContinueNotInLoop {
         location: Location,
     },
+    ReturnNotInFunction {
+        location: Location,
+    },
     BlackBoxError(BlackBoxResolutionError, Location),
     FailedToResolveTraitBound {
         trait_bound: TraitBound,
@@ -262,6 +266,7 @@ pub enum InterpreterError {
     // until the loop can be resumed properly. These cases will never be displayed to users.
     Break,
     Continue,
+    Return(Box<Value>),
 }
 
 #[allow(unused)]
@@ -317,6 +322,7 @@ impl InterpreterError {
             | InterpreterError::BlackBoxError(_, location)
             | InterpreterError::BreakNotInLoop { location, .. }
             | InterpreterError::ContinueNotInLoop { location, .. }
+            | InterpreterError::ReturnNotInFunction { location }
             | InterpreterError::TraitDefinitionMustBeAPath { location }
             | InterpreterError::FailedToResolveTraitDefinition { location }
             | InterpreterError::FailedToResolveTraitBound { location, .. }
@@ -336,8 +342,8 @@ impl InterpreterError {
 
             InterpreterError::FailedToParseMacro { error, .. } => error.location(),
             InterpreterError::NoMatchingImplFound { error } => error.location,
-            InterpreterError::Break | InterpreterError::Continue => {
-                panic!("Tried to get the location of Break/Continue error!")
+            InterpreterError::Break | InterpreterError::Continue | InterpreterError::Return(_) => {
+                panic!("Tried to get the location of Break/Continue/Return error!")
             }
         }
     }
@@ -596,6 +602,10 @@ impl<'a> From<&'a InterpreterError> for CustomDiagnostic {
                 let msg = "There is no loop to continue!".into();
                 CustomDiagnostic::simple_error(msg, String::new(), *location)
             }
+            InterpreterError::ReturnNotInFunction { location } => {
+                let msg = "There is no function to return from!".into();
+                CustomDiagnostic::simple_error(msg, String::new(), *location)
+            }
             InterpreterError::NoImpl { location } => {
                 let msg = "No impl found due to prior type error".into();
                 CustomDiagnostic::simple_error(msg, String::new(), *location)
@@ -616,6 +626,7 @@ impl<'a> From<&'a InterpreterError> for CustomDiagnostic {
             InterpreterError::NoMatchingImplFound { error, .. } => error.into(),
             InterpreterError::Break => unreachable!("Uncaught InterpreterError::Break"),
             InterpreterError::Continue => unreachable!("Uncaught InterpreterError::Continue"),
+            InterpreterError::Return(_) => unreachable!("Uncaught InterpreterError::Return"),
             InterpreterError::TraitDefinitionMustBeAPath { location } => {
                 let msg = "Trait definition arguments must be a variable or path".to_string();
                 CustomDiagnostic::simple_error(msg, String::new(), *location)
@@ -58,6 +58,9 @@ impl HirStatement {
             }),
             HirStatement::Break => StatementKind::Break,
             HirStatement::Continue => StatementKind::Continue,
+            HirStatement::Return(expr) => {
+                StatementKind::Return(Some(expr.to_display_ast(interner)))
+            }
             HirStatement::Expression(expr) => {
                 StatementKind::Expression(expr.to_display_ast(interner))
             }
@@ -58,6 +58,9 @@ pub struct Interpreter<'local, 'interner> {
 
     in_loop: bool,
 
+    /// True while evaluating the body of a function or closure, which a `return` can return from.
+    in_function: bool,
+
     current_function: Option<FuncId>,
 
     /// Maps each bound generic to each binding it has in the current callstack.
@@ -85,6 +88,7 @@ impl<'local, 'interner> Interpreter<'local, 'interner> {
             current_function,
             bound_generics: Vec::new(),
             in_loop: false,
+            in_function: false,
             bigint_solver,
         }
     }
@@ -187,7 +191,7 @@ impl<'local, 'interner> Interpreter<'local, 'interner> {
                 return Err(err);
             }
         };
-        let result = self.evaluate(function_body);
+        let result = self.evaluate_function_body(function_body);
         self.exit_function(previous_state);
         result
     }
@@ -320,31 +324,45 @@ impl<'local, 'interner> Interpreter<'local, 'interner> {
             self.define(param.ident.id, arg);
         }
 
-        let result = self.evaluate(closure.body);
+        let result = self.evaluate_function_body(closure.body);
 
         self.exit_function(previous_state);
         result
     }
 
+    /// Evaluates the body of a function or closure, stopping at the first `return` if there is one.
+    fn evaluate_function_body(&mut self, body: ExprId) -> IResult<Value> {
+        match self.evaluate(body) {
+            Err(InterpreterError::Return(value)) => Ok(*value),
+            result => result,
+        }
+    }
+
     /// Enters a function, pushing a new scope and resetting any required state.
     /// Returns the previous values of the internal state, to be reset when
     /// `exit_function` is called.
-    pub(super) fn enter_function(&mut self) -> (bool, Vec<HashMap<DefinitionId, Value>>) {
+    pub(super) fn enter_function(&mut self) -> (bool, bool, Vec<HashMap<DefinitionId, Value>>) {
         // Drain every scope except the global scope
         let mut scope = Vec::new();
         if self.elaborator.interner.comptime_scopes.len() > 1 {
             scope = self.elaborator.interner.comptime_scopes.drain(1..).collect();
         }
         self.push_scope();
-        (std::mem::take(&mut self.in_loop), scope)
+        let in_loop = std::mem::take(&mut self.in_loop);
+        let in_function = std::mem::replace(&mut self.in_function, true);
+        (in_loop, in_function, scope)
     }
 
-    pub(super) fn exit_function(&mut self, mut state: (bool, Vec<HashMap<DefinitionId, Value>>)) {
+    pub(super) fn exit_function(
+        &mut self,
+        mut state: (bool, bool, Vec<HashMap<DefinitionId, Value>>),
+    ) {
         self.in_loop = state.0;
+        self.in_function = state.1;
 
         // Keep only the global scope
         self.elaborator.interner.comptime_scopes.truncate(1);
-        self.elaborator.interner.comptime_scopes.append(&mut state.1);
+        self.elaborator.interner.comptime_scopes.append(&mut state.2);
     }
 
     pub(super) fn push_scope(&mut self) {
@@ -1112,6 +1130,7 @@ impl<'local, 'interner> Interpreter<'local, 'interner> {
             HirStatement::While(condition, block) => self.evaluate_while(condition, block),
             HirStatement::Break => self.evaluate_break(statement),
             HirStatement::Continue => self.evaluate_continue(statement),
+            HirStatement::Return(expression) => self.evaluate_return(expression, statement),
             HirStatement::Expression(expression) => self.evaluate(expression),
             HirStatement::Comptime(statement) => self.evaluate_comptime(statement),
             HirStatement::Semi(expression) => {
@@ -1427,6 +1446,16 @@ impl<'local, 'interner> Interpreter<'local, 'interner> {
         }
     }
 
+    fn evaluate_return(&mut self, expression: ExprId, id: StmtId) -> IResult<Value> {
+        if self.in_function {
+            let value = self.evaluate(expression)?;
+            Err(InterpreterError::Return(Box::new(value)))
+        } else {
+            let location = self.elaborator.interner.statement_location(id);
+            Err(InterpreterError::ReturnNotInFunction { location })
+        }
+    }
+
     pub(super) fn evaluate_comptime(&mut self, statement: StmtId) -> IResult<Value> {
         self.evaluate_statement(statement)
     }
@@ -604,6 +604,7 @@ fn secondary_attribute_name(
         }
         SecondaryAttributeKind::Export => Some("export".to_string()),
         SecondaryAttributeKind::Field(_) => Some("field".to_string()),
+        SecondaryAttributeKind::Cfg(_) => Some("cfg".to_string()),
         SecondaryAttributeKind::Tag(contents) => {
             let mut lexer = Lexer::new_with_dummy_file(contents);
             let token = lexer.next()?.ok()?;
@@ -614,6 +615,8 @@ fn secondary_attribute_name(
         SecondaryAttributeKind::Varargs => Some("varargs".to_string()),
         SecondaryAttributeKind::UseCallersScope => Some("use_callers_scope".to_string()),
         SecondaryAttributeKind::Allow(_) => Some("allow".to_string()),
+        SecondaryAttributeKind::MaxAcirOpcodes(_) => Some("max_acir_opcodes".to_string()),
+        SecondaryAttributeKind::MaxBrilligBytecode(_) => Some("max_brillig_bytecode".to_string()),
     }
 }
 
@@ -282,6 +282,26 @@ fn lambda() {
     assert!(matches!(result, Value::U8(2)));
 }
 
+#[test]
+fn early_return() {
+    let program = "comptime fn main() -> pub u8 {
+        let f = |x: u8| {
+            if x == 0 {
+                return 10;
+            }
+            x
+        };
+        for i in 0 .. 6 {
+            if i == 3 {
+                return f(0) + i;
+            }
+        }
+        f(1)
+    }";
+    let result = interpret(program);
+    assert_eq!(result, Value::U8(13));
+}
+
 #[test]
 fn non_deterministic_recursion() {
     let program = "
@@ -805,7 +805,7 @@ impl ModCollector<'_> {
         context.visited_files.insert(child_file_id, location);
 
         // Parse the AST for the module we just found and then recursively look for it's defs
-        let (ast, parsing_errors) = context.parsed_file_results(child_file_id);
+        let (ast, parsing_errors) = context.parsed_file_results_for_crate(child_file_id, crate_id);
         let ast = ast.into_sorted();
 
         errors.extend(parsing_errors.iter().map(|e| e.clone().into()).collect::<Vec<_>>());
@@ -1022,6 +1022,8 @@ pub fn collect_function(
     let is_test = test_attribute.is_some();
     let fuzz_attribute = function.def.attributes.as_fuzzing_harness();
     let is_fuzzing_harness = fuzz_attribute.is_some();
+    let bench_attribute = function.def.attributes.as_bench_function();
+    let is_bench = bench_attribute.is_some();
     let is_entry_point_function = if module_data.is_contract {
         function.attributes().is_contract_entry_point()
     } else {
@@ -1050,6 +1052,7 @@ pub fn collect_function(
 
     if !is_test
         && !is_fuzzing_harness
+        && !is_bench
         && !is_entry_point_function
         && !has_export
         && !has_allow_dead_code
@@ -1076,6 +1079,13 @@ pub fn collect_function(
         }
     }
 
+    if let Some(location) = bench_attribute {
+        if !function.def.parameters.is_empty() {
+            let error = DefCollectorErrorKind::BenchWithParameters { location };
+            errors.push(error.into());
+        }
+    }
+
     // Add function to scope/ns of the module
     let result = module_data.declare_function(name, visibility, func_id);
     if let Err((first_def, second_def)) = result {
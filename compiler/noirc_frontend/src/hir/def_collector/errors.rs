@@ -77,6 +77,8 @@ pub enum DefCollectorErrorKind {
     TestOnlyFailWithWithoutParameters { location: Location },
     #[error("The `#[fuzz]` attribute may only be used on functions with parameters")]
     FuzzingHarnessWithoutParameters { location: Location },
+    #[error("The `#[bench]` attribute may only be used on functions without parameters")]
+    BenchWithParameters { location: Location },
     #[error("`{name}` entry-point function is not allowed to have generic parameters")]
     EntryPointWithGenerics { name: String, location: Location },
 }
@@ -113,6 +115,7 @@ impl DefCollectorErrorKind {
             | DefCollectorErrorKind::ForeignImpl { location, .. }
             | DefCollectorErrorKind::TestOnlyFailWithWithoutParameters { location }
             | DefCollectorErrorKind::FuzzingHarnessWithoutParameters { location }
+            | DefCollectorErrorKind::BenchWithParameters { location }
             | DefCollectorErrorKind::EntryPointWithGenerics { location, .. } => *location,
             DefCollectorErrorKind::NotATrait { not_a_trait_name: path }
             | DefCollectorErrorKind::TraitNotFound { trait_path: path } => path.location,
@@ -294,6 +297,11 @@ impl<'a> From<&'a DefCollectorErrorKind> for Diagnostic {
                 String::new(),
                 *location,
             ),
+            DefCollectorErrorKind::BenchWithParameters { location } => Diagnostic::simple_error(
+                "The `#[bench]` attribute may only be used on functions without parameters".into(),
+                String::new(),
+                *location,
+            ),
             DefCollectorErrorKind::EntryPointWithGenerics { name, location } => Diagnostic::simple_error(
                 format!("`{name}` entry-point function is not allowed to have generic parameters"),
                 String::new(),
@@ -121,7 +121,7 @@ impl CrateDefMap {
 
         // First parse the root file.
         let root_file_id = context.crate_graph[crate_id].root_file_id;
-        let (ast, parsing_errors) = context.parsed_file_results(root_file_id);
+        let (ast, parsing_errors) = context.parsed_file_results_for_crate(root_file_id, crate_id);
         let ast = ast.into_sorted();
 
         let location = Location::new(Default::default(), root_file_id);
@@ -198,24 +198,16 @@ impl CrateDefMap {
         &'a self,
         interner: &'a NodeInterner,
     ) -> impl Iterator<Item = TestFunction> + 'a {
-        self.modules.iter().flat_map(|(_, module)| {
-            module.value_definitions().filter_map(|id| {
-                if let Some(func_id) = id.as_function() {
-                    let has_arguments = !interner.function_meta(&func_id).parameters.is_empty();
-                    let attributes = interner.function_attributes(&func_id);
-                    match attributes.function().map(|attr| &attr.kind) {
-                        Some(FunctionAttributeKind::Test(scope)) => {
-                            let location = interner.function_meta(&func_id).name.location;
-                            let scope = scope.clone();
-                            Some(TestFunction { id: func_id, scope, location, has_arguments })
-                        }
-                        _ => None,
-                    }
-                } else {
-                    None
-                }
-            })
-        })
+        self.get_all_functions_with_attribute(interner, TestFunction::from_attribute)
+    }
+
+    /// Go through all modules in this crate, and find all functions in
+    /// each module with the `#[bench]` attribute
+    pub fn get_all_bench_functions<'a>(
+        &'a self,
+        interner: &'a NodeInterner,
+    ) -> impl Iterator<Item = BenchFunction> + 'a {
+        self.get_all_functions_with_attribute(interner, BenchFunction::from_attribute)
     }
 
     /// Go through all modules in this crate, and find all functions in
@@ -224,20 +216,22 @@ impl CrateDefMap {
         &'a self,
         interner: &'a NodeInterner,
     ) -> impl Iterator<Item = FuzzingHarness> + 'a {
-        self.modules.iter().flat_map(|(_, module)| {
-            module.value_definitions().filter_map(|id| {
-                if let Some(func_id) = id.as_function() {
-                    let attributes = interner.function_attributes(&func_id);
-                    match attributes.function().map(|attr| &attr.kind) {
-                        Some(FunctionAttributeKind::FuzzingHarness(scope)) => {
-                            let location = interner.function_meta(&func_id).name.location;
-                            Some(FuzzingHarness { id: func_id, scope: scope.clone(), location })
-                        }
-                        _ => None,
-                    }
-                } else {
-                    None
-                }
+        self.get_all_functions_with_attribute(interner, FuzzingHarness::from_attribute)
+    }
+
+    /// Go through all modules in this crate, and collect the functions whose
+    /// function attribute is accepted by `from_attribute`.
+    pub(crate) fn get_all_functions_with_attribute<'a, T: 'a>(
+        &'a self,
+        interner: &'a NodeInterner,
+        from_attribute: impl Fn(&NodeInterner, FuncId, &FunctionAttributeKind) -> Option<T> + Copy + 'a,
+    ) -> impl Iterator<Item = T> + 'a {
+        self.modules.iter().flat_map(move |(_, module)| {
+            module.value_definitions().filter_map(move |id| {
+                let func_id = id.as_function()?;
+                let attributes = interner.function_attributes(&func_id);
+                let attribute = attributes.function()?;
+                from_attribute(interner, func_id, &attribute.kind)
             })
         })
     }
@@ -381,6 +375,21 @@ pub struct TestFunction {
 }
 
 impl TestFunction {
+    /// Returns the test function for `func_id` if its attribute is `#[test]`.
+    pub(crate) fn from_attribute(
+        interner: &NodeInterner,
+        func_id: FuncId,
+        attribute: &FunctionAttributeKind,
+    ) -> Option<Self> {
+        let FunctionAttributeKind::Test(scope) = attribute else {
+            return None;
+        };
+        let meta = interner.function_meta(&func_id);
+        let has_arguments = !meta.parameters.is_empty();
+        let location = meta.name.location;
+        Some(TestFunction { id: func_id, scope: scope.clone(), location, has_arguments })
+    }
+
     /// Returns true if the test function has been specified to fail
     /// This is done by annotating the function with `#[test(should_fail)]`
     /// or `#[test(should_fail_with = "reason")]`
@@ -402,6 +411,28 @@ impl TestFunction {
     }
 }
 
+pub struct BenchFunction {
+    pub id: FuncId,
+    pub location: Location,
+    pub is_unconstrained: bool,
+}
+
+impl BenchFunction {
+    /// Returns the benchmark for `func_id` if its attribute is `#[bench]`.
+    pub(crate) fn from_attribute(
+        interner: &NodeInterner,
+        func_id: FuncId,
+        attribute: &FunctionAttributeKind,
+    ) -> Option<Self> {
+        let FunctionAttributeKind::Bench = attribute else {
+            return None;
+        };
+        let location = interner.function_meta(&func_id).name.location;
+        let is_unconstrained = interner.function_modifiers(&func_id).is_unconstrained;
+        Some(BenchFunction { id: func_id, location, is_unconstrained })
+    }
+}
+
 pub struct FuzzingHarness {
     pub id: FuncId,
     pub scope: FuzzingScope,
@@ -409,6 +440,19 @@ pub struct FuzzingHarness {
 }
 
 impl FuzzingHarness {
+    /// Returns the fuzzing harness for `func_id` if its attribute is `#[fuzz]`.
+    pub(crate) fn from_attribute(
+        interner: &NodeInterner,
+        func_id: FuncId,
+        attribute: &FunctionAttributeKind,
+    ) -> Option<Self> {
+        let FunctionAttributeKind::FuzzingHarness(scope) = attribute else {
+            return None;
+        };
+        let location = interner.function_meta(&func_id).name.location;
+        Some(FuzzingHarness { id: func_id, scope: scope.clone(), location })
+    }
+
     /// Returns true if the fuzzing harness has been specified to fail only under specific reason
     /// This is done by annotating the function with
     /// `#[fuzz(only_fail_with = "reason")]`
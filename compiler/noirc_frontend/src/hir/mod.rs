@@ -10,11 +10,12 @@ use crate::debug::DebugInstrumenter;
 use crate::graph::{CrateGraph, CrateId};
 use crate::hir_def::function::FuncMeta;
 use crate::node_interner::{FuncId, NodeInterner, TypeId};
-use crate::parser::ParserError;
+use crate::parser::{CfgOptions, ParserError};
+use crate::token::{CfgPredicate, FunctionAttributeKind};
 use crate::usage_tracker::UsageTracker;
 use crate::{Generics, Kind, ParsedModule, ResolvedGeneric, TypeVariable};
 use def_collector::dc_crate::CompilationError;
-use def_map::{CrateDefMap, FuzzingHarness, fully_qualified_module_path};
+use def_map::{BenchFunction, CrateDefMap, FuzzingHarness, fully_qualified_module_path};
 use fm::{FileId, FileManager};
 use iter_extended::vecmap;
 use noirc_errors::Location;
@@ -47,6 +48,10 @@ pub struct Context<'file_manager, 'parsed_files> {
     /// This is used to issue an error if a second `mod foo;` is declared to the same file.
     pub visited_files: BTreeMap<fm::FileId, Location>,
 
+    /// The locations of items which were left out of the compilation because of a
+    /// `#[cfg(...)]` attribute, along with the condition which didn't hold.
+    pub inactive_items: Vec<(Location, CfgPredicate)>,
+
     // A map of all parsed files.
     // Same as the file manager, we take ownership of the parsed files in the WASM context.
     // Parsed files is also read only.
@@ -65,6 +70,19 @@ pub enum FunctionNameMatch {
     Contains(Vec<String>),
 }
 
+impl FunctionNameMatch {
+    /// Returns true if the given fully qualified function name matches.
+    pub fn matches(&self, name: &str) -> bool {
+        match self {
+            FunctionNameMatch::Anything => true,
+            FunctionNameMatch::Exact(patterns) => patterns.iter().any(|pattern| name == pattern),
+            FunctionNameMatch::Contains(patterns) => {
+                patterns.iter().any(|pattern| name.contains(pattern))
+            }
+        }
+    }
+}
+
 impl Context<'_, '_> {
     pub fn new(file_manager: FileManager, parsed_files: ParsedFiles) -> Context<'static, 'static> {
         Context {
@@ -72,6 +90,7 @@ impl Context<'_, '_> {
             def_maps: BTreeMap::new(),
             usage_tracker: UsageTracker::default(),
             visited_files: BTreeMap::new(),
+            inactive_items: Vec::new(),
             crate_graph: CrateGraph::default(),
             file_manager: Cow::Owned(file_manager),
             debug_instrumenter: DebugInstrumenter::default(),
@@ -90,6 +109,7 @@ impl Context<'_, '_> {
             def_maps: BTreeMap::new(),
             usage_tracker: UsageTracker::default(),
             visited_files: BTreeMap::new(),
+            inactive_items: Vec::new(),
             crate_graph: CrateGraph::default(),
             file_manager: Cow::Borrowed(file_manager),
             debug_instrumenter: DebugInstrumenter::default(),
@@ -103,6 +123,21 @@ impl Context<'_, '_> {
         self.parsed_files.get(&file_id).expect("noir file wasn't parsed").clone()
     }
 
+    /// Returns the parsed module of a file of the given crate, without the items disabled by
+    /// `#[cfg(...)]` attributes for that crate.
+    pub(crate) fn parsed_file_results_for_crate(
+        &mut self,
+        file_id: FileId,
+        crate_id: CrateId,
+    ) -> (ParsedModule, Vec<ParserError>) {
+        let (mut ast, errors) = self.parsed_file_results(file_id);
+        let crate_data = &self.crate_graph[crate_id];
+        let cfg = CfgOptions { features: &crate_data.features, test: crate_data.cfg_test };
+        let inactive_items = ast.remove_inactive_items(cfg);
+        self.inactive_items.extend(inactive_items);
+        (ast, errors)
+    }
+
     /// Returns the CrateDefMap for a given CrateId.
     /// It is perfectly valid for the compiler to look
     /// up a CrateDefMap and it is not available.
@@ -179,27 +214,27 @@ impl Context<'_, '_> {
         crate_id: &CrateId,
         pattern: &FunctionNameMatch,
     ) -> Vec<(String, TestFunction)> {
-        let interner = &self.def_interner;
-        let def_map = self.def_map(crate_id).expect("The local crate should be analyzed already");
+        self.get_all_functions_in_crate_matching(
+            crate_id,
+            pattern,
+            TestFunction::from_attribute,
+            |test_function| test_function.id,
+        )
+    }
 
-        def_map
-            .get_all_test_functions(interner)
-            .filter_map(|test_function| {
-                let fully_qualified_name =
-                    self.fully_qualified_function_name(crate_id, &test_function.id);
-                match &pattern {
-                    FunctionNameMatch::Anything => Some((fully_qualified_name, test_function)),
-                    FunctionNameMatch::Exact(patterns) => patterns
-                        .iter()
-                        .any(|pattern| &fully_qualified_name == pattern)
-                        .then_some((fully_qualified_name, test_function)),
-                    FunctionNameMatch::Contains(patterns) => patterns
-                        .iter()
-                        .any(|pattern| fully_qualified_name.contains(pattern))
-                        .then_some((fully_qualified_name, test_function)),
-                }
-            })
-            .collect()
+    /// Returns a list of all functions in the current crate marked with `#[bench]`
+    /// whose names match the given pattern.
+    pub fn get_all_bench_functions_in_crate_matching(
+        &self,
+        crate_id: &CrateId,
+        pattern: &FunctionNameMatch,
+    ) -> Vec<(String, BenchFunction)> {
+        self.get_all_functions_in_crate_matching(
+            crate_id,
+            pattern,
+            BenchFunction::from_attribute,
+            |bench_function| bench_function.id,
+        )
     }
 
     /// Returns a list of all functions in the current crate marked with `#[fuzz]`
@@ -210,25 +245,32 @@ impl Context<'_, '_> {
         crate_id: &CrateId,
         pattern: &FunctionNameMatch,
     ) -> Vec<(String, FuzzingHarness)> {
+        self.get_all_functions_in_crate_matching(
+            crate_id,
+            pattern,
+            FuzzingHarness::from_attribute,
+            |fuzzing_harness| fuzzing_harness.id,
+        )
+    }
+
+    /// Returns the functions in the current crate accepted by `from_attribute`,
+    /// together with their fully qualified names, keeping those whose name matches the pattern.
+    fn get_all_functions_in_crate_matching<T>(
+        &self,
+        crate_id: &CrateId,
+        pattern: &FunctionNameMatch,
+        from_attribute: impl Fn(&NodeInterner, FuncId, &FunctionAttributeKind) -> Option<T> + Copy,
+        function_id: impl Fn(&T) -> FuncId,
+    ) -> Vec<(String, T)> {
         let interner = &self.def_interner;
         let def_map = self.def_map(crate_id).expect("The local crate should be analyzed already");
 
         def_map
-            .get_all_fuzzing_harnesses(interner)
-            .filter_map(|fuzzing_harness| {
+            .get_all_functions_with_attribute(interner, from_attribute)
+            .filter_map(|function| {
                 let fully_qualified_name =
-                    self.fully_qualified_function_name(crate_id, &fuzzing_harness.id);
-                match &pattern {
-                    FunctionNameMatch::Anything => Some((fully_qualified_name, fuzzing_harness)),
-                    FunctionNameMatch::Exact(patterns) => patterns
-                        .iter()
-                        .any(|pattern| &fully_qualified_name == pattern)
-                        .then_some((fully_qualified_name, fuzzing_harness)),
-                    FunctionNameMatch::Contains(patterns) => patterns
-                        .iter()
-                        .any(|pattern| fully_qualified_name.contains(pattern))
-                        .then_some((fully_qualified_name, fuzzing_harness)),
-                }
+                    self.fully_qualified_function_name(crate_id, &function_id(&function));
+                pattern.matches(&fully_qualified_name).then_some((fully_qualified_name, function))
             })
             .collect()
     }
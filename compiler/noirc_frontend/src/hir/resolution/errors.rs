@@ -86,6 +86,8 @@ pub enum ResolverError {
     WhileInConstrainedFn { location: Location },
     #[error("break/continue are only allowed within loops")]
     JumpOutsideLoop { is_break: bool, location: Location },
+    #[error("`return` is only allowed within functions")]
+    ReturnOutsideFunction { location: Location },
     #[error("Only `comptime` globals can be mutable")]
     MutableGlobal { location: Location },
     #[error("Globals must have a specified type")]
@@ -108,8 +110,16 @@ pub enum ResolverError {
     NoPredicatesAttributeOnUnconstrained { ident: Ident, location: Location },
     #[error("#[fold] attribute is only allowed on constrained functions")]
     FoldAttributeOnUnconstrained { ident: Ident, location: Location },
+    #[error("#[max_acir_opcodes] attribute is only allowed on constrained functions")]
+    MaxAcirOpcodesAttributeOnUnconstrained { ident: Ident, location: Location },
+    #[error("#[max_brillig_bytecode] attribute is only allowed on unconstrained functions")]
+    MaxBrilligBytecodeAttributeOnConstrained { ident: Ident, location: Location },
     #[error("The unquote operator '$' can only be used within a quote expression")]
     UnquoteUsedOutsideQuote { location: Location },
+    #[error(
+        "The `?` operator requires the `Try` and `FromResidual` traits from the standard library"
+    )]
+    TryOperatorWithoutStdlib { location: Location },
     #[error("Invalid syntax in macro call")]
     InvalidSyntaxInMacroCall { location: Location },
     #[error("Macros must be comptime functions")]
@@ -177,7 +187,7 @@ pub enum ResolverError {
     )]
     UnconstrainedTypeParameter { ident: Ident },
     #[error("Unreachable statement")]
-    UnreachableStatement { location: Location, break_or_continue_location: Location },
+    UnreachableStatement { location: Location, jump_location: Location },
 }
 
 impl ResolverError {
@@ -207,6 +217,7 @@ impl ResolverError {
             | ResolverError::LoopWithoutBreak { location }
             | ResolverError::WhileInConstrainedFn { location }
             | ResolverError::JumpOutsideLoop { location, .. }
+            | ResolverError::ReturnOutsideFunction { location }
             | ResolverError::MutableGlobal { location }
             | ResolverError::UnspecifiedGlobalType { pattern_location: location, .. }
             | ResolverError::UnevaluatedGlobalType { location }
@@ -215,6 +226,7 @@ impl ResolverError {
             | ResolverError::GlobalLargerThanKind { location, .. }
             | ResolverError::SelfReferentialType { location }
             | ResolverError::UnquoteUsedOutsideQuote { location }
+            | ResolverError::TryOperatorWithoutStdlib { location }
             | ResolverError::InvalidSyntaxInMacroCall { location }
             | ResolverError::MacroIsNotComptime { location }
             | ResolverError::NonFunctionInAnnotation { location }
@@ -235,6 +247,8 @@ impl ResolverError {
             | ResolverError::NonU32Index { location }
             | ResolverError::NoPredicatesAttributeOnUnconstrained { location, .. }
             | ResolverError::FoldAttributeOnUnconstrained { location, .. }
+            | ResolverError::MaxAcirOpcodesAttributeOnUnconstrained { location, .. }
+            | ResolverError::MaxBrilligBytecodeAttributeOnConstrained { location, .. }
             | ResolverError::OracleMarkedAsConstrained { location, .. }
             | ResolverError::LowLevelFunctionOutsideOfStdlib { location }
             | ResolverError::UnreachableStatement { location, .. } => *location,
@@ -494,6 +508,13 @@ impl<'a> From<&'a ResolverError> for Diagnostic {
                     *location,
                 )
             },
+            ResolverError::ReturnOutsideFunction { location } => {
+                Diagnostic::simple_error(
+                    "`return` is only allowed within functions".into(),
+                    "This is not the body of a function or lambda".into(),
+                    *location,
+                )
+            },
             ResolverError::MutableGlobal { location } => {
                 Diagnostic::simple_error(
                     "Only `comptime` globals may be mutable".into(),
@@ -565,6 +586,26 @@ impl<'a> From<&'a ResolverError> for Diagnostic {
                 diag.add_note("The `#[fold]` attribute specifies whether a constrained function should be treated as a separate circuit rather than inlined into the program entry point".to_owned());
                 diag
             }
+            ResolverError::MaxAcirOpcodesAttributeOnUnconstrained { ident, location } => {
+                let mut diag = Diagnostic::simple_error(
+                    format!("misplaced #[max_acir_opcodes] attribute on unconstrained function {ident}. Only allowed on constrained functions"),
+                    "misplaced #[max_acir_opcodes] attribute".to_string(),
+                    *location,
+                );
+
+                diag.add_note("Unconstrained functions compile to Brillig; use `#[max_brillig_bytecode]` to limit their size".to_owned());
+                diag
+            }
+            ResolverError::MaxBrilligBytecodeAttributeOnConstrained { ident, location } => {
+                let mut diag = Diagnostic::simple_error(
+                    format!("misplaced #[max_brillig_bytecode] attribute on constrained function {ident}. Only allowed on unconstrained functions"),
+                    "misplaced #[max_brillig_bytecode] attribute".to_string(),
+                    *location,
+                );
+
+                diag.add_note("Constrained functions compile to ACIR; use `#[max_acir_opcodes]` to limit their size".to_owned());
+                diag
+            }
             ResolverError::UnquoteUsedOutsideQuote { location } => {
                 Diagnostic::simple_error(
                     "The unquote operator '$' can only be used within a quote expression".into(),
@@ -572,6 +613,13 @@ impl<'a> From<&'a ResolverError> for Diagnostic {
                     *location,
                 )
             },
+            ResolverError::TryOperatorWithoutStdlib { location } => {
+                Diagnostic::simple_error(
+                    "The `?` operator requires the `Try` and `FromResidual` traits from the standard library".into(),
+                    "The standard library is not available".into(),
+                    *location,
+                )
+            },
             ResolverError::InvalidSyntaxInMacroCall { location } => {
                 Diagnostic::simple_error(
                     "Invalid syntax in macro call".into(),
@@ -739,13 +787,13 @@ impl<'a> From<&'a ResolverError> for Diagnostic {
                     ident.location(),
                 )
             }
-            ResolverError::UnreachableStatement { location, break_or_continue_location} => {
+            ResolverError::UnreachableStatement { location, jump_location} => {
                 let mut diagnostic = Diagnostic::simple_warning(
                     "Unreachable statement".to_string(),
                     "Unreachable statement".to_string(),
                     *location,
                 );
-                diagnostic.add_secondary("Any code following this expression is unreachable".to_string(), *break_or_continue_location);
+                diagnostic.add_secondary("Any code following this expression is unreachable".to_string(), *jump_location);
                 diagnostic
             }
         }
@@ -18,6 +18,8 @@ pub enum HirStatement {
     While(ExprId, ExprId),
     Break,
     Continue,
+    /// An early return of the given value out of the enclosing function or lambda
+    Return(ExprId),
     Expression(ExprId),
     Semi(ExprId),
     Comptime(StmtId),
@@ -106,6 +106,8 @@ pub enum BorrowedToken<'input> {
     Semicolon,
     /// !
     Bang,
+    /// ?
+    Question,
     /// $
     DollarSign,
     /// =
@@ -236,6 +238,8 @@ pub enum Token {
     Semicolon,
     /// !
     Bang,
+    /// ?
+    Question,
     /// =
     Assign,
     /// $
@@ -318,6 +322,7 @@ pub fn token_to_borrowed_token(token: &Token) -> BorrowedToken<'_> {
         Token::Semicolon => BorrowedToken::Semicolon,
         Token::Assign => BorrowedToken::Assign,
         Token::Bang => BorrowedToken::Bang,
+        Token::Question => BorrowedToken::Question,
         Token::DollarSign => BorrowedToken::DollarSign,
         Token::LogicalAnd => BorrowedToken::LogicalAnd,
         Token::EOF => BorrowedToken::EOF,
@@ -555,6 +560,7 @@ impl fmt::Display for Token {
             Token::Semicolon => write!(f, ";"),
             Token::Assign => write!(f, "="),
             Token::Bang => write!(f, "!"),
+            Token::Question => write!(f, "?"),
             Token::DollarSign => write!(f, "$"),
             Token::LogicalAnd => write!(f, "&&"),
             Token::EOF => write!(f, "end of input"),
@@ -847,6 +853,16 @@ impl Attributes {
         })
     }
 
+    pub fn is_bench_function(&self) -> bool {
+        self.as_bench_function().is_some()
+    }
+
+    pub fn as_bench_function(&self) -> Option<Location> {
+        self.function().and_then(|attr| {
+            matches!(attr.kind, FunctionAttributeKind::Bench).then_some(attr.location)
+        })
+    }
+
     /// True if these attributes mean the given function is an entry point function if it was
     /// defined within a contract. Note that this does not check if the function is actually part
     /// of a contract.
@@ -854,6 +870,7 @@ impl Attributes {
         !self.has_contract_library_method()
             && !self.is_test_function()
             && !self.is_fuzzing_harness()
+            && !self.is_bench_function()
     }
 
     /// Returns note if a deprecated secondary attribute is found
@@ -864,6 +881,14 @@ impl Attributes {
         })
     }
 
+    /// Returns the conditions which all need to hold for this item to be compiled
+    pub fn cfg_predicates(&self) -> impl Iterator<Item = &CfgPredicate> {
+        self.secondary.iter().filter_map(|attr| match &attr.kind {
+            SecondaryAttributeKind::Cfg(predicate) => Some(predicate),
+            _ => None,
+        })
+    }
+
     pub fn get_field_attribute(&self) -> Option<String> {
         for secondary in &self.secondary {
             if let SecondaryAttributeKind::Field(field) = &secondary.kind {
@@ -942,6 +967,7 @@ pub enum FunctionAttributeKind {
     NoPredicates,
     InlineAlways,
     FuzzingHarness(FuzzingScope),
+    Bench,
 }
 
 impl FunctionAttributeKind {
@@ -1002,6 +1028,7 @@ impl FunctionAttributeKind {
             FunctionAttributeKind::NoPredicates => "no_predicates",
             FunctionAttributeKind::InlineAlways => "inline_always",
             FunctionAttributeKind::FuzzingHarness(_) => "fuzz",
+            FunctionAttributeKind::Bench => "bench",
         }
     }
 }
@@ -1023,6 +1050,25 @@ impl fmt::Display for FunctionAttributeKind {
             FunctionAttributeKind::NoPredicates => write!(f, "#[no_predicates]"),
             FunctionAttributeKind::InlineAlways => write!(f, "#[inline_always]"),
             FunctionAttributeKind::FuzzingHarness(scope) => write!(f, "#[fuzz{scope}]"),
+            FunctionAttributeKind::Bench => write!(f, "#[bench]"),
+        }
+    }
+}
+
+/// The condition of a `#[cfg(...)]` attribute.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub enum CfgPredicate {
+    /// Holds if the given feature of the crate is enabled: `feature = "foo"`
+    Feature(String),
+    /// Holds if the crate is compiled to run its tests: `test`
+    Test,
+}
+
+impl fmt::Display for CfgPredicate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CfgPredicate::Feature(feature) => write!(f, "feature = {feature:?}"),
+            CfgPredicate::Test => write!(f, "test"),
         }
     }
 }
@@ -1046,6 +1092,9 @@ pub enum SecondaryAttributeKind {
     Export,
     Field(String),
 
+    /// Only compile the item if the condition holds: `#[cfg(feature = "foo")]` or `#[cfg(test)]`
+    Cfg(CfgPredicate),
+
     /// A custom tag attribute: `#['foo]`
     Tag(String),
 
@@ -1064,6 +1113,12 @@ pub enum SecondaryAttributeKind {
 
     /// Allow chosen warnings to happen so they are silenced.
     Allow(String),
+
+    /// The maximum number of ACIR opcodes the function may compile to: `#[max_acir_opcodes(N)]`
+    MaxAcirOpcodes(u64),
+
+    /// The maximum number of Brillig opcodes the function may compile to: `#[max_brillig_bytecode(N)]`
+    MaxBrilligBytecode(u64),
 }
 
 impl SecondaryAttributeKind {
@@ -1089,10 +1144,15 @@ impl SecondaryAttributeKind {
             SecondaryAttributeKind::ContractLibraryMethod => "contract_library_method".to_string(),
             SecondaryAttributeKind::Export => "export".to_string(),
             SecondaryAttributeKind::Field(k) => format!("field({k})"),
+            SecondaryAttributeKind::Cfg(predicate) => format!("cfg({predicate})"),
             SecondaryAttributeKind::Abi(k) => format!("abi({k})"),
             SecondaryAttributeKind::Varargs => "varargs".to_string(),
             SecondaryAttributeKind::UseCallersScope => "use_callers_scope".to_string(),
             SecondaryAttributeKind::Allow(k) => format!("allow({k})"),
+            SecondaryAttributeKind::MaxAcirOpcodes(budget) => format!("max_acir_opcodes({budget})"),
+            SecondaryAttributeKind::MaxBrilligBytecode(budget) => {
+                format!("max_brillig_bytecode({budget})")
+            }
         }
     }
 }
@@ -54,6 +54,8 @@ pub enum Expression {
     Drop(Box<Expression>),
     Break,
     Continue,
+    /// An early return from the current function
+    Return(Box<Expression>),
 }
 
 impl Expression {
@@ -131,7 +133,8 @@ impl Expression {
             | Expression::Semi(_)
             | Expression::Drop(_)
             | Expression::Break
-            | Expression::Continue => None,
+            | Expression::Continue
+            | Expression::Return(_) => None,
         }
     }
 
@@ -193,7 +196,62 @@ impl Expression {
             | Expression::Clone(_)
             | Expression::Drop(_)
             | Expression::Break
-            | Expression::Continue => false,
+            | Expression::Continue
+            | Expression::Return(_) => false,
+        }
+    }
+
+    /// Check if the expression contains a `return` from the function it is in.
+    pub fn contains_return(&self) -> bool {
+        let any = |xs: &[Expression]| xs.iter().any(Expression::contains_return);
+
+        match self {
+            Expression::Return(_) => true,
+            Expression::Literal(literal) => match literal {
+                Literal::Array(literal) | Literal::Slice(literal) => any(&literal.contents),
+                Literal::FmtStr(_, _, expr) => expr.contains_return(),
+                Literal::Integer(..) | Literal::Bool(_) | Literal::Unit | Literal::Str(_) => false,
+            },
+            Expression::Block(xs) | Expression::Tuple(xs) => any(xs),
+            Expression::Unary(unary) => unary.rhs.contains_return(),
+            Expression::Binary(binary) => {
+                binary.lhs.contains_return() || binary.rhs.contains_return()
+            }
+            Expression::Index(index) => {
+                index.collection.contains_return() || index.index.contains_return()
+            }
+            Expression::Cast(cast) => cast.lhs.contains_return(),
+            Expression::For(for_) => {
+                for_.start_range.contains_return()
+                    || for_.end_range.contains_return()
+                    || for_.block.contains_return()
+            }
+            Expression::While(while_) => {
+                while_.condition.contains_return() || while_.body.contains_return()
+            }
+            Expression::If(if_) => {
+                if_.condition.contains_return()
+                    || if_.consequence.contains_return()
+                    || if_.alternative.as_ref().is_some_and(|x| x.contains_return())
+            }
+            Expression::Match(m) => {
+                m.cases.iter().any(|c| c.branch.contains_return())
+                    || m.default_case.as_ref().is_some_and(|x| x.contains_return())
+            }
+            Expression::Call(call) => call.func.contains_return() || any(&call.arguments),
+            Expression::Let(let_) => let_.expression.contains_return(),
+            Expression::Constrain(expr, _, msg) => {
+                expr.contains_return() || msg.as_ref().is_some_and(|msg| msg.0.contains_return())
+            }
+            Expression::Assign(assign) => {
+                assign.lvalue.contains_return() || assign.expression.contains_return()
+            }
+            Expression::Loop(x)
+            | Expression::ExtractTupleField(x, _)
+            | Expression::Semi(x)
+            | Expression::Clone(x)
+            | Expression::Drop(x) => x.contains_return(),
+            Expression::Ident(_) | Expression::Break | Expression::Continue => false,
         }
     }
 }
@@ -389,6 +447,20 @@ pub enum LValue {
     Dereference { reference: Box<LValue>, element_type: Type },
 }
 
+impl LValue {
+    /// Check if the lvalue contains a `return` from the function it is in.
+    pub fn contains_return(&self) -> bool {
+        match self {
+            LValue::Ident(_) => false,
+            LValue::Index { array, index, .. } => {
+                array.contains_return() || index.contains_return()
+            }
+            LValue::MemberAccess { object, .. } => object.contains_return(),
+            LValue::Dereference { reference, .. } => reference.contains_return(),
+        }
+    }
+}
+
 pub type Parameters =
     Vec<(LocalId, /*mutable:*/ bool, /*name:*/ String, Type, Visibility)>;
 
@@ -518,8 +518,8 @@ impl<'interner> Monomorphizer<'interner> {
                 }
             },
             HirExpression::Literal(HirLiteral::Unit) => ast::Expression::Block(vec![]),
-            HirExpression::Block(block) => self.block(block.statements)?,
-            HirExpression::Unsafe(block) => self.block(block.statements)?,
+            HirExpression::Block(block) => self.block(block.statements, expr)?,
+            HirExpression::Unsafe(block) => self.block(block.statements, expr)?,
 
             HirExpression::Prefix(prefix) => {
                 let rhs = self.expr(prefix.rhs)?;
@@ -789,6 +789,9 @@ impl<'interner> Monomorphizer<'interner> {
             }
             HirStatement::Break => Ok(ast::Expression::Break),
             HirStatement::Continue => Ok(ast::Expression::Continue),
+            HirStatement::Return(expr) => {
+                self.expr(expr).map(|expr| ast::Expression::Return(Box::new(expr)))
+            }
             HirStatement::Error => unreachable!(),
 
             // All `comptime` statements & expressions should be removed before runtime.
@@ -910,9 +913,25 @@ impl<'interner> Monomorphizer<'interner> {
     fn block(
         &mut self,
         statement_ids: Vec<StmtId>,
+        block: ExprId,
     ) -> Result<ast::Expression, MonomorphizationError> {
-        let stmts = try_vecmap(statement_ids, |id| self.statement(id));
-        stmts.map(ast::Expression::Block)
+        let ends_in_return = statement_ids
+            .last()
+            .is_some_and(|id| matches!(self.interner.statement(id), HirStatement::Return(_)));
+
+        let mut stmts = try_vecmap(statement_ids, |id| self.statement(id))?;
+
+        // A block ending in a `return` never produces its value, but it still needs a value
+        // of its type to be well-typed, for example in `if c { return 1; } else { 2 }`.
+        if ends_in_return {
+            let location = self.interner.expr_location(&block);
+            let typ = Self::convert_type(&self.interner.id_type(block), location)?;
+            if typ != ast::Type::Unit {
+                stmts.push(self.zeroed_value_of_type(&typ, location));
+            }
+        }
+
+        Ok(ast::Expression::Block(stmts))
     }
 
     fn unpack_pattern(
@@ -198,6 +198,10 @@ impl AstPrinter {
             }
             Expression::Break => write!(f, "break"),
             Expression::Continue => write!(f, "continue"),
+            Expression::Return(expr) => {
+                write!(f, "return ")?;
+                self.print_expr(expr, f)
+            }
             Expression::Clone(expr) => {
                 self.print_expr(expr, f)?;
                 if self.show_clone_and_drop {
@@ -171,6 +171,12 @@ pub struct NodeInterner {
     /// Holds the trait ids of the traits used for prefix operator overloading
     prefix_operator_traits: HashMap<UnaryOp, TraitId>,
 
+    /// The `Try` trait which the `?` operator desugars to
+    try_trait: Option<TraitId>,
+
+    /// The `FromResidual` trait which the `?` operator uses to convert the value it returns early
+    from_residual_trait: Option<TraitId>,
+
     /// The `Ordering` type is a semi-builtin type that is the result of the comparison traits.
     ordering_type: Option<Type>,
 
@@ -698,6 +704,8 @@ impl Default for NodeInterner {
             selected_trait_implementations: HashMap::default(),
             infix_operator_traits: HashMap::default(),
             prefix_operator_traits: HashMap::default(),
+            try_trait: None,
+            from_residual_trait: None,
             ordering_type: None,
             instantiation_bindings: HashMap::default(),
             field_indices: HashMap::default(),
@@ -977,6 +985,11 @@ impl NodeInterner {
         *func = hir_func;
     }
 
+    /// Returns the ids of all functions whose metadata has been interned.
+    pub fn function_ids(&self) -> impl Iterator<Item = FuncId> + '_ {
+        self.func_meta.keys().copied()
+    }
+
     pub fn find_function(&self, function_name: &str) -> Option<FuncId> {
         self.func_meta
             .iter()
@@ -2036,6 +2049,23 @@ impl NodeInterner {
         self.prefix_operator_traits.insert(operator, trait_id);
     }
 
+    /// Remember the given trait if it is one of the traits used by the `?` operator
+    /// (Try or FromResidual).
+    pub fn try_add_try_operator_trait(&mut self, trait_id: TraitId) {
+        let the_trait = self.get_trait(trait_id);
+
+        match the_trait.name.as_str() {
+            "Try" => self.try_trait = Some(trait_id),
+            "FromResidual" => self.from_residual_trait = Some(trait_id),
+            _ => (),
+        }
+    }
+
+    /// Retrieves the `Try` and `FromResidual` trait ids, if the stdlib defining them is present.
+    pub fn get_try_operator_traits(&self) -> Option<(TraitId, TraitId)> {
+        Some((self.try_trait?, self.from_residual_trait?))
+    }
+
     pub fn is_operator_trait(&self, trait_id: TraitId) -> bool {
         self.infix_operator_traits.values().any(|id| *id == trait_id)
             || self.prefix_operator_traits.values().any(|id| *id == trait_id)
@@ -276,6 +276,7 @@ impl LastUseContext {
             Expression::Drop(_) => unreachable!("last_uses is called before drops are inserted"),
             Expression::Break => (),
             Expression::Continue => (),
+            Expression::Return(expr) => self.track_variables_in_expression(expr),
         }
     }
 
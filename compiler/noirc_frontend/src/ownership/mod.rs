@@ -123,6 +123,7 @@ impl Context {
             Expression::Drop(_) => (),
             Expression::Break => (),
             Expression::Continue => (),
+            Expression::Return(expr) => self.handle_expression(expr),
         }
     }
 
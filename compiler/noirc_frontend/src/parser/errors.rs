@@ -66,8 +66,6 @@ pub enum ParserErrorReason {
         "Invalid type expression: '{0}'. Only unsigned integer constants up to `u32`, globals, generics, +, -, *, /, and % may be used in this context."
     )]
     InvalidTypeExpression(Expression),
-    #[error("Early 'return' is unsupported")]
-    EarlyReturn,
     #[error("Visibility is ignored on a trait method")]
     TraitVisibilityIgnored,
     #[error("Visibility is ignored on a trait impl method")]
@@ -97,6 +95,8 @@ pub enum ParserErrorReason {
     WrongNumberOfAttributeArguments { name: String, min: usize, max: usize, found: usize },
     #[error("The `deprecated` attribute expects a string argument")]
     DeprecatedAttributeExpectsAStringArgument,
+    #[error("The `{name}` attribute expects a non-negative integer argument")]
+    AttributeExpectsAnIntegerArgument { name: String },
     #[error("Unsafe block must have a safety comment above it")]
     MissingSafetyComment,
     #[error("Missing parameters for function definition")]
@@ -111,6 +111,10 @@ pub enum ParserErrorReason {
     TraitBoundsNotAllowedHere,
     #[error("Missing type for associated constant")]
     MissingTypeForAssociatedConstant,
+    #[error("`#[cfg(...)]` is only supported on items and on functions in impls")]
+    CfgAttributeNotAllowedHere,
+    #[error("Attributes are not allowed here")]
+    AttributeNotAllowedHere,
 }
 
 /// Represents a parsing error, or a parsing error in the making.
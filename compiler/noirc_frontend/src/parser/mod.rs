@@ -11,18 +11,21 @@ mod labels;
 #[allow(clippy::module_inception)]
 mod parser;
 
+use std::collections::BTreeSet;
+
 use crate::ast::{
     Documented, Ident, ImportStatement, ItemVisibility, LetStatement, ModuleDeclaration,
-    NoirEnumeration, NoirFunction, NoirStruct, NoirTrait, NoirTraitImpl, NoirTypeAlias, TypeImpl,
-    UseTree,
+    NoirEnumeration, NoirFunction, NoirStruct, NoirTrait, NoirTraitImpl, NoirTypeAlias,
+    TraitImplItemKind, TypeImpl, UseTree,
 };
-use crate::token::SecondaryAttribute;
+use crate::token::{CfgPredicate, SecondaryAttribute};
 
 pub use errors::ParserError;
 pub use errors::ParserErrorReason;
 use noirc_errors::Location;
 pub use parser::{
-    Parser, StatementOrExpressionOrLValue, parse_program, parse_program_with_dummy_file,
+    Parser, StatementOrExpressionOrLValue, parse_expression_with_dummy_file, parse_program,
+    parse_program_with_dummy_file,
 };
 
 #[derive(Clone, Default)]
@@ -140,6 +143,76 @@ impl ParsedModule {
 
         module
     }
+
+    /// Removes every item gated behind a `#[cfg(...)]` attribute whose condition doesn't hold
+    /// under `cfg`, including methods in impls and items in inline submodules.
+    ///
+    /// Returns the location of each removed item along with the condition that disabled it.
+    pub fn remove_inactive_items(&mut self, cfg: CfgOptions) -> Vec<(Location, CfgPredicate)> {
+        let mut removed = Vec::new();
+
+        self.items.retain_mut(|item| {
+            if let Some(predicate) = cfg.failing_predicate(item.cfg.iter()) {
+                removed.push((item.location, predicate));
+                return false;
+            }
+
+            match &mut item.kind {
+                ItemKind::Impl(type_impl) => type_impl.methods.retain(|(method, location)| {
+                    let predicates = method.item.attributes().cfg_predicates();
+                    let Some(predicate) = cfg.failing_predicate(predicates) else {
+                        return true;
+                    };
+                    removed.push((*location, predicate));
+                    false
+                }),
+                ItemKind::TraitImpl(trait_impl) => trait_impl.items.retain(|impl_item| {
+                    let TraitImplItemKind::Function(function) = &impl_item.item.kind else {
+                        return true;
+                    };
+                    let predicates = function.attributes().cfg_predicates();
+                    let Some(predicate) = cfg.failing_predicate(predicates) else {
+                        return true;
+                    };
+                    removed.push((impl_item.item.location, predicate));
+                    false
+                }),
+                ItemKind::Submodules(submodule) => {
+                    removed.extend(submodule.contents.remove_inactive_items(cfg));
+                }
+                _ => (),
+            }
+            true
+        });
+
+        removed
+    }
+}
+
+/// The configuration of a crate that `#[cfg(...)]` attributes are checked against.
+#[derive(Debug, Clone, Copy)]
+pub struct CfgOptions<'a> {
+    /// The features enabled on the crate.
+    pub features: &'a BTreeSet<String>,
+    /// Whether the crate is compiled to run its tests.
+    pub test: bool,
+}
+
+impl CfgOptions<'_> {
+    fn holds(&self, predicate: &CfgPredicate) -> bool {
+        match predicate {
+            CfgPredicate::Feature(feature) => self.features.contains(feature),
+            CfgPredicate::Test => self.test,
+        }
+    }
+
+    /// Returns the first of the given conditions which doesn't hold, if any.
+    fn failing_predicate<'a>(
+        &self,
+        mut predicates: impl Iterator<Item = &'a CfgPredicate>,
+    ) -> Option<CfgPredicate> {
+        predicates.find(|predicate| !self.holds(predicate)).cloned()
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -147,6 +220,9 @@ pub struct Item {
     pub kind: ItemKind,
     pub location: Location,
     pub doc_comments: Vec<String>,
+    /// The conditions which all need to hold for this item to be compiled,
+    /// taken from its `#[cfg(...)]` attributes.
+    pub cfg: Vec<CfgPredicate>,
 }
 
 #[derive(Clone, Debug)]
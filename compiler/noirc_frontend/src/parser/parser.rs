@@ -4,7 +4,7 @@ use modifiers::Modifiers;
 use noirc_errors::{Location, Span};
 
 use crate::{
-    ast::{Ident, ItemVisibility},
+    ast::{Expression, Ident, ItemVisibility},
     lexer::{Lexer, lexer::LocatedTokenResult},
     node_interner::ExprId,
     token::{FmtStrFragment, Keyword, LocatedToken, Token, TokenKind, Tokens},
@@ -61,6 +61,14 @@ pub fn parse_program_with_dummy_file(source_program: &str) -> (ParsedModule, Vec
     parse_program(source_program, FileId::dummy())
 }
 
+/// Parses a single expression, such as one typed into the debugger.
+///
+/// Fails if the source isn't exactly one expression.
+pub fn parse_expression_with_dummy_file(source: &str) -> Result<Expression, Vec<ParserError>> {
+    let parser = Parser::for_str_with_dummy_file(source);
+    parser.parse_result(Parser::parse_expression_or_error).map(|(expression, _warnings)| expression)
+}
+
 enum TokenStream<'a> {
     Lexer(Lexer<'a>),
     Tokens(Tokens),
@@ -5,7 +5,7 @@ use crate::lexer::errors::LexerErrorKind;
 use crate::parser::ParserErrorReason;
 use crate::parser::labels::ParsingRuleLabel;
 use crate::token::{
-    Attribute, FunctionAttribute, FunctionAttributeKind, FuzzingScope, MetaAttribute,
+    Attribute, CfgPredicate, FunctionAttribute, FunctionAttributeKind, FuzzingScope, MetaAttribute,
     MetaAttributeName, SecondaryAttribute, SecondaryAttributeKind, TestScope, Token,
 };
 
@@ -60,10 +60,13 @@ impl Parser<'_> {
     ///     | 'fuzz' '(' 'only_fail_with' '=' string ')'
     ///     | 'fuzz' '(' 'should_fail' ')'
     ///     | 'fuzz' '(' 'should_fail_with' '=' string ')'
+    ///     | 'bench'
     ///
     /// SecondaryAttribute
     ///     = 'abi' '(' AttributeValue ')'
     ///     | 'allow' '(' AttributeValue ')'
+    ///     | 'cfg' '(' 'feature' '=' string ')'
+    ///     | 'cfg' '(' 'test' ')'
     ///     | 'deprecated'
     ///     | 'deprecated' '(' string ')'
     ///     | 'contract_library_method'
@@ -106,6 +109,38 @@ impl Parser<'_> {
             .collect()
     }
 
+    /// `#[cfg(...)]` is only evaluated on items and on functions in impls, so anywhere else it's
+    /// reported instead of being silently ignored.
+    pub(super) fn reject_cfg_attributes(
+        &mut self,
+        attributes: Vec<(Attribute, Location)>,
+    ) -> Vec<(Attribute, Location)> {
+        attributes
+            .into_iter()
+            .filter(|(attribute, location)| {
+                let is_cfg = matches!(
+                    attribute,
+                    Attribute::Secondary(SecondaryAttribute {
+                        kind: SecondaryAttributeKind::Cfg(_),
+                        ..
+                    })
+                );
+                if is_cfg {
+                    self.push_error(ParserErrorReason::CfgAttributeNotAllowedHere, *location);
+                }
+                !is_cfg
+            })
+            .collect()
+    }
+
+    /// Parses attributes in a position that doesn't accept any, reporting each of them.
+    pub(super) fn parse_disallowed_attributes(&mut self) {
+        let attributes = self.parse_attributes();
+        for (_, location) in self.reject_cfg_attributes(attributes) {
+            self.push_error(ParserErrorReason::AttributeNotAllowedHere, location);
+        }
+    }
+
     fn parse_tag_attribute(&mut self, start_location: Location) -> Attribute {
         let mut contents = String::new();
 
@@ -151,6 +186,9 @@ impl Parser<'_> {
                     // The fuzz attribute is a secondary attribute that has `a = b` in its syntax
                     // (`only_fail_with = "..."``) or (`should_fail_with = "..."``) so we parse it differently.
                     self.parse_fuzz_attribute(start_location)
+                } else if ident.as_str() == "cfg" {
+                    // The cfg attribute also has `a = b` in its syntax (`feature = "..."`)
+                    self.parse_cfg_attribute(start_location)
                 } else {
                     // Every other attribute has the form `name(arg1, arg2, .., argN)`
                     self.parse_ident_attribute_other_than_test_and_fuzz(ident, start_location)
@@ -202,6 +240,12 @@ impl Parser<'_> {
                 let attr = SecondaryAttribute { kind, location };
                 Attribute::Secondary(attr)
             }),
+            "bench" => {
+                let kind = FunctionAttributeKind::Bench;
+                let attr = FunctionAttribute { kind, location };
+                let attr = Attribute::Function(attr);
+                self.parse_no_args_attribute(ident, arguments, attr)
+            }
             "builtin" => {
                 self.parse_single_name_attribute(ident, arguments, start_location, |name| {
                     let kind = FunctionAttributeKind::Builtin(name);
@@ -250,6 +294,20 @@ impl Parser<'_> {
                 let attr = Attribute::Function(attr);
                 self.parse_no_args_attribute(ident, arguments, attr)
             }
+            "max_acir_opcodes" => {
+                let kind = self.parse_opcode_budget_attribute(ident, arguments, |budget| {
+                    SecondaryAttributeKind::MaxAcirOpcodes(budget)
+                });
+                let attr = SecondaryAttribute { kind, location };
+                Attribute::Secondary(attr)
+            }
+            "max_brillig_bytecode" => {
+                let kind = self.parse_opcode_budget_attribute(ident, arguments, |budget| {
+                    SecondaryAttributeKind::MaxBrilligBytecode(budget)
+                });
+                let attr = SecondaryAttribute { kind, location };
+                Attribute::Secondary(attr)
+            }
             "no_predicates" => {
                 let kind = FunctionAttributeKind::NoPredicates;
                 let attr = FunctionAttribute { kind, location };
@@ -320,6 +378,46 @@ impl Parser<'_> {
         SecondaryAttributeKind::Deprecated(Some(message))
     }
 
+    /// Parses the argument of `#[max_acir_opcodes(N)]` or `#[max_brillig_bytecode(N)]`.
+    /// If it's missing or malformed an error is reported and the budget is left unlimited.
+    fn parse_opcode_budget_attribute<F>(
+        &mut self,
+        ident: &Ident,
+        mut arguments: Vec<Expression>,
+        f: F,
+    ) -> SecondaryAttributeKind
+    where
+        F: FnOnce(u64) -> SecondaryAttributeKind,
+    {
+        if arguments.len() != 1 {
+            self.push_error(
+                ParserErrorReason::WrongNumberOfAttributeArguments {
+                    name: ident.to_string(),
+                    min: 1,
+                    max: 1,
+                    found: arguments.len(),
+                },
+                ident.location(),
+            );
+            return f(u64::MAX);
+        }
+
+        let argument = arguments.remove(0);
+        let budget = match argument.kind {
+            ExpressionKind::Literal(Literal::Integer(value)) => value.try_to_unsigned::<u64>(),
+            _ => None,
+        };
+        let Some(budget) = budget else {
+            self.push_error(
+                ParserErrorReason::AttributeExpectsAnIntegerArgument { name: ident.to_string() },
+                argument.location,
+            );
+            return f(u64::MAX);
+        };
+
+        f(budget)
+    }
+
     fn parse_test_attribute(&mut self, start_location: Location) -> Attribute {
         let scope = if self.eat_left_paren() {
             let scope = if let Some(ident) = self.eat_ident() {
@@ -421,6 +519,43 @@ impl Parser<'_> {
         Attribute::Function(attr)
     }
 
+    fn parse_cfg_attribute(&mut self, start_location: Location) -> Attribute {
+        let predicate = if self.eat_left_paren() {
+            let predicate = match self.eat_ident() {
+                Some(ident) if ident.as_str() == "feature" => {
+                    self.eat_or_error(Token::Assign);
+                    self.eat_str().map(CfgPredicate::Feature)
+                }
+                Some(ident) if ident.as_str() == "test" => Some(CfgPredicate::Test),
+                _ => None,
+            };
+            self.eat_or_error(Token::RightParen);
+            predicate
+        } else {
+            None
+        };
+
+        self.skip_until_right_bracket();
+
+        let predicate = if let Some(predicate) = predicate {
+            predicate
+        } else {
+            self.errors.push(
+                LexerErrorKind::MalformedCfgAttribute {
+                    location: self.location_since(start_location),
+                }
+                .into(),
+            );
+            // A feature which can't be enabled, so that the item is never compiled.
+            CfgPredicate::Feature(String::new())
+        };
+
+        let location = self.location_since(start_location);
+        let kind = SecondaryAttributeKind::Cfg(predicate);
+        let attr = SecondaryAttribute { kind, location };
+        Attribute::Secondary(attr)
+    }
+
     fn parse_single_name_attribute<F>(
         &mut self,
         ident: &Ident,
@@ -508,7 +643,9 @@ impl Parser<'_> {
 mod tests {
     use crate::{
         parser::{Parser, parser::tests::expect_no_errors},
-        token::{Attribute, FunctionAttributeKind, SecondaryAttributeKind, TestScope},
+        token::{
+            Attribute, CfgPredicate, FunctionAttributeKind, SecondaryAttributeKind, TestScope,
+        },
     };
 
     fn parse_inner_secondary_attribute_no_errors(src: &str, expected: SecondaryAttributeKind) {
@@ -653,6 +790,13 @@ mod tests {
         parse_function_attribute_no_errors(src, expected);
     }
 
+    #[test]
+    fn parses_attribute_bench() {
+        let src = "#[bench]";
+        let expected = FunctionAttributeKind::Bench;
+        parse_function_attribute_no_errors(src, expected);
+    }
+
     #[test]
     fn parses_attribute_field() {
         let src = "#[field(bn254)]";
@@ -674,6 +818,51 @@ mod tests {
         parse_secondary_attribute_no_errors(src, expected);
     }
 
+    #[test]
+    fn parses_attribute_max_acir_opcodes() {
+        let src = "#[max_acir_opcodes(1_000)]";
+        let expected = SecondaryAttributeKind::MaxAcirOpcodes(1000);
+        parse_secondary_attribute_no_errors(src, expected);
+    }
+
+    #[test]
+    fn parses_attribute_max_brillig_bytecode() {
+        let src = "#[max_brillig_bytecode(250)]";
+        let expected = SecondaryAttributeKind::MaxBrilligBytecode(250);
+        parse_secondary_attribute_no_errors(src, expected);
+    }
+
+    #[test]
+    fn errors_on_opcode_budget_attribute_without_integer() {
+        let src = "#[max_acir_opcodes(many)]";
+        let mut parser = Parser::for_str_with_dummy_file(src);
+        let _ = parser.parse_attribute().unwrap();
+        assert_eq!(parser.errors.len(), 1);
+    }
+
+    #[test]
+    fn parses_attribute_cfg_feature() {
+        let src = "#[cfg(feature = \"extra_curves\")]";
+        let expected =
+            SecondaryAttributeKind::Cfg(CfgPredicate::Feature("extra_curves".to_string()));
+        parse_secondary_attribute_no_errors(src, expected);
+    }
+
+    #[test]
+    fn parses_attribute_cfg_test() {
+        let src = "#[cfg(test)]";
+        let expected = SecondaryAttributeKind::Cfg(CfgPredicate::Test);
+        parse_secondary_attribute_no_errors(src, expected);
+    }
+
+    #[test]
+    fn errors_on_malformed_cfg_attribute() {
+        let src = "#[cfg(debug_assertions)]";
+        let mut parser = Parser::for_str_with_dummy_file(src);
+        let _ = parser.parse_attribute().unwrap();
+        assert_eq!(parser.errors.len(), 1);
+    }
+
     #[test]
     fn parses_attribute_test_no_scope() {
         let src = "#[test]";
@@ -133,6 +133,7 @@ impl Parser<'_> {
     ///     | MemberAccessOrMethodCallExpression
     ///     | CastExpression
     ///     | IndexExpression
+    ///     | TryExpression
     fn parse_unary_right(
         &mut self,
         mut atom: Expression,
@@ -155,6 +156,11 @@ impl Parser<'_> {
             return (atom, parsed);
         }
 
+        (atom, parsed) = self.parse_try(atom, start_location);
+        if parsed {
+            return (atom, parsed);
+        }
+
         self.parse_index(atom, start_location)
     }
 
@@ -247,6 +253,17 @@ impl Parser<'_> {
         }
     }
 
+    /// TryExpression = Atom '?'
+    fn parse_try(&mut self, atom: Expression, start_location: Location) -> (Expression, bool) {
+        if !self.eat(Token::Question) {
+            return (atom, false);
+        }
+
+        let kind = ExpressionKind::Try(Box::new(atom));
+        let location = self.location_since(start_location);
+        (Expression { kind, location }, true)
+    }
+
     /// CastExpression = Atom 'as' Type
     fn parse_cast(&mut self, atom: Expression, start_location: Location) -> (Expression, bool) {
         if !self.eat_keyword(Keyword::As) {
@@ -1818,6 +1835,30 @@ mod tests {
         assert_eq!(cast_expr.r#type.to_string(), "u8");
     }
 
+    #[test]
+    fn parses_try() {
+        let src = "foo.bar()?.baz";
+        let expr = parse_expression_no_errors(src);
+        let ExpressionKind::MemberAccess(member_access) = expr.kind else {
+            panic!("Expected member access");
+        };
+        let ExpressionKind::Try(expr) = member_access.lhs.kind else {
+            panic!("Expected try");
+        };
+        assert_eq!(expr.to_string(), "foo.bar()");
+    }
+
+    #[test]
+    fn parses_try_before_binary_operator() {
+        let src = "x? + 1";
+        let expr = parse_expression_no_errors(src);
+        let ExpressionKind::Infix(infix_expr) = expr.kind else {
+            panic!("Expected infix");
+        };
+        assert!(matches!(infix_expr.lhs.kind, ExpressionKind::Try(..)));
+        assert_eq!(infix_expr.lhs.to_string(), "x?");
+    }
+
     #[test]
     fn parses_cast_missing_type() {
         let src = "
@@ -3,7 +3,7 @@ use noirc_errors::Location;
 
 use crate::{
     parser::{Item, ItemKind, ParserErrorReason, labels::ParsingRuleLabel},
-    token::{Attribute, Keyword, Token},
+    token::{Attribute, CfgPredicate, Keyword, SecondaryAttribute, SecondaryAttributeKind, Token},
 };
 
 use super::{Parser, impls::Impl, parse_many::without_separator};
@@ -105,6 +105,17 @@ impl<'a> Parser<'a> {
             }
         }
 
+        let cfg: Vec<CfgPredicate> = attributes
+            .iter()
+            .filter_map(|(attribute, _)| match attribute {
+                Attribute::Secondary(SecondaryAttribute {
+                    kind: SecondaryAttributeKind::Cfg(predicate),
+                    ..
+                }) => Some(predicate.clone()),
+                _ => None,
+            })
+            .collect();
+
         let kinds = self.parse_item_kind(attributes);
         let location = self.location_since(start_location);
 
@@ -112,7 +123,12 @@ impl<'a> Parser<'a> {
             self.push_error(ParserErrorReason::DocCommentDoesNotDocumentAnything, start_location);
         }
 
-        vecmap(kinds, |kind| Item { kind, location, doc_comments: doc_comments.clone() })
+        vecmap(kinds, |kind| Item {
+            kind,
+            location,
+            doc_comments: doc_comments.clone(),
+            cfg: cfg.clone(),
+        })
     }
 
     /// This method returns one 'ItemKind' in the majority of cases.
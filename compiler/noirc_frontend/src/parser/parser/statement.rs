@@ -38,6 +38,7 @@ impl Parser<'_> {
             }
 
             let attributes = self.parse_attributes();
+            let attributes = self.reject_cfg_attributes(attributes);
             let start_location = self.current_token_location;
             let kind = self.parse_statement_kind(attributes);
             self.statement_comments = None;
@@ -120,9 +121,8 @@ impl Parser<'_> {
         }
 
         if self.eat_keyword(Keyword::Return) {
-            self.parse_expression();
-            self.push_error(ParserErrorReason::EarlyReturn, self.location_since(start_location));
-            return Some(StatementKind::Error);
+            let expression = self.parse_expression();
+            return Some(StatementKind::Return(expression));
         }
 
         if self.at_keyword(Keyword::Let) {
@@ -749,18 +749,51 @@ mod tests {
     }
 
     #[test]
-    fn errors_on_return_statement() {
-        // This shouldn't be parsed as a call
+    fn parses_return_statement() {
+        let src = "return 1";
+        let statement = parse_statement_no_errors(src);
+        let StatementKind::Return(Some(expr)) = statement.kind else {
+            panic!("Expected return with a value");
+        };
+        assert_eq!(expr.to_string(), "1");
+    }
+
+    #[test]
+    fn parses_return_statement_without_value() {
+        let src = "return";
+        let statement = parse_statement_no_errors(src);
+        assert!(matches!(statement.kind, StatementKind::Return(None)));
+    }
+
+    #[test]
+    fn parses_return_statement_followed_by_statement() {
+        let src = "{ return; let x = 1; }";
+        let statement = parse_statement_no_errors(src);
+        let StatementKind::Expression(expr) = statement.kind else {
+            panic!("Expected expr");
+        };
+        let ExpressionKind::Block(block) = expr.kind else {
+            panic!("Expected block");
+        };
+        assert_eq!(block.statements.len(), 2);
+        assert!(matches!(block.statements[0].kind, StatementKind::Return(None)));
+    }
+
+    #[test]
+    fn parse_error_cfg_attribute_not_allowed_on_statement() {
         let src = "
-        return 1
-        ^^^^^^^^
+        #[cfg(feature = \"foo\")] let x = 1;
+        ^^^^^^^^^^^^^^^^^^^^^^^
         ";
         let (src, span) = get_source_with_error_span(src);
         let mut parser = Parser::for_str_with_dummy_file(&src);
         let statement = parser.parse_statement_or_error();
-        assert!(matches!(statement.kind, StatementKind::Error));
+        let StatementKind::Let(let_statement) = statement.kind else {
+            panic!("Expected let statement");
+        };
+        assert!(let_statement.attributes.is_empty());
         let reason = get_single_error_reason(&parser.errors, span);
-        assert!(matches!(reason, ParserErrorReason::EarlyReturn));
+        assert!(matches!(reason, ParserErrorReason::CfgAttributeNotAllowedHere));
     }
 
     #[test]
@@ -67,6 +67,7 @@ impl Parser<'_> {
         loop {
             let doc_comments_start_location = self.current_token_location;
             doc_comments = self.parse_outer_doc_comments();
+            self.parse_disallowed_attributes();
 
             visibility = self.parse_item_visibility();
 
@@ -249,6 +250,23 @@ mod tests {
         assert!(matches!(reason, ParserErrorReason::NoFunctionAttributesAllowedOnType));
     }
 
+    #[test]
+    fn parse_error_cfg_attribute_not_allowed_on_struct_field() {
+        let src = "
+        struct Foo { #[cfg(feature = \"bar\")] x: i32 }
+                     ^^^^^^^^^^^^^^^^^^^^^^^
+        ";
+        let (src, span) = get_source_with_error_span(src);
+        let (module, errors) = parse_program_with_dummy_file(&src);
+        let reason = get_single_error_reason(&errors, span);
+        assert!(matches!(reason, ParserErrorReason::CfgAttributeNotAllowedHere));
+
+        let ItemKind::Struct(noir_struct) = &module.items[0].kind else {
+            panic!("Expected struct");
+        };
+        assert_eq!(noir_struct.fields.len(), 1);
+    }
+
     #[test]
     fn recovers_on_non_field() {
         let src = "
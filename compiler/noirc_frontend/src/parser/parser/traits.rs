@@ -143,6 +143,7 @@ impl Parser<'_> {
     fn parse_trait_item_in_list(&mut self) -> Option<Documented<TraitItem>> {
         self.parse_item_in_list(ParsingRuleLabel::TraitItem, |parser| {
             let doc_comments = parser.parse_outer_doc_comments();
+            parser.parse_disallowed_attributes();
             parser.parse_trait_item().map(|item| Documented::new(item, doc_comments))
         })
     }
@@ -635,4 +636,21 @@ mod tests {
         let (_, errors) = parse_program_with_dummy_file(src);
         assert!(!errors.is_empty());
     }
+
+    #[test]
+    fn parse_error_cfg_attribute_not_allowed_on_trait_item() {
+        let src = "
+        trait Foo { #[cfg(test)] fn foo(); }
+                    ^^^^^^^^^^^^
+        ";
+        let (src, span) = get_source_with_error_span(src);
+        let (module, errors) = parse_program_with_dummy_file(&src);
+        let error = get_single_error(&errors, span);
+        assert!(matches!(error.reason(), Some(ParserErrorReason::CfgAttributeNotAllowedHere)));
+
+        let ItemKind::Trait(noir_trait) = &module.items[0].kind else {
+            panic!("Expected trait");
+        };
+        assert_eq!(noir_trait.items.len(), 1);
+    }
 }
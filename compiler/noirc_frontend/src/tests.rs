@@ -956,6 +956,7 @@ fn find_lambda_captures(stmts: &[StmtId], interner: &NodeInterner, result: &mut
             HirStatement::For(for_loop) => for_loop.block,
             HirStatement::Loop(block) => block,
             HirStatement::While(_, block) => block,
+            HirStatement::Return(expr) => expr,
             HirStatement::Error => panic!("Invalid HirStatement!"),
             HirStatement::Break => panic!("Unexpected break"),
             HirStatement::Continue => panic!("Unexpected continue"),
@@ -1051,6 +1052,38 @@ fn unresolved_path() {
     check_errors!(src);
 }
 
+#[named]
+#[test]
+fn cfg_feature_removes_disabled_items() {
+    let src = r#"
+        #[cfg(feature = "extra")]
+        fn extra() {}
+
+        fn main() {
+            extra();
+            ^^^^^ cannot find `extra` in this scope
+            ~~~~~ not found in this scope
+        }
+    "#;
+    check_errors!(src);
+}
+
+#[named]
+#[test]
+fn cfg_test_removes_test_only_items() {
+    let src = r#"
+        #[cfg(test)]
+        fn helper() {}
+
+        fn main() {
+            helper();
+            ^^^^^^ cannot find `helper` in this scope
+            ~~~~~~ not found in this scope
+        }
+    "#;
+    check_errors!(src);
+}
+
 #[named]
 #[test]
 fn resolve_literal_expr() {
@@ -1356,6 +1389,139 @@ fn break_and_continue_outside_loop() {
     check_errors!(src);
 }
 
+#[named]
+#[test]
+fn early_return() {
+    let src = r#"
+        fn main() {
+            let _ = foo(1);
+            bar(true);
+        }
+
+        fn foo(x: u32) -> u32 {
+            for i in 0 .. 10 {
+                if i == x {
+                    return i;
+                }
+            }
+            let f = |y: u32| {
+                if y == 0 {
+                    return false;
+                }
+                true
+            };
+            if f(x) { 1 } else { 2 }
+        }
+
+        fn bar(x: bool) {
+            if x {
+                return;
+            }
+            assert(false);
+        }
+    "#;
+    assert_no_errors!(src);
+}
+
+#[named]
+#[test]
+fn early_return_type_mismatch() {
+    let src = r#"
+        fn main() {
+            let _ = foo(true);
+        }
+
+        fn foo(x: bool) -> u32 {
+            if x {
+                return x;
+                       ^ Expected type u32, found type bool
+            }
+            1
+        }
+    "#;
+    check_errors!(src);
+}
+
+#[named]
+#[test]
+fn early_return_makes_rest_of_block_unreachable() {
+    let src = r#"
+        fn main() {
+            let _ = foo();
+        }
+
+        fn foo() -> u32 {
+            return 1;
+            ~~~~~~~~~ Any code following this expression is unreachable
+            2
+            ^ Unreachable statement
+        }
+    "#;
+    check_errors!(src);
+}
+
+#[named]
+#[test]
+fn early_return_nested_in_expression() {
+    let src = r#"
+        fn main() {
+            let _ = foo(true);
+            // Safety: test
+            let _ = unsafe { bar(true) };
+        }
+
+        fn foo(c: bool) -> u32 {
+            add(if c { return 1; } else { 2 }, 3)
+        }
+
+        unconstrained fn bar(c: bool) -> u32 {
+            add(if c { return 1; } else { 2 }, 3)
+        }
+
+        fn add(x: u32, y: u32) -> u32 {
+            x + y
+        }
+    "#;
+    assert_no_errors!(src);
+}
+
+#[named]
+#[test]
+fn return_outside_function() {
+    let src = r#"
+        global X: u32 = {
+            if false {
+                return 1;
+                ^^^^^^^^^ `return` is only allowed within functions
+                ~~~~~~~~~ This is not the body of a function or lambda
+            }
+            2
+        };
+
+        fn main() {
+            let _ = X;
+        }
+    "#;
+    check_errors!(src);
+}
+
+#[named]
+#[test]
+fn try_operator_without_stdlib() {
+    let src = r#"
+        fn main() {
+            let _ = foo(1);
+        }
+
+        fn foo(x: u32) -> u32 {
+            x?
+            ^^ The `?` operator requires the `Try` and `FromResidual` traits from the standard library
+            ~~ The standard library is not available
+        }
+    "#;
+    check_errors!(src);
+}
+
 // Regression for #2540
 #[named]
 #[test]
@@ -1425,6 +1591,34 @@ fn deny_fold_attribute_on_unconstrained() {
     check_errors!(src);
 }
 
+#[named]
+#[test]
+fn deny_max_acir_opcodes_attribute_on_unconstrained() {
+    let src = r#"
+        #[max_acir_opcodes(10)]
+        ^^^^^^^^^^^^^^^^^^^^^^^ misplaced #[max_acir_opcodes] attribute on unconstrained function foo. Only allowed on constrained functions
+        ~~~~~~~~~~~~~~~~~~~~~~~ misplaced #[max_acir_opcodes] attribute
+        unconstrained pub fn foo(x: Field, y: Field) {
+            assert(x != y);
+        }
+    "#;
+    check_errors!(src);
+}
+
+#[named]
+#[test]
+fn deny_max_brillig_bytecode_attribute_on_constrained() {
+    let src = r#"
+        #[max_brillig_bytecode(10)]
+        ^^^^^^^^^^^^^^^^^^^^^^^^^^^ misplaced #[max_brillig_bytecode] attribute on constrained function foo. Only allowed on unconstrained functions
+        ~~~~~~~~~~~~~~~~~~~~~~~~~~~ misplaced #[max_brillig_bytecode] attribute
+        pub fn foo(x: Field, y: Field) {
+            assert(x != y);
+        }
+    "#;
+    check_errors!(src);
+}
+
 #[named]
 #[test]
 fn deny_oracle_attribute_on_non_unconstrained() {
@@ -3615,6 +3809,19 @@ fn disallows_export_attribute_on_trait_impl_method() {
     check_errors!(src);
 }
 
+#[named]
+#[test]
+fn disallows_bench_attribute_on_function_with_parameters() {
+    let src = "
+        #[bench]
+        ^^^^^^^^ The `#[bench]` attribute may only be used on functions without parameters
+        fn foo(_x: Field) { }
+
+        fn main() { }
+    ";
+    check_errors!(src);
+}
+
 #[named]
 #[test]
 fn allows_multiple_underscore_parameters() {
@@ -1,11 +1,12 @@
-use std::path::PathBuf;
+use std::{fs::File, path::PathBuf};
 
+use acir::FieldElement;
 use bn254_blackbox_solver::Bn254BlackBoxSolver;
 use clap::Args;
 
 use crate::{
     Artifact,
-    errors::CliError,
+    errors::{CliError, FilesystemError},
     execution::{self, ExecutionResults},
 };
 use nargo::foreign_calls::{
@@ -51,6 +52,20 @@ pub struct ExecuteCommand {
     #[clap(long, conflicts_with = "oracle_resolver")]
     pub oracle_file: Option<PathBuf>,
 
+    /// Match foreign calls to the oracle transcript by name and inputs, regardless of their order,
+    /// and allow calls in the transcript to be left over.
+    ///
+    /// By default every call has to match the next call in the transcript, and the execution fails
+    /// if the transcript was not replayed in full.
+    #[clap(long, requires = "oracle_file")]
+    pub lenient_oracles: bool,
+
+    /// Path to record the oracle transcript to, in the format expected by `--oracle-file`.
+    ///
+    /// Only foreign calls which are not handled by `nargo` itself, such as prints, are recorded.
+    #[clap(long, conflicts_with = "oracle_file")]
+    pub record_oracles: Option<PathBuf>,
+
     /// JSON RPC url to solve oracle calls.
     #[clap(long, conflicts_with = "oracle_file")]
     pub oracle_resolver: Option<String>,
@@ -115,21 +130,56 @@ fn execute(circuit: &CompiledProgram, args: &ExecuteCommand) -> Result<Execution
     // and use it as a base for the default executor. Using it as the innermost rather
     // than top layer so that any extra `print` added for debugging is handled by the
     // default, rather than trying to match it to the transcript.
-    let transcript_executor = match args.oracle_file {
-        Some(ref path) => layers::Either::Left(ReplayForeignCallExecutor::from_file(path)?),
+    let replay = match args.oracle_file {
+        Some(ref path) => {
+            Some(ReplayForeignCallExecutor::from_file(path)?.with_lenient(args.lenient_oracles))
+        }
+        None => None,
+    };
+    let transcript_executor = match replay {
+        Some(ref replay) => layers::Either::Left(replay.clone()),
         None => layers::Either::Right(layers::Empty),
     };
 
-    let mut foreign_call_executor = DefaultForeignCallBuilder {
+    let foreign_call_builder = DefaultForeignCallBuilder {
         output: std::io::stdout(),
         enable_mocks: false,
         resolver_url: args.oracle_resolver.clone(),
         root_path: None,
         package_name: None,
-    }
-    .build_with_base(transcript_executor);
+    };
 
     let blackbox_solver = Bn254BlackBoxSolver(args.pedantic_solving);
 
-    execution::execute(circuit, &blackbox_solver, &mut foreign_call_executor, &args.prover_file)
+    let results = match args.record_oracles {
+        Some(ref path) => {
+            let transcript = File::create(path).map_err(|e| {
+                FilesystemError::OutputFileCreationFailed(path.clone(), e.to_string())
+            })?;
+            let mut foreign_call_executor = foreign_call_builder
+                .build_with_transcript::<_, FieldElement, _>(transcript_executor, transcript);
+            execution::execute(
+                circuit,
+                &blackbox_solver,
+                &mut foreign_call_executor,
+                &args.prover_file,
+            )
+        }
+        None => {
+            let mut foreign_call_executor =
+                foreign_call_builder.build_with_base(transcript_executor);
+            execution::execute(
+                circuit,
+                &blackbox_solver,
+                &mut foreign_call_executor,
+                &args.prover_file,
+            )
+        }
+    }?;
+
+    if let Some(replay) = replay {
+        replay.finish()?;
+    }
+
+    Ok(results)
 }
@@ -129,6 +129,9 @@ where
         Expression::Drop(expr) => {
             visit_expr_be_mut(expr, b, e);
         }
+        Expression::Return(expr) => {
+            visit_expr_be_mut(expr, b, e);
+        }
         Expression::Break => {}
         Expression::Continue => {}
     }
@@ -289,6 +292,9 @@ where
         Expression::Drop(expr) => {
             visit_expr_be(expr, b, e);
         }
+        Expression::Return(expr) => {
+            visit_expr_be(expr, b, e);
+        }
         Expression::Break => {}
         Expression::Continue => {}
     }
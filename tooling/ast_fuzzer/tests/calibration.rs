@@ -108,6 +108,7 @@ fn classify(expr: &Expression) -> Option<(&'static str, &'static str)> {
         Expression::Assign(_) => ("stmt", "assign"),
         Expression::Break => ("stmt", "break"),
         Expression::Continue => ("stmt", "continue"),
+        Expression::Return(_) => ("stmt", "return"),
     };
     Some(cat)
 }
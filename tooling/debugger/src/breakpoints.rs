@@ -0,0 +1,214 @@
+use std::fmt::Display;
+use std::str::FromStr;
+
+use acvm::FieldElement;
+use acvm::acir::native_types::Witness;
+use thiserror::Error;
+
+use crate::context::DebugLocation;
+
+/// Decides when a breakpoint stops execution, and what it does instead.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(super) struct BreakpointOptions {
+    /// A Noir expression over the variables in scope. The breakpoint is only hit when it's true.
+    pub(super) condition: Option<String>,
+    /// Which hits of the breakpoint stop execution.
+    pub(super) hit_condition: Option<HitCondition>,
+    /// A message logged when the breakpoint is hit, instead of stopping execution.
+    /// Expressions between braces are replaced by their value.
+    pub(super) log_message: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct Breakpoint {
+    pub(super) options: BreakpointOptions,
+    /// How many times the breakpoint has been hit, counting only the times its condition held.
+    pub(super) hits: usize,
+}
+
+impl Breakpoint {
+    pub(super) fn new(options: BreakpointOptions) -> Self {
+        Self { options, hits: 0 }
+    }
+}
+
+/// Which hits of a breakpoint stop execution, such as `>= 500` or `% 10`.
+///
+/// A plain number `N` is the same as `>= N`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum HitCondition {
+    Equal(usize),
+    Greater(usize),
+    GreaterOrEqual(usize),
+    Less(usize),
+    LessOrEqual(usize),
+    /// Every Nth hit.
+    Multiple(usize),
+}
+
+impl HitCondition {
+    pub(super) fn matches(&self, hits: usize) -> bool {
+        match *self {
+            HitCondition::Equal(count) => hits == count,
+            HitCondition::Greater(count) => hits > count,
+            HitCondition::GreaterOrEqual(count) => hits >= count,
+            HitCondition::Less(count) => hits < count,
+            HitCondition::LessOrEqual(count) => hits <= count,
+            HitCondition::Multiple(count) => hits % count == 0,
+        }
+    }
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub(super) enum HitConditionError {
+    #[error(
+        "Invalid hit count `{0}`, expected a number optionally preceded by ==, >, >=, <, <= or %"
+    )]
+    Invalid(String),
+    #[error("Hit count multiple must not be zero")]
+    ZeroMultiple,
+}
+
+impl FromStr for HitCondition {
+    type Err = HitConditionError;
+
+    fn from_str(source: &str) -> Result<Self, Self::Err> {
+        let source = source.trim();
+        // Longer operators go first so `>=` isn't read as `>`.
+        let operators: [(&str, fn(usize) -> HitCondition); 7] = [
+            (">=", HitCondition::GreaterOrEqual),
+            ("<=", HitCondition::LessOrEqual),
+            ("==", HitCondition::Equal),
+            (">", HitCondition::Greater),
+            ("<", HitCondition::Less),
+            ("%", HitCondition::Multiple),
+            ("", HitCondition::GreaterOrEqual),
+        ];
+        let (count, condition) = operators
+            .into_iter()
+            .find_map(|(operator, condition)| {
+                source.strip_prefix(operator).map(|count| (count, condition))
+            })
+            .expect("Every string starts with the empty operator");
+        let count: usize =
+            count.trim().parse().map_err(|_| HitConditionError::Invalid(source.to_string()))?;
+
+        let condition = condition(count);
+        if condition == HitCondition::Multiple(0) {
+            return Err(HitConditionError::ZeroMultiple);
+        }
+        Ok(condition)
+    }
+}
+
+/// A piece of execution state whose changes stop execution.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(super) enum DataBreakpoint {
+    /// A witness of the given ACIR circuit, which stops execution when it's assigned or changed.
+    Witness { circuit_id: u32, witness: Witness },
+    /// An address of the Brillig VM memory, which stops execution when it's written.
+    BrilligMemory(usize),
+}
+
+impl Display for DataBreakpoint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DataBreakpoint::Witness { circuit_id, witness } => {
+                write!(f, "witness _{} of circuit {circuit_id}", witness.witness_index())
+            }
+            DataBreakpoint::BrilligMemory(address) => write!(f, "Brillig memory at {address}"),
+        }
+    }
+}
+
+/// A change of the state watched by a data breakpoint.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct DataBreakpointHit {
+    pub(super) data_breakpoint: DataBreakpoint,
+    /// The opcode responsible for the change.
+    pub(super) location: DebugLocation,
+    /// The value before the change, if there was one.
+    pub(super) old_value: Option<FieldElement>,
+    pub(super) new_value: FieldElement,
+}
+
+impl DataBreakpointHit {
+    /// How the watched state was written, such as `changed from 0x01 to 0x02`.
+    fn describe_change(&self) -> String {
+        let new_value = self.new_value;
+        match self.old_value {
+            Some(old_value) if old_value == new_value => {
+                format!("written with its current value {new_value}")
+            }
+            Some(old_value) => format!("changed from {old_value} to {new_value}"),
+            None => format!("assigned {new_value}"),
+        }
+    }
+}
+
+impl Display for DataBreakpointHit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {} by opcode {}", self.data_breakpoint, self.describe_change(), self.location)
+    }
+}
+
+/// Replaces the expressions between braces in a log message with what `evaluate` returns for
+/// them. `{{` and `}}` stand for literal braces.
+pub(super) fn interpolate_log_message(
+    message: &str,
+    mut evaluate: impl FnMut(&str) -> String,
+) -> String {
+    let mut output = String::new();
+    let mut rest = message;
+    while let Some(index) = rest.find(['{', '}']) {
+        output.push_str(&rest[..index]);
+        let brace = &rest[index..index + 1];
+        rest = &rest[index + 1..];
+
+        if let Some(after_escape) = rest.strip_prefix(brace) {
+            output.push_str(brace);
+            rest = after_escape;
+        } else if brace == "{" {
+            let Some(end) = rest.find('}') else {
+                // An unclosed brace is kept as is.
+                output.push('{');
+                break;
+            };
+            output.push_str(&evaluate(&rest[..end]));
+            rest = &rest[end + 1..];
+        } else {
+            output.push_str(brace);
+        }
+    }
+    output.push_str(rest);
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{HitCondition, HitConditionError, interpolate_log_message};
+
+    #[test]
+    fn parses_hit_conditions() {
+        assert_eq!("500".parse(), Ok(HitCondition::GreaterOrEqual(500)));
+        assert_eq!("== 3".parse(), Ok(HitCondition::Equal(3)));
+        assert_eq!(">=2".parse(), Ok(HitCondition::GreaterOrEqual(2)));
+        assert_eq!("> 2".parse(), Ok(HitCondition::Greater(2)));
+        assert_eq!("<5".parse(), Ok(HitCondition::Less(5)));
+        assert_eq!("%10".parse(), Ok(HitCondition::Multiple(10)));
+        assert_eq!("%0".parse::<HitCondition>(), Err(HitConditionError::ZeroMultiple));
+        assert!(matches!("often".parse::<HitCondition>(), Err(HitConditionError::Invalid(_))));
+
+        assert!(HitCondition::Multiple(3).matches(6));
+        assert!(!HitCondition::Multiple(3).matches(7));
+    }
+
+    #[test]
+    fn interpolates_log_messages() {
+        let evaluate = |expression: &str| format!("<{expression}>");
+        assert_eq!(interpolate_log_message("i = {i}, x = {p.x}", evaluate), "i = <i>, x = <p.x>");
+        assert_eq!(interpolate_log_message("{{i}} {i}", evaluate), "{i} <i>");
+        assert_eq!(interpolate_log_message("unclosed {i", evaluate), "unclosed {i");
+        assert_eq!(interpolate_log_message("stray }", evaluate), "stray }");
+    }
+}
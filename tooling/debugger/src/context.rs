@@ -1,5 +1,11 @@
+use crate::breakpoints::{
+    Breakpoint, BreakpointOptions, DataBreakpoint, DataBreakpointHit, interpolate_log_message,
+};
+use crate::expression::{EvaluatedValue, EvaluationError, evaluate_expression};
 use crate::foreign_calls::DebugForeignCallExecutor;
-use acvm::acir::brillig::BitSize;
+use crate::history::{ExecutionHistory, HistoryPosition, StateEdit};
+use crate::state::FailureState;
+use acvm::acir::brillig::{BitSize, ForeignCallResult};
 use acvm::acir::circuit::brillig::{BrilligBytecode, BrilligFunctionId};
 use acvm::acir::circuit::{Circuit, Opcode, OpcodeLocation};
 use acvm::acir::native_types::{Witness, WitnessMap, WitnessStack};
@@ -8,13 +14,14 @@ use acvm::pwg::{
     ACVM, ACVMStatus, AcirCallWaitInfo, BrilligSolver, BrilligSolverStatus, ForeignCallWaitInfo,
     OpcodeNotSolvable, StepResult,
 };
-use acvm::{BlackBoxFunctionSolver, FieldElement};
+use acvm::{AcirField, BlackBoxFunctionSolver, FieldElement};
 
 use codespan_reporting::files::{Files, SimpleFile};
 use fm::FileId;
 use nargo::NargoError;
 use nargo::errors::{ExecutionError, Location, ResolvedOpcodeLocation, execution_error_from};
-use noirc_artifacts::debug::{DebugArtifact, StackFrame};
+use nargo::foreign_calls::transcript::ReplayForeignCallExecutor;
+use noirc_artifacts::debug::{DebugArtifact, DebugVars, StackFrame};
 use noirc_driver::{CompiledProgram, DebugFile};
 
 use noirc_errors::call_stack::CallStackId;
@@ -23,7 +30,7 @@ use noirc_printable_type::{PrintableType, PrintableValue};
 use thiserror::Error;
 
 use std::collections::BTreeMap;
-use std::collections::HashSet;
+use std::collections::HashMap;
 use std::path::PathBuf;
 
 /// A Noir program is composed by
@@ -237,6 +244,7 @@ pub(super) enum DebugCommandResult {
     Done,
     Ok,
     BreakpointReached(DebugLocation),
+    DataBreakpointReached(DataBreakpointHit),
     Error(NargoError<FieldElement>),
 }
 
@@ -268,6 +276,35 @@ pub struct ExecutionFrame<'a, B: BlackBoxFunctionSolver<FieldElement>> {
     acvm: ACVM<'a, FieldElement, B>,
 }
 
+impl<B: BlackBoxFunctionSolver<FieldElement>> Clone for ExecutionFrame<'_, B> {
+    fn clone(&self) -> Self {
+        Self { circuit_id: self.circuit_id, acvm: self.acvm.clone() }
+    }
+}
+
+/// How many steps apart checkpoints are taken. Stepping backwards replays at most this many steps,
+/// while every checkpoint keeps a copy of the witness maps and Brillig memory.
+#[cfg(not(test))]
+const CHECKPOINT_INTERVAL: usize = 10_000;
+/// Small enough for the tests' programs to go through several checkpoints.
+#[cfg(test)]
+const CHECKPOINT_INTERVAL: usize = 2;
+
+/// The state of execution after some number of steps, to replay execution from instead of
+/// restarting it when stepping backwards.
+struct Checkpoint<'a, B: BlackBoxFunctionSolver<FieldElement>> {
+    position: HistoryPosition,
+    /// Whether the step that led to this state stopped at a breakpoint or data breakpoint.
+    stopped: bool,
+    acvm: ACVM<'a, FieldElement, B>,
+    current_circuit_id: u32,
+    brillig_solver: Option<BrilligSolver<'a, FieldElement, B>>,
+    witness_stack: WitnessStack<FieldElement>,
+    acvm_stack: Vec<ExecutionFrame<'a, B>>,
+    debug_vars: DebugVars<FieldElement>,
+    breakpoint_hits: Vec<(DebugLocation, usize)>,
+}
+
 #[derive(Debug)]
 pub enum DebugExecutionResult {
     Solved(WitnessStack<FieldElement>),
@@ -295,6 +332,10 @@ pub struct RunParams {
 
     /// JSON RPC url to solve oracle calls
     pub oracle_resolver_url: Option<String>,
+
+    /// Oracle transcript to answer the calls that would otherwise go to the oracle resolver,
+    /// such as the one recorded when execution failed
+    pub oracle_transcript: Option<ReplayForeignCallExecutor<FieldElement>>,
 }
 
 pub(super) struct DebugContext<'a, B: BlackBoxFunctionSolver<FieldElement>> {
@@ -309,7 +350,13 @@ pub(super) struct DebugContext<'a, B: BlackBoxFunctionSolver<FieldElement>> {
     foreign_call_executor: Box<dyn DebugForeignCallExecutor + 'a>,
 
     debug_artifact: &'a DebugArtifact,
-    breakpoints: HashSet<DebugLocation>,
+    breakpoints: HashMap<DebugLocation, Breakpoint>,
+    /// Messages logged by logpoints since they were last taken.
+    breakpoint_logs: Vec<String>,
+    data_breakpoints: Vec<DataBreakpoint>,
+    /// The watched Brillig memory addresses written by the last step, so that writes which
+    /// leave a value unchanged still stop execution.
+    brillig_memory_writes: Vec<usize>,
     source_to_locations: BTreeMap<FileId, Vec<(usize, DebugLocation)>>,
 
     circuits: &'a [Circuit<FieldElement>],
@@ -317,6 +364,15 @@ pub(super) struct DebugContext<'a, B: BlackBoxFunctionSolver<FieldElement>> {
 
     acir_opcode_addresses: AddressMap,
     initial_witness: WitnessMap<FieldElement>,
+
+    history: ExecutionHistory,
+    /// Checkpoints taken every `CHECKPOINT_INTERVAL` steps, in order. They depend on the
+    /// breakpoints that were set, so they're dropped whenever breakpoints change.
+    checkpoints: Vec<Checkpoint<'a, B>>,
+    /// Foreign call results to answer calls with, in order, when reproducing a saved failure.
+    saved_foreign_call_results: Vec<ForeignCallResult<FieldElement>>,
+    /// Whether execution is being replayed to step backwards, so logpoints stay quiet.
+    replaying: bool,
 }
 
 fn initialize_acvm<'a, B: BlackBoxFunctionSolver<FieldElement>>(
@@ -357,7 +413,10 @@ impl<'a, B: BlackBoxFunctionSolver<FieldElement>> DebugContext<'a, B> {
             backend: blackbox_solver,
             foreign_call_executor,
             debug_artifact,
-            breakpoints: HashSet::new(),
+            breakpoints: HashMap::new(),
+            breakpoint_logs: Vec::new(),
+            data_breakpoints: Vec::new(),
+            brillig_memory_writes: Vec::new(),
             source_to_locations: source_to_opcodes,
             circuits,
             unconstrained_functions,
@@ -369,9 +428,17 @@ impl<'a, B: BlackBoxFunctionSolver<FieldElement>> DebugContext<'a, B> {
                 initial_witness,
                 unconstrained_functions,
             ),
+            history: ExecutionHistory::default(),
+            checkpoints: Vec::new(),
+            saved_foreign_call_results: Vec::new(),
+            replaying: false,
         }
     }
 
+    pub(super) fn current_circuit_id(&self) -> u32 {
+        self.current_circuit_id
+    }
+
     pub(super) fn get_opcodes(&self) -> &[Opcode<FieldElement>] {
         self.acvm.opcodes()
     }
@@ -389,6 +456,7 @@ impl<'a, B: BlackBoxFunctionSolver<FieldElement>> DebugContext<'a, B> {
         witness: Witness,
         value: FieldElement,
     ) -> Option<FieldElement> {
+        self.history.record_edit(StateEdit::Witness(witness, value));
         self.acvm.overwrite_witness(witness, value)
     }
 
@@ -615,7 +683,18 @@ impl<'a, B: BlackBoxFunctionSolver<FieldElement>> DebugContext<'a, B> {
         let Some(mut solver) = self.brillig_solver.take() else {
             unreachable!("Missing Brillig solver");
         };
-        match solver.step() {
+        let watched_addresses: Vec<usize> = self
+            .data_breakpoints
+            .iter()
+            .filter_map(|data_breakpoint| match data_breakpoint {
+                DataBreakpoint::BrilligMemory(address) => Some(*address),
+                DataBreakpoint::Witness { .. } => None,
+            })
+            .collect();
+        solver.watch_memory_writes(&watched_addresses);
+        let status = solver.step();
+        self.brillig_memory_writes = solver.get_watched_memory_writes().to_vec();
+        match status {
             Ok(BrilligSolverStatus::InProgress) => {
                 self.brillig_solver = Some(solver);
                 if self.breakpoint_reached() {
@@ -653,7 +732,16 @@ impl<'a, B: BlackBoxFunctionSolver<FieldElement>> DebugContext<'a, B> {
         &mut self,
         foreign_call: ForeignCallWaitInfo<FieldElement>,
     ) -> DebugCommandResult {
-        let foreign_call_result = self.foreign_call_executor.execute(&foreign_call);
+        let foreign_call_result = match self.history.replay_foreign_call(&foreign_call) {
+            Some(foreign_call_result) => Ok(foreign_call_result),
+            None => {
+                let foreign_call_result = self.foreign_call_executor.execute(&foreign_call);
+                if let Ok(foreign_call_result) = &foreign_call_result {
+                    self.history.record_foreign_call(&foreign_call, foreign_call_result);
+                }
+                foreign_call_result
+            }
+        };
 
         match foreign_call_result {
             Ok(foreign_call_result) => {
@@ -754,16 +842,203 @@ impl<'a, B: BlackBoxFunctionSolver<FieldElement>> DebugContext<'a, B> {
     }
 
     pub(super) fn step_into_opcode(&mut self) -> DebugCommandResult {
-        if self.brillig_solver.is_some() {
-            return self.step_brillig_opcode();
+        self.take_step(|context| {
+            if context.brillig_solver.is_some() {
+                return context.step_brillig_opcode();
+            }
+
+            match context.acvm.step_into_brillig() {
+                StepResult::IntoBrillig(solver) => {
+                    context.brillig_solver = Some(solver);
+                    context.step_brillig_opcode()
+                }
+                StepResult::Status(status) => context.handle_acvm_status(status),
+            }
+        })
+    }
+
+    /// Takes a step, recording it in the history, and keeps a checkpoint of the state it leads to
+    /// every `CHECKPOINT_INTERVAL` steps.
+    fn take_step(
+        &mut self,
+        step: impl FnOnce(&mut Self) -> DebugCommandResult,
+    ) -> DebugCommandResult {
+        // Execution that finished on a step that hit a data breakpoint is only reported as
+        // done when stepping again.
+        if self.is_solved() {
+            return DebugCommandResult::Done;
         }
 
-        match self.acvm.step_into_brillig() {
-            StepResult::IntoBrillig(solver) => {
-                self.brillig_solver = Some(solver);
-                self.step_brillig_opcode()
+        let result = self.watch_data_breakpoints(|context| {
+            context.history.record_step();
+            step(context)
+        });
+
+        let steps = self.history.steps();
+        if steps % CHECKPOINT_INTERVAL == 0 && !matches!(result, DebugCommandResult::Error(_)) {
+            if let Err(index) = self
+                .checkpoints
+                .binary_search_by_key(&steps, |checkpoint| checkpoint.position.steps())
+            {
+                let stopped = matches!(
+                    result,
+                    DebugCommandResult::BreakpointReached(_)
+                        | DebugCommandResult::DataBreakpointReached(_)
+                );
+                let checkpoint = self.checkpoint(stopped);
+                self.checkpoints.insert(index, checkpoint);
+            }
+        }
+        result
+    }
+
+    fn checkpoint(&self, stopped: bool) -> Checkpoint<'a, B> {
+        Checkpoint {
+            position: self.history.position(),
+            stopped,
+            acvm: self.acvm.clone(),
+            current_circuit_id: self.current_circuit_id,
+            brillig_solver: self.brillig_solver.clone(),
+            witness_stack: self.witness_stack.clone(),
+            acvm_stack: self.acvm_stack.clone(),
+            debug_vars: self.foreign_call_executor.save_debug_vars(),
+            breakpoint_hits: self
+                .breakpoints
+                .iter()
+                .map(|(location, breakpoint)| (*location, breakpoint.hits))
+                .collect(),
+        }
+    }
+
+    /// The step of the latest checkpoint taken at or before the given step, or zero if there's none.
+    fn checkpoint_step_before(&self, step: usize) -> usize {
+        let index =
+            self.checkpoints.partition_point(|checkpoint| checkpoint.position.steps() <= step);
+        index.checked_sub(1).map_or(0, |index| self.checkpoints[index].position.steps())
+    }
+
+    /// Brings execution back to the latest checkpoint taken at or before the given step, or to
+    /// the start of execution if there's none. Returns whether the step that led to the restored
+    /// state stopped at a breakpoint.
+    fn restore_checkpoint(&mut self, step: usize) -> bool {
+        let index =
+            self.checkpoints.partition_point(|checkpoint| checkpoint.position.steps() <= step);
+        let Some(checkpoint) = index.checked_sub(1).map(|index| &self.checkpoints[index]) else {
+            self.reset_execution();
+            self.history.seek(HistoryPosition::default());
+            return false;
+        };
+
+        self.acvm = checkpoint.acvm.clone();
+        self.current_circuit_id = checkpoint.current_circuit_id;
+        self.brillig_solver = checkpoint.brillig_solver.clone();
+        self.witness_stack = checkpoint.witness_stack.clone();
+        self.acvm_stack = checkpoint.acvm_stack.clone();
+        self.foreign_call_executor.restore_debug_vars(checkpoint.debug_vars.clone());
+        for breakpoint in self.breakpoints.values_mut() {
+            breakpoint.hits = 0;
+        }
+        for (location, hits) in &checkpoint.breakpoint_hits {
+            if let Some(breakpoint) = self.breakpoints.get_mut(location) {
+                breakpoint.hits = *hits;
+            }
+        }
+        self.history.seek(checkpoint.position);
+        checkpoint.stopped
+    }
+
+    /// Takes a step and checks whether it wrote the state watched by any data breakpoint, in
+    /// which case the first of them is reported instead of the step's own result. Hits on a step
+    /// that failed are logged instead, so the error is still reported.
+    fn watch_data_breakpoints(
+        &mut self,
+        step: impl FnOnce(&mut Self) -> DebugCommandResult,
+    ) -> DebugCommandResult {
+        if self.data_breakpoints.is_empty() {
+            return step(self);
+        }
+
+        let location = self.get_current_debug_location();
+        let call_stack = self.get_call_stack();
+        let acvm_depth = self.acvm_stack.len();
+        let old_values: Vec<_> = self
+            .data_breakpoints
+            .iter()
+            .map(|data_breakpoint| self.read_data_breakpoint(data_breakpoint))
+            .collect();
+
+        self.brillig_memory_writes.clear();
+        let result = step(self);
+
+        let mut hits = Vec::new();
+        for (data_breakpoint, old_value) in self.data_breakpoints.iter().zip(old_values) {
+            let Some((depth, new_value)) = self.read_data_breakpoint(data_breakpoint) else {
+                continue;
+            };
+            // Values read from different ACVM frames belong to different witness maps, so the
+            // new one counts as an assignment.
+            let old_value = old_value
+                .and_then(|(old_depth, old_value)| (old_depth == depth).then_some(old_value));
+            let written = match data_breakpoint {
+                DataBreakpoint::Witness { .. } => old_value != Some(new_value),
+                DataBreakpoint::BrilligMemory(address) => {
+                    self.brillig_memory_writes.contains(address)
+                }
+            };
+            if !written {
+                continue;
+            }
+            // A witness of a calling circuit is changed by the ACIR call opcode that's waiting
+            // for the callee, while everything else is changed by the opcode just executed.
+            let location = if depth < acvm_depth { Some(call_stack[depth]) } else { location };
+            if let Some(location) = location {
+                hits.push(DataBreakpointHit {
+                    data_breakpoint: *data_breakpoint,
+                    location,
+                    old_value,
+                    new_value,
+                });
+            }
+        }
+
+        if let DebugCommandResult::Error(_) = result {
+            for hit in hits {
+                self.log(format!("Data breakpoint hit before the failure: {hit}"));
+            }
+            return result;
+        }
+        match hits.into_iter().next() {
+            Some(hit) => DebugCommandResult::DataBreakpointReached(hit),
+            None => result,
+        }
+    }
+
+    /// Reads the value watched by a data breakpoint, along with the depth in the ACVM stack of
+    /// the frame it was read from. Witnesses are read from the innermost frame executing their
+    /// circuit, and memory from the Brillig VM being executed.
+    fn read_data_breakpoint(
+        &self,
+        data_breakpoint: &DataBreakpoint,
+    ) -> Option<(usize, FieldElement)> {
+        match *data_breakpoint {
+            DataBreakpoint::Witness { circuit_id, witness } => {
+                let (depth, witness_map) = if self.current_circuit_id == circuit_id {
+                    (self.acvm_stack.len(), self.acvm.witness_map())
+                } else {
+                    self.acvm_stack
+                        .iter()
+                        .enumerate()
+                        .rev()
+                        .find(|(_, frame)| frame.circuit_id == circuit_id)
+                        .map(|(depth, frame)| (depth, frame.acvm.witness_map()))?
+                };
+                witness_map.get(&witness).map(|value| (depth, *value))
+            }
+            DataBreakpoint::BrilligMemory(address) => {
+                let memory = self.get_brillig_memory()?;
+                let value = memory.get(address).map_or(FieldElement::zero(), MemoryValue::to_field);
+                Some((self.acvm_stack.len(), value))
             }
-            StepResult::Status(status) => self.handle_acvm_status(status),
         }
     }
 
@@ -818,8 +1093,12 @@ impl<'a, B: BlackBoxFunctionSolver<FieldElement>> DebugContext<'a, B> {
         if self.is_executing_brillig() {
             self.step_out_of_brillig_opcode()
         } else {
-            let status = self.acvm.solve_opcode();
-            self.handle_acvm_status(status)
+            // Same as `step_into_opcode` for an opcode that isn't a Brillig call,
+            // which is what gets replayed when stepping backwards.
+            self.take_step(|context| {
+                let status = context.acvm.solve_opcode();
+                context.handle_acvm_status(status)
+            })
         }
     }
 
@@ -879,6 +1158,180 @@ impl<'a, B: BlackBoxFunctionSolver<FieldElement>> DebugContext<'a, B> {
         }
     }
 
+    /// Replays execution from the latest checkpoint at or before `from_step` up to `to_step`,
+    /// applying the edits made along the way and without stopping at breakpoints. `inspect` is
+    /// called with the state before each step is taken, along with whether the step that led to
+    /// it stopped at a breakpoint.
+    fn replay_to_step(
+        &mut self,
+        from_step: usize,
+        to_step: usize,
+        inspect: impl FnMut(&Self, usize, bool),
+    ) -> DebugCommandResult {
+        self.replaying = true;
+        let result = self.replay_steps(from_step, to_step, inspect);
+        self.replaying = false;
+        result
+    }
+
+    fn replay_steps(
+        &mut self,
+        from_step: usize,
+        to_step: usize,
+        mut inspect: impl FnMut(&Self, usize, bool),
+    ) -> DebugCommandResult {
+        let mut stopped = self.restore_checkpoint(from_step);
+
+        let mut result = DebugCommandResult::Ok;
+        loop {
+            for edit in self.history.edits_at_current_step() {
+                match edit {
+                    StateEdit::Witness(witness, value) => {
+                        self.acvm.overwrite_witness(witness, value);
+                    }
+                    StateEdit::BrilligMemory(ptr, value, bit_size) => {
+                        if let Some(solver) = self.brillig_solver.as_mut() {
+                            solver.write_memory_at(
+                                ptr,
+                                MemoryValue::new_checked(value, bit_size)
+                                    .expect("Invalid value for the given bit size"),
+                            );
+                        }
+                    }
+                }
+            }
+
+            let step = self.history.steps();
+            if step >= to_step {
+                return result;
+            }
+            inspect(self, step, stopped);
+            result = self.step_into_opcode();
+            if matches!(result, DebugCommandResult::Done | DebugCommandResult::Error(_)) {
+                return result;
+            }
+            stopped = matches!(
+                result,
+                DebugCommandResult::BreakpointReached(_)
+                    | DebugCommandResult::DataBreakpointReached(_)
+            );
+        }
+    }
+
+    /// Takes execution back to the state it was in after the given number of steps,
+    /// dropping the edits made after it.
+    fn reverse_to_step(&mut self, target_step: usize) -> DebugCommandResult {
+        self.history.truncate(target_step);
+        self.checkpoints.retain(|checkpoint| checkpoint.position.steps() <= target_step);
+        // Replay at least the last step, so that its result is the one reported
+        self.replay_to_step(target_step.saturating_sub(1), target_step, |_, _, _| ())
+    }
+
+    /// Steps debugging execution back by one opcode
+    pub(super) fn reverse_step_into_opcode(&mut self) -> DebugCommandResult {
+        let target_step = self.history.steps().saturating_sub(1);
+        self.reverse_to_step(target_step)
+    }
+
+    /// Steps debugging execution back to the start of the latest run of steps that has a
+    /// different `key` than the current state and is accepted by `accept`.
+    ///
+    /// The steps are looked at one checkpoint interval at a time, starting from the latest one,
+    /// so only the intervals up to the target step get replayed.
+    fn reverse_until<K: PartialEq>(
+        &mut self,
+        key: impl Fn(&Self) -> K,
+        accept: impl Fn(&K) -> bool,
+    ) -> DebugCommandResult {
+        let start_key = key(self);
+        // The keys of the states after each step from `keys_start` to the current one.
+        let mut keys = Vec::new();
+        let mut keys_start = self.history.steps();
+        while keys_start > 0 {
+            let interval_start = self.checkpoint_step_before(keys_start - 1);
+            let mut interval_keys = Vec::with_capacity(keys_start - interval_start);
+            self.replay_to_step(interval_start, keys_start, |context, _, _| {
+                interval_keys.push(key(context));
+            });
+            interval_keys.append(&mut keys);
+            keys = interval_keys;
+            keys_start = interval_start;
+
+            let Some(mut target) =
+                keys.iter().rposition(|step_key| *step_key != start_key && accept(step_key))
+            else {
+                continue;
+            };
+            while target > 0 && keys[target - 1] == keys[target] {
+                target -= 1;
+            }
+            // The run of steps may have started in an earlier interval.
+            if target > 0 || keys_start == 0 {
+                return self.reverse_to_step(keys_start + target);
+            }
+        }
+        self.reverse_to_step(0)
+    }
+
+    /// Steps debugging execution back to the previous ACIR opcode
+    pub(super) fn reverse_step_acir_opcode(&mut self) -> DebugCommandResult {
+        self.reverse_until(
+            |context| (context.current_circuit_id, context.get_current_acir_index()),
+            |_| true,
+        )
+    }
+
+    /// Steps debugging execution back to the start of the previous source location
+    /// whose call stack depth is accepted by `accept_depth`
+    fn reverse_next(&mut self, accept_depth: impl Fn(usize) -> bool) -> DebugCommandResult {
+        self.reverse_until(
+            |context| {
+                (context.get_current_source_location(), context.get_source_call_stack().len())
+            },
+            |(location, depth)| location.is_some() && accept_depth(*depth),
+        )
+    }
+
+    /// Steps debugging execution back to the previous source location
+    pub(super) fn reverse_next_into(&mut self) -> DebugCommandResult {
+        self.reverse_next(|_| true)
+    }
+
+    /// Steps debugging execution back to the previous source location at the same (or
+    /// less) call stack depth (eg. don't dive into function calls)
+    pub(super) fn reverse_next_over(&mut self) -> DebugCommandResult {
+        let start_depth = self.get_source_call_stack().len();
+        self.reverse_next(|depth| depth <= start_depth)
+    }
+
+    /// Steps debugging execution back to the previous source location with a smaller
+    /// call stack depth (eg. back to the call of the current function)
+    pub(super) fn reverse_next_out(&mut self) -> DebugCommandResult {
+        let start_depth = self.get_source_call_stack().len();
+        self.reverse_next(|depth| depth < start_depth)
+    }
+
+    /// Runs execution backwards until the previous breakpoint or data breakpoint, or the start
+    /// of the program. The steps are looked at one checkpoint interval at a time, starting from
+    /// the latest one.
+    pub(super) fn reverse_cont(&mut self) -> DebugCommandResult {
+        let mut interval_end = self.history.steps();
+        while interval_end > 0 {
+            let interval_start = self.checkpoint_step_before(interval_end - 1);
+            let mut last_breakpoint_step = None;
+            self.replay_to_step(interval_start, interval_end, |_, step, stopped| {
+                if stopped {
+                    last_breakpoint_step = Some(step);
+                }
+            });
+            if let Some(step) = last_breakpoint_step {
+                return self.reverse_to_step(step);
+            }
+            interval_end = interval_start;
+        }
+        self.reverse_to_step(0)
+    }
+
     pub(super) fn get_brillig_memory(&self) -> Option<&[MemoryValue<FieldElement>]> {
         self.brillig_solver.as_ref().map(|solver| solver.get_memory())
     }
@@ -890,6 +1343,7 @@ impl<'a, B: BlackBoxFunctionSolver<FieldElement>> DebugContext<'a, B> {
         bit_size: BitSize,
     ) {
         if let Some(solver) = self.brillig_solver.as_mut() {
+            self.history.record_edit(StateEdit::BrilligMemory(ptr, value, bit_size));
             solver.write_memory_at(
                 ptr,
                 MemoryValue::new_checked(value, bit_size)
@@ -906,14 +1360,93 @@ impl<'a, B: BlackBoxFunctionSolver<FieldElement>> DebugContext<'a, B> {
         self.foreign_call_executor.current_stack_frame()
     }
 
-    fn breakpoint_reached(&self) -> bool {
-        if let Some(location) = self.get_current_debug_location() {
-            self.breakpoints.contains(&location)
-        } else {
-            false
+    /// Evaluates an expression over the variables of the current stack frame.
+    pub(super) fn evaluate(&self, source: &str) -> Result<EvaluatedValue, EvaluationError> {
+        evaluate_expression(source, self.current_stack_frame().as_ref())
+    }
+
+    /// Evaluates an expression over the variables of the stack frame at the given index, counting
+    /// from the outermost frame as `get_variables` does.
+    pub(super) fn evaluate_in_frame(
+        &self,
+        source: &str,
+        frame_index: usize,
+    ) -> Result<EvaluatedValue, EvaluationError> {
+        let frame = self
+            .get_variables()
+            .into_iter()
+            .nth(frame_index)
+            .ok_or(EvaluationError::UnknownStackFrame(frame_index))?;
+        evaluate_expression(source, Some(&frame))
+    }
+
+    /// Whether execution should stop at the current location. A breakpoint is only hit when its
+    /// condition holds, and it only stops execution on the hits accepted by its hit condition.
+    /// Logpoints log their message instead of stopping.
+    fn breakpoint_reached(&mut self) -> bool {
+        let Some(location) = self.get_current_debug_location() else {
+            return false;
+        };
+        let Some(breakpoint) = self.breakpoints.get(&location) else {
+            return false;
+        };
+        let options = breakpoint.options.clone();
+
+        if let Some(condition) = &options.condition {
+            match self.evaluate(condition) {
+                Ok(EvaluatedValue { value, typ: PrintableType::Boolean }) => {
+                    if value == PrintableValue::Field(FieldElement::zero()) {
+                        return false;
+                    }
+                }
+                Ok(value) => {
+                    self.log(format!(
+                        "Breakpoint condition `{condition}` is not a boolean, it's {value}"
+                    ));
+                    return true;
+                }
+                Err(error) => {
+                    self.log(format!(
+                        "Could not evaluate breakpoint condition `{condition}`: {error}"
+                    ));
+                    return true;
+                }
+            }
+        }
+
+        let breakpoint = self.breakpoints.get_mut(&location).expect("Breakpoint was found above");
+        breakpoint.hits += 1;
+        let hits = breakpoint.hits;
+        if options.hit_condition.is_some_and(|hit_condition| !hit_condition.matches(hits)) {
+            return false;
+        }
+
+        match &options.log_message {
+            Some(message) => {
+                let message = interpolate_log_message(message, |expression| {
+                    match self.evaluate(expression) {
+                        Ok(value) => value.to_string(),
+                        Err(error) => format!("<{error}>"),
+                    }
+                });
+                self.log(message);
+                false
+            }
+            None => true,
+        }
+    }
+
+    fn log(&mut self, message: String) {
+        if !self.replaying {
+            self.breakpoint_logs.push(message);
         }
     }
 
+    /// Takes the messages logged by logpoints since the last time they were taken.
+    pub(super) fn take_breakpoint_logs(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.breakpoint_logs)
+    }
+
     pub(super) fn is_valid_debug_location(&self, location: &DebugLocation) -> bool {
         if location.circuit_id as usize >= self.circuits.len() {
             return false;
@@ -938,21 +1471,60 @@ impl<'a, B: BlackBoxFunctionSolver<FieldElement>> DebugContext<'a, B> {
     }
 
     pub(super) fn is_breakpoint_set(&self, location: &DebugLocation) -> bool {
-        self.breakpoints.contains(location)
+        self.breakpoints.contains_key(location)
     }
 
     pub(super) fn add_breakpoint(&mut self, location: DebugLocation) -> bool {
-        self.breakpoints.insert(location)
+        self.add_breakpoint_with_options(location, BreakpointOptions::default())
+    }
+
+    /// Sets a breakpoint, replacing the options of any breakpoint already set at the location.
+    /// Returns whether there wasn't a breakpoint at the location yet.
+    pub(super) fn add_breakpoint_with_options(
+        &mut self,
+        location: DebugLocation,
+        options: BreakpointOptions,
+    ) -> bool {
+        self.checkpoints.clear();
+        self.breakpoints.insert(location, Breakpoint::new(options)).is_none()
     }
 
     pub(super) fn delete_breakpoint(&mut self, location: &DebugLocation) -> bool {
-        self.breakpoints.remove(location)
+        self.checkpoints.clear();
+        self.breakpoints.remove(location).is_some()
     }
 
     pub(super) fn clear_breakpoints(&mut self) {
+        self.checkpoints.clear();
         self.breakpoints.clear();
     }
 
+    /// Watches some execution state for changes. Returns whether it wasn't watched yet.
+    pub(super) fn add_data_breakpoint(&mut self, data_breakpoint: DataBreakpoint) -> bool {
+        if self.data_breakpoints.contains(&data_breakpoint) {
+            return false;
+        }
+        self.checkpoints.clear();
+        self.data_breakpoints.push(data_breakpoint);
+        true
+    }
+
+    pub(super) fn delete_data_breakpoint(&mut self, data_breakpoint: &DataBreakpoint) -> bool {
+        self.checkpoints.clear();
+        let count = self.data_breakpoints.len();
+        self.data_breakpoints.retain(|watched| watched != data_breakpoint);
+        self.data_breakpoints.len() != count
+    }
+
+    pub(super) fn clear_data_breakpoints(&mut self) {
+        self.checkpoints.clear();
+        self.data_breakpoints.clear();
+    }
+
+    pub(super) fn get_data_breakpoints(&self) -> &[DataBreakpoint] {
+        &self.data_breakpoints
+    }
+
     pub(super) fn is_solved(&self) -> bool {
         matches!(self.acvm.get_status(), ACVMStatus::Solved)
     }
@@ -964,6 +1536,47 @@ impl<'a, B: BlackBoxFunctionSolver<FieldElement>> DebugContext<'a, B> {
     }
 
     pub(super) fn restart(&mut self) {
+        self.history =
+            ExecutionHistory::with_foreign_call_results(self.saved_foreign_call_results.clone());
+        self.checkpoints.clear();
+        self.reset_execution();
+    }
+
+    /// Keeps the results of the foreign calls made so far, so that they are handed back instead
+    /// of making the calls again after restarting.
+    pub(super) fn keep_foreign_call_results(&mut self) {
+        self.saved_foreign_call_results = self.history.foreign_call_results().to_vec();
+    }
+
+    /// Restarts execution answering foreign calls with the given results, in order, instead of
+    /// making them. The calls made by the debug instrumentation are still made.
+    pub(super) fn restart_with_foreign_call_results(
+        &mut self,
+        foreign_call_results: Vec<ForeignCallResult<FieldElement>>,
+    ) {
+        self.saved_foreign_call_results = foreign_call_results;
+        self.restart();
+    }
+
+    /// Captures the current state of execution, so that it can be reproduced later by
+    /// restarting from the same initial witness with the same foreign call results.
+    pub(super) fn failure_state(&self, program_hash: u64, error: String) -> FailureState {
+        let mut witness_stack = WitnessStack::default();
+        for ExecutionFrame { circuit_id, acvm } in &self.acvm_stack {
+            witness_stack.push(*circuit_id, acvm.witness_map().clone());
+        }
+        witness_stack.push(self.current_circuit_id, self.acvm.witness_map().clone());
+
+        FailureState {
+            error,
+            program_hash,
+            initial_witness: self.initial_witness.clone(),
+            foreign_call_results: self.history.foreign_call_results().to_vec(),
+            witness_stack,
+        }
+    }
+
+    fn reset_execution(&mut self) {
         // restart everything that's progress related
         // by assigning the initial values
         self.current_circuit_id = 0;
@@ -971,6 +1584,9 @@ impl<'a, B: BlackBoxFunctionSolver<FieldElement>> DebugContext<'a, B> {
         self.witness_stack = WitnessStack::default();
         self.acvm_stack = vec![];
         self.foreign_call_executor.restart(self.debug_artifact);
+        for breakpoint in self.breakpoints.values_mut() {
+            breakpoint.hits = 0;
+        }
         self.acvm = initialize_acvm(
             self.backend,
             self.circuits,
@@ -1082,6 +1698,7 @@ fn add_opcode_locations_map(
 mod tests {
     use super::*;
 
+    use crate::breakpoints::{DataBreakpoint, HitCondition};
     use crate::foreign_calls::DefaultDebugForeignCallExecutor;
     use acvm::{
         acir::{
@@ -1095,7 +1712,8 @@ mod tests {
         },
         blackbox_solver::StubbedBlackBoxSolver,
         brillig_vm::brillig::{
-            BinaryFieldOp, HeapValueType, MemoryAddress, Opcode as BrilligOpcode, ValueOrArray,
+            BinaryFieldOp, BinaryIntOp, HeapValueType, MemoryAddress, Opcode as BrilligOpcode,
+            ValueOrArray,
         },
     };
 
@@ -1248,10 +1866,9 @@ mod tests {
         assert_eq!(context.get_current_debug_location(), None);
     }
 
-    #[test]
-    fn test_break_brillig_block_while_stepping_acir_opcodes() {
-        let solver = StubbedBlackBoxSolver::default();
-        let fe_0 = FieldElement::zero();
+    /// A circuit that computes z = x + y in a Brillig block and checks the result, where x, y
+    /// and z are witnesses 1, 2 and 3, along with the bytecode of the Brillig block.
+    fn brillig_sum_program() -> (Circuit<FieldElement>, BrilligBytecode<FieldElement>) {
         let fe_1 = FieldElement::one();
         let w_x = Witness(1);
         let w_y = Witness(2);
@@ -1315,11 +1932,19 @@ mod tests {
             Opcode::AssertZero(Expression {
                 mul_terms: vec![],
                 linear_combinations: vec![(fe_1, w_x), (fe_1, w_y), (-fe_1, w_z)],
-                q_c: fe_0,
+                q_c: FieldElement::zero(),
             }),
         ];
-        let current_witness_index = 3;
-        let circuit = Circuit { current_witness_index, opcodes, ..Circuit::default() };
+        let circuit = Circuit { current_witness_index: 3, opcodes, ..Circuit::default() };
+        (circuit, brillig_bytecode)
+    }
+
+    #[test]
+    fn test_break_brillig_block_while_stepping_acir_opcodes() {
+        let solver = StubbedBlackBoxSolver::default();
+        let fe_1 = FieldElement::one();
+
+        let (circuit, brillig_bytecode) = brillig_sum_program();
         let circuits = &[circuit];
 
         let debug_symbols = vec![];
@@ -1376,6 +2001,467 @@ mod tests {
         assert_eq!(context.get_current_debug_location(), None);
     }
 
+    #[test]
+    fn test_reverse_stepping() {
+        let solver = StubbedBlackBoxSolver::default();
+        let fe_1 = FieldElement::one();
+        let w_x = Witness(1);
+        let w_y = Witness(2);
+        let w_z = Witness(3);
+
+        let (circuit, brillig_bytecode) = brillig_sum_program();
+        let circuits = &[circuit];
+
+        let debug_symbols = vec![];
+        let file_map = BTreeMap::new();
+        let debug_artifact = &DebugArtifact { debug_symbols, file_map };
+
+        let initial_witness = BTreeMap::from([(w_x, fe_1), (w_y, fe_1)]).into();
+
+        let foreign_call_executor = Box::new(DefaultDebugForeignCallExecutor::from_artifact(
+            std::io::stdout(),
+            None,
+            debug_artifact,
+            None,
+            String::new(),
+        ));
+        let brillig_funcs = &[brillig_bytecode];
+        let mut context = DebugContext::<StubbedBlackBoxSolver>::new(
+            &solver,
+            circuits,
+            debug_artifact,
+            initial_witness,
+            foreign_call_executor,
+            brillig_funcs,
+        );
+
+        let brillig_location = |brillig_index| DebugLocation {
+            circuit_id: 0,
+            opcode_location: OpcodeLocation::Brillig { acir_index: 0, brillig_index },
+            brillig_function_id: Some(BrilligFunctionId(0)),
+        };
+        let acir_location = |acir_index| DebugLocation {
+            circuit_id: 0,
+            opcode_location: OpcodeLocation::Acir(acir_index),
+            brillig_function_id: None,
+        };
+
+        for _ in 0..3 {
+            assert!(matches!(context.step_into_opcode(), DebugCommandResult::Ok));
+        }
+        assert_eq!(context.get_current_debug_location(), Some(brillig_location(3)));
+
+        // step back into the Brillig block
+        let result = context.reverse_step_into_opcode();
+        assert!(matches!(result, DebugCommandResult::Ok));
+        assert_eq!(context.get_current_debug_location(), Some(brillig_location(2)));
+
+        // step back out of the Brillig block
+        let result = context.reverse_step_acir_opcode();
+        assert!(matches!(result, DebugCommandResult::Ok));
+        assert_eq!(context.get_current_debug_location(), Some(acir_location(0)));
+
+        // stepping back from the start of the program does nothing
+        let result = context.reverse_step_into_opcode();
+        assert!(matches!(result, DebugCommandResult::Ok));
+        assert_eq!(context.get_current_debug_location(), Some(acir_location(0)));
+
+        // run to the end, then step back to the last ACIR opcode, after the Brillig block wrote z,
+        // replaying from the last checkpoint taken on the way
+        assert!(matches!(context.cont(), DebugCommandResult::Done));
+        assert!(!context.checkpoints.is_empty());
+        let result = context.reverse_step_into_opcode();
+        assert!(matches!(result, DebugCommandResult::Ok));
+        assert_eq!(context.get_current_debug_location(), Some(acir_location(1)));
+        assert_eq!(context.get_witness_map().get(&w_z), Some(&FieldElement::from(2u64)));
+
+        // run backwards to the breakpoint in the Brillig block
+        assert!(context.add_breakpoint(brillig_location(1)));
+        let result = context.reverse_cont();
+        assert!(matches!(result, DebugCommandResult::BreakpointReached(_)));
+        assert_eq!(context.get_current_debug_location(), Some(brillig_location(1)));
+
+        // there's no breakpoint before it, so run backwards to the start of the program
+        let result = context.reverse_cont();
+        assert!(matches!(result, DebugCommandResult::Ok));
+        assert_eq!(context.get_current_debug_location(), Some(acir_location(0)));
+    }
+
+    #[test]
+    fn test_breakpoint_conditions_and_logpoints() {
+        let solver = StubbedBlackBoxSolver::default();
+        let fe_1 = FieldElement::one();
+        let w_x = Witness(1);
+
+        // x - 1 = 0, three times
+        let x_is_one = Opcode::AssertZero(Expression {
+            mul_terms: vec![],
+            linear_combinations: vec![(fe_1, w_x)],
+            q_c: -fe_1,
+        });
+        let opcodes = vec![x_is_one.clone(), x_is_one.clone(), x_is_one];
+        let circuit = Circuit { current_witness_index: 1, opcodes, ..Circuit::default() };
+        let circuits = &[circuit];
+
+        let debug_symbols = vec![];
+        let file_map = BTreeMap::new();
+        let debug_artifact = &DebugArtifact { debug_symbols, file_map };
+
+        let initial_witness = BTreeMap::from([(w_x, fe_1)]).into();
+
+        let foreign_call_executor = Box::new(DefaultDebugForeignCallExecutor::from_artifact(
+            std::io::stdout(),
+            None,
+            debug_artifact,
+            None,
+            String::new(),
+        ));
+        let mut context = DebugContext::<StubbedBlackBoxSolver>::new(
+            &solver,
+            circuits,
+            debug_artifact,
+            initial_witness,
+            foreign_call_executor,
+            &[],
+        );
+
+        let location = DebugLocation {
+            circuit_id: 0,
+            opcode_location: OpcodeLocation::Acir(1),
+            brillig_function_id: None,
+        };
+
+        // a logpoint logs its message without stopping
+        let options = BreakpointOptions {
+            log_message: Some(String::from("reached {1 + 1}")),
+            ..BreakpointOptions::default()
+        };
+        assert!(context.add_breakpoint_with_options(location, options));
+        assert!(matches!(context.cont(), DebugCommandResult::Done));
+        assert_eq!(context.take_breakpoint_logs(), vec![String::from("reached 0x02")]);
+
+        // the opcode only runs once, so a breakpoint that stops on its second hit never stops
+        context.restart();
+        let options = BreakpointOptions {
+            hit_condition: Some(HitCondition::Equal(2)),
+            ..BreakpointOptions::default()
+        };
+        assert!(!context.add_breakpoint_with_options(location, options));
+        assert!(matches!(context.cont(), DebugCommandResult::Done));
+        assert!(context.take_breakpoint_logs().is_empty());
+
+        // false conditions don't stop
+        context.restart();
+        let options = BreakpointOptions {
+            condition: Some(String::from("1 == 2")),
+            ..BreakpointOptions::default()
+        };
+        context.add_breakpoint_with_options(location, options);
+        assert!(matches!(context.cont(), DebugCommandResult::Done));
+
+        // conditions that can't be evaluated stop and log why
+        context.restart();
+        let options = BreakpointOptions {
+            condition: Some(String::from("missing > 1")),
+            ..BreakpointOptions::default()
+        };
+        context.add_breakpoint_with_options(location, options);
+        assert!(matches!(context.cont(), DebugCommandResult::BreakpointReached(_)));
+        assert_eq!(context.take_breakpoint_logs().len(), 1);
+    }
+
+    #[test]
+    fn test_breakpoint_hit_conditions() {
+        let solver = StubbedBlackBoxSolver::default();
+
+        let zero = MemoryAddress::direct(0);
+        let counter = MemoryAddress::direct(1);
+        let one = MemoryAddress::direct(2);
+        let limit = MemoryAddress::direct(3);
+        let keep_looping = MemoryAddress::direct(4);
+        let u32_const = |destination, value: u64| BrilligOpcode::Const {
+            destination,
+            bit_size: BitSize::Integer(IntegerBitSize::U32),
+            value: FieldElement::from(value),
+        };
+
+        // This Brillig block is equivalent to: for counter in 1..=5 {}
+        let brillig_bytecode = BrilligBytecode {
+            bytecode: vec![
+                u32_const(zero, 0),
+                u32_const(counter, 0),
+                u32_const(one, 1),
+                u32_const(limit, 5),
+                BrilligOpcode::BinaryIntOp {
+                    destination: counter,
+                    op: BinaryIntOp::Add,
+                    bit_size: IntegerBitSize::U32,
+                    lhs: counter,
+                    rhs: one,
+                },
+                BrilligOpcode::BinaryIntOp {
+                    destination: keep_looping,
+                    op: BinaryIntOp::LessThan,
+                    bit_size: IntegerBitSize::U32,
+                    lhs: counter,
+                    rhs: limit,
+                },
+                BrilligOpcode::JumpIf { condition: keep_looping, location: 4 },
+                BrilligOpcode::Stop { return_data: HeapVector { pointer: zero, size: zero } },
+            ],
+        };
+        let opcodes = vec![Opcode::BrilligCall {
+            id: BrilligFunctionId(0),
+            inputs: vec![],
+            outputs: vec![],
+            predicate: None,
+        }];
+        let circuit = Circuit { current_witness_index: 0, opcodes, ..Circuit::default() };
+        let circuits = &[circuit];
+
+        let debug_symbols = vec![];
+        let file_map = BTreeMap::new();
+        let debug_artifact = &DebugArtifact { debug_symbols, file_map };
+
+        let foreign_call_executor = Box::new(DefaultDebugForeignCallExecutor::from_artifact(
+            std::io::stdout(),
+            None,
+            debug_artifact,
+            None,
+            String::new(),
+        ));
+        let brillig_funcs = &[brillig_bytecode];
+        let mut context = DebugContext::<StubbedBlackBoxSolver>::new(
+            &solver,
+            circuits,
+            debug_artifact,
+            WitnessMap::default(),
+            foreign_call_executor,
+            brillig_funcs,
+        );
+
+        // the start of the loop body, which is hit once per iteration
+        let location = DebugLocation {
+            circuit_id: 0,
+            opcode_location: OpcodeLocation::Brillig { acir_index: 0, brillig_index: 4 },
+            brillig_function_id: Some(BrilligFunctionId(0)),
+        };
+        // the iterations that already ran when the loop body is reached
+        let iterations = |context: &DebugContext<StubbedBlackBoxSolver>| {
+            context.get_brillig_memory().map(|memory| memory[counter.to_usize()].to_field())
+        };
+
+        // `== 3` stops on the third hit only
+        let options = BreakpointOptions {
+            hit_condition: Some(HitCondition::Equal(3)),
+            ..BreakpointOptions::default()
+        };
+        assert!(context.add_breakpoint_with_options(location, options));
+        assert!(matches!(context.cont(), DebugCommandResult::BreakpointReached(_)));
+        assert_eq!(iterations(&context), Some(FieldElement::from(2u64)));
+        assert!(matches!(context.cont(), DebugCommandResult::Done));
+
+        // `% 2` stops on every second hit
+        context.restart();
+        let options = BreakpointOptions {
+            hit_condition: Some(HitCondition::Multiple(2)),
+            ..BreakpointOptions::default()
+        };
+        assert!(!context.add_breakpoint_with_options(location, options));
+        assert!(matches!(context.cont(), DebugCommandResult::BreakpointReached(_)));
+        assert_eq!(iterations(&context), Some(FieldElement::from(1u64)));
+        assert!(matches!(context.cont(), DebugCommandResult::BreakpointReached(_)));
+        assert_eq!(iterations(&context), Some(FieldElement::from(3u64)));
+        assert!(matches!(context.cont(), DebugCommandResult::Done));
+    }
+
+    #[test]
+    fn test_data_breakpoints() {
+        let solver = StubbedBlackBoxSolver::default();
+        let fe_1 = FieldElement::one();
+        let w_x = Witness(1);
+        let w_y = Witness(2);
+        let w_z = Witness(3);
+
+        let (circuit, brillig_bytecode) = brillig_sum_program();
+        let circuits = &[circuit];
+
+        let debug_symbols = vec![];
+        let file_map = BTreeMap::new();
+        let debug_artifact = &DebugArtifact { debug_symbols, file_map };
+
+        let initial_witness = BTreeMap::from([(w_x, fe_1), (w_y, fe_1)]).into();
+
+        let foreign_call_executor = Box::new(DefaultDebugForeignCallExecutor::from_artifact(
+            std::io::stdout(),
+            None,
+            debug_artifact,
+            None,
+            String::new(),
+        ));
+        let brillig_funcs = &[brillig_bytecode];
+        let mut context = DebugContext::<StubbedBlackBoxSolver>::new(
+            &solver,
+            circuits,
+            debug_artifact,
+            initial_witness,
+            foreign_call_executor,
+            brillig_funcs,
+        );
+
+        let brillig_location = |brillig_index| DebugLocation {
+            circuit_id: 0,
+            opcode_location: OpcodeLocation::Brillig { acir_index: 0, brillig_index },
+            brillig_function_id: Some(BrilligFunctionId(0)),
+        };
+        let memory = DataBreakpoint::BrilligMemory(1);
+        let zero_memory = DataBreakpoint::BrilligMemory(2);
+        let witness = DataBreakpoint::Witness { circuit_id: 0, witness: w_z };
+        assert!(context.add_data_breakpoint(memory));
+        assert!(context.add_data_breakpoint(zero_memory));
+        assert!(context.add_data_breakpoint(witness));
+        assert!(!context.add_data_breakpoint(witness));
+
+        // memory address 2 is written with the zero it already reads as
+        let DebugCommandResult::DataBreakpointReached(hit) = context.cont() else {
+            panic!("Expected the memory data breakpoint to be reached");
+        };
+        assert_eq!(hit.data_breakpoint, zero_memory);
+        assert_eq!(hit.location, brillig_location(1));
+        assert_eq!(hit.old_value, Some(FieldElement::zero()));
+        assert_eq!(hit.new_value, FieldElement::zero());
+
+        // y is copied into memory address 1 from the calldata
+        let DebugCommandResult::DataBreakpointReached(hit) = context.cont() else {
+            panic!("Expected the memory data breakpoint to be reached");
+        };
+        assert_eq!(hit.data_breakpoint, memory);
+        assert_eq!(hit.location, brillig_location(3));
+        assert_eq!(hit.new_value, fe_1);
+
+        // z is assigned when the Brillig block stops
+        let DebugCommandResult::DataBreakpointReached(hit) = context.cont() else {
+            panic!("Expected the witness data breakpoint to be reached");
+        };
+        assert_eq!(hit.data_breakpoint, witness);
+        assert_eq!(hit.location, brillig_location(5));
+        assert_eq!(hit.old_value, None);
+        assert_eq!(hit.new_value, FieldElement::from(2u64));
+
+        assert!(matches!(context.cont(), DebugCommandResult::Done));
+
+        assert!(context.delete_data_breakpoint(&memory));
+        assert!(context.delete_data_breakpoint(&zero_memory));
+        assert_eq!(context.get_data_breakpoints(), &[witness]);
+    }
+
+    #[test]
+    fn test_data_breakpoint_on_last_opcode() {
+        let solver = StubbedBlackBoxSolver::default();
+        let fe_1 = FieldElement::one();
+        let w_x = Witness(1);
+
+        // x - 1 = 0, which assigns x
+        let opcodes = vec![Opcode::AssertZero(Expression {
+            mul_terms: vec![],
+            linear_combinations: vec![(fe_1, w_x)],
+            q_c: -fe_1,
+        })];
+        let circuit = Circuit { current_witness_index: 1, opcodes, ..Circuit::default() };
+        let circuits = &[circuit];
+
+        let debug_symbols = vec![];
+        let file_map = BTreeMap::new();
+        let debug_artifact = &DebugArtifact { debug_symbols, file_map };
+
+        let foreign_call_executor = Box::new(DefaultDebugForeignCallExecutor::from_artifact(
+            std::io::stdout(),
+            None,
+            debug_artifact,
+            None,
+            String::new(),
+        ));
+        let mut context = DebugContext::<StubbedBlackBoxSolver>::new(
+            &solver,
+            circuits,
+            debug_artifact,
+            WitnessMap::default(),
+            foreign_call_executor,
+            &[],
+        );
+
+        let witness = DataBreakpoint::Witness { circuit_id: 0, witness: w_x };
+        assert!(context.add_data_breakpoint(witness));
+
+        // the hit is reported before execution is done
+        let DebugCommandResult::DataBreakpointReached(hit) = context.cont() else {
+            panic!("Expected the witness data breakpoint to be reached");
+        };
+        assert_eq!(hit.data_breakpoint, witness);
+        assert_eq!(hit.new_value, fe_1);
+        assert!(matches!(context.cont(), DebugCommandResult::Done));
+    }
+
+    #[test]
+    fn test_failure_state() {
+        let solver = StubbedBlackBoxSolver::default();
+        let fe_1 = FieldElement::one();
+        let w_x = Witness(1);
+
+        // x - 1 = 0, then x - 2 = 0, which fails
+        let opcodes = vec![
+            Opcode::AssertZero(Expression {
+                mul_terms: vec![],
+                linear_combinations: vec![(fe_1, w_x)],
+                q_c: -fe_1,
+            }),
+            Opcode::AssertZero(Expression {
+                mul_terms: vec![],
+                linear_combinations: vec![(fe_1, w_x)],
+                q_c: -FieldElement::from(2u64),
+            }),
+        ];
+        let circuit = Circuit { current_witness_index: 1, opcodes, ..Circuit::default() };
+        let circuits = &[circuit];
+
+        let debug_symbols = vec![];
+        let file_map = BTreeMap::new();
+        let debug_artifact = &DebugArtifact { debug_symbols, file_map };
+
+        let initial_witness: WitnessMap<FieldElement> = BTreeMap::from([(w_x, fe_1)]).into();
+
+        let foreign_call_executor = Box::new(DefaultDebugForeignCallExecutor::from_artifact(
+            std::io::stdout(),
+            None,
+            debug_artifact,
+            None,
+            String::new(),
+        ));
+        let mut context = DebugContext::<StubbedBlackBoxSolver>::new(
+            &solver,
+            circuits,
+            debug_artifact,
+            initial_witness.clone(),
+            foreign_call_executor,
+            &[],
+        );
+
+        assert!(matches!(context.cont(), DebugCommandResult::Error(_)));
+        let failure_state = context.failure_state(0, String::from("failed"));
+        assert_eq!(failure_state.initial_witness, initial_witness);
+        assert_eq!(failure_state.witness_stack.peek().unwrap().witness, initial_witness);
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("failure.json");
+        failure_state.save(&path).unwrap();
+        assert_eq!(FailureState::load(&path).unwrap(), failure_state);
+
+        // reproducing the failure ends up in the same state
+        context.restart_with_foreign_call_results(failure_state.foreign_call_results.clone());
+        assert!(matches!(context.cont(), DebugCommandResult::Error(_)));
+        assert_eq!(context.failure_state(0, String::from("failed")), failure_state);
+    }
+
     #[test]
     fn test_address_debug_location_mapping() {
         let solver = StubbedBlackBoxSolver::default();
@@ -1,28 +1,33 @@
 use std::collections::BTreeMap;
 use std::io::{Read, Write};
 
+use acvm::acir::native_types::Witness;
 use acvm::{BlackBoxFunctionSolver, FieldElement};
 use bn254_blackbox_solver::Bn254BlackBoxSolver;
 use nargo::NargoError;
+use nargo::foreign_calls::transcript::ReplayForeignCallExecutor;
 
 use crate::DebugProject;
+use crate::breakpoints::{BreakpointOptions, DataBreakpoint, DataBreakpointHit, HitConditionError};
 use crate::context::{DebugCommandResult, DebugLocation, RunParams};
 use crate::context::{DebugContext, DebugExecutionResult};
+use crate::expression::{type_name, validate_expression};
 use crate::foreign_calls::DefaultDebugForeignCallExecutor;
 
 use dap::errors::ServerError;
-use dap::events::StoppedEventBody;
+use dap::events::{OutputEventBody, StoppedEventBody};
 use dap::prelude::Event;
 use dap::requests::{Command, Request, SetBreakpointsArguments};
 use dap::responses::{
-    ContinueResponse, DisassembleResponse, ResponseBody, ScopesResponse, SetBreakpointsResponse,
+    ContinueResponse, DataBreakpointInfoResponse, DisassembleResponse, EvaluateResponse,
+    ResponseBody, ScopesResponse, SetBreakpointsResponse, SetDataBreakpointsResponse,
     SetExceptionBreakpointsResponse, SetInstructionBreakpointsResponse, StackTraceResponse,
     ThreadsResponse, VariablesResponse,
 };
 use dap::server::Server;
 use dap::types::{
-    Breakpoint, DisassembledInstruction, Scope, Source, StackFrame, SteppingGranularity,
-    StoppedEventReason, Thread, Variable,
+    Breakpoint, DataBreakpointAccessType, DisassembledInstruction, OutputEventCategory, Scope,
+    Source, StackFrame, SteppingGranularity, StoppedEventReason, Thread, Variable,
 };
 use noirc_artifacts::debug::DebugArtifact;
 
@@ -36,14 +41,16 @@ pub struct DapSession<'a, R: Read, W: Write, B: BlackBoxFunctionSolver<FieldElem
     debug_artifact: &'a DebugArtifact,
     running: bool,
     next_breakpoint_id: BreakpointId,
-    instruction_breakpoints: Vec<(DebugLocation, BreakpointId)>,
-    source_breakpoints: BTreeMap<FileId, Vec<(DebugLocation, BreakpointId)>>,
+    instruction_breakpoints: Vec<(DebugLocation, BreakpointId, BreakpointOptions)>,
+    source_breakpoints: BTreeMap<FileId, Vec<(DebugLocation, BreakpointId, BreakpointOptions)>>,
+    data_breakpoints: Vec<(DataBreakpoint, BreakpointId)>,
     last_result: DebugCommandResult,
 }
 
 enum ScopeReferences {
     Locals = 1,
     WitnessMap = 2,
+    BrilligMemory = 3,
     InvalidScope = 0,
 }
 
@@ -52,6 +59,7 @@ impl From<i64> for ScopeReferences {
         match value {
             1 => Self::Locals,
             2 => Self::WitnessMap,
+            3 => Self::BrilligMemory,
             _ => Self::InvalidScope,
         }
     }
@@ -64,15 +72,17 @@ impl<'a, R: Read, W: Write, B: BlackBoxFunctionSolver<FieldElement>> DapSession<
         project: &'a DebugProject,
         debug_artifact: &'a DebugArtifact,
         foreign_call_resolver_url: Option<String>,
+        oracle_transcript: Option<ReplayForeignCallExecutor<FieldElement>>,
     ) -> Self {
         let context = DebugContext::new(
             solver,
             &project.compiled_program.program.functions,
             debug_artifact,
             project.initial_witness.clone(),
-            Box::new(DefaultDebugForeignCallExecutor::from_artifact(
+            Box::new(DefaultDebugForeignCallExecutor::from_artifact_with_transcript(
                 std::io::stdout(),
                 foreign_call_resolver_url,
+                oracle_transcript,
                 debug_artifact,
                 Some(project.root_dir.clone()),
                 project.package_name.clone(),
@@ -87,6 +97,7 @@ impl<'a, R: Read, W: Write, B: BlackBoxFunctionSolver<FieldElement>> DapSession<
             next_breakpoint_id: 1,
             instruction_breakpoints: vec![],
             source_breakpoints: BTreeMap::new(),
+            data_breakpoints: vec![],
             last_result: DebugCommandResult::Ok,
         }
     }
@@ -179,12 +190,32 @@ impl<'a, R: Read, W: Write, B: BlackBoxFunctionSolver<FieldElement>> DapSession<
                 Command::Continue(_) => {
                     self.handle_continue(req)?;
                 }
+                Command::StepBack(ref args) => {
+                    let granularity =
+                        args.granularity.as_ref().unwrap_or(&SteppingGranularity::Statement);
+                    match granularity {
+                        SteppingGranularity::Instruction => self.handle_step_back(req)?,
+                        _ => self.handle_next_back(req)?,
+                    }
+                }
+                Command::ReverseContinue(_) => {
+                    self.handle_reverse_continue(req)?;
+                }
                 Command::Scopes(_) => {
                     self.handle_scopes(req)?;
                 }
                 Command::Variables(ref _args) => {
                     self.handle_variables(req)?;
                 }
+                Command::Evaluate(_) => {
+                    self.handle_evaluate(req)?;
+                }
+                Command::DataBreakpointInfo(_) => {
+                    self.handle_data_breakpoint_info(req)?;
+                }
+                Command::SetDataBreakpoints(_) => {
+                    self.handle_set_data_breakpoints(req)?;
+                }
                 _ => {
                     eprintln!("ERROR: unhandled command: {:?}", req.command);
                 }
@@ -326,15 +357,36 @@ impl<'a, R: Read, W: Write, B: BlackBoxFunctionSolver<FieldElement>> DapSession<
         self.handle_execution_result(result)
     }
 
+    fn handle_step_back(&mut self, req: Request) -> Result<(), ServerError> {
+        let result = self.context.reverse_step_into_opcode();
+        eprintln!("INFO: stepped back by instruction with result {result:?}");
+        self.server.respond(req.ack()?)?;
+        self.handle_execution_result(result)
+    }
+
+    fn handle_next_back(&mut self, req: Request) -> Result<(), ServerError> {
+        let result = self.context.reverse_next_over();
+        eprintln!("INFO: stepped back by statement with result {result:?}");
+        self.server.respond(req.ack()?)?;
+        self.handle_execution_result(result)
+    }
+
+    fn handle_reverse_continue(&mut self, req: Request) -> Result<(), ServerError> {
+        let result = self.context.reverse_cont();
+        eprintln!("INFO: reverse continue with result {result:?}");
+        self.server.respond(req.ack()?)?;
+        self.handle_execution_result(result)
+    }
+
     fn find_breakpoints_at_location(&self, debug_location: &DebugLocation) -> Vec<i64> {
         let mut result = vec![];
-        for (location, id) in &self.instruction_breakpoints {
+        for (location, id, _) in &self.instruction_breakpoints {
             if debug_location == location {
                 result.push(*id);
             }
         }
         for breakpoints in self.source_breakpoints.values() {
-            for (location, id) in breakpoints {
+            for (location, id, _) in breakpoints {
                 if debug_location == location {
                     result.push(*id);
                 }
@@ -344,6 +396,7 @@ impl<'a, R: Read, W: Write, B: BlackBoxFunctionSolver<FieldElement>> DapSession<
     }
 
     fn handle_execution_result(&mut self, result: DebugCommandResult) -> Result<(), ServerError> {
+        self.send_breakpoint_logs()?;
         self.last_result = result;
         match &self.last_result {
             DebugCommandResult::Done => {
@@ -372,11 +425,56 @@ impl<'a, R: Read, W: Write, B: BlackBoxFunctionSolver<FieldElement>> DapSession<
                     hit_breakpoint_ids: Some(breakpoint_ids),
                 }))?;
             }
+            DebugCommandResult::DataBreakpointReached(hit) => {
+                let breakpoint_ids = self
+                    .data_breakpoints
+                    .iter()
+                    .filter(|(data_breakpoint, _)| *data_breakpoint == hit.data_breakpoint)
+                    .map(|(_, id)| *id)
+                    .collect();
+                let text = self.describe_data_breakpoint_hit(hit);
+                self.server.send_event(Event::Stopped(StoppedEventBody {
+                    reason: StoppedEventReason::Data,
+                    description: Some(String::from("Paused at data breakpoint")),
+                    thread_id: Some(0),
+                    preserve_focus_hint: Some(false),
+                    text: Some(text),
+                    all_threads_stopped: Some(false),
+                    hit_breakpoint_ids: Some(breakpoint_ids),
+                }))?;
+            }
             DebugCommandResult::Error(_) => self.server.send_event(Event::Terminated(None))?,
         }
         Ok(())
     }
 
+    /// Describes the change that stopped execution at a data breakpoint, along with the
+    /// source location of the opcode responsible for it.
+    fn describe_data_breakpoint_hit(&self, hit: &DataBreakpointHit) -> String {
+        let source_location = self
+            .context
+            .get_source_location_for_debug_location(&hit.location)
+            .last()
+            .and_then(|source_location| {
+                let path = self.debug_artifact.file_map.get(&source_location.file)?.path.display();
+                let line = self.debug_artifact.location_line_number(*source_location).ok()?;
+                Some(format!(" at {path}:{line}"))
+            })
+            .unwrap_or_default();
+        format!("{hit}{source_location}")
+    }
+
+    fn send_breakpoint_logs(&mut self) -> Result<(), ServerError> {
+        for message in self.context.take_breakpoint_logs() {
+            self.server.send_event(Event::Output(OutputEventBody {
+                category: Some(OutputEventCategory::Console),
+                output: format!("{message}\n"),
+                ..OutputEventBody::default()
+            }))?;
+        }
+        Ok(())
+    }
+
     fn get_next_breakpoint_id(&mut self) -> BreakpointId {
         let id = self.next_breakpoint_id;
         self.next_breakpoint_id += 1;
@@ -385,12 +483,12 @@ impl<'a, R: Read, W: Write, B: BlackBoxFunctionSolver<FieldElement>> DapSession<
 
     fn reinstall_breakpoints(&mut self) {
         self.context.clear_breakpoints();
-        for (location, _) in &self.instruction_breakpoints {
-            self.context.add_breakpoint(*location);
+        for (location, _, options) in &self.instruction_breakpoints {
+            self.context.add_breakpoint_with_options(*location, options.clone());
         }
         for breakpoints in self.source_breakpoints.values() {
-            for (location, _) in breakpoints {
-                self.context.add_breakpoint(*location);
+            for (location, _, options) in breakpoints {
+                self.context.add_breakpoint_with_options(*location, options.clone());
             }
         }
     }
@@ -401,7 +499,7 @@ impl<'a, R: Read, W: Write, B: BlackBoxFunctionSolver<FieldElement>> DapSession<
         };
 
         // compute breakpoints to set and return
-        let mut breakpoints_to_set: Vec<(DebugLocation, i64)> = vec![];
+        let mut breakpoints_to_set: Vec<(DebugLocation, i64, BreakpointOptions)> = vec![];
         let breakpoints: Vec<Breakpoint> = args
             .breakpoints
             .iter()
@@ -426,8 +524,22 @@ impl<'a, R: Read, W: Write, B: BlackBoxFunctionSolver<FieldElement>> DapSession<
                         ..Breakpoint::default()
                     };
                 };
+                let options = match breakpoint_options(
+                    breakpoint.condition.as_ref(),
+                    breakpoint.hit_condition.as_ref(),
+                    None,
+                ) {
+                    Ok(options) => options,
+                    Err(message) => {
+                        return Breakpoint {
+                            verified: false,
+                            message: Some(message),
+                            ..Breakpoint::default()
+                        };
+                    }
+                };
                 let id = self.get_next_breakpoint_id();
-                breakpoints_to_set.push((location, id));
+                breakpoints_to_set.push((location, id, options));
                 Breakpoint {
                     id: Some(id),
                     verified: true,
@@ -469,7 +581,7 @@ impl<'a, R: Read, W: Write, B: BlackBoxFunctionSolver<FieldElement>> DapSession<
         let Some(breakpoints) = &args.breakpoints else {
             return vec![];
         };
-        let mut breakpoints_to_set: Vec<(DebugLocation, i64)> = vec![];
+        let mut breakpoints_to_set: Vec<(DebugLocation, i64, BreakpointOptions)> = vec![];
         let breakpoints = breakpoints
             .iter()
             .map(|breakpoint| {
@@ -494,10 +606,24 @@ impl<'a, R: Read, W: Write, B: BlackBoxFunctionSolver<FieldElement>> DapSession<
                         ..Breakpoint::default()
                     };
                 }
+                let options = match breakpoint_options(
+                    breakpoint.condition.as_ref(),
+                    breakpoint.hit_condition.as_ref(),
+                    breakpoint.log_message.as_ref(),
+                ) {
+                    Ok(options) => options,
+                    Err(message) => {
+                        return Breakpoint {
+                            verified: false,
+                            message: Some(message),
+                            ..Breakpoint::default()
+                        };
+                    }
+                };
                 let breakpoint_address = self.context.debug_location_to_address(&location);
                 let instruction_reference = format!("{}", breakpoint_address);
                 let breakpoint_id = self.get_next_breakpoint_id();
-                breakpoints_to_set.push((location, breakpoint_id));
+                breakpoints_to_set.push((location, breakpoint_id, options));
                 Breakpoint {
                     id: Some(breakpoint_id),
                     verified: true,
@@ -540,6 +666,11 @@ impl<'a, R: Read, W: Write, B: BlackBoxFunctionSolver<FieldElement>> DapSession<
                     variables_reference: ScopeReferences::WitnessMap as i64,
                     ..Scope::default()
                 },
+                Scope {
+                    name: String::from("Brillig Memory"),
+                    variables_reference: ScopeReferences::BrilligMemory as i64,
+                    ..Scope::default()
+                },
             ],
         })))?;
         Ok(())
@@ -577,6 +708,21 @@ impl<'a, R: Read, W: Write, B: BlackBoxFunctionSolver<FieldElement>> DapSession<
             .collect()
     }
 
+    fn build_brillig_memory(&self) -> Vec<Variable> {
+        let Some(memory) = self.context.get_brillig_memory() else {
+            return vec![];
+        };
+        memory
+            .iter()
+            .enumerate()
+            .map(|(address, value)| Variable {
+                name: address.to_string(),
+                value: value.to_string(),
+                ..Variable::default()
+            })
+            .collect()
+    }
+
     fn handle_variables(&mut self, req: Request) -> Result<(), ServerError> {
         let Command::Variables(ref args) = req.command else {
             unreachable!("handle_variables called on a different request");
@@ -585,6 +731,7 @@ impl<'a, R: Read, W: Write, B: BlackBoxFunctionSolver<FieldElement>> DapSession<
         let variables: Vec<_> = match scope {
             ScopeReferences::Locals => self.build_local_variables(),
             ScopeReferences::WitnessMap => self.build_witness_map(),
+            ScopeReferences::BrilligMemory => self.build_brillig_memory(),
             _ => {
                 eprintln!(
                     "handle_variables with an unknown variables_reference {}",
@@ -598,6 +745,122 @@ impl<'a, R: Read, W: Write, B: BlackBoxFunctionSolver<FieldElement>> DapSession<
         Ok(())
     }
 
+    /// Evaluates watch, hover and REPL expressions. The client asks for watch expressions again
+    /// every time execution stops.
+    fn handle_evaluate(&mut self, req: Request) -> Result<(), ServerError> {
+        let Command::Evaluate(ref args) = req.command else {
+            unreachable!("handle_evaluate called on a different request");
+        };
+        // Stack frame ids are their index in `get_variables`, see `build_stack_trace`
+        let evaluation = match args.frame_id {
+            Some(frame_id) => match usize::try_from(frame_id) {
+                Ok(frame_index) => self.context.evaluate_in_frame(&args.expression, frame_index),
+                Err(_) => {
+                    self.server
+                        .respond(req.error(&format!("Invalid stack frame id {frame_id}")))?;
+                    return Ok(());
+                }
+            },
+            None => self.context.evaluate(&args.expression),
+        };
+        match evaluation {
+            Ok(value) => {
+                let result = value.to_string();
+                let type_field = Some(type_name(&value.typ));
+                self.server.respond(req.success(ResponseBody::Evaluate(EvaluateResponse {
+                    result,
+                    type_field,
+                    presentation_hint: None,
+                    variables_reference: 0,
+                    named_variables: None,
+                    indexed_variables: None,
+                    memory_reference: None,
+                })))?;
+            }
+            Err(error) => {
+                let message = error.to_string();
+                self.server.respond(req.error(&message))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Data breakpoints can be set on the variables of the witness map and Brillig memory
+    /// scopes, and stop execution when the variable is written.
+    fn handle_data_breakpoint_info(&mut self, req: Request) -> Result<(), ServerError> {
+        let Command::DataBreakpointInfo(ref args) = req.command else {
+            unreachable!("handle_data_breakpoint_info called on a different request");
+        };
+        let scope: ScopeReferences = args.variables_reference.unwrap_or_default().into();
+        let data_breakpoint = match scope {
+            ScopeReferences::WitnessMap => {
+                args.name.strip_prefix('_').and_then(|index| index.parse().ok()).map(|index| {
+                    DataBreakpoint::Witness {
+                        circuit_id: self.context.current_circuit_id(),
+                        witness: Witness(index),
+                    }
+                })
+            }
+            ScopeReferences::BrilligMemory => {
+                args.name.parse().ok().map(DataBreakpoint::BrilligMemory)
+            }
+            _ => None,
+        };
+        let response = match data_breakpoint {
+            Some(data_breakpoint) => DataBreakpointInfoResponse {
+                data_id: Some(data_breakpoint_id(&data_breakpoint)),
+                description: data_breakpoint.to_string(),
+                access_types: Some(vec![DataBreakpointAccessType::Write]),
+                can_persist: Some(false),
+            },
+            None => DataBreakpointInfoResponse {
+                data_id: None,
+                description: String::from(
+                    "Data breakpoints can only be set on witnesses and Brillig memory",
+                ),
+                access_types: None,
+                can_persist: None,
+            },
+        };
+        self.server.respond(req.success(ResponseBody::DataBreakpointInfo(response)))?;
+        Ok(())
+    }
+
+    fn handle_set_data_breakpoints(&mut self, req: Request) -> Result<(), ServerError> {
+        let Command::SetDataBreakpoints(ref args) = req.command else {
+            unreachable!("handle_set_data_breakpoints called on a different request");
+        };
+
+        let mut data_breakpoints_to_set = vec![];
+        let breakpoints: Vec<Breakpoint> = args
+            .breakpoints
+            .iter()
+            .map(|breakpoint| {
+                let Some(data_breakpoint) = parse_data_breakpoint_id(&breakpoint.data_id) else {
+                    return Breakpoint {
+                        verified: false,
+                        message: Some(format!("Invalid data id {}", breakpoint.data_id)),
+                        ..Breakpoint::default()
+                    };
+                };
+                let id = self.get_next_breakpoint_id();
+                data_breakpoints_to_set.push((data_breakpoint, id));
+                Breakpoint { id: Some(id), verified: true, ..Breakpoint::default() }
+            })
+            .collect();
+
+        self.context.clear_data_breakpoints();
+        for (data_breakpoint, _) in &data_breakpoints_to_set {
+            self.context.add_data_breakpoint(*data_breakpoint);
+        }
+        self.data_breakpoints = data_breakpoints_to_set;
+
+        self.server.respond(req.success(ResponseBody::SetDataBreakpoints(
+            SetDataBreakpointsResponse { breakpoints },
+        )))?;
+        Ok(())
+    }
+
     pub fn last_error(self) -> Option<NargoError<FieldElement>> {
         match self.last_result {
             DebugCommandResult::Error(error) => Some(error),
@@ -606,6 +869,55 @@ impl<'a, R: Read, W: Write, B: BlackBoxFunctionSolver<FieldElement>> DapSession<
     }
 }
 
+/// Reads the condition, hit condition and log message of a breakpoint set by the client,
+/// or returns why they are invalid.
+fn breakpoint_options(
+    condition: Option<&String>,
+    hit_condition: Option<&String>,
+    log_message: Option<&String>,
+) -> Result<BreakpointOptions, String> {
+    // Clients send empty strings for fields the user cleared.
+    let non_empty =
+        |field: Option<&String>| field.filter(|field| !field.trim().is_empty()).cloned();
+
+    let condition = non_empty(condition);
+    if let Some(condition) = &condition {
+        validate_expression(condition).map_err(|error| error.to_string())?;
+    }
+    let hit_condition = non_empty(hit_condition)
+        .map(|hit_condition| hit_condition.parse())
+        .transpose()
+        .map_err(|error: HitConditionError| error.to_string())?;
+    let log_message = non_empty(log_message);
+
+    Ok(BreakpointOptions { condition, hit_condition, log_message })
+}
+
+/// Identifies a data breakpoint to the client, as `witness:<circuit id>:<witness index>` or
+/// `memory:<address>`.
+fn data_breakpoint_id(data_breakpoint: &DataBreakpoint) -> String {
+    match data_breakpoint {
+        DataBreakpoint::Witness { circuit_id, witness } => {
+            format!("witness:{circuit_id}:{}", witness.witness_index())
+        }
+        DataBreakpoint::BrilligMemory(address) => format!("memory:{address}"),
+    }
+}
+
+fn parse_data_breakpoint_id(data_id: &str) -> Option<DataBreakpoint> {
+    let mut parts = data_id.split(':');
+    let data_breakpoint = match parts.next()? {
+        "witness" => {
+            let circuit_id = parts.next()?.parse().ok()?;
+            let witness = Witness(parts.next()?.parse().ok()?);
+            DataBreakpoint::Witness { circuit_id, witness }
+        }
+        "memory" => DataBreakpoint::BrilligMemory(parts.next()?.parse().ok()?),
+        _ => return None,
+    };
+    parts.next().is_none().then_some(data_breakpoint)
+}
+
 pub fn run_session<R: Read, W: Write>(
     server: &mut Server<R, W>,
     project: DebugProject,
@@ -617,8 +929,14 @@ pub fn run_session<R: Read, W: Write>(
     };
 
     let solver = Bn254BlackBoxSolver(run_params.pedantic_solving);
-    let mut session =
-        DapSession::new(server, &solver, &project, &debug_artifact, run_params.oracle_resolver_url);
+    let mut session = DapSession::new(
+        server,
+        &solver,
+        &project,
+        &debug_artifact,
+        run_params.oracle_resolver_url,
+        run_params.oracle_transcript,
+    );
 
     session.run_loop()?;
     if session.context.is_solved() {
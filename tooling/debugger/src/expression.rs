@@ -0,0 +1,741 @@
+//! Evaluates Noir expressions typed into the debugger, such as `point.x + offsets[i]`,
+//! against the variables of the current stack frame.
+//!
+//! Only expressions that read variables are supported: variables, struct and tuple member
+//! accesses, indexing, literals and arithmetic, comparison and bitwise operators.
+
+use acvm::{AcirField, FieldElement};
+use noirc_artifacts::debug::StackFrame;
+use noirc_frontend::ast::{
+    ArrayLiteral, BinaryOpKind, Expression, ExpressionKind, Literal, UnaryOp,
+};
+use noirc_frontend::parser::parse_expression_with_dummy_file;
+use noirc_frontend::signed_field::SignedField;
+use noirc_printable_type::{PrintableType, PrintableValue, PrintableValueDisplay};
+use thiserror::Error;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub(super) enum EvaluationError {
+    #[error("Could not parse expression: {0}")]
+    Parse(String),
+    #[error("Unsupported expression: {0}")]
+    Unsupported(String),
+    #[error("No variables are available at this point in execution")]
+    NoStackFrame,
+    #[error("No stack frame with id {0}")]
+    UnknownStackFrame(usize),
+    #[error("No variable named `{0}` in the current scope")]
+    UnknownVariable(String),
+    #[error("Value of type {typ} has no member `{member}`")]
+    UnknownMember { typ: String, member: String },
+    #[error("Value of type {0} can't be indexed")]
+    NotIndexable(String),
+    #[error("Index {index} is out of bounds for length {length}")]
+    IndexOutOfBounds { index: SignedField, length: usize },
+    #[error("Operator `{operator}` can't be applied to {typ}")]
+    InvalidOperand { operator: String, typ: String },
+    #[error("Operator `{operator}` can't be applied to {lhs} and {rhs}")]
+    InvalidOperands { operator: String, lhs: String, rhs: String },
+    #[error("Literal {literal} doesn't fit in {typ}")]
+    LiteralOutOfRange { literal: SignedField, typ: String },
+    #[error("Operator `{operator}` overflows {typ}")]
+    Overflow { operator: String, typ: String },
+    #[error("Division by zero")]
+    DivisionByZero,
+}
+
+/// The result of evaluating an expression.
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct EvaluatedValue {
+    pub(super) value: PrintableValue<FieldElement>,
+    pub(super) typ: PrintableType,
+}
+
+impl std::fmt::Display for EvaluatedValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let display = PrintableValueDisplay::Plain(self.value.clone(), self.typ.clone());
+        write!(f, "{display}")
+    }
+}
+
+/// Parses and evaluates `source` against the variables of `frame`.
+pub(super) fn evaluate_expression(
+    source: &str,
+    frame: Option<&StackFrame<FieldElement>>,
+) -> Result<EvaluatedValue, EvaluationError> {
+    let expression = parse_expression(source)?;
+    let value = Evaluator { frame }.evaluate(&expression)?;
+    Ok(value.into_typed())
+}
+
+/// Checks that `source` is an expression, without evaluating it.
+pub(super) fn validate_expression(source: &str) -> Result<(), EvaluationError> {
+    parse_expression(source).map(|_| ())
+}
+
+fn parse_expression(source: &str) -> Result<Expression, EvaluationError> {
+    parse_expression_with_dummy_file(source).map_err(|errors| {
+        let messages: Vec<String> = errors.iter().map(|error| error.to_string()).collect();
+        EvaluationError::Parse(messages.join(", "))
+    })
+}
+
+/// A value produced while evaluating an expression.
+#[derive(Debug, Clone)]
+enum Value {
+    /// An integer literal, whose type is only known once it's combined with a typed value.
+    Literal(SignedField),
+    Typed(EvaluatedValue),
+}
+
+impl Value {
+    fn typed(value: PrintableValue<FieldElement>, typ: PrintableType) -> Self {
+        Value::Typed(EvaluatedValue { value, typ })
+    }
+
+    fn bool(value: bool) -> Self {
+        Value::typed(PrintableValue::Field(value.into()), PrintableType::Boolean)
+    }
+
+    /// Integer literals that were never combined with a typed value are fields.
+    fn into_typed(self) -> EvaluatedValue {
+        match self {
+            Value::Literal(literal) => EvaluatedValue {
+                value: PrintableValue::Field(literal.to_field_element()),
+                typ: PrintableType::Field,
+            },
+            Value::Typed(value) => value,
+        }
+    }
+
+    fn type_name(&self) -> String {
+        match self {
+            Value::Literal(_) => "an integer literal".to_string(),
+            Value::Typed(value) => type_name(&value.typ),
+        }
+    }
+
+    /// Reads a numeric value, including booleans, as a signed field so integers of any width
+    /// and sign can be operated on in the same way.
+    fn as_signed_field(&self) -> Option<SignedField> {
+        match self {
+            Value::Literal(literal) => Some(*literal),
+            Value::Typed(EvaluatedValue { value: PrintableValue::Field(field), typ }) => {
+                match typ {
+                    PrintableType::Field
+                    | PrintableType::UnsignedInteger { .. }
+                    | PrintableType::Boolean => Some(SignedField::positive(*field)),
+                    PrintableType::SignedInteger { width } => {
+                        Some(decode_signed_integer(*field, *width))
+                    }
+                    _ => None,
+                }
+            }
+            Value::Typed(_) => None,
+        }
+    }
+}
+
+struct Evaluator<'a, 'frame> {
+    frame: Option<&'a StackFrame<'frame, FieldElement>>,
+}
+
+impl Evaluator<'_, '_> {
+    fn evaluate(&self, expression: &Expression) -> Result<Value, EvaluationError> {
+        match &expression.kind {
+            ExpressionKind::Literal(literal) => self.evaluate_literal(literal, expression),
+            ExpressionKind::Variable(path) => {
+                let Some(name) = path.as_ident() else {
+                    return Err(EvaluationError::Unsupported(expression.to_string()));
+                };
+                self.lookup_variable(name.as_str())
+            }
+            ExpressionKind::Parenthesized(expression) => self.evaluate(expression),
+            ExpressionKind::Tuple(elements) => {
+                let elements = elements
+                    .iter()
+                    .map(|element| Ok(self.evaluate(element)?.into_typed()))
+                    .collect::<Result<Vec<_>, _>>()?;
+                let (array_elements, types) =
+                    elements.into_iter().map(|element| (element.value, element.typ)).unzip();
+                Ok(Value::typed(
+                    PrintableValue::Vec { array_elements, is_slice: false },
+                    PrintableType::Tuple { types },
+                ))
+            }
+            ExpressionKind::MemberAccess(member_access) => {
+                let lhs = self.evaluate(&member_access.lhs)?.into_typed();
+                member(lhs, member_access.rhs.as_str())
+            }
+            ExpressionKind::Index(index) => {
+                let collection = self.evaluate(&index.collection)?.into_typed();
+                let index = self.evaluate(&index.index)?;
+                element_at(collection, index)
+            }
+            ExpressionKind::Prefix(prefix) => {
+                let rhs = self.evaluate(&prefix.rhs)?;
+                evaluate_prefix(prefix.operator, rhs)
+            }
+            ExpressionKind::Infix(infix) => {
+                let lhs = self.evaluate(&infix.lhs)?;
+                let rhs = self.evaluate(&infix.rhs)?;
+                evaluate_infix(infix.operator.contents, lhs, rhs)
+            }
+            _ => Err(EvaluationError::Unsupported(expression.to_string())),
+        }
+    }
+
+    fn evaluate_literal(
+        &self,
+        literal: &Literal,
+        expression: &Expression,
+    ) -> Result<Value, EvaluationError> {
+        match literal {
+            Literal::Integer(value) => Ok(Value::Literal(*value)),
+            Literal::Bool(value) => Ok(Value::bool(*value)),
+            Literal::Str(string) => Ok(Value::typed(
+                PrintableValue::String(string.clone()),
+                PrintableType::String { length: string.len() as u32 },
+            )),
+            Literal::Unit => {
+                Ok(Value::typed(PrintableValue::Field(FieldElement::zero()), PrintableType::Unit))
+            }
+            Literal::Array(ArrayLiteral::Standard(elements)) => {
+                self.evaluate_array_literal(elements, false)
+            }
+            Literal::Slice(ArrayLiteral::Standard(elements)) => {
+                self.evaluate_array_literal(elements, true)
+            }
+            _ => Err(EvaluationError::Unsupported(expression.to_string())),
+        }
+    }
+
+    fn evaluate_array_literal(
+        &self,
+        elements: &[Expression],
+        is_slice: bool,
+    ) -> Result<Value, EvaluationError> {
+        let elements =
+            elements.iter().map(|element| self.evaluate(element)).collect::<Result<Vec<_>, _>>()?;
+
+        // Like the compiler does, literals take the type of the first typed element.
+        let element_type = elements
+            .iter()
+            .find_map(|element| match element {
+                Value::Literal(_) => None,
+                Value::Typed(value) => Some(value.typ.clone()),
+            })
+            .unwrap_or(PrintableType::Field);
+
+        let array_elements = elements
+            .into_iter()
+            .map(|element| {
+                let element = cast_literal(element, &element_type)?;
+                if element.typ != element_type {
+                    let operator = if is_slice { "&[..]" } else { "[..]" }.to_string();
+                    return Err(EvaluationError::InvalidOperands {
+                        operator,
+                        lhs: type_name(&element_type),
+                        rhs: type_name(&element.typ),
+                    });
+                }
+                Ok(element.value)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let typ = Box::new(element_type);
+        let typ = if is_slice {
+            PrintableType::Slice { typ }
+        } else {
+            PrintableType::Array { length: array_elements.len() as u32, typ }
+        };
+        Ok(Value::typed(PrintableValue::Vec { array_elements, is_slice }, typ))
+    }
+
+    fn lookup_variable(&self, name: &str) -> Result<Value, EvaluationError> {
+        let frame = self.frame.ok_or(EvaluationError::NoStackFrame)?;
+        // Variables are in declaration order, so the last one with the name shadows the others.
+        let (_, value, typ) = frame
+            .variables
+            .iter()
+            .rev()
+            .find(|(variable_name, ..)| *variable_name == name)
+            .ok_or_else(|| EvaluationError::UnknownVariable(name.to_string()))?;
+        Ok(Value::typed((*value).clone(), dereference((*typ).clone())))
+    }
+}
+
+/// References hold the value they point to, so they can be read as that value.
+fn dereference(typ: PrintableType) -> PrintableType {
+    match typ {
+        PrintableType::Reference { typ, .. } => dereference(*typ),
+        typ => typ,
+    }
+}
+
+fn member(lhs: EvaluatedValue, member: &str) -> Result<Value, EvaluationError> {
+    let unknown_member =
+        || EvaluationError::UnknownMember { typ: type_name(&lhs.typ), member: member.to_string() };
+
+    match (&lhs.value, &lhs.typ) {
+        (PrintableValue::Struct(values), PrintableType::Struct { fields, .. }) => {
+            let (_, typ) =
+                fields.iter().find(|(name, _)| name == member).ok_or_else(unknown_member)?;
+            let value = values.get(member).ok_or_else(unknown_member)?;
+            Ok(Value::typed(value.clone(), dereference(typ.clone())))
+        }
+        (PrintableValue::Vec { array_elements, .. }, PrintableType::Tuple { types }) => {
+            let index: usize = member.parse().map_err(|_| unknown_member())?;
+            let value = array_elements.get(index).ok_or_else(unknown_member)?;
+            let typ = types.get(index).ok_or_else(unknown_member)?;
+            Ok(Value::typed(value.clone(), dereference(typ.clone())))
+        }
+        _ => Err(unknown_member()),
+    }
+}
+
+fn element_at(collection: EvaluatedValue, index: Value) -> Result<Value, EvaluationError> {
+    let (array_elements, typ) = match (&collection.value, &collection.typ) {
+        (PrintableValue::Vec { array_elements, .. }, PrintableType::Array { typ, .. })
+        | (PrintableValue::Vec { array_elements, .. }, PrintableType::Slice { typ }) => {
+            (array_elements, typ)
+        }
+        _ => return Err(EvaluationError::NotIndexable(type_name(&collection.typ))),
+    };
+
+    let is_integer = matches!(
+        &index,
+        Value::Literal(_)
+            | Value::Typed(EvaluatedValue {
+                typ: PrintableType::Field | PrintableType::UnsignedInteger { .. },
+                ..
+            })
+    );
+    if !is_integer {
+        return Err(EvaluationError::InvalidOperand {
+            operator: "[]".to_string(),
+            typ: index.type_name(),
+        });
+    }
+
+    let index = index.as_signed_field().expect("Checked to be an integer above");
+    let value = index
+        .try_to_unsigned::<usize>()
+        .and_then(|position| array_elements.get(position))
+        .ok_or(EvaluationError::IndexOutOfBounds { index, length: array_elements.len() })?;
+    Ok(Value::typed(value.clone(), dereference((**typ).clone())))
+}
+
+fn evaluate_prefix(operator: UnaryOp, rhs: Value) -> Result<Value, EvaluationError> {
+    let invalid_operand = |rhs: &Value| EvaluationError::InvalidOperand {
+        operator: operator.to_string(),
+        typ: rhs.type_name(),
+    };
+
+    match operator {
+        // References and dereferences are transparent, as variables already hold what they point to.
+        UnaryOp::Reference { .. } | UnaryOp::Dereference { .. } => Ok(rhs),
+        UnaryOp::Minus => match &rhs {
+            Value::Literal(literal) => Ok(Value::Literal(-*literal)),
+            Value::Typed(value) => match value.typ {
+                PrintableType::Field | PrintableType::SignedInteger { .. } => {
+                    let negated = -rhs.as_signed_field().ok_or_else(|| invalid_operand(&rhs))?;
+                    encode_number(negated, &value.typ, &operator.to_string())
+                }
+                _ => Err(invalid_operand(&rhs)),
+            },
+        },
+        UnaryOp::Not => match &rhs {
+            Value::Typed(EvaluatedValue { value: PrintableValue::Field(field), typ }) => {
+                match typ {
+                    PrintableType::Boolean => Ok(Value::bool(field.is_zero())),
+                    PrintableType::UnsignedInteger { width }
+                    | PrintableType::SignedInteger { width } => {
+                        let inverted = !field.to_u128() & width_mask(*width);
+                        Ok(Value::typed(PrintableValue::Field(inverted.into()), typ.clone()))
+                    }
+                    _ => Err(invalid_operand(&rhs)),
+                }
+            }
+            _ => Err(invalid_operand(&rhs)),
+        },
+    }
+}
+
+fn evaluate_infix(
+    operator: BinaryOpKind,
+    lhs: Value,
+    rhs: Value,
+) -> Result<Value, EvaluationError> {
+    let (lhs, rhs) = unify(lhs, rhs)?;
+    let invalid_operands = || EvaluationError::InvalidOperands {
+        operator: operator.to_string(),
+        lhs: lhs.type_name(),
+        rhs: rhs.type_name(),
+    };
+
+    // Both sides have the same type from here on, unless they are both literals.
+    let typ = match (&lhs, &rhs) {
+        (Value::Typed(lhs), Value::Typed(rhs)) if lhs.typ != rhs.typ => {
+            return Err(invalid_operands());
+        }
+        (Value::Typed(value), _) | (_, Value::Typed(value)) => Some(value.typ.clone()),
+        (Value::Literal(_), Value::Literal(_)) => None,
+    };
+
+    match operator {
+        BinaryOpKind::Equal | BinaryOpKind::NotEqual => {
+            let equal = lhs.clone().into_typed().value == rhs.clone().into_typed().value;
+            return Ok(Value::bool(equal == (operator == BinaryOpKind::Equal)));
+        }
+        _ => (),
+    }
+
+    let is_numeric = matches!(
+        typ,
+        None | Some(
+            PrintableType::Field
+                | PrintableType::UnsignedInteger { .. }
+                | PrintableType::SignedInteger { .. }
+        )
+    );
+    let is_integer = matches!(
+        typ,
+        None | Some(PrintableType::UnsignedInteger { .. } | PrintableType::SignedInteger { .. })
+    );
+    let is_bitwise = is_integer || typ == Some(PrintableType::Boolean);
+
+    let (Some(lhs_number), Some(rhs_number)) = (lhs.as_signed_field(), rhs.as_signed_field())
+    else {
+        return Err(invalid_operands());
+    };
+
+    let result = match operator {
+        BinaryOpKind::Less
+        | BinaryOpKind::LessEqual
+        | BinaryOpKind::Greater
+        | BinaryOpKind::GreaterEqual => {
+            if !is_numeric {
+                return Err(invalid_operands());
+            }
+            let ordering = lhs_number.cmp(&rhs_number);
+            let result = match operator {
+                BinaryOpKind::Less => ordering.is_lt(),
+                BinaryOpKind::LessEqual => ordering.is_le(),
+                BinaryOpKind::Greater => ordering.is_gt(),
+                _ => ordering.is_ge(),
+            };
+            return Ok(Value::bool(result));
+        }
+        BinaryOpKind::Add | BinaryOpKind::Subtract | BinaryOpKind::Multiply if is_numeric => {
+            match operator {
+                BinaryOpKind::Add => lhs_number + rhs_number,
+                BinaryOpKind::Subtract => lhs_number - rhs_number,
+                _ => lhs_number * rhs_number,
+            }
+        }
+        // Literals default to fields, so dividing two of them is a field division.
+        BinaryOpKind::Divide if matches!(typ, None | Some(PrintableType::Field)) => {
+            if rhs_number.absolute_value().is_zero() {
+                return Err(EvaluationError::DivisionByZero);
+            }
+            SignedField::positive(lhs_number.to_field_element() / rhs_number.to_field_element())
+        }
+        BinaryOpKind::Divide | BinaryOpKind::Modulo if is_integer => {
+            let (Some(lhs_absolute), Some(rhs_absolute)) = (
+                lhs_number.absolute_value().try_into_u128(),
+                rhs_number.absolute_value().try_into_u128(),
+            ) else {
+                return Err(invalid_operands());
+            };
+            if rhs_absolute == 0 {
+                return Err(EvaluationError::DivisionByZero);
+            }
+            // Integer division truncates towards zero, and the remainder has the sign of the lhs.
+            if operator == BinaryOpKind::Divide {
+                let quotient = SignedField::positive(lhs_absolute / rhs_absolute);
+                if lhs_number.is_negative() != rhs_number.is_negative() {
+                    -quotient
+                } else {
+                    quotient
+                }
+            } else {
+                let remainder = SignedField::positive(lhs_absolute % rhs_absolute);
+                if lhs_number.is_negative() { -remainder } else { remainder }
+            }
+        }
+        BinaryOpKind::And | BinaryOpKind::Or | BinaryOpKind::Xor if is_bitwise => {
+            let width = integer_width(typ.as_ref());
+            let (Some(lhs_bits), Some(rhs_bits)) =
+                (to_bits(lhs_number, width), to_bits(rhs_number, width))
+            else {
+                return Err(invalid_operands());
+            };
+            let bits = match operator {
+                BinaryOpKind::And => lhs_bits & rhs_bits,
+                BinaryOpKind::Or => lhs_bits | rhs_bits,
+                _ => lhs_bits ^ rhs_bits,
+            };
+            let field = FieldElement::from(bits);
+            return match typ {
+                Some(typ) => Ok(Value::typed(PrintableValue::Field(field), typ)),
+                None => Ok(Value::Literal(SignedField::positive(field))),
+            };
+        }
+        BinaryOpKind::ShiftLeft | BinaryOpKind::ShiftRight
+            if matches!(typ, None | Some(PrintableType::UnsignedInteger { .. })) =>
+        {
+            let (Some(lhs_absolute), Some(shift)) = (
+                lhs_number.try_to_unsigned::<u128>(),
+                rhs_number.try_to_unsigned::<u32>().filter(|shift| *shift < 128),
+            ) else {
+                return Err(invalid_operands());
+            };
+            if operator == BinaryOpKind::ShiftRight {
+                SignedField::positive(lhs_absolute >> shift)
+            } else {
+                let shifted = lhs_absolute
+                    .checked_shl(shift)
+                    .filter(|shifted| shifted >> shift == lhs_absolute);
+                let Some(shifted) = shifted else {
+                    return Err(EvaluationError::Overflow {
+                        operator: operator.to_string(),
+                        typ: lhs.type_name(),
+                    });
+                };
+                SignedField::positive(shifted)
+            }
+        }
+        _ => return Err(invalid_operands()),
+    };
+
+    match typ {
+        Some(typ) => encode_number(result, &typ, &operator.to_string()),
+        None => Ok(Value::Literal(result)),
+    }
+}
+
+/// Gives integer literals the type of the value they are combined with.
+fn unify(lhs: Value, rhs: Value) -> Result<(Value, Value), EvaluationError> {
+    match (&lhs, &rhs) {
+        (Value::Literal(_), Value::Typed(typed)) => {
+            let typ = typed.typ.clone();
+            Ok((Value::Typed(cast_literal(lhs, &typ)?), rhs))
+        }
+        (Value::Typed(typed), Value::Literal(_)) => {
+            let typ = typed.typ.clone();
+            Ok((lhs, Value::Typed(cast_literal(rhs, &typ)?)))
+        }
+        _ => Ok((lhs, rhs)),
+    }
+}
+
+fn cast_literal(value: Value, typ: &PrintableType) -> Result<EvaluatedValue, EvaluationError> {
+    match value {
+        Value::Literal(literal) => match typ {
+            PrintableType::Field
+            | PrintableType::UnsignedInteger { .. }
+            | PrintableType::SignedInteger { .. } => {
+                let out_of_range =
+                    || EvaluationError::LiteralOutOfRange { literal, typ: type_name(typ) };
+                let field = encode_integer(literal, typ).ok_or_else(out_of_range)?;
+                Ok(EvaluatedValue { value: PrintableValue::Field(field), typ: typ.clone() })
+            }
+            // The literal can't be used as this type, so it's left as a field to be reported
+            // as an invalid operand.
+            _ => Ok(Value::Literal(literal).into_typed()),
+        },
+        Value::Typed(value) => Ok(value),
+    }
+}
+
+/// Stores the result of an operation as a value of `typ`, failing if it doesn't fit.
+fn encode_number(
+    number: SignedField,
+    typ: &PrintableType,
+    operator: &str,
+) -> Result<Value, EvaluationError> {
+    let field = encode_integer(number, typ).ok_or_else(|| EvaluationError::Overflow {
+        operator: operator.to_string(),
+        typ: type_name(typ),
+    })?;
+    Ok(Value::typed(PrintableValue::Field(field), typ.clone()))
+}
+
+/// Encodes a number as a value of a numeric type, signed integers as their two's complement,
+/// or returns `None` if it doesn't fit in the type.
+fn encode_integer(number: SignedField, typ: &PrintableType) -> Option<FieldElement> {
+    match typ {
+        PrintableType::Field => Some(number.to_field_element()),
+        PrintableType::UnsignedInteger { width } => {
+            let fits = !number.is_negative() && number.absolute_value().num_bits() <= *width;
+            fits.then(|| number.absolute_value())
+        }
+        PrintableType::SignedInteger { width } => {
+            let absolute = number.absolute_value().try_into_u128()?;
+            let limit = 1u128 << (width - 1);
+            let fits = if number.is_negative() { absolute <= limit } else { absolute < limit };
+            fits.then(|| FieldElement::from(to_bits(number, *width).expect("Fits in a u128")))
+        }
+        _ => None,
+    }
+}
+
+fn decode_signed_integer(field: FieldElement, width: u32) -> SignedField {
+    let bits = field.to_u128();
+    if (bits >> (width - 1)) == 1 {
+        // Two's complement relative to the width of the integer
+        SignedField::negative(((bits ^ width_mask(width)) + 1) & width_mask(width))
+    } else {
+        SignedField::positive(bits)
+    }
+}
+
+/// The bits of an integer of the given width, negative numbers in two's complement.
+fn to_bits(number: SignedField, width: u32) -> Option<u128> {
+    let absolute = number.absolute_value().try_into_u128()?;
+    if number.is_negative() {
+        Some(absolute.wrapping_neg() & width_mask(width))
+    } else {
+        Some(absolute & width_mask(width))
+    }
+}
+
+fn integer_width(typ: Option<&PrintableType>) -> u32 {
+    match typ {
+        Some(PrintableType::UnsignedInteger { width } | PrintableType::SignedInteger { width }) => {
+            *width
+        }
+        Some(PrintableType::Boolean) => 1,
+        _ => 128,
+    }
+}
+
+fn width_mask(width: u32) -> u128 {
+    if width >= 128 { u128::MAX } else { (1 << width) - 1 }
+}
+
+/// The name of a type as it's written in Noir source code.
+pub(super) fn type_name(typ: &PrintableType) -> String {
+    match typ {
+        PrintableType::Field => "Field".to_string(),
+        PrintableType::Array { length, typ } => format!("[{}; {length}]", type_name(typ)),
+        PrintableType::Slice { typ } => format!("[{}]", type_name(typ)),
+        PrintableType::Tuple { types } => {
+            let types: Vec<String> = types.iter().map(type_name).collect();
+            format!("({})", types.join(", "))
+        }
+        PrintableType::SignedInteger { width } => format!("i{width}"),
+        PrintableType::UnsignedInteger { width } => format!("u{width}"),
+        PrintableType::Boolean => "bool".to_string(),
+        PrintableType::Struct { name, .. } | PrintableType::Enum { name, .. } => name.clone(),
+        PrintableType::String { length } => format!("str<{length}>"),
+        PrintableType::Function { .. } => "function".to_string(),
+        PrintableType::Reference { typ, mutable } => {
+            let mutable = if *mutable { "mut " } else { "" };
+            format!("&{mutable}{}", type_name(typ))
+        }
+        PrintableType::Unit => "()".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use acvm::FieldElement;
+    use noirc_artifacts::debug::StackFrame;
+    use noirc_printable_type::{PrintableType, PrintableValue};
+
+    use super::{EvaluationError, evaluate_expression, type_name};
+
+    fn field(value: u128) -> PrintableValue<FieldElement> {
+        PrintableValue::Field(FieldElement::from(value))
+    }
+
+    fn evaluate(source: &str) -> Result<String, EvaluationError> {
+        let point_type = PrintableType::Struct {
+            name: "Point".to_string(),
+            fields: vec![
+                ("x".to_string(), PrintableType::Field),
+                ("y".to_string(), PrintableType::Field),
+            ],
+        };
+        let point = PrintableValue::Struct(BTreeMap::from([
+            ("x".to_string(), field(3)),
+            ("y".to_string(), field(4)),
+        ]));
+        let u8_type = PrintableType::UnsignedInteger { width: 8 };
+        let i8_type = PrintableType::SignedInteger { width: 8 };
+        let array_type = PrintableType::Array { length: 3, typ: Box::new(u8_type.clone()) };
+        let array = PrintableValue::Vec {
+            array_elements: vec![field(10), field(20), field(250)],
+            is_slice: false,
+        };
+        let tuple_type =
+            PrintableType::Tuple { types: vec![PrintableType::Boolean, i8_type.clone()] };
+        // (true, -2)
+        let tuple =
+            PrintableValue::Vec { array_elements: vec![field(1), field(254)], is_slice: false };
+        let field_type = PrintableType::Field;
+        let shadowed = field(1);
+        let index = field(1);
+        let u32_type = PrintableType::UnsignedInteger { width: 32 };
+
+        let frame = StackFrame {
+            function_name: "main",
+            function_params: vec!["point"],
+            variables: vec![
+                ("point", &point, &point_type),
+                ("index", &shadowed, &field_type),
+                ("array", &array, &array_type),
+                ("tuple", &tuple, &tuple_type),
+                ("index", &index, &u32_type),
+            ],
+        };
+        evaluate_expression(source, Some(&frame)).map(|value| value.to_string())
+    }
+
+    #[test]
+    fn evaluates_variables_and_members() {
+        assert_eq!(evaluate("point.x").unwrap(), "0x03");
+        assert_eq!(evaluate("point.x * point.y + 1").unwrap(), "0x0d");
+        assert_eq!(evaluate("array[index]").unwrap(), "20");
+        assert_eq!(evaluate("array[2] / 3").unwrap(), "83");
+        assert_eq!(evaluate("tuple.1 * 3").unwrap(), "-6");
+        assert_eq!(evaluate("!tuple.0 | (array[0] > 5)").unwrap(), "true");
+        assert_eq!(evaluate("(point.x, array[0] == 10)").unwrap(), "(0x03, true)");
+    }
+
+    #[test]
+    fn reports_invalid_expressions() {
+        assert_eq!(evaluate("nope"), Err(EvaluationError::UnknownVariable("nope".to_string())));
+        assert!(matches!(evaluate("point.z"), Err(EvaluationError::UnknownMember { .. })));
+        assert!(matches!(evaluate("array[3]"), Err(EvaluationError::IndexOutOfBounds { .. })));
+        assert!(matches!(evaluate("array[2] + 10"), Err(EvaluationError::Overflow { .. })));
+        assert!(matches!(
+            evaluate("array[0] + point.x"),
+            Err(EvaluationError::InvalidOperands { .. })
+        ));
+        assert!(matches!(
+            evaluate("array[0] + 256"),
+            Err(EvaluationError::LiteralOutOfRange { .. })
+        ));
+        assert!(matches!(evaluate("foo(1)"), Err(EvaluationError::Unsupported(_))));
+        assert!(matches!(evaluate("point."), Err(EvaluationError::Parse(_))));
+    }
+
+    #[test]
+    fn names_types_as_written_in_noir() {
+        let typ = PrintableType::Array {
+            length: 2,
+            typ: Box::new(PrintableType::Tuple {
+                types: vec![
+                    PrintableType::UnsignedInteger { width: 8 },
+                    PrintableType::Slice { typ: Box::new(PrintableType::Field) },
+                ],
+            }),
+        };
+        assert_eq!(type_name(&typ), "[(u8, [Field]); 2]");
+    }
+}
@@ -6,7 +6,9 @@ use acvm::{
     pwg::ForeignCallWaitInfo,
 };
 use nargo::foreign_calls::{
-    DefaultForeignCallBuilder, ForeignCallError, ForeignCallExecutor, layers::Layer,
+    DefaultForeignCallBuilder, ForeignCallError, ForeignCallExecutor,
+    layers::{self, Either, Layer},
+    transcript::ReplayForeignCallExecutor,
 };
 use noirc_artifacts::debug::{DebugArtifact, DebugVars, StackFrame};
 use noirc_errors::debug_info::{DebugFnId, DebugVarId};
@@ -43,6 +45,10 @@ pub trait DebugForeignCallExecutor: ForeignCallExecutor<FieldElement> {
     fn get_variables(&self) -> Vec<StackFrame<FieldElement>>;
     fn current_stack_frame(&self) -> Option<StackFrame<FieldElement>>;
     fn restart(&mut self, artifact: &DebugArtifact);
+    /// The state of the variables tracked by the debug instrumentation, to be restored
+    /// with `restore_debug_vars` when execution is brought back to this point.
+    fn save_debug_vars(&self) -> DebugVars<FieldElement>;
+    fn restore_debug_vars(&mut self, debug_vars: DebugVars<FieldElement>);
 }
 
 #[derive(Default)]
@@ -54,10 +60,16 @@ impl DefaultDebugForeignCallExecutor {
     fn make<'a, W: 'a + std::io::Write>(
         output: W,
         resolver_url: Option<String>,
+        oracle_transcript: Option<ReplayForeignCallExecutor<FieldElement>>,
         ex: DefaultDebugForeignCallExecutor,
         root_path: Option<PathBuf>,
         package_name: String,
     ) -> impl DebugForeignCallExecutor + 'a {
+        // Like when executing, the transcript only answers the calls that aren't prints or mocks.
+        let base = match oracle_transcript {
+            Some(oracle_transcript) => Either::Left(oracle_transcript),
+            None => Either::Right(layers::Empty),
+        };
         DefaultForeignCallBuilder {
             output,
             enable_mocks: true,
@@ -65,7 +77,7 @@ impl DefaultDebugForeignCallExecutor {
             root_path: root_path.clone(),
             package_name: Some(package_name),
         }
-        .build()
+        .build_with_base(base)
         .add_layer(ex)
     }
 
@@ -76,7 +88,7 @@ impl DefaultDebugForeignCallExecutor {
         root_path: Option<PathBuf>,
         package_name: String,
     ) -> impl DebugForeignCallExecutor + 'a {
-        Self::make(output, resolver_url, Self::default(), root_path, package_name)
+        Self::make(output, resolver_url, None, Self::default(), root_path, package_name)
     }
 
     pub fn from_artifact<'a, W: 'a + std::io::Write>(
@@ -85,10 +97,30 @@ impl DefaultDebugForeignCallExecutor {
         artifact: &DebugArtifact,
         root_path: Option<PathBuf>,
         package_name: String,
+    ) -> impl DebugForeignCallExecutor + use<'a, W> {
+        Self::from_artifact_with_transcript(
+            output,
+            resolver_url,
+            None,
+            artifact,
+            root_path,
+            package_name,
+        )
+    }
+
+    /// Like `from_artifact`, answering the calls that would go to the resolver from an oracle
+    /// transcript when one is given.
+    pub fn from_artifact_with_transcript<'a, W: 'a + std::io::Write>(
+        output: W,
+        resolver_url: Option<String>,
+        oracle_transcript: Option<ReplayForeignCallExecutor<FieldElement>>,
+        artifact: &DebugArtifact,
+        root_path: Option<PathBuf>,
+        package_name: String,
     ) -> impl DebugForeignCallExecutor + use<'a, W> {
         let mut ex = Self::default();
         ex.load_artifact(artifact);
-        Self::make(output, resolver_url, ex, root_path, package_name)
+        Self::make(output, resolver_url, oracle_transcript, ex, root_path, package_name)
     }
 
     pub fn load_artifact(&mut self, artifact: &DebugArtifact) {
@@ -114,6 +146,14 @@ impl DebugForeignCallExecutor for DefaultDebugForeignCallExecutor {
         self.debug_vars = DebugVars::default();
         self.load_artifact(artifact);
     }
+
+    fn save_debug_vars(&self) -> DebugVars<FieldElement> {
+        self.debug_vars.clone()
+    }
+
+    fn restore_debug_vars(&mut self, debug_vars: DebugVars<FieldElement>) {
+        self.debug_vars = debug_vars;
+    }
 }
 
 fn debug_var_id(value: &FieldElement) -> DebugVarId {
@@ -219,4 +259,12 @@ where
     fn restart(&mut self, artifact: &DebugArtifact) {
         self.handler.restart(artifact);
     }
+
+    fn save_debug_vars(&self) -> DebugVars<FieldElement> {
+        self.handler().save_debug_vars()
+    }
+
+    fn restore_debug_vars(&mut self, debug_vars: DebugVars<FieldElement>) {
+        self.handler.restore_debug_vars(debug_vars);
+    }
 }
@@ -0,0 +1,134 @@
+use acvm::FieldElement;
+use acvm::acir::brillig::{BitSize, ForeignCallResult};
+use acvm::acir::native_types::Witness;
+use acvm::pwg::ForeignCallWaitInfo;
+
+use crate::foreign_calls::DebugForeignCall;
+
+/// A change made by the user to the state of the execution.
+#[derive(Debug, Clone)]
+pub(super) enum StateEdit {
+    Witness(Witness, FieldElement),
+    BrilligMemory(usize, FieldElement, BitSize),
+}
+
+/// How far execution has gone through its history.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub(super) struct HistoryPosition {
+    /// The number of opcodes stepped into since execution started.
+    steps: usize,
+    /// How many of the recorded foreign call results execution has used.
+    foreign_calls_made: usize,
+}
+
+impl HistoryPosition {
+    pub(super) fn steps(&self) -> usize {
+        self.steps
+    }
+}
+
+/// Everything needed to bring execution back to any step already taken.
+///
+/// The ACVM and the Brillig VM are deterministic apart from foreign calls, so instead of
+/// snapshotting their state after every step, the debugger keeps a checkpoint every so often and
+/// stepping backwards replays execution from the nearest checkpoint before the target step.
+/// Foreign call results are recorded the first time they are made and handed back during replays,
+/// so oracles aren't called again and output isn't printed twice. Calls to the debug
+/// instrumentation are always executed, as they rebuild the variables shown by the debugger.
+#[derive(Debug, Default)]
+pub(super) struct ExecutionHistory {
+    /// The number of opcodes stepped into since execution started.
+    steps: usize,
+    /// The results of the foreign calls made so far, in the order they were made.
+    foreign_call_results: Vec<ForeignCallResult<FieldElement>>,
+    /// How many of `foreign_call_results` the current execution has already used.
+    foreign_calls_made: usize,
+    /// The edits made by the user, along with the step they were made at and the number of
+    /// foreign calls made before them.
+    edits: Vec<(usize, usize, StateEdit)>,
+}
+
+impl ExecutionHistory {
+    /// Starts a history in which the given foreign call results have already been recorded,
+    /// so that they are handed back instead of making the calls.
+    pub(super) fn with_foreign_call_results(
+        foreign_call_results: Vec<ForeignCallResult<FieldElement>>,
+    ) -> Self {
+        Self { foreign_call_results, ..Self::default() }
+    }
+
+    pub(super) fn foreign_call_results(&self) -> &[ForeignCallResult<FieldElement>] {
+        &self.foreign_call_results
+    }
+
+    pub(super) fn steps(&self) -> usize {
+        self.steps
+    }
+
+    pub(super) fn record_step(&mut self) {
+        self.steps += 1;
+    }
+
+    pub(super) fn position(&self) -> HistoryPosition {
+        HistoryPosition { steps: self.steps, foreign_calls_made: self.foreign_calls_made }
+    }
+
+    /// Goes back to a position execution has already been at, keeping what's been recorded so
+    /// it can be replayed from there.
+    pub(super) fn seek(&mut self, position: HistoryPosition) {
+        self.steps = position.steps;
+        self.foreign_calls_made = position.foreign_calls_made;
+    }
+
+    /// Forgets the edits made after `step`, along with the foreign call results recorded
+    /// after them, as execution may not take the same path without them.
+    pub(super) fn truncate(&mut self, step: usize) {
+        let first_dropped = self.edits.iter().position(|(edit_step, _, _)| *edit_step > step);
+        if let Some(index) = first_dropped {
+            let (_, foreign_calls_made, _) = self.edits[index];
+            self.foreign_call_results.truncate(foreign_calls_made);
+            self.edits.truncate(index);
+        }
+    }
+
+    /// Returns the recorded result of a foreign call, if execution has already gone past it.
+    pub(super) fn replay_foreign_call(
+        &mut self,
+        foreign_call: &ForeignCallWaitInfo<FieldElement>,
+    ) -> Option<ForeignCallResult<FieldElement>> {
+        if DebugForeignCall::lookup(&foreign_call.function).is_some() {
+            return None;
+        }
+        let result = self.foreign_call_results.get(self.foreign_calls_made)?.clone();
+        self.foreign_calls_made += 1;
+        Some(result)
+    }
+
+    pub(super) fn record_foreign_call(
+        &mut self,
+        foreign_call: &ForeignCallWaitInfo<FieldElement>,
+        result: &ForeignCallResult<FieldElement>,
+    ) {
+        if DebugForeignCall::lookup(&foreign_call.function).is_some() {
+            return;
+        }
+        self.foreign_call_results.push(result.clone());
+        self.foreign_calls_made += 1;
+    }
+
+    /// Records an edit made at the current step. Execution may take a different path from
+    /// here on, so the foreign call results recorded after this point are dropped.
+    pub(super) fn record_edit(&mut self, edit: StateEdit) {
+        self.foreign_call_results.truncate(self.foreign_calls_made);
+        self.edits.push((self.steps, self.foreign_calls_made, edit));
+    }
+
+    /// The edits made at the current step, in the order they were made.
+    pub(super) fn edits_at_current_step(&self) -> Vec<StateEdit> {
+        self.edits
+            .iter()
+            .filter(|(step, _, _)| *step == self.steps)
+            .map(|(_, _, edit)| edit.clone())
+            .collect()
+    }
+}
@@ -1,9 +1,13 @@
+mod breakpoints;
 mod context;
 mod dap;
 pub mod errors;
+mod expression;
 mod foreign_calls;
+mod history;
 mod repl;
 mod source_code_printer;
+mod state;
 
 use std::io::{Read, Write};
 
@@ -13,9 +17,21 @@ use ::dap::server::Server;
 pub use context::DebugExecutionResult;
 pub use context::DebugProject;
 pub use context::RunParams;
+pub use state::{FailureState, FailureStateError};
 
 pub fn run_repl_session(project: DebugProject, run_params: RunParams) -> DebugExecutionResult {
-    repl::run(project, run_params)
+    repl::run(project, run_params, repl::SessionStart::Beginning)
+}
+
+/// Runs the program up to the point where it fails, and opens a REPL session there.
+/// When a saved failure state is given, its oracle call results are used instead of
+/// calling the oracles again.
+pub fn run_post_mortem_repl_session(
+    project: DebugProject,
+    run_params: RunParams,
+    failure_state: Option<FailureState>,
+) -> DebugExecutionResult {
+    repl::run(project, run_params, repl::SessionStart::Failure(failure_state))
 }
 
 pub fn run_dap_loop<R: Read, W: Write>(
@@ -1,8 +1,10 @@
 use crate::DebugProject;
+use crate::breakpoints::{DataBreakpoint, DataBreakpointHit};
 use crate::context::{
     DebugCommandResult, DebugContext, DebugExecutionResult, DebugLocation, DebugStackFrame,
     RunParams,
 };
+use crate::state::FailureState;
 use noirc_driver::CompiledProgram;
 
 use crate::foreign_calls::DefaultDebugForeignCallExecutor;
@@ -10,6 +12,7 @@ use noirc_artifacts::debug::DebugArtifact;
 
 use easy_repl::{CommandStatus, Repl, command};
 use std::cell::RefCell;
+use std::path::Path;
 use std::sync::mpsc::{self, Receiver, Sender};
 use std::thread;
 
@@ -47,18 +50,39 @@ pub(super) enum DebugCommandAPI {
     NextOver,
     NextOut,
     Cont,
+    ReverseStepAcirOpcode,
+    ReverseStepIntoOpcode,
+    ReverseNextInto,
+    ReverseNextOver,
+    ReverseNextOut,
+    ReverseCont,
     UpdateWitness(u32, String),
     WriteBrilligMemory(usize, String, u32),
     ShowVariables,
+    Evaluate(String),
+    AddWatch(String),
+    DeleteWatch(usize),
+    ShowWatches,
+    AddDataBreakpoint(String, usize),
+    DeleteDataBreakpoint(String, usize),
     ShowWitnessMap,
     ShowWitness(u32),
     ShowBrilligMemory,
     ShowCurrentCallStack,
     ShowCurrentVmStatus,
     ShowOpcodes,
+    SaveFailureState(String),
     Terminate,
 }
 
+/// Where a REPL session starts.
+pub(super) enum SessionStart {
+    /// At the start of the program.
+    Beginning,
+    /// At the point where the program fails, reproducing a saved failure state if given.
+    Failure(Option<FailureState>),
+}
+
 #[derive(Debug)]
 pub(super) enum DebuggerStatus {
     Idle,
@@ -71,11 +95,15 @@ pub struct AsyncReplDebugger<'a> {
     debug_artifact: &'a DebugArtifact,
     initial_witness: WitnessMap<FieldElement>,
     unconstrained_functions: Vec<BrilligBytecode<FieldElement>>,
+    /// The hash of the program being debugged, saved along with its failure state.
+    program_hash: u64,
     command_receiver: Receiver<DebugCommandAPI>,
     status_sender: Sender<DebuggerStatus>,
     last_result: DebugCommandResult,
     pedantic_solving: bool,
     raw_source_printing: bool,
+    /// Expressions evaluated every time execution stops.
+    watches: Vec<String>,
 }
 
 impl<'a> AsyncReplDebugger<'a> {
@@ -97,9 +125,11 @@ impl<'a> AsyncReplDebugger<'a> {
             debug_artifact,
             last_result,
             unconstrained_functions: compiled_program.program.unconstrained_functions.clone(),
+            program_hash: compiled_program.hash,
             raw_source_printing,
             initial_witness,
             pedantic_solving,
+            watches: Vec::new(),
         }
     }
 
@@ -110,6 +140,7 @@ impl<'a> AsyncReplDebugger<'a> {
     pub(super) fn start_debugging(
         mut self,
         foreign_call_executor: Box<dyn DebugForeignCallExecutor + 'a>,
+        start: SessionStart,
     ) {
         let blackbox_solver = &Bn254BlackBoxSolver(self.pedantic_solving);
         let circuits = &self.circuits.clone();
@@ -128,6 +159,10 @@ impl<'a> AsyncReplDebugger<'a> {
             self.last_result = DebugCommandResult::Done
         }
 
+        if let SessionStart::Failure(failure_state) = start {
+            self.run_to_failure(&mut context, failure_state);
+        }
+
         println!("Debugger ready to receive messages..");
         loop {
             // recv blocks until it receives message
@@ -168,12 +203,52 @@ impl<'a> AsyncReplDebugger<'a> {
                         println!("(Continuing execution...)");
                         context.cont()
                     }),
+                    DebugCommandAPI::ReverseStepAcirOpcode => self
+                        .handle_reverse_step(&mut context, |context| {
+                            context.reverse_step_acir_opcode()
+                        }),
+                    DebugCommandAPI::ReverseStepIntoOpcode => self
+                        .handle_reverse_step(&mut context, |context| {
+                            context.reverse_step_into_opcode()
+                        }),
+                    DebugCommandAPI::ReverseNextInto => self
+                        .handle_reverse_step(&mut context, |context| context.reverse_next_into()),
+                    DebugCommandAPI::ReverseNextOver => self
+                        .handle_reverse_step(&mut context, |context| context.reverse_next_over()),
+                    DebugCommandAPI::ReverseNextOut => {
+                        self.handle_reverse_step(&mut context, |context| context.reverse_next_out())
+                    }
+                    DebugCommandAPI::ReverseCont => {
+                        self.handle_reverse_step(&mut context, |context| {
+                            println!("(Continuing execution backwards...)");
+                            context.reverse_cont()
+                        })
+                    }
                     DebugCommandAPI::AddBreakpointAtLine(line_number) => {
                         Self::add_breakpoint_at_line(&mut context, line_number);
                     }
                     DebugCommandAPI::ShowVariables => {
                         Self::show_variables(&mut context);
                     }
+                    DebugCommandAPI::Evaluate(expression) => {
+                        Self::evaluate(&context, &expression);
+                    }
+                    DebugCommandAPI::AddWatch(expression) => {
+                        self.add_watch(&context, expression);
+                    }
+                    DebugCommandAPI::DeleteWatch(index) => {
+                        self.delete_watch(index);
+                    }
+                    DebugCommandAPI::ShowWatches => {
+                        self.show_watches(&context);
+                        Self::show_data_breakpoints(&context);
+                    }
+                    DebugCommandAPI::AddDataBreakpoint(kind, index) => {
+                        Self::add_data_breakpoint(&mut context, &kind, index);
+                    }
+                    DebugCommandAPI::DeleteDataBreakpoint(kind, index) => {
+                        Self::delete_data_breakpoint(&mut context, &kind, index);
+                    }
                     DebugCommandAPI::ShowWitnessMap => {
                         Self::show_witness_map(&mut context);
                     }
@@ -189,6 +264,9 @@ impl<'a> AsyncReplDebugger<'a> {
                     DebugCommandAPI::ShowOpcodes => {
                         self.show_opcodes(&mut context);
                     }
+                    DebugCommandAPI::SaveFailureState(path) => {
+                        self.save_failure_state(&context, &path);
+                    }
                     DebugCommandAPI::ShowCurrentVmStatus => {
                         self.show_current_vm_status(&mut context);
                     }
@@ -238,6 +316,7 @@ impl<'a> AsyncReplDebugger<'a> {
                     &locations,
                     self.raw_source_printing,
                 );
+                self.show_watches(context);
             }
         }
     }
@@ -398,7 +477,7 @@ impl<'a> AsyncReplDebugger<'a> {
         }
     }
 
-    fn handle_result(&mut self, result: DebugCommandResult) {
+    fn handle_result(&mut self, context: &Context<'_>, result: DebugCommandResult) {
         self.last_result = result;
         match &self.last_result {
             DebugCommandResult::Done => {
@@ -408,6 +487,9 @@ impl<'a> AsyncReplDebugger<'a> {
             DebugCommandResult::BreakpointReached(location) => {
                 println!("Stopped at breakpoint in opcode {}", location);
             }
+            DebugCommandResult::DataBreakpointReached(hit) => {
+                self.show_data_breakpoint_hit(context, hit);
+            }
             DebugCommandResult::Error(error) => {
                 println!("ERROR: {}", error);
             }
@@ -419,7 +501,9 @@ impl<'a> AsyncReplDebugger<'a> {
         F: Fn(&mut Context) -> DebugCommandResult,
     {
         let should_execute = match self.last_result {
-            DebugCommandResult::Ok | DebugCommandResult::BreakpointReached(..) => true,
+            DebugCommandResult::Ok
+            | DebugCommandResult::BreakpointReached(..)
+            | DebugCommandResult::DataBreakpointReached(..) => true,
             DebugCommandResult::Done => {
                 println!("Execution finished");
                 false
@@ -432,11 +516,25 @@ impl<'a> AsyncReplDebugger<'a> {
         };
         if should_execute {
             let result = step(context);
+            for message in context.take_breakpoint_logs() {
+                println!("{message}");
+            }
             self.show_current_vm_status(context);
-            self.handle_result(result);
+            self.handle_result(context, result);
         }
     }
 
+    /// Unlike stepping forward, stepping backwards is possible once execution has finished
+    /// or failed, so the cause of a failure can be tracked down.
+    fn handle_reverse_step<F>(&mut self, context: &mut Context<'_>, step: F)
+    where
+        F: Fn(&mut Context) -> DebugCommandResult,
+    {
+        let result = step(context);
+        self.show_current_vm_status(context);
+        self.handle_result(context, result);
+    }
+
     fn restart_session(&mut self, context: &mut Context<'_>) {
         context.restart();
         self.last_result = DebugCommandResult::Ok;
@@ -524,6 +622,142 @@ impl<'a> AsyncReplDebugger<'a> {
         }
     }
 
+    fn evaluate(context: &Context<'_>, expression: &str) {
+        match context.evaluate(expression) {
+            Ok(value) => println!("{value}"),
+            Err(error) => println!("{error}"),
+        }
+    }
+
+    fn add_watch(&mut self, context: &Context<'_>, expression: String) {
+        self.watches.push(expression);
+        let index = self.watches.len() - 1;
+        self.show_watch(context, index);
+    }
+
+    fn delete_watch(&mut self, index: usize) {
+        if index < self.watches.len() {
+            let expression = self.watches.remove(index);
+            println!("Removed watch #{index}: {expression}");
+        } else {
+            println!("No watch #{index}");
+        }
+    }
+
+    fn show_watches(&self, context: &Context<'_>) {
+        for index in 0..self.watches.len() {
+            self.show_watch(context, index);
+        }
+    }
+
+    fn show_watch(&self, context: &Context<'_>, index: usize) {
+        let expression = &self.watches[index];
+        match context.evaluate(expression) {
+            Ok(value) => println!("#{index}: {expression} = {value}"),
+            Err(error) => println!("#{index}: {expression} = <{error}>"),
+        }
+    }
+
+    /// Parses a data breakpoint given as `witness <index>` or `memory <address>`. Witnesses
+    /// belong to the circuit being executed.
+    fn parse_data_breakpoint(
+        context: &Context<'_>,
+        kind: &str,
+        index: usize,
+    ) -> Option<DataBreakpoint> {
+        match kind {
+            "witness" => {
+                let Ok(index) = u32::try_from(index) else {
+                    println!("Invalid witness index: {index}");
+                    return None;
+                };
+                Some(DataBreakpoint::Witness {
+                    circuit_id: context.current_circuit_id(),
+                    witness: Witness::from(index),
+                })
+            }
+            "memory" => Some(DataBreakpoint::BrilligMemory(index)),
+            _ => {
+                println!("Can only watch a `witness` or Brillig `memory`, not `{kind}`");
+                None
+            }
+        }
+    }
+
+    fn add_data_breakpoint(context: &mut Context<'_>, kind: &str, index: usize) {
+        let Some(data_breakpoint) = Self::parse_data_breakpoint(context, kind, index) else {
+            return;
+        };
+        if context.add_data_breakpoint(data_breakpoint) {
+            println!("Watching {data_breakpoint}");
+        } else {
+            println!("Already watching {data_breakpoint}");
+        }
+    }
+
+    fn delete_data_breakpoint(context: &mut Context<'_>, kind: &str, index: usize) {
+        let Some(data_breakpoint) = Self::parse_data_breakpoint(context, kind, index) else {
+            return;
+        };
+        if context.delete_data_breakpoint(&data_breakpoint) {
+            println!("Stopped watching {data_breakpoint}");
+        } else {
+            println!("Not watching {data_breakpoint}");
+        }
+    }
+
+    fn show_data_breakpoints(context: &Context<'_>) {
+        for data_breakpoint in context.get_data_breakpoints() {
+            println!("Watching {data_breakpoint}");
+        }
+    }
+
+    fn show_data_breakpoint_hit(&self, context: &Context<'_>, hit: &DataBreakpointHit) {
+        println!("Stopped at data breakpoint: {hit}");
+        let locations = context.get_source_location_for_debug_location(&hit.location);
+        print_source_code_location(self.debug_artifact, &locations, self.raw_source_printing);
+    }
+
+    /// Runs execution until it fails, answering foreign calls with the results in the
+    /// failure state when given.
+    fn run_to_failure(&mut self, context: &mut Context<'_>, failure_state: Option<FailureState>) {
+        if let Some(failure_state) = &failure_state {
+            println!("Reproducing failure: {}", failure_state.error);
+            context.restart_with_foreign_call_results(failure_state.foreign_call_results.clone());
+        }
+        if matches!(self.last_result, DebugCommandResult::Ok) {
+            println!("(Running until execution fails...)");
+            let result = context.cont();
+            self.handle_result(context, result);
+        }
+
+        let DebugCommandResult::Error(error) = &self.last_result else {
+            println!("Execution did not fail");
+            return;
+        };
+        // Oracles may answer differently the next time, so restarting reproduces the same failure
+        context.keep_foreign_call_results();
+        if let Some(failure_state) = failure_state {
+            let reproduced = context.failure_state(self.program_hash, error.to_string());
+            if reproduced.witness_stack != failure_state.witness_stack {
+                println!(
+                    "WARNING: The witnesses differ from the saved failure state. The program may have changed since it was saved."
+                );
+            }
+        }
+    }
+
+    fn save_failure_state(&self, context: &Context<'_>, path: &str) {
+        let DebugCommandResult::Error(error) = &self.last_result else {
+            println!("Execution has not failed, so there is no failure state to save");
+            return;
+        };
+        match context.failure_state(self.program_hash, error.to_string()).save(Path::new(path)) {
+            Ok(()) => println!("Failure state saved to {path}"),
+            Err(error) => println!("{error}"),
+        }
+    }
+
     fn terminate(self, context: Context<'_>) {
         let result = if context.is_solved() {
             let solved_witness_stack = context.finalize();
@@ -593,6 +827,24 @@ impl DebugController {
     pub fn next_out(&self) {
         self.call_debugger(DebugCommandAPI::NextOut);
     }
+    pub fn reverse_step_acir_opcode(&self) {
+        self.call_debugger(DebugCommandAPI::ReverseStepAcirOpcode);
+    }
+    pub fn reverse_step_into_opcode(&self) {
+        self.call_debugger(DebugCommandAPI::ReverseStepIntoOpcode);
+    }
+    pub fn reverse_next_into(&self) {
+        self.call_debugger(DebugCommandAPI::ReverseNextInto);
+    }
+    pub fn reverse_next_over(&self) {
+        self.call_debugger(DebugCommandAPI::ReverseNextOver);
+    }
+    pub fn reverse_next_out(&self) {
+        self.call_debugger(DebugCommandAPI::ReverseNextOut);
+    }
+    pub fn reverse_cont(&self) {
+        self.call_debugger(DebugCommandAPI::ReverseCont);
+    }
     pub fn restart_session(&self) {
         self.call_debugger(DebugCommandAPI::Restart);
     }
@@ -614,9 +866,30 @@ impl DebugController {
     pub fn show_vars(&self) {
         self.call_debugger(DebugCommandAPI::ShowVariables);
     }
+    pub fn evaluate(&self, expression: String) {
+        self.call_debugger(DebugCommandAPI::Evaluate(expression));
+    }
+    pub fn add_watch(&self, expression: String) {
+        self.call_debugger(DebugCommandAPI::AddWatch(expression));
+    }
+    pub fn delete_watch(&self, index: usize) {
+        self.call_debugger(DebugCommandAPI::DeleteWatch(index));
+    }
+    pub fn show_watches(&self) {
+        self.call_debugger(DebugCommandAPI::ShowWatches);
+    }
+    pub fn add_data_breakpoint(&self, kind: String, index: usize) {
+        self.call_debugger(DebugCommandAPI::AddDataBreakpoint(kind, index));
+    }
+    pub fn delete_data_breakpoint(&self, kind: String, index: usize) {
+        self.call_debugger(DebugCommandAPI::DeleteDataBreakpoint(kind, index));
+    }
     pub fn show_opcodes(&self) {
         self.call_debugger(DebugCommandAPI::ShowOpcodes);
     }
+    pub fn save_failure_state(&self, path: String) {
+        self.call_debugger(DebugCommandAPI::SaveFailureState(path));
+    }
     pub fn show_witness_map(&self) {
         self.call_debugger(DebugCommandAPI::ShowWitnessMap);
     }
@@ -637,19 +910,25 @@ impl DebugController {
     }
 }
 
-pub fn run(project: DebugProject, run_params: RunParams) -> DebugExecutionResult {
+pub(super) fn run(
+    project: DebugProject,
+    run_params: RunParams,
+    start: SessionStart,
+) -> DebugExecutionResult {
     let debug_artifact = DebugArtifact {
         debug_symbols: project.compiled_program.debug.clone(),
         file_map: project.compiled_program.file_map.clone(),
     };
 
-    let foreign_call_executor = Box::new(DefaultDebugForeignCallExecutor::from_artifact(
-        std::io::stdout(),
-        run_params.oracle_resolver_url,
-        &debug_artifact,
-        Some(project.root_dir),
-        project.package_name,
-    ));
+    let foreign_call_executor =
+        Box::new(DefaultDebugForeignCallExecutor::from_artifact_with_transcript(
+            std::io::stdout(),
+            run_params.oracle_resolver_url,
+            run_params.oracle_transcript,
+            &debug_artifact,
+            Some(project.root_dir),
+            project.package_name,
+        ));
 
     let (command_tx, command_rx) = mpsc::channel::<DebugCommandAPI>();
     let (status_tx, status_rx) = mpsc::channel::<DebuggerStatus>();
@@ -663,7 +942,7 @@ pub fn run(project: DebugProject, run_params: RunParams) -> DebugExecutionResult
             run_params.raw_source_printing.unwrap_or(false),
             run_params.pedantic_solving,
         );
-        debugger.start_debugging(foreign_call_executor);
+        debugger.start_debugging(foreign_call_executor, start);
     });
 
     let context =
@@ -733,6 +1012,66 @@ pub fn run(project: DebugProject, run_params: RunParams) -> DebugExecutionResult
                 }
             },
         )
+        .add(
+            "reverse-step",
+            command! {
+                "step back to the previous ACIR opcode",
+                () => || {
+                    ref_context.borrow_mut().reverse_step_acir_opcode();
+                    Ok(CommandStatus::Done)
+                }
+            },
+        )
+        .add(
+            "reverse-into",
+            command! {
+                "step back to the previous opcode",
+                () => || {
+                    ref_context.borrow_mut().reverse_step_into_opcode();
+                    Ok(CommandStatus::Done)
+                }
+            },
+        )
+        .add(
+            "reverse-next",
+            command! {
+                "step back to the previous source location",
+                () => || {
+                    ref_context.borrow_mut().reverse_next_into();
+                    Ok(CommandStatus::Done)
+                }
+            },
+        )
+        .add(
+            "reverse-over",
+            command! {
+                "step back to the previous source location without diving into function calls",
+                () => || {
+                    ref_context.borrow_mut().reverse_next_over();
+                    Ok(CommandStatus::Done)
+                }
+            },
+        )
+        .add(
+            "reverse-out",
+            command! {
+                "step back to the call of the current function",
+                () => || {
+                    ref_context.borrow_mut().reverse_next_out();
+                    Ok(CommandStatus::Done)
+                }
+            },
+        )
+        .add(
+            "reverse-continue",
+            command! {
+                "run backwards until the previous breakpoint or the start of the program",
+                () => || {
+                    ref_context.borrow_mut().reverse_cont();
+                    Ok(CommandStatus::Done)
+                }
+            },
+        )
         .add(
             "restart",
             command! {
@@ -853,6 +1192,76 @@ pub fn run(project: DebugProject, run_params: RunParams) -> DebugExecutionResult
                 }
             },
         )
+        .add(
+            "print",
+            command! {
+                "evaluate an expression over the variables in scope, e.g. `print \"a.x + b[1]\"`",
+                (expression: String) => |expression| {
+                    ref_context.borrow_mut().evaluate(expression);
+                    Ok(CommandStatus::Done)
+                }
+            },
+        )
+        .add(
+            "watch",
+            command! {
+                "evaluate an expression every time execution stops",
+                (expression: String) => |expression| {
+                    ref_context.borrow_mut().add_watch(expression);
+                    Ok(CommandStatus::Done)
+                }
+            },
+        )
+        .add(
+            "watch",
+            command! {
+                "stop when a witness (`watch witness 3`) or Brillig memory address (`watch memory 3`) changes",
+                (kind: String, index: usize) => |kind, index| {
+                    ref_context.borrow_mut().add_data_breakpoint(kind, index);
+                    Ok(CommandStatus::Done)
+                }
+            },
+        )
+        .add(
+            "unwatch",
+            command! {
+                "stop evaluating the watch expression with the given number",
+                (index: usize) => |index| {
+                    ref_context.borrow_mut().delete_watch(index);
+                    Ok(CommandStatus::Done)
+                }
+            },
+        )
+        .add(
+            "unwatch",
+            command! {
+                "stop watching a witness or Brillig memory address",
+                (kind: String, index: usize) => |kind, index| {
+                    ref_context.borrow_mut().delete_data_breakpoint(kind, index);
+                    Ok(CommandStatus::Done)
+                }
+            },
+        )
+        .add(
+            "watches",
+            command! {
+                "evaluate all watch expressions and list the watched witnesses and memory",
+                () => || {
+                    ref_context.borrow().show_watches();
+                    Ok(CommandStatus::Done)
+                }
+            },
+        )
+        .add(
+            "save-state",
+            command! {
+                "save the state execution failed in, to debug it later with `nargo debug --load-state`",
+                (path: String) => |path| {
+                    ref_context.borrow().save_failure_state(path);
+                    Ok(CommandStatus::Done)
+                }
+            },
+        )
         .build()
         .expect("Failed to initialize debugger repl");
 
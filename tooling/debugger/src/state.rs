@@ -0,0 +1,110 @@
+use std::path::Path;
+
+use acvm::FieldElement;
+use acvm::acir::brillig::ForeignCallResult;
+use acvm::acir::native_types::{WitnessMap, WitnessStack};
+use base64::Engine;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use thiserror::Error;
+
+/// The state a program failed in, saved so that the failure can be debugged later on.
+///
+/// Execution is reproduced by running the program again from the same inputs, answering its
+/// oracle calls with the results recorded when it failed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FailureState {
+    /// The error execution failed with.
+    pub error: String,
+    /// The hash of the compiled program that failed, to check that the same program is debugged
+    /// when the failure is reproduced.
+    pub program_hash: u64,
+    /// The inputs execution started from.
+    #[serde(
+        serialize_with = "serialize_witness_map_base64",
+        deserialize_with = "deserialize_witness_map_base64"
+    )]
+    pub initial_witness: WitnessMap<FieldElement>,
+    /// The results of the oracle calls made before the failure, in the order they were made.
+    pub foreign_call_results: Vec<ForeignCallResult<FieldElement>>,
+    /// The witnesses solved when execution failed, with the circuits being called on top.
+    #[serde(
+        serialize_with = "serialize_witness_stack_base64",
+        deserialize_with = "deserialize_witness_stack_base64"
+    )]
+    pub witness_stack: WitnessStack<FieldElement>,
+}
+
+#[derive(Debug, Error)]
+pub enum FailureStateError {
+    #[error("Failed to access failure state file {0}: {1}")]
+    Io(String, std::io::Error),
+
+    #[error("Failed to read failure state file {0}: {1}")]
+    Deserialization(String, serde_json::Error),
+
+    #[error(
+        "The failure state was saved for a different program, which may have changed since the failure"
+    )]
+    ProgramMismatch,
+}
+
+impl FailureState {
+    pub fn save(&self, path: &Path) -> Result<(), FailureStateError> {
+        let json = serde_json::to_string(self).expect("Failure state should serialize");
+        std::fs::write(path, json)
+            .map_err(|error| FailureStateError::Io(path.display().to_string(), error))
+    }
+
+    pub fn load(path: &Path) -> Result<Self, FailureStateError> {
+        let json = std::fs::read_to_string(path)
+            .map_err(|error| FailureStateError::Io(path.display().to_string(), error))?;
+        serde_json::from_str(&json)
+            .map_err(|error| FailureStateError::Deserialization(path.display().to_string(), error))
+    }
+
+    /// Checks that the failure state was saved while debugging the program with the given hash.
+    pub fn check_program_hash(&self, program_hash: u64) -> Result<(), FailureStateError> {
+        if self.program_hash == program_hash {
+            Ok(())
+        } else {
+            Err(FailureStateError::ProgramMismatch)
+        }
+    }
+}
+
+// Witnesses are stored in the same compressed format as witness files, rather than as JSON.
+
+fn serialize_witness_map_base64<S: Serializer>(
+    witness_map: &WitnessMap<FieldElement>,
+    s: S,
+) -> Result<S::Ok, S::Error> {
+    let bytes = witness_map.serialize().map_err(serde::ser::Error::custom)?;
+    s.serialize_str(&base64::engine::general_purpose::STANDARD.encode(bytes))
+}
+
+fn deserialize_witness_map_base64<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<WitnessMap<FieldElement>, D::Error> {
+    let bytes = deserialize_base64(deserializer)?;
+    WitnessMap::deserialize(&bytes).map_err(serde::de::Error::custom)
+}
+
+fn serialize_witness_stack_base64<S: Serializer>(
+    witness_stack: &WitnessStack<FieldElement>,
+    s: S,
+) -> Result<S::Ok, S::Error> {
+    let bytes = witness_stack.serialize().map_err(serde::ser::Error::custom)?;
+    s.serialize_str(&base64::engine::general_purpose::STANDARD.encode(bytes))
+}
+
+fn deserialize_witness_stack_base64<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<WitnessStack<FieldElement>, D::Error> {
+    let bytes = deserialize_base64(deserializer)?;
+    WitnessStack::deserialize(&bytes).map_err(serde::de::Error::custom)
+}
+
+fn deserialize_base64<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+    let encoded: String = Deserialize::deserialize(deserializer)?;
+    base64::engine::general_purpose::STANDARD.decode(encoded).map_err(serde::de::Error::custom)
+}
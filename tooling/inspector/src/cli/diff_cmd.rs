@@ -0,0 +1,630 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::{Path, PathBuf},
+};
+
+use acir::circuit::AcirOpcodeLocation;
+use clap::Args;
+use color_eyre::eyre::{self, bail};
+use noir_artifact_cli::Artifact;
+use noirc_abi::{Abi, AbiType, AbiVisibility, Sign};
+use noirc_artifacts::{debug::DebugArtifact, program::ProgramArtifact};
+use serde::Serialize;
+
+/// Compare the circuits of two artifacts, e.g. before and after a compiler upgrade.
+#[derive(Debug, Clone, Args)]
+pub(crate) struct DiffCommand {
+    /// The artifact to compare against
+    before: PathBuf,
+
+    /// The artifact to compare
+    after: PathBuf,
+
+    /// Output a JSON formatted report. Changes to this format are not currently considered breaking.
+    #[clap(long)]
+    json: bool,
+
+    /// Name of the function to compare, if the artifacts are contracts.
+    #[clap(long)]
+    contract_fn: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct DiffReport {
+    programs: Vec<ProgramDiff>,
+}
+
+#[derive(Debug, Serialize)]
+struct ProgramDiff {
+    name: String,
+    acir_opcodes: CountDiff,
+    brillig_opcodes: CountDiff,
+    functions: Vec<FunctionDiff>,
+    unconstrained_functions: Vec<FunctionDiff>,
+    abi_changes: Vec<AbiChange>,
+}
+
+#[derive(Debug, Default, Clone, Copy, Serialize)]
+struct CountDiff {
+    before: usize,
+    after: usize,
+    delta: i64,
+}
+
+impl CountDiff {
+    fn new(before: usize, after: usize) -> Self {
+        Self { before, after, delta: after as i64 - before as i64 }
+    }
+}
+
+impl std::fmt::Display for CountDiff {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} -> {} ({:+})", self.before, self.after, self.delta)
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct FunctionDiff {
+    name: String,
+    /// Whether the function only exists in one of the artifacts.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    status: Option<FunctionStatus>,
+    opcodes: CountDiff,
+    /// The change in the number of opcodes generated for each source location, largest first.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    locations: Vec<LocationDiff>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum FunctionStatus {
+    Added,
+    Removed,
+}
+
+#[derive(Debug, Serialize)]
+struct LocationDiff {
+    location: String,
+    opcodes: CountDiff,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum AbiChange {
+    ParameterAdded { name: String, typ: String },
+    ParameterRemoved { name: String, typ: String },
+    ParameterChanged { name: String, before: String, after: String },
+    ReturnTypeChanged { before: Option<String>, after: Option<String> },
+}
+
+impl std::fmt::Display for AbiChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AbiChange::ParameterAdded { name, typ } => {
+                write!(f, "parameter `{name}: {typ}` was added")
+            }
+            AbiChange::ParameterRemoved { name, typ } => {
+                write!(f, "parameter `{name}: {typ}` was removed")
+            }
+            AbiChange::ParameterChanged { name, before, after } => {
+                write!(f, "parameter `{name}` changed from `{before}` to `{after}`")
+            }
+            AbiChange::ReturnTypeChanged { before, after } => {
+                let before = before.as_deref().unwrap_or("()");
+                let after = after.as_deref().unwrap_or("()");
+                write!(f, "return type changed from `{before}` to `{after}`")
+            }
+        }
+    }
+}
+
+pub(crate) fn run(args: DiffCommand) -> eyre::Result<()> {
+    let before = read_programs(&args.before, args.contract_fn.as_deref())?;
+    let after = read_programs(&args.after, args.contract_fn.as_deref())?;
+    let roots = (artifact_root(&args.before), artifact_root(&args.after));
+
+    let programs = match (before, after) {
+        // Programs are compared regardless of their names, which come from the artifact file names.
+        (Programs::Program(_, before), Programs::Program(name, after)) => {
+            vec![diff_programs(name, Some(&before), Some(&after), &roots)]
+        }
+        (Programs::Contract(before), Programs::Contract(after)) => {
+            align_by_name(&program_names(&before), &program_names(&after))
+                .into_iter()
+                .map(|(name, before_index, after_index)| {
+                    let before = before_index.map(|index| &before[index].1);
+                    let after = after_index.map(|index| &after[index].1);
+                    diff_programs(name, before, after, &roots)
+                })
+                .collect()
+        }
+        _ => bail!("Cannot compare a program artifact with a contract artifact"),
+    };
+
+    let report = DiffReport { programs };
+    if args.json {
+        println!("{}", serde_json::to_string(&report)?);
+    } else {
+        print_report(&report);
+    }
+
+    Ok(())
+}
+
+enum Programs {
+    Program(String, ProgramArtifact),
+    /// The functions of a contract, each compiled into its own program.
+    Contract(Vec<(String, ProgramArtifact)>),
+}
+
+fn read_programs(path: &Path, contract_fn: Option<&str>) -> eyre::Result<Programs> {
+    let programs = match Artifact::read_from_file(path)? {
+        Artifact::Program(program) => {
+            let name = path
+                .with_extension("")
+                .file_name()
+                .map(|s| s.to_string_lossy().to_string())
+                .unwrap_or_else(|| "artifact".to_string());
+            Programs::Program(name, program)
+        }
+        Artifact::Contract(contract) => Programs::Contract(
+            contract
+                .functions
+                .into_iter()
+                .filter(|f| contract_fn.map(|n| n == f.name).unwrap_or(true))
+                .map(|f| {
+                    let name = format!("{}::{}", contract.name, f.name);
+                    let program = f.into_compiled_program(
+                        contract.noir_version.clone(),
+                        contract.file_map.clone(),
+                    );
+                    (name, program.into())
+                })
+                .collect(),
+        ),
+    };
+    Ok(programs)
+}
+
+/// The directory source paths are displayed relative to, so that artifacts compiled in different
+/// checkouts can be compared: the project directory for artifacts in its `target` directory,
+/// otherwise the directory of the artifact.
+fn artifact_root(path: &Path) -> PathBuf {
+    let path = std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf());
+    let dir = path.parent().unwrap_or(Path::new(""));
+    match dir.file_name() {
+        Some(name) if name == "target" => dir.parent().unwrap_or(dir).to_path_buf(),
+        _ => dir.to_path_buf(),
+    }
+}
+
+fn program_names(programs: &[(String, ProgramArtifact)]) -> Vec<String> {
+    programs.iter().map(|(name, _)| name.clone()).collect()
+}
+
+/// Pair up items by name, in the order of `after` followed by the items which were removed.
+///
+/// Items sharing a name are paired in the order they appear in.
+fn align_by_name(
+    before: &[String],
+    after: &[String],
+) -> Vec<(String, Option<usize>, Option<usize>)> {
+    fn keys(names: &[String]) -> Vec<(&str, usize)> {
+        let mut occurrences: HashMap<&str, usize> = HashMap::new();
+        names
+            .iter()
+            .map(|name| {
+                let occurrence = occurrences.entry(name.as_str()).or_default();
+                *occurrence += 1;
+                (name.as_str(), *occurrence)
+            })
+            .collect()
+    }
+
+    let before_keys = keys(before);
+    let mut before_indices: HashMap<_, _> =
+        before_keys.iter().enumerate().map(|(index, key)| (*key, index)).collect();
+
+    let mut aligned: Vec<_> = keys(after)
+        .into_iter()
+        .enumerate()
+        .map(|(after_index, key)| {
+            (key.0.to_string(), before_indices.remove(&key), Some(after_index))
+        })
+        .collect();
+
+    aligned.extend(before_keys.into_iter().enumerate().filter_map(|(before_index, key)| {
+        before_indices.contains_key(&key).then(|| (key.0.to_string(), Some(before_index), None))
+    }));
+    aligned
+}
+
+/// Compare two versions of a program; `roots` are the [artifact_root]s of the two artifacts.
+fn diff_programs(
+    name: String,
+    before: Option<&ProgramArtifact>,
+    after: Option<&ProgramArtifact>,
+    roots: &(PathBuf, PathBuf),
+) -> ProgramDiff {
+    let before_debug = before.map(debug_artifact);
+    let after_debug = after.map(debug_artifact);
+    let names = |program: Option<&ProgramArtifact>| program.map(|p| p.names.clone());
+    let brillig_names =
+        |program: Option<&ProgramArtifact>| program.map(|p| p.brillig_names.clone());
+
+    let functions =
+        align_by_name(&names(before).unwrap_or_default(), &names(after).unwrap_or_default())
+            .into_iter()
+            .map(|(name, before_index, after_index)| {
+                let locations = |program: Option<&ProgramArtifact>,
+                                 debug: Option<&DebugArtifact>,
+                                 root: &Path,
+                                 index: Option<usize>| {
+                    match (program, debug, index) {
+                        (Some(program), Some(debug), Some(index)) => {
+                            let opcodes = program.bytecode.functions[index].opcodes.len();
+                            opcodes_per_location(debug, root, index, opcodes)
+                        }
+                        _ => BTreeMap::new(),
+                    }
+                };
+                let before_locations =
+                    locations(before, before_debug.as_ref(), &roots.0, before_index);
+                let after_locations = locations(after, after_debug.as_ref(), &roots.1, after_index);
+                let opcodes =
+                    CountDiff::new(before_locations.values().sum(), after_locations.values().sum());
+                FunctionDiff {
+                    name,
+                    status: function_status(before_index, after_index),
+                    opcodes,
+                    locations: diff_locations(before_locations, after_locations),
+                }
+            })
+            .collect::<Vec<_>>();
+
+    let unconstrained_functions = align_by_name(
+        &brillig_names(before).unwrap_or_default(),
+        &brillig_names(after).unwrap_or_default(),
+    )
+    .into_iter()
+    .map(|(name, before_index, after_index)| {
+        let bytecode_len = |program: Option<&ProgramArtifact>, index: Option<usize>| {
+            program
+                .zip(index)
+                .map(|(program, index)| {
+                    program.bytecode.unconstrained_functions[index].bytecode.len()
+                })
+                .unwrap_or_default()
+        };
+        FunctionDiff {
+            name,
+            status: function_status(before_index, after_index),
+            opcodes: CountDiff::new(
+                bytecode_len(before, before_index),
+                bytecode_len(after, after_index),
+            ),
+            locations: Vec::new(),
+        }
+    })
+    .collect::<Vec<_>>();
+
+    let total = |functions: &[FunctionDiff]| {
+        CountDiff::new(
+            functions.iter().map(|function| function.opcodes.before).sum(),
+            functions.iter().map(|function| function.opcodes.after).sum(),
+        )
+    };
+
+    ProgramDiff {
+        name,
+        acir_opcodes: total(&functions),
+        brillig_opcodes: total(&unconstrained_functions),
+        abi_changes: diff_abis(before.map(|p| &p.abi), after.map(|p| &p.abi)),
+        functions,
+        unconstrained_functions,
+    }
+}
+
+fn debug_artifact(program: &ProgramArtifact) -> DebugArtifact {
+    DebugArtifact {
+        debug_symbols: program.debug_symbols.debug_infos.clone(),
+        file_map: program.file_map.clone(),
+    }
+}
+
+fn function_status(before: Option<usize>, after: Option<usize>) -> Option<FunctionStatus> {
+    match (before, after) {
+        (None, Some(_)) => Some(FunctionStatus::Added),
+        (Some(_), None) => Some(FunctionStatus::Removed),
+        _ => None,
+    }
+}
+
+/// Count the opcodes of an ACIR function by the source location they were generated from.
+///
+/// Opcodes are attributed to the innermost location of their call stack, whose path is displayed
+/// relative to `root` if it's inside of it.
+fn opcodes_per_location(
+    debug: &DebugArtifact,
+    root: &Path,
+    function_index: usize,
+    opcodes: usize,
+) -> BTreeMap<String, usize> {
+    let debug_info = debug.debug_symbols.get(function_index);
+    let mut counts = BTreeMap::new();
+    for index in 0..opcodes {
+        let location = debug_info
+            .and_then(|debug_info| {
+                let call_stack_id =
+                    debug_info.acir_locations.get(&AcirOpcodeLocation::new(index))?;
+                Some((debug_info, call_stack_id))
+            })
+            .and_then(|(debug_info, call_stack_id)| {
+                debug_info.location_tree.get_call_stack(*call_stack_id).last().copied()
+            })
+            .and_then(|location| {
+                let file = debug.file_map.get(&location.file)?;
+                let path = file.path.strip_prefix(root).unwrap_or(&file.path);
+                let line = debug.location_line_number(location).ok()?;
+                let column = debug.location_column_number(location).ok()?;
+                Some(format!("{}:{line}:{column}", path.display()))
+            })
+            .unwrap_or_else(|| "<unknown>".to_string());
+        *counts.entry(location).or_default() += 1;
+    }
+    counts
+}
+
+fn diff_locations(
+    before: BTreeMap<String, usize>,
+    mut after: BTreeMap<String, usize>,
+) -> Vec<LocationDiff> {
+    let mut locations: Vec<_> = before
+        .into_iter()
+        .map(|(location, before)| {
+            let after = after.remove(&location).unwrap_or_default();
+            LocationDiff { location, opcodes: CountDiff::new(before, after) }
+        })
+        .collect();
+    locations.extend(
+        after
+            .into_iter()
+            .map(|(location, after)| LocationDiff { location, opcodes: CountDiff::new(0, after) }),
+    );
+    locations.retain(|location| location.opcodes.delta != 0);
+    locations.sort_by_key(|location| std::cmp::Reverse(location.opcodes.delta.abs()));
+    locations
+}
+
+fn diff_abis(before: Option<&Abi>, after: Option<&Abi>) -> Vec<AbiChange> {
+    let (Some(before), Some(after)) = (before, after) else {
+        return Vec::new();
+    };
+
+    let mut changes = Vec::new();
+    for parameter in &after.parameters {
+        let after_type = display_parameter_type(&parameter.typ, parameter.visibility);
+        match before.parameters.iter().find(|p| p.name == parameter.name) {
+            None => changes
+                .push(AbiChange::ParameterAdded { name: parameter.name.clone(), typ: after_type }),
+            Some(before_parameter) => {
+                let before_type =
+                    display_parameter_type(&before_parameter.typ, before_parameter.visibility);
+                if before_type != after_type {
+                    changes.push(AbiChange::ParameterChanged {
+                        name: parameter.name.clone(),
+                        before: before_type,
+                        after: after_type,
+                    });
+                }
+            }
+        }
+    }
+    for parameter in &before.parameters {
+        if !after.parameters.iter().any(|p| p.name == parameter.name) {
+            changes.push(AbiChange::ParameterRemoved {
+                name: parameter.name.clone(),
+                typ: display_parameter_type(&parameter.typ, parameter.visibility),
+            });
+        }
+    }
+
+    let return_type = |abi: &Abi| {
+        abi.return_type.as_ref().map(|typ| display_parameter_type(&typ.abi_type, typ.visibility))
+    };
+    if return_type(before) != return_type(after) {
+        changes.push(AbiChange::ReturnTypeChanged {
+            before: return_type(before),
+            after: return_type(after),
+        });
+    }
+    changes
+}
+
+fn display_parameter_type(typ: &AbiType, visibility: AbiVisibility) -> String {
+    match visibility {
+        AbiVisibility::Public => format!("pub {}", display_abi_type(typ)),
+        AbiVisibility::Private => display_abi_type(typ),
+        AbiVisibility::DataBus => format!("call_data {}", display_abi_type(typ)),
+    }
+}
+
+/// Display an ABI type the way it would be written in Noir.
+fn display_abi_type(typ: &AbiType) -> String {
+    match typ {
+        AbiType::Field => "Field".to_string(),
+        AbiType::Boolean => "bool".to_string(),
+        AbiType::Integer { sign: Sign::Unsigned, width } => format!("u{width}"),
+        AbiType::Integer { sign: Sign::Signed, width } => format!("i{width}"),
+        AbiType::Array { length, typ } => format!("[{}; {length}]", display_abi_type(typ)),
+        AbiType::String { length } => format!("str<{length}>"),
+        AbiType::Struct { path, .. } => path.clone(),
+        AbiType::Tuple { fields } => {
+            let fields: Vec<_> = fields.iter().map(display_abi_type).collect();
+            format!("({})", fields.join(", "))
+        }
+    }
+}
+
+fn print_report(report: &DiffReport) {
+    for program in &report.programs {
+        println!("Program `{}`:", program.name);
+        println!("  ACIR opcodes: {}", program.acir_opcodes);
+        println!("  Brillig opcodes: {}", program.brillig_opcodes);
+
+        for function in &program.functions {
+            print_function("ACIR function", function);
+        }
+        for function in &program.unconstrained_functions {
+            print_function("Brillig function", function);
+        }
+        for change in &program.abi_changes {
+            println!("  ABI: {change}");
+        }
+    }
+}
+
+/// Print a function along with its changed locations, unless it didn't change.
+fn print_function(kind: &str, function: &FunctionDiff) {
+    let status = match function.status {
+        Some(FunctionStatus::Added) => " (added)",
+        Some(FunctionStatus::Removed) => " (removed)",
+        None if function.opcodes.delta == 0 && function.locations.is_empty() => return,
+        None => "",
+    };
+    println!("  {kind} `{}`{status}: {}", function.name, function.opcodes);
+    for location in &function.locations {
+        println!("    {}: {:+}", location.location, location.opcodes.delta);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::BTreeMap, path::Path};
+
+    use noirc_abi::{Abi, AbiParameter, AbiReturnType, AbiType, AbiVisibility, Sign};
+
+    use super::{AbiChange, align_by_name, artifact_root, diff_abis, diff_locations};
+
+    fn names(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn aligns_functions_by_name() {
+        let aligned =
+            align_by_name(&names(&["main", "foo", "bar"]), &names(&["main", "baz", "foo"]));
+        assert_eq!(
+            aligned,
+            vec![
+                ("main".to_string(), Some(0), Some(0)),
+                // `bar` was renamed to `baz`, which shows up as one function added and one removed.
+                ("baz".to_string(), None, Some(1)),
+                ("foo".to_string(), Some(1), Some(2)),
+                ("bar".to_string(), Some(2), None),
+            ]
+        );
+    }
+
+    #[test]
+    fn aligns_functions_sharing_a_name_in_order() {
+        let aligned = align_by_name(&names(&["main", "foo", "foo"]), &names(&["foo", "main"]));
+        assert_eq!(
+            aligned,
+            vec![
+                ("foo".to_string(), Some(1), Some(0)),
+                ("main".to_string(), Some(0), Some(1)),
+                ("foo".to_string(), Some(2), None),
+            ]
+        );
+    }
+
+    #[test]
+    fn diffs_locations_largest_change_first() {
+        let before = BTreeMap::from([
+            ("src/main.nr:1:1".to_string(), 3),
+            ("src/main.nr:2:1".to_string(), 5),
+            ("src/main.nr:3:1".to_string(), 1),
+        ]);
+        let after = BTreeMap::from([
+            ("src/main.nr:1:1".to_string(), 4),
+            ("src/main.nr:2:1".to_string(), 5),
+            ("src/main.nr:4:1".to_string(), 7),
+        ]);
+
+        let locations: Vec<_> = diff_locations(before, after)
+            .into_iter()
+            .map(|location| (location.location, location.opcodes.delta))
+            .collect();
+        assert_eq!(
+            locations,
+            vec![
+                ("src/main.nr:4:1".to_string(), 7),
+                ("src/main.nr:1:1".to_string(), 1),
+                ("src/main.nr:3:1".to_string(), -1),
+            ]
+        );
+    }
+
+    fn parameter(name: &str, typ: AbiType, visibility: AbiVisibility) -> AbiParameter {
+        AbiParameter { name: name.to_string(), typ, visibility }
+    }
+
+    #[test]
+    fn diffs_abis() {
+        let u32_type = AbiType::Integer { sign: Sign::Unsigned, width: 32 };
+        let before = Abi {
+            parameters: vec![
+                parameter("x", AbiType::Field, AbiVisibility::Private),
+                parameter("y", u32_type.clone(), AbiVisibility::Private),
+                parameter("z", AbiType::Boolean, AbiVisibility::Private),
+            ],
+            return_type: None,
+            ..Abi::default()
+        };
+        let after = Abi {
+            parameters: vec![
+                parameter("x", AbiType::Field, AbiVisibility::Public),
+                parameter("y", u32_type.clone(), AbiVisibility::Private),
+                parameter(
+                    "w",
+                    AbiType::Array { length: 2, typ: Box::new(u32_type) },
+                    AbiVisibility::Private,
+                ),
+            ],
+            return_type: Some(AbiReturnType {
+                abi_type: AbiType::Field,
+                visibility: AbiVisibility::Public,
+            }),
+            ..Abi::default()
+        };
+
+        let changes: Vec<_> =
+            diff_abis(Some(&before), Some(&after)).iter().map(AbiChange::to_string).collect();
+        assert_eq!(
+            changes,
+            vec![
+                "parameter `x` changed from `Field` to `pub Field`",
+                "parameter `w: [u32; 2]` was added",
+                "parameter `z: bool` was removed",
+                "return type changed from `()` to `pub Field`",
+            ]
+        );
+
+        assert!(diff_abis(Some(&before), Some(&before)).is_empty());
+        // An added or removed program has no ABI to compare against.
+        assert!(diff_abis(None, Some(&after)).is_empty());
+    }
+
+    #[test]
+    fn artifact_root_is_the_project_of_the_target_directory() {
+        assert_eq!(
+            artifact_root(Path::new("/work/project/target/main.json")),
+            Path::new("/work/project")
+        );
+        assert_eq!(
+            artifact_root(Path::new("/work/artifacts/main.json")),
+            Path::new("/work/artifacts")
+        );
+    }
+}
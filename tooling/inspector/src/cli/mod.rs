@@ -2,6 +2,7 @@ use clap::{Parser, Subcommand, command};
 use color_eyre::eyre;
 use const_format::formatcp;
 
+mod diff_cmd;
 mod info_cmd;
 mod print_acir_cmd;
 
@@ -20,6 +21,7 @@ struct InspectorCli {
 #[derive(Subcommand, Clone, Debug)]
 enum InspectorCommand {
     Info(info_cmd::InfoCommand),
+    Diff(diff_cmd::DiffCommand),
     PrintAcir(print_acir_cmd::PrintAcirCommand),
 }
 
@@ -28,6 +30,7 @@ pub(crate) fn start_cli() -> eyre::Result<()> {
 
     match command {
         InspectorCommand::Info(args) => info_cmd::run(args),
+        InspectorCommand::Diff(args) => diff_cmd::run(args),
         InspectorCommand::PrintAcir(args) => print_acir_cmd::run(args),
     }
 }
@@ -4,7 +4,7 @@
 #![cfg_attr(not(test), warn(unused_crate_dependencies, unused_extern_crates))]
 
 use std::{
-    collections::{BTreeMap, HashMap, HashSet},
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
     future::Future,
     ops::{self, ControlFlow},
     path::{Path, PathBuf},
@@ -32,7 +32,9 @@ use nargo::{
     parse_all,
     workspace::Workspace,
 };
-use nargo_toml::{PackageSelection, find_file_manifest, resolve_workspace_from_toml};
+use nargo_toml::{
+    PackageSelection, REGISTRY_ENV, ResolveOptions, find_file_manifest, resolve_workspace_from_toml,
+};
 use noirc_driver::{NOIR_ARTIFACT_VERSION_STRING, file_manager_with_stdlib, prepare_crate};
 use noirc_frontend::{
     ParsedModule,
@@ -268,12 +270,18 @@ fn byte_span_to_range<'a, F: files::Files<'a> + ?Sized>(
     }
 }
 
+/// Options for resolving workspaces, taking the registry index from the environment as the CLI does.
+pub(crate) fn workspace_resolve_options() -> ResolveOptions {
+    ResolveOptions { registry: std::env::var(REGISTRY_ENV).ok(), ..Default::default() }
+}
+
 pub(crate) fn resolve_workspace_for_source_path(file_path: &Path) -> Result<Workspace, LspError> {
     if let Some(toml_path) = find_file_manifest(file_path) {
         match resolve_workspace_from_toml(
             &toml_path,
             PackageSelection::All,
             Some(NOIR_ARTIFACT_VERSION_STRING.to_string()),
+            &workspace_resolve_options(),
         ) {
             Ok(workspace) => return Ok(workspace),
             Err(error) => {
@@ -309,6 +317,8 @@ pub(crate) fn resolve_workspace_for_source_path(file_path: &Path) -> Result<Work
         entry_path: PathBuf::from(file_path),
         name: crate_name,
         dependencies: BTreeMap::new(),
+        dev_dependencies: BTreeMap::new(),
+        features: BTreeSet::new(),
         expression_width: None,
     };
     let workspace = Workspace {
@@ -337,7 +347,8 @@ pub(crate) fn prepare_package<'file_manager, 'parsed_files>(
     parsed_files: &'parsed_files ParsedFiles,
     package: &Package,
 ) -> (Context<'file_manager, 'parsed_files>, CrateId) {
-    let (mut context, crate_id) = nargo::prepare_package(file_manager, parsed_files, package);
+    let (mut context, crate_id) =
+        nargo::prepare_package_for_tests(file_manager, parsed_files, package);
     context.activate_lsp_mode();
     (context, crate_id)
 }
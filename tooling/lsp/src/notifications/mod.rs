@@ -13,6 +13,7 @@ use fxhash::FxHashMap as HashMap;
 use noirc_driver::check_crate;
 use noirc_errors::reporter::CustomLabel;
 use noirc_errors::{CustomDiagnostic, DiagnosticKind, Location};
+use noirc_frontend::token::CfgPredicate;
 
 use crate::types::{
     Diagnostic, DiagnosticSeverity, DidChangeConfigurationParams, DidChangeTextDocumentParams,
@@ -141,10 +142,16 @@ pub(crate) fn process_workspace_for_noir_document(
         let (mut context, crate_id) =
             crate::prepare_package(&workspace_file_manager, &parsed_files, package);
 
-        let file_diagnostics = match check_crate(&mut context, crate_id, &Default::default()) {
+        let mut file_diagnostics = match check_crate(&mut context, crate_id, &Default::default()) {
             Ok(((), warnings)) => warnings,
             Err(errors_and_warnings) => errors_and_warnings,
         };
+        file_diagnostics.extend(
+            context
+                .inactive_items
+                .iter()
+                .map(|(location, predicate)| inactive_item_diagnostic(*location, predicate)),
+        );
 
         // We don't add test headings for a package if it contains no `#[test]` functions
         if let Some(tests) = get_package_tests_in_crate(&context, &crate_id, &package.name) {
@@ -189,6 +196,20 @@ pub(crate) fn process_workspace_for_noir_document(
     Ok(())
 }
 
+/// Items disabled by a `#[cfg(feature = "...")]` attribute are shown as unnecessary code.
+fn inactive_item_diagnostic(location: Location, predicate: &CfgPredicate) -> CustomDiagnostic {
+    let message = match predicate {
+        CfgPredicate::Feature(feature) => {
+            format!("code is inactive because feature `{feature}` is not enabled")
+        }
+        CfgPredicate::Test => "code is inactive because it is only compiled for tests".to_string(),
+    };
+    let mut diagnostic =
+        CustomDiagnostic::simple_info(message, "inactive code".to_string(), location);
+    diagnostic.unnecessary = true;
+    diagnostic
+}
+
 fn publish_diagnostics(
     state: &mut LspState,
     package_root_dir: &PathBuf,
@@ -579,7 +579,9 @@ fn get_expression_name(expression: &Expression) -> Option<String> {
         ExpressionKind::Call(call) => get_expression_name(&call.func),
         ExpressionKind::MethodCall(method_call) => Some(method_call.method_name.to_string()),
         ExpressionKind::Cast(cast) => get_expression_name(&cast.lhs),
-        ExpressionKind::Parenthesized(expr) => get_expression_name(expr),
+        ExpressionKind::Parenthesized(expr) | ExpressionKind::Try(expr) => {
+            get_expression_name(expr)
+        }
         ExpressionKind::AsTraitPath(path) => Some(path.impl_item.to_string()),
         ExpressionKind::TypePath(path) => Some(path.item.to_string()),
         ExpressionKind::Constrain(constrain) => Some(constrain.kind.to_string()),
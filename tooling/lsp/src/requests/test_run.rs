@@ -1,6 +1,6 @@
 use std::future::{self, Future};
 
-use crate::insert_all_files_for_workspace_into_file_manager;
+use crate::{insert_all_files_for_workspace_into_file_manager, workspace_resolve_options};
 use async_lsp::{ErrorCode, ResponseError};
 use nargo::{
     foreign_calls::DefaultForeignCallBuilder,
@@ -42,6 +42,7 @@ fn on_test_run_request_inner(
         &toml_path,
         PackageSelection::Selected(crate_name.clone()),
         Some(NOIR_ARTIFACT_VERSION_STRING.to_string()),
+        &workspace_resolve_options(),
     )
     .map_err(|err| {
         // If we found a manifest, but the workspace is invalid, we raise an error about it
@@ -1,6 +1,6 @@
 use std::future::{self, Future};
 
-use crate::insert_all_files_for_workspace_into_file_manager;
+use crate::{insert_all_files_for_workspace_into_file_manager, workspace_resolve_options};
 use async_lsp::lsp_types::{LogMessageParams, MessageType};
 use async_lsp::{ErrorCode, LanguageClient, ResponseError};
 use nargo_toml::{PackageSelection, find_package_manifest, resolve_workspace_from_toml};
@@ -43,6 +43,7 @@ fn on_tests_request_inner(
         &toml_path,
         PackageSelection::All,
         Some(NOIR_ARTIFACT_VERSION_STRING.to_string()),
+        &workspace_resolve_options(),
     )
     .map_err(|err| {
         // If we found a manifest, but the workspace is invalid, we raise an error about it
@@ -582,11 +582,14 @@ fn secondary_attribute_with_file(
         | SecondaryAttributeKind::ContractLibraryMethod
         | SecondaryAttributeKind::Export
         | SecondaryAttributeKind::Field(_)
+        | SecondaryAttributeKind::Cfg(_)
         | SecondaryAttributeKind::Tag(..)
         | SecondaryAttributeKind::Abi(_)
         | SecondaryAttributeKind::Varargs
         | SecondaryAttributeKind::UseCallersScope
-        | SecondaryAttributeKind::Allow(_) => secondary_attribute.kind,
+        | SecondaryAttributeKind::Allow(_)
+        | SecondaryAttributeKind::MaxAcirOpcodes(_)
+        | SecondaryAttributeKind::MaxBrilligBytecode(_) => secondary_attribute.kind,
     };
     SecondaryAttribute { kind, location: location_with_file(secondary_attribute.location, file) }
 }
@@ -665,6 +668,9 @@ fn expression_kind_with_file(kind: ExpressionKind, file: FileId) -> ExpressionKi
         ExpressionKind::Unquote(expression) => {
             ExpressionKind::Unquote(Box::new(expression_with_file(*expression, file)))
         }
+        ExpressionKind::Try(expression) => {
+            ExpressionKind::Try(Box::new(expression_with_file(*expression, file)))
+        }
         ExpressionKind::Comptime(block_expression, location) => ExpressionKind::Comptime(
             block_expression_with_file(block_expression, file),
             location_with_file(location, file),
@@ -903,6 +909,9 @@ fn statement_kind_with_file(kind: StatementKind, file: FileId) -> StatementKind
         StatementKind::Semi(expression) => {
             StatementKind::Semi(expression_with_file(expression, file))
         }
+        StatementKind::Return(expression) => StatementKind::Return(
+            expression.map(|expression| expression_with_file(expression, file)),
+        ),
         StatementKind::Interned(..)
         | StatementKind::Break
         | StatementKind::Continue
@@ -5,6 +5,8 @@ pub const SRC_DIR: &str = "src";
 pub const TARGET_DIR: &str = "target";
 /// The directory to store serialized ACIR representations of exported library functions.
 pub const EXPORT_DIR: &str = "export";
+/// The directory to store the failing inputs found by fuzzing harnesses.
+pub const FUZZ_CRASHES_DIR: &str = "fuzz_crashes";
 
 // Files
 /// The file from which Nargo pulls prover inputs
@@ -116,7 +116,7 @@ fn extract_locations_from_error<F: AcirField>(
     error: &ExecutionError<F>,
     debug: &[DebugInfo],
 ) -> Option<Vec<Location>> {
-    let mut opcode_locations = match error {
+    let opcode_locations = match error {
         ExecutionError::SolvingError(
             OpcodeResolutionError::BrilligFunctionFailed { .. },
             acir_call_stack,
@@ -142,7 +142,27 @@ fn extract_locations_from_error<F: AcirField>(
         _ => None,
     }?;
 
-    // Insert the top-level Acir location where the Brillig function failed
+    let brillig_function_id = match error {
+        ExecutionError::SolvingError(
+            OpcodeResolutionError::BrilligFunctionFailed { function_id, .. },
+            _,
+        ) => Some(*function_id),
+        ExecutionError::AssertionFailed(_, _, function_id) => *function_id,
+        _ => None,
+    };
+
+    Some(resolve_call_stack(opcode_locations, brillig_function_id, debug))
+}
+
+/// Resolves the source locations of a call stack built up during execution.
+///
+/// `brillig_function_id` must be set if the call stack ends within a Brillig function.
+pub(crate) fn resolve_call_stack(
+    mut opcode_locations: Vec<ResolvedOpcodeLocation>,
+    brillig_function_id: Option<BrilligFunctionId>,
+    debug: &[DebugInfo],
+) -> Vec<Location> {
+    // Insert the top-level Acir location where the Brillig function was called
     for (i, resolved_location) in opcode_locations.iter().enumerate() {
         if let ResolvedOpcodeLocation {
             acir_function_index,
@@ -162,36 +182,23 @@ fn extract_locations_from_error<F: AcirField>(
         }
     }
 
-    let brillig_function_id = match error {
-        ExecutionError::SolvingError(
-            OpcodeResolutionError::BrilligFunctionFailed { function_id, .. },
-            _,
-        ) => Some(*function_id),
-        ExecutionError::AssertionFailed(_, _, function_id) => *function_id,
-        _ => None,
-    };
-
-    Some(
-        opcode_locations
-            .iter()
-            .flat_map(|resolved_location| {
-                let call_stack_id = match resolved_location.opcode_location {
-                    OpcodeLocation::Acir(idx) => *debug[resolved_location.acir_function_index]
-                        .acir_locations
-                        .get(&AcirOpcodeLocation::new(idx))
-                        .unwrap_or(&CallStackId::root()),
-                    OpcodeLocation::Brillig { brillig_index, .. } => *debug
-                        [resolved_location.acir_function_index]
-                        .brillig_locations[&brillig_function_id.unwrap()]
-                        .get(&BrilligOpcodeLocation(brillig_index))
-                        .unwrap_or(&CallStackId::root()),
-                };
-                debug[resolved_location.acir_function_index]
-                    .location_tree
-                    .get_call_stack(call_stack_id)
-            })
-            .collect(),
-    )
+    opcode_locations
+        .iter()
+        .flat_map(|resolved_location| {
+            let call_stack_id = match resolved_location.opcode_location {
+                OpcodeLocation::Acir(idx) => *debug[resolved_location.acir_function_index]
+                    .acir_locations
+                    .get(&AcirOpcodeLocation::new(idx))
+                    .unwrap_or(&CallStackId::root()),
+                OpcodeLocation::Brillig { brillig_index, .. } => *debug
+                    [resolved_location.acir_function_index]
+                    .brillig_locations[&brillig_function_id.unwrap()]
+                    .get(&BrilligOpcodeLocation(brillig_index))
+                    .unwrap_or(&CallStackId::root()),
+            };
+            debug[resolved_location.acir_function_index].location_tree.get_call_stack(call_stack_id)
+        })
+        .collect()
 }
 
 fn extract_message_from_error(
@@ -6,6 +6,7 @@ use super::{
     layers::{self, Either, Layer, Layering},
     mocker::{DisabledMockForeignCallExecutor, MockForeignCallExecutor},
     print::PrintForeignCallExecutor,
+    transcript::LoggingForeignCallExecutor,
 };
 
 #[cfg(feature = "rpc")]
@@ -67,6 +68,9 @@ impl<W> DefaultForeignCallBuilder<W> {
     }
 
     /// Set or unset resolver url.
+    ///
+    /// Besides `http://` URLs, the resolver can be reached by spawning a process with
+    /// `stdio:<program> [args..]`, or over a Unix domain socket with `unix:<path>`.
     #[cfg(feature = "rpc")]
     pub fn with_resolver_url(mut self, resolver_url: Option<String>) -> Self {
         self.resolver_url = resolver_url;
@@ -90,17 +94,7 @@ impl<W> DefaultForeignCallBuilder<W> {
         let executor = {
             #[cfg(feature = "rpc")]
             {
-                use rand::Rng;
-
-                base.add_layer(self.resolver_url.map(|resolver_url| {
-                    let id = rand::thread_rng().r#gen();
-                    RPCForeignCallExecutor::new(
-                        &resolver_url,
-                        id,
-                        self.root_path,
-                        self.package_name,
-                    )
-                }))
+                base.add_layer(self.resolver())
             }
             #[cfg(not(feature = "rpc"))]
             {
@@ -116,6 +110,52 @@ impl<W> DefaultForeignCallBuilder<W> {
             })
             .add_layer(PrintForeignCallExecutor::new(self.output))
     }
+
+    /// Compose the executor layers with `base` as the default handler, and write every call
+    /// resolved by the external resolver or by `base` into `transcript`, so that it can be
+    /// replayed with a [ReplayForeignCallExecutor](super::transcript::ReplayForeignCallExecutor).
+    ///
+    /// Calls handled by the print and mock layers are not recorded, as they would be handled
+    /// by the same layers when the transcript is replayed.
+    #[cfg_attr(not(feature = "rpc"), allow(unused_mut))]
+    pub fn build_with_transcript<B, F, T>(
+        mut self,
+        base: B,
+        transcript: T,
+    ) -> impl ForeignCallExecutor<F>
+    where
+        F: AcirField + Serialize + for<'de> Deserialize<'de>,
+        B: ForeignCallExecutor<F>,
+        W: std::io::Write,
+        T: std::io::Write,
+    {
+        // Move the resolver under the logging layer, so its responses are recorded.
+        #[cfg(feature = "rpc")]
+        let base = {
+            let resolver = self.resolver();
+            self.resolver_url = None;
+            base.add_layer(resolver)
+        };
+
+        let base = LoggingForeignCallExecutor::new(base, transcript);
+        self.build_with_base(base)
+    }
+
+    /// Create an executor that calls the external resolver, if a resolver URL was set.
+    #[cfg(feature = "rpc")]
+    fn resolver(&self) -> Option<RPCForeignCallExecutor> {
+        use rand::Rng;
+
+        self.resolver_url.as_ref().map(|resolver_url| {
+            let id = rand::thread_rng().r#gen();
+            RPCForeignCallExecutor::new(
+                resolver_url,
+                id,
+                self.root_path.clone(),
+                self.package_name.clone(),
+            )
+        })
+    }
 }
 
 /// Facilitate static typing of layers on a base layer, so inner layers can be accessed.
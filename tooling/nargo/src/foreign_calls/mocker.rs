@@ -7,6 +7,57 @@ use noirc_printable_type::decode_string_value;
 
 use super::{ForeignCall, ForeignCallError, ForeignCallExecutor};
 
+// The kinds of `std::test::ParamMatcher`, which must match those in `noir_stdlib/src/test.nr`.
+const MATCH_ANY: u64 = 0;
+const MATCH_EQ: u64 = 1;
+const MATCH_LT: u64 = 2;
+const MATCH_GT: u64 = 3;
+const MATCH_BETWEEN: u64 = 4;
+
+/// A predicate which an oracle parameter must satisfy for a mock to match a call.
+///
+/// Matchers are applied to every field of their parameter, so that a matcher for an array
+/// parameter only matches if all of its elements do.
+#[derive(Debug, PartialEq, Eq, Clone)]
+enum ParamMatcher<F> {
+    Any,
+    Eq(F),
+    LessThan(F),
+    GreaterThan(F),
+    /// Matches values within the inclusive range.
+    Between(F, F),
+}
+
+impl<F: AcirField> ParamMatcher<F> {
+    /// Parses a matcher from the `(kind, lower, upper)` fields of a `std::test::ParamMatcher`.
+    fn from_fields(fields: &[F]) -> Result<Self, ForeignCallError> {
+        let [kind, lower, upper] = fields else {
+            return Err(ForeignCallError::InvalidParamMatcher(format!(
+                "expected 3 fields, got {}",
+                fields.len()
+            )));
+        };
+        match kind.try_to_u64() {
+            Some(MATCH_ANY) => Ok(ParamMatcher::Any),
+            Some(MATCH_EQ) => Ok(ParamMatcher::Eq(*lower)),
+            Some(MATCH_LT) => Ok(ParamMatcher::LessThan(*upper)),
+            Some(MATCH_GT) => Ok(ParamMatcher::GreaterThan(*lower)),
+            Some(MATCH_BETWEEN) => Ok(ParamMatcher::Between(*lower, *upper)),
+            _ => Err(ForeignCallError::InvalidParamMatcher(format!("unknown kind {kind}"))),
+        }
+    }
+
+    fn matches(&self, param: &ForeignCallParam<F>) -> bool {
+        param.fields().iter().all(|value| match self {
+            ParamMatcher::Any => true,
+            ParamMatcher::Eq(expected) => value == expected,
+            ParamMatcher::LessThan(upper) => value < upper,
+            ParamMatcher::GreaterThan(lower) => value > lower,
+            ParamMatcher::Between(lower, upper) => lower <= value && value <= upper,
+        })
+    }
+}
+
 /// This struct represents an oracle mock. It can be used for testing programs that use oracles.
 #[derive(Debug, PartialEq, Eq, Clone)]
 struct MockedCall<F> {
@@ -16,14 +67,19 @@ struct MockedCall<F> {
     name: String,
     /// Optionally match the parameters
     params: Option<Vec<ForeignCallParam<F>>>,
+    /// Optionally match the parameters against predicates, one per parameter
+    param_matchers: Option<Vec<ParamMatcher<F>>>,
     /// The parameters with which the mock was last called
     last_called_params: Option<Vec<ForeignCallParam<F>>>,
-    /// The result to return when this mock is called
-    result: ForeignCallResult<F>,
+    /// The results to return when this mock is called, in order.
+    /// The last result keeps being returned once the ones before it have been used up.
+    results: Vec<ForeignCallResult<F>>,
     /// How many times should this mock be called before it is removed
     times_left: Option<u64>,
     /// How many times this mock was actually called
     times_called: u32,
+    /// How many times this mock is expected to be called by the end of the test
+    expected_times: Option<u64>,
 }
 
 impl<F> MockedCall<F> {
@@ -32,17 +88,58 @@ impl<F> MockedCall<F> {
             id,
             name,
             params: None,
+            param_matchers: None,
             last_called_params: None,
-            result: ForeignCallResult { values: vec![] },
+            results: vec![],
             times_left: None,
             times_called: 0,
+            expected_times: None,
         }
     }
 }
 
-impl<F: PartialEq> MockedCall<F> {
+impl<F: AcirField> MockedCall<F> {
     fn matches(&self, name: &str, params: &[ForeignCallParam<F>]) -> bool {
-        self.name == name && (self.params.is_none() || self.params.as_deref() == Some(params))
+        self.name == name
+            && (self.params.is_none() || self.params.as_deref() == Some(params))
+            && self.param_matchers.as_ref().is_none_or(|matchers| {
+                matchers.len() == params.len()
+                    && matchers.iter().zip(params).all(|(matcher, param)| matcher.matches(param))
+            })
+    }
+
+    /// Take the result for the next call to this mock.
+    fn next_result(&mut self) -> ForeignCallResult<F> {
+        if self.results.len() > 1 {
+            self.results.remove(0)
+        } else {
+            self.results.last().cloned().unwrap_or_default()
+        }
+    }
+
+    fn unsatisfied_expectation(&self) -> Option<UnsatisfiedMockExpectation> {
+        let expected_times = self.expected_times?;
+        (u64::from(self.times_called) != expected_times).then(|| UnsatisfiedMockExpectation {
+            mock_id: self.id,
+            message: format!(
+                "Mock of oracle `{}` was expected to be called {expected_times} time(s), but was called {} time(s)",
+                self.name, self.times_called
+            ),
+        })
+    }
+}
+
+/// An expectation set on an oracle mock which wasn't met by the end of a test.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct UnsatisfiedMockExpectation {
+    /// The id of the mock, which is the order in which it was created
+    pub mock_id: usize,
+    pub message: String,
+}
+
+impl std::fmt::Display for UnsatisfiedMockExpectation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)
     }
 }
 
@@ -52,6 +149,8 @@ pub struct MockForeignCallExecutor<F> {
     last_mock_id: usize,
     /// The registered mocks
     mocked_responses: Vec<MockedCall<F>>,
+    /// Mocks with expectations which were removed before the end of the test, kept around to be verified.
+    removed_mocks: Vec<MockedCall<F>>,
 }
 
 impl<F: AcirField> MockForeignCallExecutor<F> {
@@ -74,6 +173,28 @@ impl<F: AcirField> MockForeignCallExecutor<F> {
         self.mocked_responses.iter_mut().find(|response| response.id == id)
     }
 
+    fn remove_mock(&mut self, position: usize) {
+        let mock = self.mocked_responses.remove(position);
+        if mock.expected_times.is_some() {
+            self.removed_mocks.push(mock);
+        }
+    }
+
+    /// Check the expectations of all the mocks which were created.
+    fn verify(&self) -> Result<(), ForeignCallError> {
+        let unsatisfied: Vec<_> = self
+            .removed_mocks
+            .iter()
+            .chain(&self.mocked_responses)
+            .filter_map(MockedCall::unsatisfied_expectation)
+            .collect();
+        if unsatisfied.is_empty() {
+            Ok(())
+        } else {
+            Err(ForeignCallError::UnsatisfiedMockExpectations(unsatisfied))
+        }
+    }
+
     fn parse_string(param: &ForeignCallParam<F>) -> String {
         let fields: Vec<_> = param.fields().to_vec();
         decode_string_value(&fields)
@@ -123,7 +244,16 @@ where
                 let (id, params) = Self::extract_mock_id(&foreign_call.inputs)?;
                 self.find_mock_by_id_mut(id)
                     .unwrap_or_else(|| panic!("Unknown mock id {}", id))
-                    .result = ForeignCallResult { values: params.to_vec() };
+                    .results = vec![ForeignCallResult { values: params.to_vec() }];
+
+                Ok(ForeignCallResult::default())
+            }
+            Some(ForeignCall::AddMockReturns) => {
+                let (id, params) = Self::extract_mock_id(&foreign_call.inputs)?;
+                self.find_mock_by_id_mut(id)
+                    .unwrap_or_else(|| panic!("Unknown mock id {}", id))
+                    .results
+                    .push(ForeignCallResult { values: params.to_vec() });
 
                 Ok(ForeignCallResult::default())
             }
@@ -138,9 +268,44 @@ where
 
                 Ok(ForeignCallResult::default())
             }
+            Some(ForeignCall::SetMockExpectedTimes) => {
+                let (id, params) = Self::extract_mock_id(&foreign_call.inputs)?;
+                let times =
+                    params[0].unwrap_field().try_to_u64().expect("Invalid bit size of times");
+
+                self.find_mock_by_id_mut(id)
+                    .unwrap_or_else(|| panic!("Unknown mock id {}", id))
+                    .expected_times = Some(times);
+
+                Ok(ForeignCallResult::default())
+            }
+            Some(ForeignCall::SetMockParamMatchers) => {
+                let (id, params) = Self::extract_mock_id(&foreign_call.inputs)?;
+                let matchers = params
+                    .first()
+                    .ok_or(ForeignCallError::MissingForeignCallInputs)?
+                    .fields()
+                    .chunks(3)
+                    .map(ParamMatcher::from_fields)
+                    .collect::<Result<_, _>>()?;
+
+                self.find_mock_by_id_mut(id)
+                    .unwrap_or_else(|| panic!("Unknown mock id {}", id))
+                    .param_matchers = Some(matchers);
+
+                Ok(ForeignCallResult::default())
+            }
             Some(ForeignCall::ClearMock) => {
                 let (id, _) = Self::extract_mock_id(&foreign_call.inputs)?;
-                self.mocked_responses.retain(|response| response.id != id);
+                if let Some(position) =
+                    self.mocked_responses.iter().position(|response| response.id == id)
+                {
+                    self.remove_mock(position);
+                }
+                Ok(ForeignCallResult::default())
+            }
+            Some(ForeignCall::VerifyMocks) => {
+                self.verify()?;
                 Ok(ForeignCallResult::default())
             }
             Some(ForeignCall::GetTimesCalled) => {
@@ -167,7 +332,7 @@ where
 
                     mock.last_called_params = Some(foreign_call.inputs.clone());
 
-                    let result = mock.result.values.clone();
+                    let result = mock.next_result();
 
                     // Update the total number of calls to the mock
                     mock.times_called += 1;
@@ -175,11 +340,11 @@ where
                     if let Some(times_left) = &mut mock.times_left {
                         *times_left -= 1;
                         if *times_left == 0 {
-                            self.mocked_responses.remove(response_position);
+                            self.remove_mock(response_position);
                         }
                     }
 
-                    Ok(result.into())
+                    Ok(result)
                 } else {
                     Err(ForeignCallError::NoHandler(foreign_call_name.to_string()))
                 }
@@ -203,7 +368,10 @@ impl<F> ForeignCallExecutor<F> for DisabledMockForeignCallExecutor {
             | ForeignCall::GetMockLastParams
             | ForeignCall::SetMockReturns
             | ForeignCall::SetMockTimes
-            | ForeignCall::ClearMock,
+            | ForeignCall::ClearMock
+            | ForeignCall::SetMockExpectedTimes
+            | ForeignCall::SetMockParamMatchers
+            | ForeignCall::AddMockReturns,
         ) = ForeignCall::lookup(foreign_call_name)
         {
             // Returning an error instead of panicking so this can be tested.
@@ -212,3 +380,104 @@ impl<F> ForeignCallExecutor<F> for DisabledMockForeignCallExecutor {
         Err(ForeignCallError::NoHandler(foreign_call.function.clone()))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use acvm::{
+        FieldElement,
+        acir::brillig::{ForeignCallParam, ForeignCallResult},
+        pwg::ForeignCallWaitInfo,
+    };
+
+    use crate::foreign_calls::{ForeignCall, ForeignCallError, ForeignCallExecutor};
+
+    use super::MockForeignCallExecutor;
+
+    fn field(value: u128) -> ForeignCallParam<FieldElement> {
+        FieldElement::from(value).into()
+    }
+
+    fn call(
+        executor: &mut MockForeignCallExecutor<FieldElement>,
+        function: &str,
+        inputs: Vec<ForeignCallParam<FieldElement>>,
+    ) -> Result<ForeignCallResult<FieldElement>, ForeignCallError> {
+        executor.execute(&ForeignCallWaitInfo { function: function.to_string(), inputs })
+    }
+
+    fn create_mock(executor: &mut MockForeignCallExecutor<FieldElement>, name: &str) -> u128 {
+        let name = name.bytes().map(|byte| FieldElement::from(u128::from(byte))).collect();
+        let id = call(executor, "create_mock", vec![ForeignCallParam::Array(name)]).unwrap();
+        id.values[0].unwrap_field().to_u128()
+    }
+
+    #[test]
+    fn returns_values_in_sequence() {
+        let mut executor = MockForeignCallExecutor::default();
+        let id = create_mock(&mut executor, "foo");
+        call(&mut executor, "set_mock_returns", vec![field(id), field(1)]).unwrap();
+        call(&mut executor, "add_mock_returns", vec![field(id), field(2)]).unwrap();
+
+        assert_eq!(call(&mut executor, "foo", vec![]).unwrap(), FieldElement::from(1_u128).into());
+        assert_eq!(call(&mut executor, "foo", vec![]).unwrap(), FieldElement::from(2_u128).into());
+        assert_eq!(call(&mut executor, "foo", vec![]).unwrap(), FieldElement::from(2_u128).into());
+    }
+
+    #[test]
+    fn matches_params_against_predicates() {
+        let mut executor = MockForeignCallExecutor::default();
+        let id = create_mock(&mut executor, "foo");
+        // `between(2, 4)` for a single parameter
+        let matchers = ForeignCallParam::Array(vec![4_u128.into(), 2_u128.into(), 4_u128.into()]);
+        call(&mut executor, "set_mock_param_matchers", vec![field(id), matchers]).unwrap();
+
+        assert!(call(&mut executor, "foo", vec![field(3)]).is_ok());
+        let array = ForeignCallParam::Array(vec![2_u128.into(), 4_u128.into()]);
+        assert!(call(&mut executor, "foo", vec![array]).is_ok());
+
+        for inputs in [vec![field(5)], vec![field(3), field(3)]] {
+            assert!(matches!(
+                call(&mut executor, "foo", inputs),
+                Err(ForeignCallError::NoHandler(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn rejects_unknown_param_matcher_kinds() {
+        let mut executor = MockForeignCallExecutor::default();
+        let id = create_mock(&mut executor, "foo");
+        let matchers = ForeignCallParam::Array(vec![5_u128.into(), 0_u128.into(), 0_u128.into()]);
+
+        assert!(matches!(
+            call(&mut executor, "set_mock_param_matchers", vec![field(id), matchers]),
+            Err(ForeignCallError::InvalidParamMatcher(_))
+        ));
+    }
+
+    #[test]
+    fn verifies_expected_call_counts() {
+        let mut executor = MockForeignCallExecutor::default();
+        let verify = ForeignCall::VerifyMocks.name();
+
+        let cleared = create_mock(&mut executor, "foo");
+        call(&mut executor, "set_mock_expected_times", vec![field(cleared), field(1)]).unwrap();
+        call(&mut executor, "clear_mock", vec![field(cleared)]).unwrap();
+
+        let called = create_mock(&mut executor, "bar");
+        call(&mut executor, "set_mock_expected_times", vec![field(called), field(1)]).unwrap();
+        call(&mut executor, "bar", vec![]).unwrap();
+
+        let Err(ForeignCallError::UnsatisfiedMockExpectations(unsatisfied)) =
+            call(&mut executor, verify, vec![])
+        else {
+            panic!("expected the cleared mock to fail verification");
+        };
+        assert_eq!(unsatisfied.len(), 1);
+        assert_eq!(unsatisfied[0].mock_id, cleared as usize);
+        assert_eq!(
+            unsatisfied[0].message,
+            "Mock of oracle `foo` was expected to be called 1 time(s), but was called 0 time(s)"
+        );
+    }
+}
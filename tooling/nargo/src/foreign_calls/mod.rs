@@ -34,6 +34,11 @@ pub enum ForeignCall {
     SetMockTimes,
     ClearMock,
     GetTimesCalled,
+    SetMockExpectedTimes,
+    SetMockParamMatchers,
+    AddMockReturns,
+    /// Not called by the stdlib; issued by `nargo test` to check mock expectations once a test has finished.
+    VerifyMocks,
 }
 
 impl std::fmt::Display for ForeignCall {
@@ -53,6 +58,10 @@ impl ForeignCall {
             ForeignCall::SetMockTimes => "set_mock_times",
             ForeignCall::ClearMock => "clear_mock",
             ForeignCall::GetTimesCalled => "get_times_called",
+            ForeignCall::SetMockExpectedTimes => "set_mock_expected_times",
+            ForeignCall::SetMockParamMatchers => "set_mock_param_matchers",
+            ForeignCall::AddMockReturns => "add_mock_returns",
+            ForeignCall::VerifyMocks => "verify_mocks",
         }
     }
 
@@ -66,6 +75,10 @@ impl ForeignCall {
             "set_mock_times" => Some(ForeignCall::SetMockTimes),
             "clear_mock" => Some(ForeignCall::ClearMock),
             "get_times_called" => Some(ForeignCall::GetTimesCalled),
+            "set_mock_expected_times" => Some(ForeignCall::SetMockExpectedTimes),
+            "set_mock_param_matchers" => Some(ForeignCall::SetMockParamMatchers),
+            "add_mock_returns" => Some(ForeignCall::AddMockReturns),
+            "verify_mocks" => Some(ForeignCall::VerifyMocks),
             _ => None,
         }
     }
@@ -88,11 +101,20 @@ pub enum ForeignCallError {
     #[error("Failed calling external resolver. {0}")]
     ExternalResolverError(#[from] jsonrpsee::core::client::Error),
 
+    #[error("Invalid oracle resolver URL: {0}")]
+    InvalidResolverUrl(String),
+
     #[error("Assert message resolved after an unsatisfied constrain. {0}")]
     ResolvedAssertMessage(String),
 
     #[error("Failed to replay oracle transcript: {0}")]
     TranscriptError(String),
+
+    #[error("Invalid mock param matcher: {0}")]
+    InvalidParamMatcher(String),
+
+    #[error("{}", .0.iter().map(ToString::to_string).collect::<Vec<_>>().join("\n"))]
+    UnsatisfiedMockExpectations(Vec<mocker::UnsatisfiedMockExpectation>),
 }
 
 impl From<TryFromParamsError> for ForeignCallError {
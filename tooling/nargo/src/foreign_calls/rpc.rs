@@ -10,6 +10,10 @@ use serde::{Deserialize, Serialize};
 
 use super::{ForeignCallError, ForeignCallExecutor};
 
+mod stream;
+
+use stream::{StreamClient, StreamTarget};
+
 #[derive(Debug)]
 pub struct RPCForeignCallExecutor {
     /// A randomly generated id for this `DefaultForeignCallExecutor`.
@@ -17,12 +21,16 @@ pub struct RPCForeignCallExecutor {
     /// This is used so that a single `external_resolver` can distinguish between requests from multiple
     /// instantiations of `DefaultForeignCallExecutor`.
     id: u64,
-    /// JSON RPC client to resolve foreign calls
-    external_resolver: HttpClient,
-    /// External resolver target. We are keeping it to be able to restart httpClient if necessary
+    /// JSON RPC client to resolve foreign calls.
+    ///
+    /// Clients which need to spawn a process or open a socket are only connected on the first foreign call.
+    external_resolver: Option<ResolverClient>,
+    /// External resolver target. We are keeping it to be able to restart the client if necessary
     ///
     /// See [`noir-lang/noir#7463`][<https://github.com/noir-lang/noir/issues/7463>]
-    resolver_url: String,
+    ///
+    /// An invalid resolver URL is kept as an error, which is returned from the first foreign call.
+    resolver_target: Result<ResolverTarget, String>,
     /// Root path to the program or workspace in execution.
     root_path: Option<PathBuf>,
     /// Name of the package in execution
@@ -32,6 +40,109 @@ pub struct RPCForeignCallExecutor {
     runtime: tokio::runtime::Runtime,
 }
 
+/// The location of an external resolver, as parsed from the resolver URL.
+///
+/// * `http://` and `https://` URLs are served over HTTP.
+/// * `stdio:<program> [args..]` spawns the program and exchanges newline-delimited JSON-RPC messages
+///   over its stdin and stdout. Arguments are split on whitespace, except within single or double
+///   quotes, and a backslash escapes the next character outside of single quotes.
+/// * `unix:<path>` exchanges newline-delimited JSON-RPC messages over a Unix domain socket.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ResolverTarget {
+    Http(String),
+    Stream(StreamTarget),
+}
+
+impl ResolverTarget {
+    fn parse(resolver_url: &str) -> Result<Self, String> {
+        if let Some(command) = resolver_url.strip_prefix("stdio:") {
+            let mut words = split_command(command)
+                .map_err(|error| format!("{error} in `{resolver_url}`"))?
+                .into_iter();
+            let Some(program) = words.next() else {
+                return Err(format!("missing program in `{resolver_url}`"));
+            };
+            let args = words.collect();
+            return Ok(ResolverTarget::Stream(StreamTarget::Stdio { program, args }));
+        }
+
+        if let Some(path) = resolver_url.strip_prefix("unix:") {
+            let path = path.strip_prefix("//").unwrap_or(path);
+            if path.is_empty() {
+                return Err(format!("missing socket path in `{resolver_url}`"));
+            }
+            #[cfg(unix)]
+            return Ok(ResolverTarget::Stream(StreamTarget::Unix(PathBuf::from(path))));
+            #[cfg(not(unix))]
+            return Err(format!(
+                "Unix sockets are not supported on this platform: `{resolver_url}`"
+            ));
+        }
+
+        Ok(ResolverTarget::Http(resolver_url.to_string()))
+    }
+
+    fn connect(&self) -> Result<ResolverClient, ForeignCallError> {
+        match self {
+            ResolverTarget::Http(url) => build_http_client(url).map(ResolverClient::Http),
+            ResolverTarget::Stream(target) => StreamClient::connect(target, foreign_call_timeout())
+                .map(ResolverClient::Stream)
+                .map_err(|error| jsonrpsee::core::ClientError::Transport(Box::new(error)).into()),
+        }
+    }
+}
+
+/// Split a command line into words, honouring single and double quotes and backslash escapes.
+fn split_command(command: &str) -> Result<Vec<String>, String> {
+    let mut words = Vec::new();
+    let mut word: Option<String> = None;
+    let mut chars = command.chars();
+
+    while let Some(char) = chars.next() {
+        match char {
+            '\'' => {
+                let word = word.get_or_insert_with(String::new);
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(char) => word.push(char),
+                        None => return Err("unterminated single quote".to_string()),
+                    }
+                }
+            }
+            '"' => {
+                let word = word.get_or_insert_with(String::new);
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(char) => word.push(char),
+                            None => return Err("unterminated double quote".to_string()),
+                        },
+                        Some(char) => word.push(char),
+                        None => return Err("unterminated double quote".to_string()),
+                    }
+                }
+            }
+            '\\' => match chars.next() {
+                Some(char) => word.get_or_insert_with(String::new).push(char),
+                None => return Err("trailing backslash".to_string()),
+            },
+            char if char.is_whitespace() => words.extend(word.take()),
+            char => word.get_or_insert_with(String::new).push(char),
+        }
+    }
+    words.extend(word);
+
+    Ok(words)
+}
+
+#[derive(Debug)]
+enum ResolverClient {
+    Http(HttpClient),
+    Stream(StreamClient),
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 struct ResolveForeignCallRequest<F> {
     /// A session ID which allows the external RPC server to link this foreign call request to other foreign calls
@@ -63,7 +174,14 @@ impl RPCForeignCallExecutor {
         root_path: Option<PathBuf>,
         package_name: Option<String>,
     ) -> Self {
-        let oracle_resolver = build_http_client(resolver_url);
+        let resolver_target = ResolverTarget::parse(resolver_url);
+
+        // HTTP clients don't connect until the first request, so they can be built up front,
+        // which also validates the URL.
+        let external_resolver = match &resolver_target {
+            Ok(ResolverTarget::Http(url)) => build_http_client(url).ok().map(ResolverClient::Http),
+            _ => None,
+        };
 
         // Opcodes are executed in the `ProgramExecutor::execute_circuit` one by one in a loop,
         // we don't need a concurrent thread pool.
@@ -74,8 +192,8 @@ impl RPCForeignCallExecutor {
             .expect("failed to build tokio runtime");
 
         RPCForeignCallExecutor {
-            external_resolver: oracle_resolver,
-            resolver_url: resolver_url.to_string(),
+            external_resolver,
+            resolver_target,
             id,
             root_path,
             package_name,
@@ -86,7 +204,7 @@ impl RPCForeignCallExecutor {
     fn send_foreign_call<F>(
         &mut self,
         foreign_call: &ForeignCallWaitInfo<F>,
-    ) -> Result<ForeignCallResult<F>, jsonrpsee::core::ClientError>
+    ) -> Result<ForeignCallResult<F>, ForeignCallError>
     where
         F: AcirField + Serialize + for<'a> Deserialize<'a>,
     {
@@ -100,24 +218,51 @@ impl RPCForeignCallExecutor {
                 .or(Some(String::new())),
             package_name: self.package_name.clone().or(Some(String::new())),
         };
-        let encoded_params = rpc_params!(params);
-        self.runtime.block_on(async {
-            self.external_resolver.request("resolve_foreign_call", encoded_params).await
-        })
+
+        if self.external_resolver.is_none() {
+            let target = self
+                .resolver_target
+                .as_ref()
+                .map_err(|error| ForeignCallError::InvalidResolverUrl(error.clone()))?;
+            self.external_resolver = Some(target.connect()?);
+        }
+
+        let response =
+            match self.external_resolver.as_mut().expect("external resolver is connected") {
+                ResolverClient::Http(client) => {
+                    let encoded_params = rpc_params!(params);
+                    self.runtime.block_on(async {
+                        client.request("resolve_foreign_call", encoded_params).await
+                    })
+                }
+                // Send the parameters as a single element array, the same way `rpc_params!` does.
+                ResolverClient::Stream(client) => client.request("resolve_foreign_call", [params]),
+            };
+        response.map_err(ForeignCallError::from)
+    }
+
+    fn is_http(&self) -> bool {
+        matches!(self.resolver_target, Ok(ResolverTarget::Http(_)))
     }
 }
 
-fn build_http_client(target: &str) -> HttpClient {
+fn foreign_call_timeout() -> Option<std::time::Duration> {
+    std::env::var("NARGO_FOREIGN_CALL_TIMEOUT")
+        .ok()
+        .and_then(|timeout| timeout.parse().ok())
+        .map(std::time::Duration::from_millis)
+}
+
+fn build_http_client(target: &str) -> Result<HttpClient, ForeignCallError> {
     let mut client_builder = HttpClientBuilder::new();
 
-    if let Some(Ok(timeout)) =
-        std::env::var("NARGO_FOREIGN_CALL_TIMEOUT").ok().map(|timeout| timeout.parse())
-    {
-        let timeout_duration = std::time::Duration::from_millis(timeout);
+    if let Some(timeout_duration) = foreign_call_timeout() {
         client_builder = client_builder.request_timeout(timeout_duration);
     };
 
-    client_builder.build(target).expect("Invalid oracle resolver URL")
+    client_builder
+        .build(target)
+        .map_err(|error| ForeignCallError::InvalidResolverUrl(format!("`{target}`: {error}")))
 }
 
 impl<F> ForeignCallExecutor<F> for RPCForeignCallExecutor
@@ -134,13 +279,27 @@ where
             Ok(parsed_response) => Ok(parsed_response),
             // TODO: This is a workaround for noir-lang/noir#7463
             // The client is losing connection with the server and it's not being able to manage it
-            // so we are re-creating the HttpClient when it happens
-            Err(jsonrpsee::core::ClientError::Transport(_)) => {
-                self.external_resolver = build_http_client(&self.resolver_url);
+            // so we are re-creating the client when it happens.
+            Err(ForeignCallError::ExternalResolverError(
+                jsonrpsee::core::ClientError::Transport(_),
+            )) if self.is_http() => {
+                self.external_resolver = None;
                 let parsed_response = self.send_foreign_call(foreign_call)?;
                 Ok(parsed_response)
             }
-            Err(other) => Err(ForeignCallError::from(other)),
+            // A stdio or socket resolver may have already acted on the request before the
+            // connection failed, so it isn't sent again; the client is dropped, which also stops
+            // a spawned resolver, and the next call connects again.
+            Err(
+                error @ ForeignCallError::ExternalResolverError(
+                    jsonrpsee::core::ClientError::Transport(_),
+                ),
+            ) => {
+                self.external_resolver = None;
+                Err(error)
+            }
+            // The resolver answered, e.g. by rejecting the call, so it keeps serving later calls.
+            Err(error) => Err(error),
         }
     }
 }
@@ -157,8 +316,8 @@ mod tests {
     use tokio::sync::{mpsc, oneshot};
 
     use super::{
-        ForeignCallExecutor, RPCForeignCallExecutor, ResolveForeignCallRequest,
-        ResolveForeignCallResult,
+        ForeignCallError, ForeignCallExecutor, RPCForeignCallExecutor, ResolveForeignCallRequest,
+        ResolveForeignCallResult, ResolverTarget, StreamTarget,
     };
 
     #[rpc(server)]
@@ -313,4 +472,191 @@ mod tests {
         let result_2 = executor_2.execute(&foreign_call).await.unwrap();
         assert_ne!(result_1, result_2);
     }
+
+    #[test]
+    fn parses_resolver_targets() {
+        assert_eq!(
+            ResolverTarget::parse("http://localhost:5555").unwrap(),
+            ResolverTarget::Http("http://localhost:5555".to_string())
+        );
+        assert_eq!(
+            ResolverTarget::parse("stdio:./oracle --verbose").unwrap(),
+            ResolverTarget::Stream(StreamTarget::Stdio {
+                program: "./oracle".to_string(),
+                args: vec!["--verbose".to_string()],
+            })
+        );
+        assert!(ResolverTarget::parse("stdio:").is_err());
+        assert!(ResolverTarget::parse("unix:").is_err());
+    }
+
+    #[test]
+    fn parses_quoted_stdio_arguments() {
+        assert_eq!(
+            ResolverTarget::parse(r#"stdio:"./my oracle" --name 'a b' --path "c\"d" e\ f"#)
+                .unwrap(),
+            ResolverTarget::Stream(StreamTarget::Stdio {
+                program: "./my oracle".to_string(),
+                args: vec![
+                    "--name".to_string(),
+                    "a b".to_string(),
+                    "--path".to_string(),
+                    "c\"d".to_string(),
+                    "e f".to_string(),
+                ],
+            })
+        );
+        assert_eq!(
+            ResolverTarget::parse("stdio:oracle ''").unwrap(),
+            ResolverTarget::Stream(StreamTarget::Stdio {
+                program: "oracle".to_string(),
+                args: vec![String::new()],
+            })
+        );
+        assert!(ResolverTarget::parse("stdio:oracle 'unterminated").is_err());
+    }
+
+    #[test]
+    fn invalid_resolver_url_is_reported_as_an_error() {
+        let mut executor = RPCForeignCallExecutor::new("stdio:'oracle", 8, None, None);
+
+        let foreign_call: ForeignCallWaitInfo<FieldElement> =
+            ForeignCallWaitInfo { function: "echo".to_string(), inputs: Vec::new() };
+
+        let error = executor.execute(&foreign_call).unwrap_err();
+        assert!(matches!(error, ForeignCallError::InvalidResolverUrl(_)));
+
+        let mut executor = RPCForeignCallExecutor::new("not a url", 9, None, None);
+        let error = executor.execute(&foreign_call).unwrap_err();
+        assert!(matches!(error, ForeignCallError::InvalidResolverUrl(_)));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn parses_unix_socket_targets() {
+        let expected = ResolverTarget::Stream(StreamTarget::Unix("/tmp/oracle.sock".into()));
+        assert_eq!(ResolverTarget::parse("unix:/tmp/oracle.sock").unwrap(), expected);
+        assert_eq!(ResolverTarget::parse("unix:///tmp/oracle.sock").unwrap(), expected);
+    }
+
+    /// Serve newline-delimited JSON-RPC on a Unix socket, echoing the inputs of every foreign call.
+    #[cfg(unix)]
+    fn build_unix_oracle_server(path: &std::path::Path) {
+        use std::io::{BufRead, BufReader, Write};
+
+        let listener = std::os::unix::net::UnixListener::bind(path).unwrap();
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let stream = stream.unwrap();
+                let mut writer = stream.try_clone().unwrap();
+                for line in BufReader::new(stream).lines() {
+                    let request: serde_json::Value = serde_json::from_str(&line.unwrap()).unwrap();
+                    let response = serde_json::json!({
+                        "jsonrpc": "2.0",
+                        "id": request["id"],
+                        "result": { "values": request["params"][0]["inputs"] },
+                    });
+                    writeln!(writer, "{response}").unwrap();
+                }
+            }
+        });
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_oracle_resolver_over_unix_socket() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("oracle.sock");
+        build_unix_oracle_server(&path);
+
+        let url = format!("unix:{}", path.display());
+        let mut executor = RPCForeignCallExecutor::new(&url, 6, None, None);
+
+        let foreign_call: ForeignCallWaitInfo<FieldElement> = ForeignCallWaitInfo {
+            function: "echo".to_string(),
+            inputs: vec![ForeignCallParam::Single(1_u128.into())],
+        };
+
+        let result = executor.execute(&foreign_call);
+        assert_eq!(result.unwrap(), ForeignCallResult { values: foreign_call.inputs.clone() });
+
+        // Subsequent calls reuse the same connection.
+        let result = executor.execute(&foreign_call);
+        assert_eq!(result.unwrap(), ForeignCallResult { values: foreign_call.inputs });
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn stdio_resolver_is_not_sent_the_request_again_after_it_fails() {
+        let dir = tempfile::tempdir().unwrap();
+        let requests = dir.path().join("requests");
+
+        // The resolver records the request, then exits without answering.
+        let url = format!("stdio:sh -c 'head -n 1 >> \"$0\"' '{}'", requests.display());
+        let mut executor = RPCForeignCallExecutor::new(&url, 10, None, None);
+
+        let foreign_call: ForeignCallWaitInfo<FieldElement> =
+            ForeignCallWaitInfo { function: "echo".to_string(), inputs: Vec::new() };
+
+        assert!(executor.execute(&foreign_call).is_err());
+        assert_eq!(std::fs::read_to_string(requests).unwrap().lines().count(), 1);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn stdio_resolver_answers_after_rejecting_a_call() {
+        let dir = tempfile::tempdir().unwrap();
+        let script = dir.path().join("oracle.sh");
+
+        // The resolver rejects the first request and answers the second. Were it restarted after
+        // the rejection, it would reject the second request too.
+        std::fs::write(
+            &script,
+            r#"read -r request
+echo '{"jsonrpc":"2.0","id":0,"error":{"code":-32000,"message":"rejected"}}'
+read -r request
+echo '{"jsonrpc":"2.0","id":1,"result":{"values":[]}}'
+"#,
+        )
+        .unwrap();
+        let url = format!("stdio:sh '{}'", script.display());
+        let mut executor = RPCForeignCallExecutor::new(&url, 11, None, None);
+
+        let foreign_call: ForeignCallWaitInfo<FieldElement> =
+            ForeignCallWaitInfo { function: "echo".to_string(), inputs: Vec::new() };
+
+        let error = executor.execute(&foreign_call).unwrap_err();
+        assert!(matches!(
+            error,
+            ForeignCallError::ExternalResolverError(jsonrpsee::core::ClientError::Call(_))
+        ));
+
+        let result = executor.execute(&foreign_call);
+        assert_eq!(result.unwrap(), ForeignCallResult { values: Vec::new() });
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn stdio_resolver_times_out() {
+        let target =
+            StreamTarget::Stdio { program: "sleep".to_string(), args: vec!["10".to_string()] };
+        let timeout = std::time::Duration::from_millis(100);
+        let mut client = super::StreamClient::connect(&target, Some(timeout)).unwrap();
+
+        let start = std::time::Instant::now();
+        let result: Result<serde_json::Value, _> = client.request("resolve_foreign_call", [0]);
+        assert!(matches!(result, Err(jsonrpsee::core::ClientError::Transport(_))));
+        assert!(start.elapsed() < std::time::Duration::from_secs(5));
+    }
+
+    #[test]
+    fn stdio_resolver_reports_missing_program() {
+        let mut executor =
+            RPCForeignCallExecutor::new("stdio:./this-oracle-does-not-exist", 7, None, None);
+
+        let foreign_call: ForeignCallWaitInfo<FieldElement> =
+            ForeignCallWaitInfo { function: "echo".to_string(), inputs: Vec::new() };
+
+        assert!(executor.execute(&foreign_call).is_err());
+    }
 }
@@ -0,0 +1,202 @@
+//! Newline-delimited JSON-RPC transports for external resolvers which are not reachable over HTTP.
+//!
+//! Each request is written as a single line of JSON, and the resolver is expected to answer with a
+//! single line of JSON carrying the same `id`. Lines which don't carry the id of the pending request
+//! (e.g. notifications) are ignored.
+use std::io::{BufRead, BufReader, Write};
+use std::process::{Child, ChildStdout, Command, Stdio};
+use std::sync::mpsc::{Receiver, RecvTimeoutError};
+use std::time::Duration;
+
+use jsonrpsee::core::client::Error as ClientError;
+use jsonrpsee::types::ErrorObjectOwned;
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+
+/// Where a [StreamClient] should connect to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) enum StreamTarget {
+    /// Spawn `program` with `args` and talk to it over its stdin and stdout.
+    Stdio { program: String, args: Vec<String> },
+    /// Connect to a Unix domain socket at the given path.
+    #[cfg(unix)]
+    Unix(std::path::PathBuf),
+}
+
+/// A synchronous JSON-RPC client over a pair of byte streams.
+pub(super) struct StreamClient {
+    target: StreamTarget,
+    reader: LineReader,
+    writer: Box<dyn Write + Send>,
+    /// The resolver process, if we spawned one, so that it can be shut down with the client.
+    child: Option<Child>,
+    next_id: u64,
+}
+
+/// Reads the lines of the responses.
+enum LineReader {
+    /// A stream which enforces the timeout itself, like a socket.
+    Stream(Box<dyn BufRead + Send>),
+    /// Lines read on a separate thread, because pipes don't support timeouts.
+    Channel { lines: Receiver<std::io::Result<String>>, timeout: Option<Duration> },
+}
+
+impl LineReader {
+    /// Read lines of the child's stdout on a background thread, which stops at the end of the
+    /// output, i.e. when the child exits, or once the client is dropped.
+    fn spawn(stdout: ChildStdout, timeout: Option<Duration>) -> Self {
+        let (sender, lines) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            let mut stdout = BufReader::new(stdout);
+            loop {
+                let mut line = String::new();
+                let line = match stdout.read_line(&mut line) {
+                    Ok(0) => break,
+                    Ok(_) => Ok(line),
+                    Err(error) => Err(error),
+                };
+                let failed = line.is_err();
+                if sender.send(line).is_err() || failed {
+                    break;
+                }
+            }
+        });
+        LineReader::Channel { lines, timeout }
+    }
+
+    /// Read the next line, returning `None` at the end of the stream.
+    fn read_line(&mut self) -> std::io::Result<Option<String>> {
+        match self {
+            LineReader::Stream(reader) => {
+                let mut line = String::new();
+                let read = reader.read_line(&mut line)?;
+                Ok((read > 0).then_some(line))
+            }
+            LineReader::Channel { lines, timeout: None } => lines.recv().ok().transpose(),
+            LineReader::Channel { lines, timeout: Some(timeout) } => {
+                match lines.recv_timeout(*timeout) {
+                    Ok(line) => line.map(Some),
+                    Err(RecvTimeoutError::Disconnected) => Ok(None),
+                    Err(RecvTimeoutError::Timeout) => Err(std::io::Error::new(
+                        std::io::ErrorKind::TimedOut,
+                        format!("external resolver did not respond within {timeout:?}"),
+                    )),
+                }
+            }
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct Request<'a, P> {
+    jsonrpc: &'static str,
+    id: u64,
+    method: &'a str,
+    params: P,
+}
+
+#[derive(Deserialize)]
+struct Response {
+    #[serde(default)]
+    id: Option<serde_json::Value>,
+    #[serde(default)]
+    result: Option<serde_json::Value>,
+    #[serde(default)]
+    error: Option<ErrorObjectOwned>,
+}
+
+impl StreamClient {
+    /// Connect to the target. The `timeout` applies to every read and, on sockets, every write.
+    pub(super) fn connect(
+        target: &StreamTarget,
+        timeout: Option<Duration>,
+    ) -> std::io::Result<Self> {
+        let (reader, writer, child): (_, Box<dyn Write + Send>, _) = match target {
+            StreamTarget::Stdio { program, args } => {
+                let mut child = Command::new(program)
+                    .args(args)
+                    .stdin(Stdio::piped())
+                    .stdout(Stdio::piped())
+                    .stderr(Stdio::inherit())
+                    .spawn()?;
+                let stdin = child.stdin.take().expect("stdin is piped");
+                let stdout = child.stdout.take().expect("stdout is piped");
+                (LineReader::spawn(stdout, timeout), Box::new(stdin), Some(child))
+            }
+            #[cfg(unix)]
+            StreamTarget::Unix(path) => {
+                let stream = std::os::unix::net::UnixStream::connect(path)?;
+                stream.set_read_timeout(timeout)?;
+                stream.set_write_timeout(timeout)?;
+                let reader = LineReader::Stream(Box::new(BufReader::new(stream.try_clone()?)));
+                (reader, Box::new(stream), None)
+            }
+        };
+
+        Ok(StreamClient { target: target.clone(), reader, writer, child, next_id: 0 })
+    }
+
+    /// Send a request and block until the response with the matching id arrives.
+    pub(super) fn request<P, R>(&mut self, method: &str, params: P) -> Result<R, ClientError>
+    where
+        P: Serialize,
+        R: DeserializeOwned,
+    {
+        let id = self.next_id;
+        self.next_id += 1;
+
+        let request = Request { jsonrpc: "2.0", id, method, params };
+        let mut line = serde_json::to_string(&request).map_err(ClientError::ParseError)?;
+        line.push('\n');
+        self.writer.write_all(line.as_bytes()).map_err(transport_error)?;
+        self.writer.flush().map_err(transport_error)?;
+
+        loop {
+            let Some(line) = self.reader.read_line().map_err(transport_error)? else {
+                return Err(transport_error(std::io::Error::new(
+                    std::io::ErrorKind::UnexpectedEof,
+                    "external resolver closed the connection",
+                )));
+            };
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let response: Response =
+                serde_json::from_str(&line).map_err(ClientError::ParseError)?;
+            if response.id.as_ref().and_then(|id| id.as_u64()) != Some(id) {
+                continue;
+            }
+            if let Some(error) = response.error {
+                return Err(ClientError::Call(error));
+            }
+            let Some(result) = response.result else {
+                return Err(ClientError::Custom(format!(
+                    "response to request {id} has neither a result nor an error"
+                )));
+            };
+            return serde_json::from_value(result).map_err(ClientError::ParseError);
+        }
+    }
+}
+
+fn transport_error(error: std::io::Error) -> ClientError {
+    ClientError::Transport(Box::new(error))
+}
+
+impl std::fmt::Debug for StreamClient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("StreamClient")
+            .field("target", &self.target)
+            .field("next_id", &self.next_id)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Drop for StreamClient {
+    fn drop(&mut self) {
+        if let Some(mut child) = self.child.take() {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+    }
+}
@@ -1,4 +1,8 @@
-use std::{collections::VecDeque, path::Path};
+use std::{
+    collections::VecDeque,
+    path::Path,
+    sync::{Arc, Mutex},
+};
 
 use acvm::{AcirField, acir::brillig::ForeignCallResult, pwg::ForeignCallWaitInfo};
 use serde::{Deserialize, Serialize};
@@ -13,6 +17,11 @@ pub enum TranscriptError {
 
     #[error(transparent)]
     DeserializationError(#[from] serde_json::Error),
+
+    #[error(
+        "{count} foreign call(s) in the oracle transcript were not made, starting with '{next}'"
+    )]
+    UnusedCalls { count: usize, next: String },
 }
 
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
@@ -59,8 +68,24 @@ where
 /// Replay an oracle transcript which was logged with [LoggingForeignCallExecutor].
 ///
 /// This is expected to be the last executor in the stack, e.g. prints can be handled above it.
+///
+/// By default every call has to match the next entry of the transcript, and [Self::finish] checks
+/// that the whole transcript was replayed. In lenient mode a call is answered by the first entry
+/// in the transcript with the same name and inputs, and calls which don't appear in the transcript
+/// are left unhandled.
+///
+/// Clones share the transcript, so that a clone can be checked with [Self::finish] after the
+/// executor itself was moved into the stack of executors used for the execution.
+#[derive(Debug, Clone)]
 pub struct ReplayForeignCallExecutor<F> {
-    transcript: VecDeque<LogItem<F>>,
+    transcript: Arc<Mutex<VecDeque<LogItem<F>>>>,
+    lenient: bool,
+}
+
+impl<F> Default for ReplayForeignCallExecutor<F> {
+    fn default() -> Self {
+        Self { transcript: Arc::default(), lenient: false }
+    }
 }
 
 impl<F: for<'a> Deserialize<'a>> ReplayForeignCallExecutor<F> {
@@ -70,7 +95,27 @@ impl<F: for<'a> Deserialize<'a>> ReplayForeignCallExecutor<F> {
         let transcript =
             contents.lines().map(serde_json::from_str).collect::<Result<VecDeque<_>, _>>()?;
 
-        Ok(Self { transcript })
+        Ok(Self { transcript: Arc::new(Mutex::new(transcript)), lenient: false })
+    }
+}
+
+impl<F> ReplayForeignCallExecutor<F> {
+    /// Match calls by name and inputs, regardless of their order, and allow calls to be left over.
+    pub fn with_lenient(mut self, lenient: bool) -> Self {
+        self.lenient = lenient;
+        self
+    }
+
+    /// Check that every call in the transcript was replayed, unless in lenient mode.
+    pub fn finish(&self) -> Result<(), TranscriptError> {
+        let transcript = self.transcript.lock().expect("transcript lock poisoned");
+        match transcript.front() {
+            Some(next) if !self.lenient => Err(TranscriptError::UnusedCalls {
+                count: transcript.len(),
+                next: next.call.function.clone(),
+            }),
+            _ => Ok(()),
+        }
     }
 }
 
@@ -82,9 +127,21 @@ where
         &mut self,
         foreign_call: &ForeignCallWaitInfo<F>,
     ) -> Result<ForeignCallResult<F>, ForeignCallError> {
+        let mut transcript = self.transcript.lock().expect("transcript lock poisoned");
+        if self.lenient {
+            let position = transcript.iter().position(|next| {
+                next.call.function == foreign_call.function
+                    && next.call.inputs == foreign_call.inputs
+            });
+            return match position.and_then(|position| transcript.remove(position)) {
+                Some(next) => Ok(next.result),
+                None => Err(ForeignCallError::NoHandler(foreign_call.function.clone())),
+            };
+        }
+
         let error = |msg| Err(ForeignCallError::TranscriptError(msg));
         // Verify without popping.
-        if let Some(next) = self.transcript.front() {
+        if let Some(next) = transcript.front() {
             if next.call.function != foreign_call.function {
                 let msg = format!(
                     "unexpected foreign call; expected '{}', got '{}'",
@@ -101,10 +158,91 @@ where
             }
         }
         // Consume the next call.
-        if let Some(next) = self.transcript.pop_front() {
+        if let Some(next) = transcript.pop_front() {
             Ok(next.result)
         } else {
             error("unexpected foreign call; no more calls in transcript".to_string())
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use acvm::{FieldElement, acir::brillig::ForeignCallResult, pwg::ForeignCallWaitInfo};
+
+    use crate::foreign_calls::{ForeignCallError, ForeignCallExecutor, layers::Empty};
+
+    use super::{LoggingForeignCallExecutor, ReplayForeignCallExecutor, TranscriptError};
+
+    fn call(function: &str, input: u128) -> ForeignCallWaitInfo<FieldElement> {
+        ForeignCallWaitInfo {
+            function: function.to_string(),
+            inputs: vec![FieldElement::from(input).into()],
+        }
+    }
+
+    /// Record the calls against an executor which always returns an empty result,
+    /// and load the transcript into a replay executor.
+    fn record(
+        calls: &[ForeignCallWaitInfo<FieldElement>],
+    ) -> ReplayForeignCallExecutor<FieldElement> {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("transcript.jsonl");
+        let file = std::fs::File::create(&path).unwrap();
+
+        let mut executor = LoggingForeignCallExecutor::new(Empty, file);
+        for call in calls {
+            executor.execute(call).unwrap();
+        }
+        drop(executor);
+
+        ReplayForeignCallExecutor::from_file(&path).unwrap()
+    }
+
+    #[test]
+    fn lenient_replay_matches_calls_out_of_order() {
+        let mut executor =
+            record(&[call("foo", 1), call("bar", 2), call("baz", 3)]).with_lenient(true);
+
+        assert_eq!(executor.execute(&call("bar", 2)).unwrap(), ForeignCallResult::default());
+        assert!(matches!(executor.execute(&call("foo", 3)), Err(ForeignCallError::NoHandler(_))));
+        assert_eq!(executor.execute(&call("foo", 1)).unwrap(), ForeignCallResult::default());
+        assert!(executor.finish().is_ok());
+    }
+
+    #[test]
+    fn replay_fails_on_diverging_calls() {
+        let mut executor = record(&[call("foo", 1), call("bar", 2)]);
+
+        assert!(matches!(
+            executor.execute(&call("bar", 2)),
+            Err(ForeignCallError::TranscriptError(_))
+        ));
+        assert!(matches!(
+            executor.execute(&call("foo", 3)),
+            Err(ForeignCallError::TranscriptError(_))
+        ));
+        assert!(executor.execute(&call("foo", 1)).is_ok());
+        assert!(executor.execute(&call("bar", 2)).is_ok());
+        assert!(matches!(
+            executor.execute(&call("bar", 2)),
+            Err(ForeignCallError::TranscriptError(_))
+        ));
+    }
+
+    #[test]
+    fn replay_fails_on_unused_calls() {
+        let executor = record(&[call("foo", 1), call("bar", 2)]);
+
+        // The executor is moved into the stack of executors, while the clone is kept for checking.
+        let mut stack = executor.clone();
+        assert!(stack.execute(&call("foo", 1)).is_ok());
+        drop(stack);
+
+        let Err(TranscriptError::UnusedCalls { count, next }) = executor.finish() else {
+            panic!("expected the unused call to be reported");
+        };
+        assert_eq!(count, 1);
+        assert_eq!(next, "bar");
+    }
+}
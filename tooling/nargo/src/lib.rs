@@ -43,6 +43,7 @@ pub fn prepare_dependencies(
         match dep {
             Dependency::Remote { package } | Dependency::Local { package } => {
                 let crate_id = prepare_dependency(context, &package.entry_path);
+                context.crate_graph.enable_features(crate_id, package.features.iter().cloned());
                 add_dep(context, parent_crate, crate_id, dep_name.clone());
                 prepare_dependencies(context, crate_id, &package.dependencies);
             }
@@ -170,7 +171,7 @@ fn collect_all_files_in_packages_dependencies(
     seen_filenames: &mut HashSet<PathBuf>,
     processed_entry_paths: &mut HashSet<PathBuf>,
 ) {
-    for (_, dep) in package.dependencies.iter() {
+    for (_, dep) in package.dependencies.iter().chain(&package.dev_dependencies) {
         match dep {
             Dependency::Local { package } | Dependency::Remote { package } => {
                 collect_all_files_in_package(
@@ -291,8 +292,44 @@ pub fn prepare_package<'file_manager, 'parsed_files>(
     let mut context = Context::from_ref_file_manager(file_manager, parsed_files);
 
     let crate_id = prepare_crate(&mut context, &package.entry_path);
+    context.crate_graph.enable_features(crate_id, package.features.iter().cloned());
 
     prepare_dependencies(&mut context, crate_id, &package.dependencies);
 
     (context, crate_id)
 }
+
+/// Like [prepare_package], but also makes the `[dev-dependencies]` of the package available
+/// to its root crate and compiles the items annotated with `#[cfg(test)]`.
+/// Used when testing, fuzzing or benchmarking the package.
+#[tracing::instrument(level = "trace", skip_all)]
+pub fn prepare_package_for_tests<'file_manager, 'parsed_files>(
+    file_manager: &'file_manager FileManager,
+    parsed_files: &'parsed_files ParsedFiles,
+    package: &Package,
+) -> (Context<'file_manager, 'parsed_files>, CrateId) {
+    let (mut context, crate_id) = prepare_package(file_manager, parsed_files, package);
+
+    context.crate_graph.enable_cfg_test(crate_id);
+    prepare_dependencies(&mut context, crate_id, &package.dev_dependencies);
+
+    (context, crate_id)
+}
+
+/// Escapes text for an XML attribute or element, dropping the control characters XML can't represent.
+pub fn xml_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for char in text.chars() {
+        match char {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            '\t' | '\n' | '\r' => escaped.push(char),
+            char if char.is_control() => (),
+            char => escaped.push(char),
+        }
+    }
+    escaped
+}
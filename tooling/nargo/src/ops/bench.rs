@@ -0,0 +1,238 @@
+use std::{collections::BTreeMap, time::Instant};
+
+use acvm::{BlackBoxFunctionSolver, FieldElement, acir::native_types::WitnessMap};
+use noirc_driver::{CompileOptions, DEFAULT_EXPRESSION_WIDTH, compile_no_check};
+use noirc_errors::CustomDiagnostic;
+use noirc_frontend::hir::{Context, def_map::BenchFunction};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    NargoError, errors::try_to_diagnose_runtime_error, foreign_calls::ForeignCallExecutor,
+};
+
+use super::{execute_program, execute_program_with_profiling};
+
+/// The metric holding the wall-clock time of an unconstrained bench.
+const TIME_METRIC: &str = "Time (s)";
+
+/// What was measured when running a `#[bench]` function.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum BenchMeasurement {
+    /// A constrained bench, measured by the size of its circuit.
+    Constrained { acir_opcodes: usize },
+    /// An unconstrained bench, measured by the number of Brillig opcodes it executed and
+    /// the average wall-clock time of an execution, in seconds.
+    Unconstrained { brillig_opcodes: usize, time_secs: f64 },
+}
+
+impl BenchMeasurement {
+    /// The name and value of each metric of this measurement.
+    pub fn metrics(&self) -> Vec<(&'static str, f64)> {
+        match self {
+            BenchMeasurement::Constrained { acir_opcodes } => {
+                vec![("ACIR opcodes", *acir_opcodes as f64)]
+            }
+            BenchMeasurement::Unconstrained { brillig_opcodes, time_secs } => {
+                vec![("Brillig opcodes", *brillig_opcodes as f64), (TIME_METRIC, *time_secs)]
+            }
+        }
+    }
+
+    /// The metrics that increased by more than `threshold` percent compared to `baseline`.
+    ///
+    /// Wall-clock times are noisier than opcode counts, so they are checked against their own
+    /// `time_threshold` instead. Measurements of a different kind can't be compared, so they
+    /// never regress.
+    pub fn regressions(
+        &self,
+        baseline: &BenchMeasurement,
+        threshold: f64,
+        time_threshold: f64,
+    ) -> Vec<BenchRegression> {
+        if std::mem::discriminant(self) != std::mem::discriminant(baseline) {
+            return Vec::new();
+        }
+
+        self.metrics()
+            .into_iter()
+            .zip(baseline.metrics())
+            .filter_map(|((metric, current), (_, baseline))| {
+                let change = percentage_change(baseline, current)?;
+                let threshold = if metric == TIME_METRIC { time_threshold } else { threshold };
+                (change > threshold).then_some(BenchRegression {
+                    metric,
+                    baseline,
+                    current,
+                    change,
+                })
+            })
+            .collect()
+    }
+}
+
+/// A metric of a bench that got worse than its baseline by more than the allowed threshold.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BenchRegression {
+    pub metric: &'static str,
+    pub baseline: f64,
+    pub current: f64,
+    /// The change from the baseline, in percent.
+    pub change: f64,
+}
+
+/// The measurements of all benches of a workspace, keyed by package name and bench name.
+///
+/// This is what gets saved to, and compared against, a baseline file.
+pub type BenchBaseline = BTreeMap<String, BTreeMap<String, BenchMeasurement>>;
+
+/// The change from `baseline` to `current`, in percent.
+///
+/// Returns `None` when the baseline is zero, unless nothing changed.
+pub fn percentage_change(baseline: f64, current: f64) -> Option<f64> {
+    if baseline == 0.0 {
+        (current == 0.0).then_some(0.0)
+    } else {
+        Some((current - baseline) / baseline * 100.0)
+    }
+}
+
+#[derive(Debug)]
+pub enum BenchStatus {
+    Measured(BenchMeasurement),
+    Fail { message: String, error_diagnostic: Option<CustomDiagnostic> },
+    CompileError(CustomDiagnostic),
+}
+
+/// Compiles and runs a `#[bench]` function.
+///
+/// Constrained benches are measured by the number of ACIR opcodes they compile to, after
+/// being executed once to make sure they succeed. Unconstrained benches are executed once to
+/// count the Brillig opcodes they go through, and then `iterations` more times to time them.
+pub fn run_bench<B, F, E>(
+    blackbox_solver: &B,
+    context: &mut Context,
+    bench_function: &BenchFunction,
+    config: &CompileOptions,
+    iterations: usize,
+    build_foreign_call_executor: F,
+) -> BenchStatus
+where
+    B: BlackBoxFunctionSolver<FieldElement>,
+    F: Fn() -> E,
+    E: ForeignCallExecutor<FieldElement>,
+{
+    let compiled_program = match compile_no_check(context, config, bench_function.id, None, false) {
+        Ok(compiled_program) => compiled_program,
+        Err(err) => return BenchStatus::CompileError(err.into()),
+    };
+
+    // Do the same optimizations as `compile_cmd`.
+    let target_width = config.expression_width.unwrap_or(DEFAULT_EXPRESSION_WIDTH);
+    let compiled_program = crate::ops::transform_program(compiled_program, target_width);
+    let program = &compiled_program.program;
+
+    let fail = |error: NargoError<FieldElement>| {
+        let error_diagnostic =
+            try_to_diagnose_runtime_error(&error, &compiled_program.abi, &compiled_program.debug);
+        BenchStatus::Fail { message: error.to_string(), error_diagnostic }
+    };
+
+    if bench_function.is_unconstrained || config.force_brillig {
+        let profiling_samples = match execute_program_with_profiling(
+            program,
+            WitnessMap::new(),
+            blackbox_solver,
+            &mut build_foreign_call_executor(),
+        ) {
+            Ok((_, profiling_samples)) => profiling_samples,
+            Err(error) => return fail(error),
+        };
+
+        let iterations = iterations.max(1);
+        let start = Instant::now();
+        for _ in 0..iterations {
+            if let Err(error) = execute_program(
+                program,
+                WitnessMap::new(),
+                blackbox_solver,
+                &mut build_foreign_call_executor(),
+            ) {
+                return fail(error);
+            }
+        }
+        let time_secs = start.elapsed().as_secs_f64() / iterations as f64;
+
+        BenchStatus::Measured(BenchMeasurement::Unconstrained {
+            brillig_opcodes: profiling_samples.len(),
+            time_secs,
+        })
+    } else {
+        if let Err(error) = execute_program(
+            program,
+            WitnessMap::new(),
+            blackbox_solver,
+            &mut build_foreign_call_executor(),
+        ) {
+            return fail(error);
+        }
+
+        let acir_opcodes = program.functions.iter().map(|function| function.opcodes.len()).sum();
+        BenchStatus::Measured(BenchMeasurement::Constrained { acir_opcodes })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BenchMeasurement, BenchRegression, percentage_change};
+
+    #[test]
+    fn computes_percentage_change() {
+        assert_eq!(percentage_change(200.0, 250.0), Some(25.0));
+        assert_eq!(percentage_change(200.0, 150.0), Some(-25.0));
+        assert_eq!(percentage_change(0.0, 0.0), Some(0.0));
+        assert_eq!(percentage_change(0.0, 1.0), None);
+    }
+
+    #[test]
+    fn reports_metrics_over_threshold() {
+        let baseline = BenchMeasurement::Unconstrained { brillig_opcodes: 100, time_secs: 1.0 };
+        let current = BenchMeasurement::Unconstrained { brillig_opcodes: 120, time_secs: 1.04 };
+
+        let regressions = current.regressions(&baseline, 5.0, 5.0);
+        assert_eq!(
+            regressions,
+            vec![BenchRegression {
+                metric: "Brillig opcodes",
+                baseline: 100.0,
+                current: 120.0,
+                change: 20.0
+            }]
+        );
+        assert!(current.regressions(&baseline, 25.0, 25.0).is_empty());
+    }
+
+    #[test]
+    fn checks_times_against_their_own_threshold() {
+        let baseline = BenchMeasurement::Unconstrained { brillig_opcodes: 100, time_secs: 1.0 };
+        let current = BenchMeasurement::Unconstrained { brillig_opcodes: 100, time_secs: 1.2 };
+
+        assert!(current.regressions(&baseline, 5.0, 25.0).is_empty());
+        assert_eq!(
+            current.regressions(&baseline, 25.0, 5.0),
+            vec![BenchRegression {
+                metric: "Time (s)",
+                baseline: 1.0,
+                current: 1.2,
+                change: percentage_change(1.0, 1.2).unwrap()
+            }]
+        );
+    }
+
+    #[test]
+    fn does_not_compare_different_kinds() {
+        let baseline = BenchMeasurement::Constrained { acir_opcodes: 10 };
+        let current = BenchMeasurement::Unconstrained { brillig_opcodes: 100, time_secs: 1.0 };
+        assert!(current.regressions(&baseline, 0.0, 0.0).is_empty());
+    }
+}
@@ -95,7 +95,13 @@ pub fn compile_program_with_debug_instrumenter(
     context.debug_instrumenter = debug_instrumenter;
     context.package_build_path = workspace.package_build_path(package);
 
-    noirc_driver::compile_main(&mut context, crate_id, compile_options, cached_program)
+    // Opcode budgets are checked against the circuit transformed for the package's expression width.
+    let compile_options = CompileOptions {
+        expression_width: compile_options.expression_width.or(package.expression_width),
+        ..compile_options.clone()
+    };
+
+    noirc_driver::compile_main(&mut context, crate_id, &compile_options, cached_program)
 }
 
 #[tracing::instrument(level = "trace", skip_all, fields(package_name = package.name.to_string()))]
@@ -0,0 +1,438 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Write,
+    path::{Path, PathBuf},
+};
+
+use acvm::{
+    acir::circuit::{AcirOpcodeLocation, BrilligOpcodeLocation, brillig::BrilligFunctionId},
+    pwg::ProfilingSample,
+};
+use fm::{FileManager, codespan_files::Files};
+use noirc_errors::{call_stack::CallStackId, debug_info::DebugInfo};
+
+use crate::xml_escape;
+
+/// The ACIR opcodes and Brillig instructions executed by a program, together with how many
+/// times each of them was executed.
+///
+/// Opcodes are keyed by the index of the ACIR function they belong to, which is also the index
+/// of the [DebugInfo] holding their source locations.
+#[derive(Debug, Default)]
+pub(crate) struct ExecutionCoverage {
+    acir_opcodes: BTreeMap<(usize, AcirOpcodeLocation), u64>,
+    brillig_opcodes: BTreeMap<(usize, BrilligFunctionId, BrilligOpcodeLocation), u64>,
+}
+
+impl ExecutionCoverage {
+    /// Record that an ACIR opcode was executed, either because the ACVM solved it or because
+    /// it's the opcode the execution failed on.
+    pub(crate) fn record_acir_opcode(
+        &mut self,
+        acir_function_index: usize,
+        location: AcirOpcodeLocation,
+    ) {
+        *self.acir_opcodes.entry((acir_function_index, location)).or_default() += 1;
+    }
+
+    /// Record the Brillig instructions executed by the unconstrained calls of an ACIR function.
+    ///
+    /// The ACVM takes one profiling sample per Brillig instruction, whose call stack ends with
+    /// the instruction that was executed.
+    pub(crate) fn record_brillig_samples(
+        &mut self,
+        acir_function_index: usize,
+        samples: &[ProfilingSample],
+    ) {
+        for sample in samples {
+            let (Some(brillig_function_id), Some(location)) = (
+                sample.brillig_function_id,
+                sample.call_stack.last().and_then(|location| location.to_brillig_location()),
+            ) else {
+                continue;
+            };
+            let key = (acir_function_index, brillig_function_id, location);
+            *self.brillig_opcodes.entry(key).or_default() += 1;
+        }
+    }
+}
+
+/// The number of times each source line was executed, keyed by file path and 1-based line number.
+///
+/// A line is only reported if some ACIR opcode or Brillig instruction was generated for it,
+/// in which case it's reported even if it was never executed.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct SourceCoverage {
+    files: BTreeMap<PathBuf, BTreeMap<u32, u64>>,
+}
+
+impl SourceCoverage {
+    /// Map the opcodes executed by a program back to the source lines they were generated from.
+    ///
+    /// Every location in the call stack of an opcode counts as executed, so calls to inlined
+    /// functions are covered too. The hit count of a line is the highest hit count of the opcodes
+    /// generated for it.
+    pub(crate) fn from_execution(
+        execution: &ExecutionCoverage,
+        debug: &[DebugInfo],
+        file_manager: &FileManager,
+    ) -> Self {
+        let mut coverage = SourceCoverage::default();
+
+        for (acir_function_index, debug_info) in debug.iter().enumerate() {
+            for (location, call_stack_id) in &debug_info.acir_locations {
+                let key = (acir_function_index, *location);
+                let hits = execution.acir_opcodes.get(&key).copied().unwrap_or_default();
+                coverage.record_call_stack(debug_info, *call_stack_id, hits, file_manager);
+            }
+
+            for (brillig_function_id, locations) in &debug_info.brillig_locations {
+                for (location, call_stack_id) in locations {
+                    let key = (acir_function_index, *brillig_function_id, *location);
+                    let hits = execution.brillig_opcodes.get(&key).copied().unwrap_or_default();
+                    coverage.record_call_stack(debug_info, *call_stack_id, hits, file_manager);
+                }
+            }
+        }
+
+        coverage
+    }
+
+    fn record_call_stack(
+        &mut self,
+        debug_info: &DebugInfo,
+        call_stack_id: CallStackId,
+        hits: u64,
+        file_manager: &FileManager,
+    ) {
+        let files = file_manager.as_file_map();
+
+        // A recursive call stack can go through the same line more than once.
+        let lines: BTreeSet<_> = debug_info
+            .location_tree
+            .get_call_stack(call_stack_id)
+            .into_iter()
+            .filter_map(|location| {
+                let path = file_manager.path(location.file)?;
+                let line_index = files.line_index(location.file, location.span.start() as usize);
+                Some((path, line_index.ok()? as u32 + 1))
+            })
+            .collect();
+
+        for (path, line) in lines {
+            let line_hits =
+                self.files.entry(path.to_path_buf()).or_default().entry(line).or_default();
+            *line_hits = (*line_hits).max(hits);
+        }
+    }
+
+    /// Add up the hit counts of another coverage, such as the one of another test.
+    pub fn merge(&mut self, other: SourceCoverage) {
+        for (path, lines) in other.files {
+            let file = self.files.entry(path).or_default();
+            for (line, hits) in lines {
+                *file.entry(line).or_default() += hits;
+            }
+        }
+    }
+
+    /// Only keep the files whose path satisfies the predicate.
+    pub fn retain_files(&mut self, mut predicate: impl FnMut(&Path) -> bool) {
+        self.files.retain(|path, _| predicate(path));
+    }
+
+    fn lines_covered(lines: &BTreeMap<u32, u64>) -> usize {
+        lines.values().filter(|hits| **hits > 0).count()
+    }
+}
+
+/// Render the coverage of each package as an lcov tracefile, with one test per package.
+pub fn lcov_report(packages: &BTreeMap<String, SourceCoverage>) -> String {
+    let mut report = String::new();
+    for (package_name, coverage) in packages {
+        for (path, lines) in &coverage.files {
+            writeln!(report, "TN:{package_name}").unwrap();
+            writeln!(report, "SF:{}", path.display()).unwrap();
+            for (line, hits) in lines {
+                writeln!(report, "DA:{line},{hits}").unwrap();
+            }
+            writeln!(report, "LF:{}", lines.len()).unwrap();
+            writeln!(report, "LH:{}", SourceCoverage::lines_covered(lines)).unwrap();
+            writeln!(report, "end_of_record").unwrap();
+        }
+    }
+    report
+}
+
+/// Render the coverage of each package as a Cobertura XML report, with file paths made relative
+/// to `source_root`.
+pub fn cobertura_report(packages: &BTreeMap<String, SourceCoverage>, source_root: &Path) -> String {
+    let line_rate = |covered: usize, valid: usize| {
+        if valid == 0 { 1.0 } else { covered as f64 / valid as f64 }
+    };
+
+    let mut total_covered = 0;
+    let mut total_valid = 0;
+    let mut packages_xml = String::new();
+    for (package_name, coverage) in packages {
+        let mut package_covered = 0;
+        let mut package_valid = 0;
+        let mut classes_xml = String::new();
+        for (path, lines) in &coverage.files {
+            let covered = SourceCoverage::lines_covered(lines);
+            package_covered += covered;
+            package_valid += lines.len();
+
+            let file_name = path.strip_prefix(source_root).unwrap_or(path).display().to_string();
+            writeln!(
+                classes_xml,
+                r#"        <class name="{}" filename="{}" line-rate="{:.4}" branch-rate="0" complexity="0">"#,
+                xml_escape(&file_name),
+                xml_escape(&file_name),
+                line_rate(covered, lines.len()),
+            )
+            .unwrap();
+            writeln!(classes_xml, "          <methods/>").unwrap();
+            writeln!(classes_xml, "          <lines>").unwrap();
+            for (line, hits) in lines {
+                writeln!(classes_xml, r#"            <line number="{line}" hits="{hits}"/>"#)
+                    .unwrap();
+            }
+            writeln!(classes_xml, "          </lines>").unwrap();
+            writeln!(classes_xml, "        </class>").unwrap();
+        }
+        total_covered += package_covered;
+        total_valid += package_valid;
+
+        writeln!(
+            packages_xml,
+            r#"    <package name="{}" line-rate="{:.4}" branch-rate="0" complexity="0">"#,
+            xml_escape(package_name),
+            line_rate(package_covered, package_valid),
+        )
+        .unwrap();
+        writeln!(packages_xml, "      <classes>").unwrap();
+        packages_xml.push_str(&classes_xml);
+        writeln!(packages_xml, "      </classes>").unwrap();
+        writeln!(packages_xml, "    </package>").unwrap();
+    }
+
+    let mut report = String::new();
+    writeln!(report, r#"<?xml version="1.0" ?>"#).unwrap();
+    writeln!(
+        report,
+        r#"<!DOCTYPE coverage SYSTEM "http://cobertura.sourceforge.net/xml/coverage-04.dtd">"#
+    )
+    .unwrap();
+    writeln!(
+        report,
+        r#"<coverage line-rate="{:.4}" branch-rate="0" lines-covered="{total_covered}" lines-valid="{total_valid}" branches-covered="0" branches-valid="0" complexity="0" version="0" timestamp="0">"#,
+        line_rate(total_covered, total_valid),
+    )
+    .unwrap();
+    writeln!(report, "  <sources>").unwrap();
+    writeln!(report, "    <source>{}</source>", xml_escape(&source_root.display().to_string()))
+        .unwrap();
+    writeln!(report, "  </sources>").unwrap();
+    writeln!(report, "  <packages>").unwrap();
+    report.push_str(&packages_xml);
+    writeln!(report, "  </packages>").unwrap();
+    writeln!(report, "</coverage>").unwrap();
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::BTreeMap,
+        path::{Path, PathBuf},
+    };
+
+    use acvm::{
+        AcirField, FieldElement,
+        acir::{
+            circuit::{
+                AcirOpcodeLocation, BrilligOpcodeLocation, Circuit, Opcode, OpcodeLocation,
+                Program, brillig::BrilligFunctionId, opcodes::AcirFunctionId,
+            },
+            native_types::{Expression, Witness, WitnessMap},
+        },
+        blackbox_solver::StubbedBlackBoxSolver,
+        pwg::ProfilingSample,
+    };
+    use fm::{FileId, FileManager};
+    use noirc_errors::{Location, Span, call_stack::CallStackHelper, debug_info::DebugInfo};
+
+    use crate::{NargoError, foreign_calls::layers::Empty, ops::execute::execute_program_inner};
+
+    use super::{ExecutionCoverage, SourceCoverage, cobertura_report, lcov_report};
+
+    const SOURCE: &str = "\
+fn main(x: Field) {
+    foo(x);
+    assert(x == 1);
+}
+
+fn foo(x: Field) {
+    assert(x != 0);
+}
+";
+
+    fn coverage(path: &str, lines: &[(u32, u64)]) -> SourceCoverage {
+        let lines = lines.iter().copied().collect();
+        SourceCoverage { files: BTreeMap::from([(PathBuf::from(path), lines)]) }
+    }
+
+    #[test]
+    fn merge_adds_up_hits() {
+        let mut coverage_a = coverage("/project/src/main.nr", &[(1, 1), (2, 0)]);
+        coverage_a.merge(coverage("/project/src/main.nr", &[(2, 3), (5, 0)]));
+
+        assert_eq!(coverage_a, coverage("/project/src/main.nr", &[(1, 1), (2, 3), (5, 0)]));
+    }
+
+    #[test]
+    fn renders_lcov() {
+        let packages = BTreeMap::from([(
+            "foo".to_string(),
+            coverage("/project/src/main.nr", &[(1, 2), (3, 0)]),
+        )]);
+
+        let expected = "\
+TN:foo
+SF:/project/src/main.nr
+DA:1,2
+DA:3,0
+LF:2
+LH:1
+end_of_record
+";
+        assert_eq!(lcov_report(&packages), expected);
+    }
+
+    #[test]
+    fn renders_cobertura() {
+        let packages = BTreeMap::from([(
+            "foo".to_string(),
+            coverage("/project/src/main.nr", &[(1, 2), (3, 0)]),
+        )]);
+
+        let report = cobertura_report(&packages, &PathBuf::from("/project"));
+        assert!(report.contains(r#"lines-covered="1" lines-valid="2""#));
+        assert!(report.contains("<source>/project</source>"));
+        assert!(report.contains(r#"<package name="foo" line-rate="0.5000""#));
+        assert!(report.contains(r#"filename="src/main.nr""#));
+        assert!(report.contains(r#"<line number="3" hits="0"/>"#));
+    }
+
+    /// The location of the first occurrence of `text` in [SOURCE].
+    fn location_of(file: FileId, text: &str) -> Location {
+        let start = SOURCE.find(text).unwrap() as u32;
+        Location::new(Span::from(start..start + text.len() as u32), file)
+    }
+
+    #[test]
+    fn from_execution_maps_opcode_hits_to_the_lines_of_their_call_stacks() {
+        let mut file_manager = FileManager::new(Path::new("/project"));
+        let file = file_manager
+            .add_file_with_source(Path::new("src/main.nr"), SOURCE.to_string())
+            .unwrap();
+
+        let call = location_of(file, "foo(x)");
+        let inner_assert = location_of(file, "x != 0");
+        let outer_assert = location_of(file, "x == 1");
+
+        let mut call_stacks = CallStackHelper::default();
+        let inlined_call_stack = call_stacks.get_or_insert_locations(&vec![call, inner_assert]);
+        let assert_call_stack = call_stacks.get_or_insert_locations(&vec![outer_assert]);
+
+        let brillig_function_id = BrilligFunctionId(0);
+        let debug_info = DebugInfo {
+            acir_locations: BTreeMap::from([
+                (AcirOpcodeLocation::new(0), inlined_call_stack),
+                (AcirOpcodeLocation::new(1), assert_call_stack),
+            ]),
+            brillig_locations: BTreeMap::from([(
+                brillig_function_id,
+                BTreeMap::from([(BrilligOpcodeLocation(0), inlined_call_stack)]),
+            )]),
+            location_tree: call_stacks.to_location_tree(),
+            ..DebugInfo::default()
+        };
+
+        let mut execution = ExecutionCoverage::default();
+        execution.record_acir_opcode(0, AcirOpcodeLocation::new(0));
+        let samples: Vec<_> = (0..3)
+            .map(|_| ProfilingSample {
+                call_stack: vec![
+                    OpcodeLocation::Acir(0),
+                    OpcodeLocation::Brillig { acir_index: 0, brillig_index: 0 },
+                ],
+                brillig_function_id: Some(brillig_function_id),
+            })
+            .collect();
+        execution.record_brillig_samples(0, &samples);
+
+        let source_coverage =
+            SourceCoverage::from_execution(&execution, &[debug_info], &file_manager);
+
+        // The call to `foo` was executed once, `foo`'s assertion once in ACIR and three times
+        // in Brillig, and `main`'s assertion never.
+        assert_eq!(source_coverage, coverage("/project/src/main.nr", &[(2, 1), (3, 0), (7, 3)]));
+    }
+
+    #[test]
+    fn records_the_callers_opcodes_when_a_nested_acir_call_fails() {
+        // `x - 1 == 0` holds in `main`, and `x == 0` fails in the function it calls.
+        let x_minus_one = Expression {
+            mul_terms: Vec::new(),
+            linear_combinations: vec![(FieldElement::one(), Witness(0))],
+            q_c: -FieldElement::one(),
+        };
+        let x = Expression {
+            mul_terms: Vec::new(),
+            linear_combinations: vec![(FieldElement::one(), Witness(0))],
+            q_c: FieldElement::zero(),
+        };
+        let main = Circuit {
+            current_witness_index: 0,
+            opcodes: vec![
+                Opcode::AssertZero(x_minus_one.clone()),
+                Opcode::Call {
+                    id: AcirFunctionId(1),
+                    inputs: vec![Witness(0)],
+                    outputs: Vec::new(),
+                    predicate: None,
+                },
+                Opcode::AssertZero(x_minus_one),
+            ],
+            ..Circuit::default()
+        };
+        let callee = Circuit {
+            current_witness_index: 0,
+            opcodes: vec![Opcode::AssertZero(x)],
+            ..Circuit::default()
+        };
+        let program =
+            Program { functions: vec![main, callee], unconstrained_functions: Vec::new() };
+
+        let mut execution = ExecutionCoverage::default();
+        let result = execute_program_inner(
+            &program,
+            WitnessMap::from(BTreeMap::from([(Witness(0), FieldElement::one())])),
+            &StubbedBlackBoxSolver::default(),
+            &mut Empty,
+            false,
+            None,
+            Some(&mut execution),
+        );
+        assert!(matches!(result, Err(NargoError::ExecutionError(_))));
+
+        let expected = BTreeMap::from([
+            ((0, AcirOpcodeLocation::new(0)), 1),
+            ((0, AcirOpcodeLocation::new(1)), 1),
+            ((1, AcirOpcodeLocation::new(0)), 1),
+        ]);
+        assert_eq!(execution.acir_opcodes, expected);
+    }
+}
@@ -8,12 +8,15 @@ use noirc_driver::{
 };
 use noirc_frontend::{
     debug::DebugInstrumenter,
-    hir::{Context, FunctionNameMatch, ParsedFiles, def_map::TestFunction},
+    hir::{
+        Context, FunctionNameMatch, ParsedFiles,
+        def_map::{FuzzingHarness, TestFunction},
+    },
 };
 
 use crate::{
     errors::CompileError, insert_all_files_for_workspace_into_file_manager, package::Package,
-    parse_all, prepare_package, workspace::Workspace,
+    parse_all, prepare_package, prepare_package_for_tests, workspace::Workspace,
 };
 
 use super::{
@@ -81,6 +84,20 @@ pub fn compile_test_fn_for_debugging(
     Ok(compiled_program)
 }
 
+pub fn compile_fuzzing_harness_for_debugging(
+    fuzzing_harness: &FuzzingHarness,
+    context: &mut Context,
+    package: &Package,
+    compile_options: CompileOptions,
+) -> Result<CompiledProgram, noirc_driver::CompileError> {
+    let compiled_program =
+        compile_no_check(context, &compile_options, fuzzing_harness.id, None, false)?;
+    let expression_width =
+        get_target_width(package.expression_width, compile_options.expression_width);
+    let compiled_program = transform_program(compiled_program, expression_width);
+    Ok(compiled_program)
+}
+
 pub fn compile_bin_package_for_debugging(
     workspace: &Workspace,
     package: &Package,
@@ -146,16 +163,25 @@ pub fn compile_options_for_debugging(
     }
 }
 
+/// Prepares a package to be compiled with debug instrumentation.
+///
+/// If `for_tests` is set, the package is prepared as with [prepare_package_for_tests],
+/// so that its tests and fuzzing harnesses can be debugged.
 pub fn prepare_package_for_debug<'a>(
     file_manager: &'a FileManager,
     parsed_files: &'a mut ParsedFiles,
     package: &'a Package,
     workspace: &Workspace,
+    for_tests: bool,
 ) -> (Context<'a, 'a>, CrateId) {
     let debug_instrumenter = instrument_package_files(parsed_files, file_manager, package);
 
     // -- This :down: is from nargo::ops(compile).compile_program_with_debug_instrumenter
-    let (mut context, crate_id) = prepare_package(file_manager, parsed_files, package);
+    let (mut context, crate_id) = if for_tests {
+        prepare_package_for_tests(file_manager, parsed_files, package)
+    } else {
+        prepare_package(file_manager, parsed_files, package)
+    };
     link_to_debug_crate(&mut context, crate_id);
     context.debug_instrumenter = debug_instrumenter;
     context.package_build_path = workspace.package_build_path(package);
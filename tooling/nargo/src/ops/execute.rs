@@ -1,9 +1,13 @@
-use acvm::acir::circuit::brillig::BrilligBytecode;
-use acvm::acir::circuit::{OpcodeLocation, Program};
+use std::collections::BTreeMap;
+
+use acvm::acir::brillig::{ForeignCallParam, ForeignCallResult};
+use acvm::acir::circuit::brillig::{BrilligBytecode, BrilligFunctionId};
+use acvm::acir::circuit::{AcirOpcodeLocation, OpcodeLocation, Program};
 use acvm::acir::native_types::WitnessStack;
 use acvm::brillig_vm::BranchToFeatureMap;
 use acvm::pwg::{
-    ACVM, ACVMStatus, ErrorLocation, OpcodeNotSolvable, OpcodeResolutionError, ProfilingSamples,
+    ACVM, ACVMStatus, ErrorLocation, ForeignCallWaitInfo, OpcodeNotSolvable, OpcodeResolutionError,
+    ProfilingSample, ProfilingSamples,
 };
 use acvm::{AcirField, BlackBoxFunctionSolver};
 type NargoErrorAndCoverage<F> = (NargoError<F>, Option<Vec<u32>>);
@@ -13,7 +17,15 @@ use acvm::{acir::circuit::Circuit, acir::native_types::WitnessMap};
 
 use crate::NargoError;
 use crate::errors::{ExecutionError, ResolvedOpcodeLocation, execution_error_from};
-use crate::foreign_calls::ForeignCallExecutor;
+use crate::foreign_calls::{ForeignCall, ForeignCallExecutor};
+
+use super::coverage::ExecutionCoverage;
+
+/// The call stacks at which oracle mocks were created, keyed by the id of the mock.
+///
+/// The Brillig function id is needed to resolve the source locations of the call stack.
+pub(crate) type MockCallStacks =
+    BTreeMap<usize, (Vec<ResolvedOpcodeLocation>, Option<BrilligFunctionId>)>;
 
 struct ProgramExecutor<'a, F: AcirField, B: BlackBoxFunctionSolver<F>, E: ForeignCallExecutor<F>> {
     functions: &'a [Circuit<F>],
@@ -54,6 +66,12 @@ struct ProgramExecutor<'a, F: AcirField, B: BlackBoxFunctionSolver<F>, E: Foreig
 
     // Partial witness on failure
     failing_partial_witness: Option<WitnessMap<F>>,
+
+    // Call stacks at which oracle mocks were created, if we want to track them
+    mock_call_stacks: Option<MockCallStacks>,
+
+    // Opcodes executed so far, if we want to collect coverage
+    coverage: Option<ExecutionCoverage>,
 }
 
 impl<'a, F: AcirField, B: BlackBoxFunctionSolver<F>, E: ForeignCallExecutor<F>>
@@ -80,6 +98,8 @@ impl<'a, F: AcirField, B: BlackBoxFunctionSolver<F>, E: ForeignCallExecutor<F>>
             last_fuzzing_trace: None,
             return_witness_on_failure: false,
             failing_partial_witness: None,
+            mock_call_stacks: None,
+            coverage: None,
         }
     }
 
@@ -95,6 +115,75 @@ impl<'a, F: AcirField, B: BlackBoxFunctionSolver<F>, E: ForeignCallExecutor<F>>
         self.return_witness_on_failure = return_witness_on_failure;
     }
 
+    fn with_mock_call_stacks(&mut self) {
+        self.mock_call_stacks = Some(MockCallStacks::default());
+    }
+
+    fn with_coverage(&mut self) {
+        self.coverage = Some(ExecutionCoverage::default());
+    }
+
+    /// Record the Brillig instructions executed by the current function, if collecting coverage.
+    fn record_brillig_coverage(&mut self, samples: &[ProfilingSample]) {
+        if let Some(coverage) = &mut self.coverage {
+            coverage.record_brillig_samples(self.current_function_index, samples);
+        }
+    }
+
+    /// Run the ACVM until it stops solving opcodes.
+    ///
+    /// If we are collecting coverage the opcodes are solved one at a time, so that every opcode
+    /// the ACVM moves past is recorded, as well as the opcode it fails on.
+    fn solve(&mut self, acvm: &mut ACVM<'_, F, B>) -> ACVMStatus<F> {
+        let Some(coverage) = &mut self.coverage else {
+            return acvm.solve();
+        };
+        while *acvm.get_status() == ACVMStatus::InProgress {
+            let opcode_index = acvm.instruction_pointer();
+            let status = acvm.solve_opcode();
+            if acvm.instruction_pointer() != opcode_index
+                || matches!(status, ACVMStatus::Failure(_))
+            {
+                let location = AcirOpcodeLocation::new(opcode_index);
+                coverage.record_acir_opcode(self.current_function_index, location);
+            }
+        }
+        acvm.get_status().clone()
+    }
+
+    /// Record the call stack at which a mock was created, if the foreign call created one.
+    fn record_mock_call_stack(
+        &mut self,
+        acvm: &ACVM<'_, F, B>,
+        foreign_call: &ForeignCallWaitInfo<F>,
+        foreign_call_result: &ForeignCallResult<F>,
+    ) {
+        let Some(mock_call_stacks) = &mut self.mock_call_stacks else {
+            return;
+        };
+        if !matches!(ForeignCall::lookup(&foreign_call.function), Some(ForeignCall::CreateMock)) {
+            return;
+        }
+        let Some(ForeignCallParam::Single(mock_id)) = foreign_call_result.values.first() else {
+            return;
+        };
+        let Some(mock_id) = mock_id.try_to_u64().and_then(|id| usize::try_from(id).ok()) else {
+            return;
+        };
+
+        let mut call_stack = self.call_stack.clone();
+        let brillig_function_id = acvm.get_brillig_call_stack().map(|(function_id, locations)| {
+            call_stack.extend(locations.into_iter().map(|opcode_location| {
+                ResolvedOpcodeLocation {
+                    acir_function_index: self.current_function_index,
+                    opcode_location,
+                }
+            }));
+            function_id
+        });
+        mock_call_stacks.insert(mock_id, (call_stack, brillig_function_id));
+    }
+
     fn finalize(self) -> WitnessStack<F> {
         self.witness_stack
     }
@@ -112,11 +201,12 @@ impl<'a, F: AcirField, B: BlackBoxFunctionSolver<F>, E: ForeignCallExecutor<F>>
             self.unconstrained_functions,
             &circuit.assert_messages,
         );
-        acvm.with_profiler(self.profiling_active);
+        // Coverage is collected from the profiling samples of the Brillig VM.
+        acvm.with_profiler(self.profiling_active || self.coverage.is_some());
         acvm.with_brillig_fuzzing(self.brillig_branch_to_feature_map);
 
         loop {
-            let solver_status = acvm.solve();
+            let solver_status = self.solve(&mut acvm);
 
             match solver_status {
                 ACVMStatus::Solved => break,
@@ -128,6 +218,9 @@ impl<'a, F: AcirField, B: BlackBoxFunctionSolver<F>, E: ForeignCallExecutor<F>>
                     if self.return_witness_on_failure {
                         self.failing_partial_witness = Some(acvm.witness_map().clone());
                     }
+                    if self.coverage.is_some() {
+                        self.record_brillig_coverage(&acvm.take_profiling_samples());
+                    }
                     match &error {
                         OpcodeResolutionError::UnsatisfiedConstrain {
                             opcode_location: ErrorLocation::Resolved(opcode_location),
@@ -165,6 +258,7 @@ impl<'a, F: AcirField, B: BlackBoxFunctionSolver<F>, E: ForeignCallExecutor<F>>
                 }
                 ACVMStatus::RequiresForeignCall(foreign_call) => {
                     let foreign_call_result = self.foreign_call_executor.execute(&foreign_call)?;
+                    self.record_mock_call_stack(&acvm, &foreign_call, &foreign_call_result);
                     acvm.resolve_pending_foreign_call(foreign_call_result);
                 }
                 ACVMStatus::RequiresAcirCall(call_info) => {
@@ -182,7 +276,21 @@ impl<'a, F: AcirField, B: BlackBoxFunctionSolver<F>, E: ForeignCallExecutor<F>>
                     let acir_to_call = &self.functions[call_info.id.as_usize()];
                     let initial_witness = call_info.initial_witness;
                     // TODO: Profiling among multiple circuits is not supported
-                    let (call_solved_witness, _) = self.execute_circuit(initial_witness)?;
+                    let (call_solved_witness, _) = match self.execute_circuit(initial_witness) {
+                        Ok(result) => result,
+                        Err(error) => {
+                            // The caller was executed up to, and including, its call opcode.
+                            if let Some(coverage) = &mut self.coverage {
+                                let location = AcirOpcodeLocation::new(acvm.instruction_pointer());
+                                coverage.record_acir_opcode(acir_function_caller, location);
+                                coverage.record_brillig_samples(
+                                    acir_function_caller,
+                                    &acvm.take_profiling_samples(),
+                                );
+                            }
+                            return Err(error);
+                        }
+                    };
 
                     // Set tracking index back to the parent function after ACIR call execution
                     self.current_function_index = acir_function_caller;
@@ -211,7 +319,13 @@ impl<'a, F: AcirField, B: BlackBoxFunctionSolver<F>, E: ForeignCallExecutor<F>>
         // included in a failure case.
         self.call_stack.clear();
 
-        let profiling_samples = acvm.take_profiling_samples();
+        let mut profiling_samples = acvm.take_profiling_samples();
+        if self.coverage.is_some() {
+            self.record_brillig_coverage(&profiling_samples);
+            if !self.profiling_active {
+                profiling_samples.clear();
+            }
+        }
         self.last_fuzzing_trace = acvm.get_brillig_fuzzing_trace();
         Ok((acvm.finalize(), profiling_samples))
     }
@@ -230,6 +344,8 @@ pub fn execute_program<F: AcirField, B: BlackBoxFunctionSolver<F>, E: ForeignCal
         blackbox_solver,
         foreign_call_executor,
         profiling_active,
+        None,
+        None,
     )?;
     assert!(profiling_samples.is_empty(), "Expected no profiling samples");
 
@@ -253,6 +369,8 @@ pub fn execute_program_with_profiling<
         blackbox_solver,
         foreign_call_executor,
         profiling_active,
+        None,
+        None,
     )
 }
 pub(crate) fn execute_program_with_brillig_fuzzing<
@@ -322,14 +440,25 @@ pub(crate) fn execute_program_with_acir_fuzzing<
         }
     }
 }
-#[tracing::instrument(level = "trace", skip_all)]
 
-fn execute_program_inner<F: AcirField, B: BlackBoxFunctionSolver<F>, E: ForeignCallExecutor<F>>(
+/// Execute the program, optionally keeping track of the call stack at which each oracle mock
+/// was created into `mock_call_stacks`.
+///
+/// If `coverage` is given, the opcodes executed by the program are recorded into it,
+/// even if the execution fails.
+#[tracing::instrument(level = "trace", skip_all)]
+pub(crate) fn execute_program_inner<
+    F: AcirField,
+    B: BlackBoxFunctionSolver<F>,
+    E: ForeignCallExecutor<F>,
+>(
     program: &Program<F>,
     initial_witness: WitnessMap<F>,
     blackbox_solver: &B,
     foreign_call_executor: &mut E,
     profiling_active: bool,
+    mock_call_stacks: Option<&mut MockCallStacks>,
+    coverage: Option<&mut ExecutionCoverage>,
 ) -> Result<(WitnessStack<F>, ProfilingSamples), NargoError<F>> {
     let mut executor = ProgramExecutor::new(
         &program.functions,
@@ -338,7 +467,22 @@ fn execute_program_inner<F: AcirField, B: BlackBoxFunctionSolver<F>, E: ForeignC
         foreign_call_executor,
         profiling_active,
     );
-    let (main_witness, profiling_samples) = executor.execute_circuit(initial_witness)?;
+    if mock_call_stacks.is_some() {
+        executor.with_mock_call_stacks();
+    }
+    if coverage.is_some() {
+        executor.with_coverage();
+    }
+    let result = executor.execute_circuit(initial_witness);
+    if let (Some(coverage), Some(executed)) = (coverage, executor.coverage.take()) {
+        *coverage = executed;
+    }
+    if let (Some(mock_call_stacks), Some(created)) =
+        (mock_call_stacks, executor.mock_call_stacks.take())
+    {
+        *mock_call_stacks = created;
+    }
+    let (main_witness, profiling_samples) = result?;
     executor.witness_stack.push(0, main_witness);
 
     Ok((executor.finalize(), profiling_samples))
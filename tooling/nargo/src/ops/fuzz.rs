@@ -1,3 +1,5 @@
+use std::path::{Path, PathBuf};
+
 use acvm::{
     BlackBoxFunctionSolver, FieldElement,
     acir::native_types::{WitnessMap, WitnessStack},
@@ -8,22 +10,43 @@ use noir_greybox_fuzzer::{
     FuzzedExecutorExecutionConfiguration, FuzzedExecutorFailureConfiguration,
     FuzzedExecutorFolderConfiguration, WitnessAndCoverage,
 };
-use noirc_abi::{Abi, InputMap};
-use noirc_driver::{CompileOptions, compile_no_check};
+use noirc_abi::{
+    Abi, InputMap,
+    errors::{AbiError, InputParserError},
+    input_parser::Format,
+};
+use noirc_driver::{CompileOptions, CompiledProgram, compile_no_check};
 use noirc_errors::CustomDiagnostic;
 use noirc_frontend::hir::{Context, def_map::FuzzingHarness};
+use sha2::{Digest, Sha256};
 
 use crate::foreign_calls::ForeignCallExecutor;
 use crate::{
+    NargoError,
+    constants::FUZZ_CRASHES_DIR,
     errors::try_to_diagnose_runtime_error,
     foreign_calls::layers,
     ops::{
         execute::execute_program_with_acir_fuzzing, execute::execute_program_with_brillig_fuzzing,
         test::TestForeignCallExecutor,
     },
+    package::Package,
 };
 
-use super::execute_program;
+use super::{TestStatus, execute_program};
+
+#[derive(Debug, thiserror::Error)]
+pub enum FuzzingCrashError {
+    #[error(transparent)]
+    IoError(#[from] std::io::Error),
+
+    #[error(transparent)]
+    InputParserError(#[from] InputParserError),
+
+    #[error(transparent)]
+    AbiError(#[from] AbiError),
+}
+
 /// Configuration for fuzzing loop execution
 pub struct FuzzExecutionConfig {
     /// Number of threads to use for fuzzing
@@ -337,3 +360,222 @@ where
 fn output(show_output: bool) -> Box<dyn std::io::Write> {
     if show_output { Box::new(std::io::stdout()) } else { Box::new(std::io::empty()) }
 }
+
+/// The directory holding the failing inputs found while fuzzing a harness.
+///
+/// These are kept in the package, under `fuzz_crashes/<harness name>`, unless a
+/// `fuzzing_failure_dir` is given, in which case they go to `<dir>/<package>/<harness name>`.
+pub fn fuzzing_crashes_dir(
+    package: &Package,
+    fuzzing_failure_dir: Option<&str>,
+    fuzzing_harness_name: &str,
+) -> PathBuf {
+    let dir = match fuzzing_failure_dir {
+        Some(dir) => PathBuf::from(dir).join(package.name.to_string()),
+        None => package.root_dir.join(FUZZ_CRASHES_DIR),
+    };
+    dir.join(fuzzing_harness_name.replace("::", "/"))
+}
+
+/// Save a failing input as a `Prover.toml`-style reproducer in `crashes_dir`, returning its path.
+///
+/// Reproducers are named after the SHA-256 hash of their contents, so the same input is only
+/// saved once and keeps its name across builds of nargo.
+pub fn save_fuzzing_crash(
+    crashes_dir: &Path,
+    input_map: &InputMap,
+    abi: &Abi,
+) -> Result<PathBuf, FuzzingCrashError> {
+    let contents = Format::Toml.serialize(input_map, abi)?;
+
+    let hash = hex::encode(Sha256::digest(contents.as_bytes()));
+    let path = crashes_dir.join(format!("crash-{hash}")).with_extension("toml");
+
+    std::fs::create_dir_all(crashes_dir)?;
+    std::fs::write(&path, contents)?;
+    Ok(path)
+}
+
+/// The reproducers saved in `crashes_dir`, sorted by path.
+pub fn fuzzing_crashes(crashes_dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(crashes_dir) else {
+        return Vec::new();
+    };
+    let mut crashes: Vec<_> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "toml"))
+        .collect();
+    crashes.sort();
+    crashes
+}
+
+/// Re-run the failing inputs saved for a fuzzing harness, as a regression test.
+///
+/// Each input is executed both as ACIR and as Brillig, like the fuzzer does. The test passes
+/// if the harness now behaves as expected on every input.
+pub fn replay_fuzzing_crashes<'a, B, F, E>(
+    blackbox_solver: &B,
+    context: &mut Context,
+    fuzzing_harness: &FuzzingHarness,
+    crash_files: &[PathBuf],
+    config: &CompileOptions,
+    build_foreign_call_executor: F,
+) -> TestStatus
+where
+    B: BlackBoxFunctionSolver<FieldElement>,
+    F: Fn(Box<dyn std::io::Write + 'a>, layers::Unhandled) -> E,
+    E: ForeignCallExecutor<FieldElement>,
+{
+    let mut programs = Vec::new();
+    for force_brillig in [false, true] {
+        let config = CompileOptions { force_brillig, ..config.clone() };
+        match compile_no_check(context, &config, fuzzing_harness.id, None, false) {
+            Ok(program) => programs.push(program),
+            Err(err) => return TestStatus::CompileError(err.into()),
+        }
+    }
+
+    for crash_file in crash_files {
+        for program in &programs {
+            let result = replay_fuzzing_crash(
+                blackbox_solver,
+                program,
+                fuzzing_harness,
+                crash_file,
+                &build_foreign_call_executor,
+            );
+            if let Err((message, error_diagnostic)) = result {
+                let message = format!("{}: {message}", crash_file.display());
+                return TestStatus::Fail { message, error_diagnostic };
+            }
+        }
+    }
+
+    TestStatus::Pass
+}
+
+fn replay_fuzzing_crash<'a, B, F, E>(
+    blackbox_solver: &B,
+    program: &CompiledProgram,
+    fuzzing_harness: &FuzzingHarness,
+    crash_file: &Path,
+    build_foreign_call_executor: &F,
+) -> Result<(), (String, Option<CustomDiagnostic>)>
+where
+    B: BlackBoxFunctionSolver<FieldElement>,
+    F: Fn(Box<dyn std::io::Write + 'a>, layers::Unhandled) -> E,
+    E: ForeignCallExecutor<FieldElement>,
+{
+    let initial_witness = read_fuzzing_crash(crash_file, &program.abi)
+        .map_err(|err| (format!("Failed to read failing input: {err}"), None))?;
+
+    let foreign_call_executor = build_foreign_call_executor(output(false), layers::Unhandled);
+    let mut foreign_call_executor = TestForeignCallExecutor::new(foreign_call_executor);
+    let result = execute_program(
+        &program.program,
+        initial_witness,
+        blackbox_solver,
+        &mut foreign_call_executor,
+    );
+
+    check_fuzzing_crash_result(fuzzing_harness, program, result)
+}
+
+fn read_fuzzing_crash(
+    crash_file: &Path,
+    abi: &Abi,
+) -> Result<WitnessMap<FieldElement>, FuzzingCrashError> {
+    let contents = std::fs::read_to_string(crash_file)?;
+    let input_map = Format::Toml.parse(&contents, abi)?;
+    Ok(abi.encode(&input_map, None)?)
+}
+
+/// Check the result of executing a fuzzing harness the same way the fuzzer does.
+fn check_fuzzing_crash_result(
+    fuzzing_harness: &FuzzingHarness,
+    program: &CompiledProgram,
+    result: Result<WitnessStack<FieldElement>, NargoError<FieldElement>>,
+) -> Result<(), (String, Option<CustomDiagnostic>)> {
+    let err = match result {
+        Ok(_) if fuzzing_harness.should_fail_enabled() => {
+            return Err(("Input should make the harness fail, but it didn't".to_string(), None));
+        }
+        Ok(_) => return Ok(()),
+        Err(err) => err,
+    };
+
+    let message = err
+        .user_defined_failure_message(&program.abi.error_types)
+        .unwrap_or_else(|| err.to_string());
+    let expected_failure = match fuzzing_harness.failure_reason() {
+        Some(reason) => message.contains(&reason),
+        None => fuzzing_harness.should_fail_enabled(),
+    };
+    if expected_failure {
+        return Ok(());
+    }
+
+    let error_diagnostic = try_to_diagnose_runtime_error(&err, &program.abi, &program.debug);
+    Err((message, error_diagnostic))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use acvm::FieldElement;
+    use noirc_abi::{
+        Abi, AbiParameter, AbiType, AbiVisibility, InputMap, input_parser::InputValue,
+    };
+
+    use super::{fuzzing_crashes, read_fuzzing_crash, save_fuzzing_crash};
+
+    fn abi() -> Abi {
+        let parameter = |name: &str| AbiParameter {
+            name: name.to_string(),
+            typ: AbiType::Field,
+            visibility: AbiVisibility::Private,
+        };
+        Abi {
+            parameters: vec![parameter("x"), parameter("y")],
+            return_type: None,
+            error_types: BTreeMap::new(),
+        }
+    }
+
+    #[test]
+    fn saved_crashes_are_read_back_as_the_same_input() {
+        let crashes_dir = tempfile::tempdir().unwrap();
+        let abi = abi();
+        let input_map = InputMap::from([
+            ("x".to_string(), InputValue::Field(FieldElement::from(3_u128))),
+            ("y".to_string(), InputValue::Field(FieldElement::from(5_u128))),
+        ]);
+
+        let path = save_fuzzing_crash(crashes_dir.path(), &input_map, &abi).unwrap();
+        assert_eq!(fuzzing_crashes(crashes_dir.path()), vec![path.clone()]);
+
+        let witness_map = read_fuzzing_crash(&path, &abi).unwrap();
+        assert_eq!(witness_map, abi.encode(&input_map, None).unwrap());
+    }
+
+    #[test]
+    fn crashes_are_named_after_a_sha256_hash_of_their_contents() {
+        let crashes_dir = tempfile::tempdir().unwrap();
+        let abi = abi();
+        let input_map = InputMap::from([
+            ("x".to_string(), InputValue::Field(FieldElement::from(3_u128))),
+            ("y".to_string(), InputValue::Field(FieldElement::from(5_u128))),
+        ]);
+
+        let path = save_fuzzing_crash(crashes_dir.path(), &input_map, &abi).unwrap();
+        let name = path.file_stem().unwrap().to_str().unwrap();
+        let hash = name.strip_prefix("crash-").unwrap();
+        assert_eq!(hash.len(), 64);
+        assert!(hash.chars().all(|char| char.is_ascii_hexdigit()));
+
+        // Saving the same input again reuses the same reproducer.
+        assert_eq!(save_fuzzing_crash(crashes_dir.path(), &input_map, &abi).unwrap(), path);
+        assert_eq!(fuzzing_crashes(crashes_dir.path()).len(), 1);
+    }
+}
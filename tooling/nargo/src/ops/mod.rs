@@ -1,3 +1,6 @@
+pub use self::bench::{
+    BenchBaseline, BenchMeasurement, BenchRegression, BenchStatus, percentage_change, run_bench,
+};
 pub use self::check::check_program;
 pub use self::compile::{
     check_crate_and_report_errors, collect_errors, compile_contract, compile_program,
@@ -6,17 +9,22 @@ pub use self::compile::{
 pub use self::optimize::{optimize_contract, optimize_program};
 pub use self::transform::{transform_contract, transform_program};
 
+pub use self::coverage::{SourceCoverage, cobertura_report, lcov_report};
 pub use self::execute::{execute_program, execute_program_with_profiling};
 pub use self::fuzz::{
-    FuzzExecutionConfig, FuzzFolderConfig, FuzzingRunStatus, run_fuzzing_harness,
+    FuzzExecutionConfig, FuzzFolderConfig, FuzzingCrashError, FuzzingRunStatus, fuzzing_crashes,
+    fuzzing_crashes_dir, replay_fuzzing_crashes, run_fuzzing_harness, save_fuzzing_crash,
 };
 pub use self::test::{
-    FuzzConfig, TestStatus, check_expected_failure_message, fuzz_test, run_or_fuzz_test, run_test,
+    FuzzConfig, PropertyTestConfig, TestStatus, TestWithArgumentsConfig,
+    check_expected_failure_message, fuzz_test, property_test, run_or_fuzz_test, run_test,
     test_status_program_compile_fail, test_status_program_compile_pass,
 };
 
+mod bench;
 mod check;
 mod compile;
+mod coverage;
 pub mod debug;
 mod execute;
 mod fuzz;
@@ -8,11 +8,16 @@ use acvm::{
     },
     pwg::ForeignCallWaitInfo,
 };
-use noirc_abi::{Abi, input_parser::json::serialize_to_json};
+use fm::FileManager;
+use noirc_abi::{Abi, InputMap, arbitrary::arb_input_map, input_parser::json::serialize_to_json};
 use noirc_driver::{
     CompileError, CompileOptions, CompiledProgram, DEFAULT_EXPRESSION_WIDTH, compile_no_check,
 };
 use noirc_errors::{CustomDiagnostic, debug_info::DebugInfo};
+use proptest::test_runner::{
+    Config as ProptestConfig, RngAlgorithm, TestCaseError, TestError, TestRng, TestRunner,
+};
+
 use noirc_frontend::{
     hir::{
         Context,
@@ -23,14 +28,18 @@ use noirc_frontend::{
 
 use crate::{
     NargoError,
-    errors::try_to_diagnose_runtime_error,
+    errors::{resolve_call_stack, try_to_diagnose_runtime_error},
     foreign_calls::{
-        ForeignCallError, ForeignCallExecutor, layers, transcript::LoggingForeignCallExecutor,
+        ForeignCall, ForeignCallError, ForeignCallExecutor, layers,
+        mocker::UnsatisfiedMockExpectation, transcript::LoggingForeignCallExecutor,
     },
 };
 
 use super::{
-    FuzzExecutionConfig, FuzzFolderConfig, FuzzingRunStatus, execute_program, run_fuzzing_harness,
+    FuzzExecutionConfig, FuzzFolderConfig, FuzzingRunStatus,
+    coverage::{ExecutionCoverage, SourceCoverage},
+    execute::{MockCallStacks, execute_program_inner},
+    run_fuzzing_harness,
 };
 
 #[derive(Debug)]
@@ -52,7 +61,24 @@ pub struct FuzzConfig {
     pub execution_config: FuzzExecutionConfig,
 }
 
-/// Runs a test function. This will either run the test or fuzz it, depending on whether the function has arguments.
+pub struct PropertyTestConfig {
+    /// Number of generated inputs to run the test with
+    pub cases: u32,
+    /// Seed for generating the inputs, so that a run can be reproduced
+    pub seed: u64,
+}
+
+/// How test functions with arguments are run.
+pub enum TestWithArgumentsConfig {
+    /// Run the test with inputs generated from the types of its arguments.
+    Property(PropertyTestConfig),
+    /// Run the fuzzer on the test.
+    Fuzz(FuzzConfig),
+}
+
+/// Runs a test function. This will either run the test, or fuzz it or run it as a property test, depending on whether the function has arguments.
+///
+/// If `coverage` is given, the source lines executed by the test are added to it. Tests with arguments are not covered.
 #[allow(clippy::too_many_arguments)]
 pub fn run_or_fuzz_test<'a, W, B, F, E>(
     blackbox_solver: &B,
@@ -61,8 +87,9 @@ pub fn run_or_fuzz_test<'a, W, B, F, E>(
     output: W,
     package_name: String,
     config: &CompileOptions,
-    fuzz_config: FuzzConfig,
+    arguments_config: TestWithArgumentsConfig,
     build_foreign_call_executor: F,
+    coverage: Option<&mut SourceCoverage>,
 ) -> TestStatus
 where
     W: std::io::Write + 'a,
@@ -71,22 +98,34 @@ where
     E: ForeignCallExecutor<FieldElement>,
 {
     if test_function.has_arguments {
-        fuzz_test::<B, F, E>(
-            context,
-            test_function,
-            package_name,
-            config,
-            fuzz_config,
-            build_foreign_call_executor,
-        )
+        match arguments_config {
+            TestWithArgumentsConfig::Property(property_config) => property_test::<W, B, F, E>(
+                blackbox_solver,
+                context,
+                test_function,
+                output,
+                config,
+                property_config,
+                build_foreign_call_executor,
+            ),
+            TestWithArgumentsConfig::Fuzz(fuzz_config) => fuzz_test::<B, F, E>(
+                context,
+                test_function,
+                package_name,
+                config,
+                fuzz_config,
+                build_foreign_call_executor,
+            ),
+        }
     } else {
-        run_test::<W, B, F, E>(
+        run_test_with_coverage::<W, B, F, E>(
             blackbox_solver,
             context,
             test_function,
             output,
             config,
             build_foreign_call_executor,
+            coverage,
         )
     }
 }
@@ -100,6 +139,34 @@ pub fn run_test<'a, W, B, F, E>(
     config: &CompileOptions,
     build_foreign_call_executor: F,
 ) -> TestStatus
+where
+    W: std::io::Write + 'a,
+    B: BlackBoxFunctionSolver<FieldElement>,
+    F: Fn(Box<dyn std::io::Write + 'a>, layers::Unhandled) -> E,
+    E: ForeignCallExecutor<FieldElement>,
+{
+    run_test_with_coverage(
+        blackbox_solver,
+        context,
+        test_function,
+        output,
+        config,
+        build_foreign_call_executor,
+        None,
+    )
+}
+
+/// Runs a test function, adding the source lines it executed to `coverage` if it's given.
+/// This assumes the function has no arguments.
+fn run_test_with_coverage<'a, W, B, F, E>(
+    blackbox_solver: &B,
+    context: &mut Context,
+    test_function: &TestFunction,
+    output: W,
+    config: &CompileOptions,
+    build_foreign_call_executor: F,
+    coverage: Option<&mut SourceCoverage>,
+) -> TestStatus
 where
     W: std::io::Write + 'a,
     B: BlackBoxFunctionSolver<FieldElement>,
@@ -114,6 +181,7 @@ where
             output,
             config,
             build_foreign_call_executor,
+            coverage.map(|coverage| (coverage, &*context.file_manager)),
         ),
         Err(err) => test_status_program_compile_fail(err, test_function),
     }
@@ -126,6 +194,7 @@ fn run_test_impl<'a, W, B, F, E>(
     output: W,
     config: &CompileOptions,
     build_foreign_call_executor: F,
+    coverage: Option<(&mut SourceCoverage, &FileManager)>,
 ) -> TestStatus
 where
     W: std::io::Write + 'a,
@@ -137,6 +206,35 @@ where
     let target_width = config.expression_width.unwrap_or(DEFAULT_EXPRESSION_WIDTH);
     let compiled_program = crate::ops::transform_program(compiled_program, target_width);
 
+    execute_test_program(
+        blackbox_solver,
+        &compiled_program,
+        test_function,
+        WitnessMap::new(),
+        Box::new(output),
+        &build_foreign_call_executor,
+        coverage,
+    )
+}
+
+/// Executes a test program with the given inputs and returns the resulting status.
+///
+/// The expectations set on oracle mocks are only checked if the test ran to completion.
+/// If `coverage` is given, the source lines executed by the test are added to it.
+fn execute_test_program<'a, B, F, E>(
+    blackbox_solver: &B,
+    compiled_program: &CompiledProgram,
+    test_function: &TestFunction,
+    initial_witness: WitnessMap<FieldElement>,
+    output: Box<dyn std::io::Write + 'a>,
+    build_foreign_call_executor: &F,
+    coverage: Option<(&mut SourceCoverage, &FileManager)>,
+) -> TestStatus
+where
+    B: BlackBoxFunctionSolver<FieldElement>,
+    F: Fn(Box<dyn std::io::Write + 'a>, layers::Unhandled) -> E,
+    E: ForeignCallExecutor<FieldElement>,
+{
     let ignore_foreign_call_failures =
         std::env::var("NARGO_IGNORE_TEST_FAILURES_FROM_FOREIGN_CALLS")
             .is_ok_and(|var| &var == "true");
@@ -157,16 +255,33 @@ where
     // Run the backend to ensure the PWG evaluates functions like std::hash::pedersen,
     // otherwise constraints involving these expressions will not error.
     // Use a base layer that doesn't handle anything, which we handle in the `execute` below.
-    let foreign_call_executor = build_foreign_call_executor(Box::new(output), layers::Unhandled);
+    let foreign_call_executor = build_foreign_call_executor(output, layers::Unhandled);
     let foreign_call_executor = TestForeignCallExecutor::new(foreign_call_executor);
     let mut foreign_call_executor = LoggingForeignCallExecutor::new(foreign_call_executor, writer);
 
-    let circuit_execution = execute_program(
+    let mut mock_call_stacks = MockCallStacks::default();
+    let mut execution_coverage = coverage.as_ref().map(|_| ExecutionCoverage::default());
+    let profiling_active = false;
+    let circuit_execution = execute_program_inner(
         &compiled_program.program,
-        WitnessMap::new(),
+        initial_witness,
         blackbox_solver,
         &mut foreign_call_executor,
-    );
+        profiling_active,
+        Some(&mut mock_call_stacks),
+        execution_coverage.as_mut(),
+    )
+    .map(|(witness_stack, _)| witness_stack);
+
+    if let (Some((coverage, file_manager)), Some(execution_coverage)) =
+        (coverage, execution_coverage)
+    {
+        coverage.merge(SourceCoverage::from_execution(
+            &execution_coverage,
+            &compiled_program.debug,
+            file_manager,
+        ));
+    }
 
     let status = test_status_program_compile_pass(
         test_function,
@@ -175,7 +290,19 @@ where
         &circuit_execution,
     );
 
-    let foreign_call_executor = foreign_call_executor.executor;
+    let mut foreign_call_executor = foreign_call_executor.executor;
+
+    // Only check the expectations of mocks once the test ran to completion.
+    if matches!(status, TestStatus::Pass) && circuit_execution.is_ok() {
+        let unsatisfied = foreign_call_executor.verify_mocks::<FieldElement>();
+        if !unsatisfied.is_empty() {
+            return test_status_unsatisfied_mocks(
+                &unsatisfied,
+                &mock_call_stacks,
+                &compiled_program.debug,
+            );
+        }
+    }
 
     if let TestStatus::Fail { .. } = status {
         if ignore_foreign_call_failures && foreign_call_executor.encountered_unknown_foreign_call {
@@ -188,6 +315,160 @@ where
     }
 }
 
+/// A test ran to completion but some of the expectations set on its oracle mocks were not met.
+///
+/// The error diagnostic points at where the first of these mocks was created.
+fn test_status_unsatisfied_mocks(
+    unsatisfied: &[UnsatisfiedMockExpectation],
+    mock_call_stacks: &MockCallStacks,
+    debug: &[DebugInfo],
+) -> TestStatus {
+    let message = unsatisfied
+        .iter()
+        .map(|expectation| format!("error: {expectation}"))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let error_diagnostic = unsatisfied.iter().find_map(|expectation| {
+        let (call_stack, brillig_function_id) = mock_call_stacks.get(&expectation.mock_id)?;
+        let call_stack = resolve_call_stack(call_stack.clone(), *brillig_function_id, debug);
+        let location = *call_stack.last()?;
+        let diagnostic = CustomDiagnostic::simple_error(
+            expectation.message.clone(),
+            "Mock created here".to_string(),
+            location,
+        );
+        Some(diagnostic.with_call_stack(call_stack))
+    });
+
+    TestStatus::Fail { message, error_diagnostic }
+}
+
+/// Runs a test function with inputs generated from the types of its arguments.
+/// This assumes the function has arguments.
+///
+/// A failing input is shrunk down to a minimal one, which is reported together with the seed
+/// the inputs were generated from. Only the run on that input writes to `output`, as the output
+/// of every generated input would drown it out.
+pub fn property_test<'a, W, B, F, E>(
+    blackbox_solver: &B,
+    context: &mut Context,
+    test_function: &TestFunction,
+    output: W,
+    config: &CompileOptions,
+    property_config: PropertyTestConfig,
+    build_foreign_call_executor: F,
+) -> TestStatus
+where
+    W: std::io::Write + 'a,
+    B: BlackBoxFunctionSolver<FieldElement>,
+    F: Fn(Box<dyn std::io::Write + 'a>, layers::Unhandled) -> E,
+    E: ForeignCallExecutor<FieldElement>,
+{
+    match compile_no_check(context, config, test_function.id, None, false) {
+        Ok(compiled_program) => property_test_impl(
+            blackbox_solver,
+            compiled_program,
+            test_function,
+            output,
+            config,
+            property_config,
+            build_foreign_call_executor,
+        ),
+        Err(err) => test_status_program_compile_fail(err, test_function),
+    }
+}
+
+fn property_test_impl<'a, W, B, F, E>(
+    blackbox_solver: &B,
+    compiled_program: CompiledProgram,
+    test_function: &TestFunction,
+    output: W,
+    config: &CompileOptions,
+    property_config: PropertyTestConfig,
+    build_foreign_call_executor: F,
+) -> TestStatus
+where
+    W: std::io::Write + 'a,
+    B: BlackBoxFunctionSolver<FieldElement>,
+    F: Fn(Box<dyn std::io::Write + 'a>, layers::Unhandled) -> E,
+    E: ForeignCallExecutor<FieldElement>,
+{
+    // Do the same optimizations as `compile_cmd`.
+    let target_width = config.expression_width.unwrap_or(DEFAULT_EXPRESSION_WIDTH);
+    let compiled_program = crate::ops::transform_program(compiled_program, target_width);
+    let abi = &compiled_program.abi;
+
+    let run_case = |input_map: &InputMap, output: Box<dyn std::io::Write + 'a>| {
+        let initial_witness = match abi.encode(input_map, None) {
+            Ok(initial_witness) => initial_witness,
+            Err(err) => {
+                let message = format!("Failed to encode generated input: {err}");
+                return TestStatus::Fail { message, error_diagnostic: None };
+            }
+        };
+        execute_test_program(
+            blackbox_solver,
+            &compiled_program,
+            test_function,
+            initial_witness,
+            output,
+            &build_foreign_call_executor,
+            None,
+        )
+    };
+
+    let seed = property_config.seed;
+    let mut runner = property_test_runner(&property_config);
+
+    // The runner keeps shrinking a failing input for as long as the test keeps failing.
+    let result = runner.run(&arb_input_map(abi), |input_map| {
+        if run_case(&input_map, Box::new(std::io::empty())).failed() {
+            Err(TestCaseError::fail("test failed"))
+        } else {
+            Ok(())
+        }
+    });
+
+    match result {
+        Ok(()) => TestStatus::Pass,
+        Err(TestError::Abort(reason)) => {
+            let message = format!("Property test aborted: {reason}\nSeed: {seed}");
+            TestStatus::Fail { message, error_diagnostic: None }
+        }
+        Err(TestError::Fail(_, input_map)) => {
+            let input =
+                serialize_to_json(&input_map, abi).expect("Couldn't serialize input to JSON");
+            let (message, error_diagnostic) = match run_case(&input_map, Box::new(output)) {
+                TestStatus::Fail { message, error_diagnostic } => (message, error_diagnostic),
+                TestStatus::CompileError(diagnostic) => {
+                    (diagnostic.message.clone(), Some(diagnostic))
+                }
+                TestStatus::Pass | TestStatus::Skipped => {
+                    ("Test failed, but passed when run again with the same input".to_string(), None)
+                }
+            };
+            let message = format!("{message}\nMinimal failing input: {input}\nSeed: {seed}");
+            TestStatus::Fail { message, error_diagnostic }
+        }
+    }
+}
+
+/// A runner generating the inputs of a property test from its seed, so that the same seed
+/// always generates, and shrinks to, the same inputs.
+fn property_test_runner(property_config: &PropertyTestConfig) -> TestRunner {
+    let mut rng_seed = [0; 32];
+    rng_seed[..8].copy_from_slice(&property_config.seed.to_le_bytes());
+    TestRunner::new_with_rng(
+        ProptestConfig {
+            cases: property_config.cases,
+            failure_persistence: None,
+            ..ProptestConfig::default()
+        },
+        TestRng::from_seed(RngAlgorithm::ChaCha, &rng_seed),
+    )
+}
+
 /// Runs the fuzzer on a test function. This assumes the function has arguments.
 pub fn fuzz_test<'a, B, F, E>(
     context: &mut Context,
@@ -420,6 +701,23 @@ impl<E> TestForeignCallExecutor<E> {
     pub(crate) fn new(executor: E) -> Self {
         Self { executor, encountered_unknown_foreign_call: false }
     }
+
+    /// Ask the mocks of the inner executor to check their expectations, returning the ones which were not met.
+    ///
+    /// Executors without mocks don't handle the request, in which case there is nothing to verify.
+    fn verify_mocks<F: AcirField>(&mut self) -> Vec<UnsatisfiedMockExpectation>
+    where
+        E: ForeignCallExecutor<F>,
+    {
+        let foreign_call = ForeignCallWaitInfo {
+            function: ForeignCall::VerifyMocks.name().to_string(),
+            inputs: vec![],
+        };
+        match self.executor.execute(&foreign_call) {
+            Err(ForeignCallError::UnsatisfiedMockExpectations(unsatisfied)) => unsatisfied,
+            _ => Vec::new(),
+        }
+    }
 }
 
 impl<E, F> ForeignCallExecutor<F> for TestForeignCallExecutor<E>
@@ -444,3 +742,66 @@ where
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, collections::BTreeMap};
+
+    use acvm::{AcirField, FieldElement};
+    use noirc_abi::{
+        Abi, AbiParameter, AbiType, AbiVisibility, InputMap, Sign, arbitrary::arb_input_map,
+        input_parser::InputValue,
+    };
+    use proptest::test_runner::{TestCaseError, TestError};
+
+    use super::{PropertyTestConfig, property_test_runner};
+
+    fn abi() -> Abi {
+        let parameter = AbiParameter {
+            name: "x".to_string(),
+            typ: AbiType::Integer { sign: Sign::Unsigned, width: 32 },
+            visibility: AbiVisibility::Private,
+        };
+        Abi { parameters: vec![parameter], return_type: None, error_types: BTreeMap::new() }
+    }
+
+    fn x(input_map: &InputMap) -> FieldElement {
+        let InputValue::Field(x) = input_map["x"] else { panic!("Expected a field") };
+        x
+    }
+
+    #[test]
+    fn shrinks_a_failing_input_to_a_minimal_one() {
+        let mut runner = property_test_runner(&PropertyTestConfig { cases: 256, seed: 0 });
+        let result = runner.run(&arb_input_map(&abi()), |input_map| {
+            if x(&input_map).to_u128() >= 1000 {
+                Err(TestCaseError::fail("x is too large"))
+            } else {
+                Ok(())
+            }
+        });
+
+        let Err(TestError::Fail(_, input_map)) = result else {
+            panic!("Expected the property to fail");
+        };
+        assert_eq!(x(&input_map), FieldElement::from(1000_u128));
+    }
+
+    #[test]
+    fn the_same_seed_generates_the_same_inputs() {
+        let generate = |seed| {
+            let inputs = RefCell::new(Vec::new());
+            let mut runner = property_test_runner(&PropertyTestConfig { cases: 16, seed });
+            runner
+                .run(&arb_input_map(&abi()), |input_map| {
+                    inputs.borrow_mut().push(x(&input_map));
+                    Ok(())
+                })
+                .unwrap();
+            inputs.into_inner()
+        };
+
+        assert_eq!(generate(42), generate(42));
+        assert_ne!(generate(42), generate(43));
+    }
+}
@@ -1,4 +1,8 @@
-use std::{collections::BTreeMap, fmt::Display, path::PathBuf};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Display,
+    path::PathBuf,
+};
 
 use acvm::acir::circuit::ExpressionWidth;
 pub use noirc_driver::CrateName;
@@ -52,6 +56,11 @@ pub struct Package {
     pub entry_path: PathBuf,
     pub name: CrateName,
     pub dependencies: BTreeMap<CrateName, Dependency>,
+    /// Dependencies which are only wired into the crate graph when testing or fuzzing the package.
+    /// These are only resolved for workspace members, so they're always empty for dependencies.
+    pub dev_dependencies: BTreeMap<CrateName, Dependency>,
+    /// The features enabled for this package, which make its `#[cfg(feature = "...")]` items active.
+    pub features: BTreeSet<String>,
     pub expression_width: Option<ExpressionWidth>,
 }
 
@@ -19,7 +19,8 @@ use nargo::{
     workspace::Workspace,
 };
 use nargo_toml::{
-    ManifestError, PackageSelection, get_package_manifest, resolve_workspace_from_toml,
+    ManifestError, PackageSelection, ResolveOptions, get_package_manifest,
+    resolve_workspace_from_toml,
 };
 use noirc_driver::{
     CompilationResult, CompileOptions, CrateName, NOIR_ARTIFACT_VERSION_STRING, check_crate,
@@ -86,6 +87,7 @@ fn read_workspace(
         &toml_path,
         selection,
         Some(NOIR_ARTIFACT_VERSION_STRING.to_owned()),
+        &ResolveOptions::default(),
     )?;
 
     Ok(workspace)
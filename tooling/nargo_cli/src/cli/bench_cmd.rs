@@ -0,0 +1,313 @@
+use std::path::{Path, PathBuf};
+
+use bn254_blackbox_solver::Bn254BlackBoxSolver;
+use clap::Args;
+use fm::FileManager;
+use nargo::{
+    foreign_calls::DefaultForeignCallBuilder,
+    insert_all_files_for_workspace_into_file_manager,
+    ops::{
+        BenchBaseline, BenchStatus, check_crate_and_report_errors, percentage_change, run_bench,
+    },
+    package::Package,
+    parse_all, prepare_package_for_tests,
+    workspace::Workspace,
+};
+use nargo_toml::PackageSelection;
+use noirc_driver::CompileOptions;
+use noirc_frontend::hir::{FunctionNameMatch, ParsedFiles};
+use prettytable::{Table, row};
+
+use crate::errors::CliError;
+
+use super::{LockType, PackageOptions, WorkspaceCommand};
+
+/// The file results are saved to and compared against in the target directory,
+/// if no `--baseline` is given.
+const DEFAULT_BASELINE_FILE: &str = "bench_baseline.json";
+
+/// Run the benchmarks for this program
+#[derive(Debug, Clone, Args)]
+pub(crate) struct BenchCommand {
+    /// If given, only benches with names containing this string will be run
+    bench_names: Vec<String>,
+
+    /// Only run benches that match exactly
+    #[clap(long)]
+    exact: bool,
+
+    #[clap(flatten)]
+    pub(super) package_options: PackageOptions,
+
+    #[clap(flatten)]
+    compile_options: CompileOptions,
+
+    /// JSON RPC url to solve oracle calls
+    #[clap(long)]
+    oracle_resolver: Option<String>,
+
+    /// Number of times each unconstrained bench is executed to measure its average time
+    #[arg(long, default_value_t = 10)]
+    iterations: usize,
+
+    /// File to compare the results against (defaults to `target/bench_baseline.json`)
+    #[arg(long)]
+    baseline: Option<PathBuf>,
+
+    /// Save the results to the baseline file instead of comparing them against it
+    #[arg(long)]
+    save_baseline: bool,
+
+    /// Fail if an opcode count of a bench got worse than its baseline by more than this percentage
+    #[arg(long, default_value_t = 5.0)]
+    threshold: f64,
+
+    /// Fail if the time of a bench got worse than its baseline by more than this percentage
+    #[arg(long, default_value_t = 25.0)]
+    time_threshold: f64,
+}
+
+impl WorkspaceCommand for BenchCommand {
+    fn package_selection(&self) -> PackageSelection {
+        self.package_options.package_selection()
+    }
+
+    fn lock_type(&self) -> LockType {
+        // Reads the code to compile benches in memory, but doesn't save artifacts.
+        LockType::None
+    }
+}
+
+pub(crate) fn run(args: BenchCommand, workspace: Workspace) -> Result<(), CliError> {
+    let mut file_manager = workspace.new_file_manager();
+    insert_all_files_for_workspace_into_file_manager(&workspace, &mut file_manager);
+    let parsed_files = parse_all(&file_manager);
+
+    let pattern = if args.bench_names.is_empty() {
+        FunctionNameMatch::Anything
+    } else if args.exact {
+        FunctionNameMatch::Exact(args.bench_names.clone())
+    } else {
+        FunctionNameMatch::Contains(args.bench_names.clone())
+    };
+
+    // Benches are run one at a time so they don't slow each other down.
+    let mut results = BenchBaseline::new();
+    let mut bench_count = 0;
+    for package in &workspace {
+        let statuses = run_package_benches(
+            &args,
+            &workspace,
+            &file_manager,
+            &parsed_files,
+            package,
+            &pattern,
+        )?;
+        bench_count += statuses.len();
+
+        for (bench_name, status) in statuses {
+            match status {
+                BenchStatus::Measured(measurement) => {
+                    results
+                        .entry(package.name.to_string())
+                        .or_default()
+                        .insert(bench_name, measurement);
+                }
+                BenchStatus::Fail { message, error_diagnostic } => {
+                    eprintln!("[{}] Bench {bench_name} failed: {message}", package.name);
+                    if let Some(diagnostic) = error_diagnostic {
+                        report_diagnostic(&file_manager, diagnostic, &args.compile_options);
+                    }
+                }
+                BenchStatus::CompileError(diagnostic) => {
+                    eprintln!("[{}] Bench {bench_name} failed to compile", package.name);
+                    report_diagnostic(&file_manager, diagnostic, &args.compile_options);
+                }
+            }
+        }
+    }
+
+    if bench_count == 0 {
+        return match &pattern {
+            FunctionNameMatch::Exact(patterns) | FunctionNameMatch::Contains(patterns) => {
+                Err(CliError::Generic(format!("Found 0 benches matching {}.", patterns.join(", "))))
+            }
+            // If we are running all benches in a workspace, having none is not an error
+            FunctionNameMatch::Anything => {
+                println!("Found no benches in this workspace.");
+                Ok(())
+            }
+        };
+    }
+    let failed_count = bench_count - results.values().map(|benches| benches.len()).sum::<usize>();
+
+    let baseline_path = args
+        .baseline
+        .clone()
+        .unwrap_or_else(|| workspace.target_directory_path().join(DEFAULT_BASELINE_FILE));
+
+    let regression_count = if args.save_baseline {
+        show_results(&results, None, args.threshold, args.time_threshold);
+        save_baseline(&baseline_path, &results)?;
+        println!("Saved baseline to {}", baseline_path.display());
+        0
+    } else {
+        let baseline = read_baseline(&baseline_path)?;
+        show_results(&results, baseline.as_ref(), args.threshold, args.time_threshold)
+    };
+
+    if failed_count > 0 {
+        let plural = if failed_count == 1 { "" } else { "es" };
+        return Err(CliError::Generic(format!("{failed_count} bench{plural} failed")));
+    }
+    if regression_count > 0 {
+        let plural = if regression_count == 1 { "" } else { "es" };
+        return Err(CliError::Generic(format!(
+            "{regression_count} bench{plural} got worse than the baseline by more than {}% \
+            ({}% for times)",
+            args.threshold, args.time_threshold
+        )));
+    }
+    Ok(())
+}
+
+/// Compiles a package and runs each of its benches that match the pattern.
+fn run_package_benches(
+    args: &BenchCommand,
+    workspace: &Workspace,
+    file_manager: &FileManager,
+    parsed_files: &ParsedFiles,
+    package: &Package,
+    pattern: &FunctionNameMatch,
+) -> Result<Vec<(String, BenchStatus)>, CliError> {
+    let (mut context, crate_id) = prepare_package_for_tests(file_manager, parsed_files, package);
+    check_crate_and_report_errors(&mut context, crate_id, &args.compile_options)?;
+
+    let blackbox_solver = Bn254BlackBoxSolver(args.compile_options.pedantic_solving);
+    let bench_functions = context.get_all_bench_functions_in_crate_matching(&crate_id, pattern);
+
+    Ok(bench_functions
+        .into_iter()
+        .map(|(bench_name, bench_function)| {
+            let status = run_bench(
+                &blackbox_solver,
+                &mut context,
+                &bench_function,
+                &args.compile_options,
+                args.iterations,
+                || {
+                    DefaultForeignCallBuilder {
+                        output: std::io::empty(),
+                        enable_mocks: true,
+                        resolver_url: args.oracle_resolver.clone(),
+                        root_path: Some(workspace.root_dir.clone()),
+                        package_name: Some(package.name.to_string()),
+                    }
+                    .build()
+                },
+            );
+            (bench_name, status)
+        })
+        .collect())
+}
+
+/// Prints a table of the results, comparing them against the baseline if there's one,
+/// and returns how many benches got worse than the baseline by more than `threshold` percent,
+/// or `time_threshold` percent for times.
+fn show_results(
+    results: &BenchBaseline,
+    baseline: Option<&BenchBaseline>,
+    threshold: f64,
+    time_threshold: f64,
+) -> usize {
+    let mut table = Table::new();
+    table.set_titles(
+        row![Fm->"Package", Fm->"Bench", Fm->"Metric", Fm->"Value", Fm->"Baseline", Fm->"Change"],
+    );
+
+    let mut regression_count = 0;
+    for (package_name, benches) in results {
+        for (bench_name, measurement) in benches {
+            let baseline_measurement =
+                baseline.and_then(|baseline| baseline.get(package_name)?.get(bench_name));
+            let regressions = baseline_measurement
+                .map(|baseline| measurement.regressions(baseline, threshold, time_threshold))
+                .unwrap_or_default();
+            if !regressions.is_empty() {
+                regression_count += 1;
+            }
+
+            let baseline_metrics = baseline_measurement.map(|baseline| baseline.metrics());
+            for (index, (metric, value)) in measurement.metrics().into_iter().enumerate() {
+                let baseline_value = baseline_metrics
+                    .as_ref()
+                    .and_then(|metrics| metrics.get(index))
+                    .filter(|(baseline_metric, _)| *baseline_metric == metric)
+                    .map(|(_, baseline_value)| *baseline_value);
+                let (baseline_value, change) = match baseline_value {
+                    Some(baseline_value) => (
+                        format_value(baseline_value),
+                        percentage_change(baseline_value, value)
+                            .map(|change| format!("{change:+.2}%"))
+                            .unwrap_or_else(|| "-".to_string()),
+                    ),
+                    None => ("-".to_string(), "-".to_string()),
+                };
+
+                if regressions.iter().any(|regression| regression.metric == metric) {
+                    table.add_row(row![package_name, bench_name, metric, format_value(value), baseline_value, Fr->change]);
+                } else {
+                    table.add_row(row![
+                        package_name,
+                        bench_name,
+                        metric,
+                        format_value(value),
+                        baseline_value,
+                        change
+                    ]);
+                }
+            }
+        }
+    }
+    table.printstd();
+
+    regression_count
+}
+
+fn format_value(value: f64) -> String {
+    if value.fract() == 0.0 { format!("{value}") } else { format!("{value:.6}") }
+}
+
+fn read_baseline(path: &Path) -> Result<Option<BenchBaseline>, CliError> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let baseline = std::fs::read_to_string(path)
+        .map_err(|err| err.to_string())
+        .and_then(|contents| serde_json::from_str(&contents).map_err(|err| err.to_string()))
+        .map_err(|err| {
+            CliError::Generic(format!("Failed to read bench baseline {}: {err}", path.display()))
+        })?;
+    Ok(Some(baseline))
+}
+
+fn save_baseline(path: &Path, results: &BenchBaseline) -> Result<(), CliError> {
+    let contents = serde_json::to_string_pretty(results).expect("Failed to serialize results");
+    let dir = path.parent().unwrap_or(Path::new("."));
+    std::fs::create_dir_all(dir).and_then(|_| std::fs::write(path, contents)).map_err(|err| {
+        CliError::Generic(format!("Failed to save bench baseline {}: {err}", path.display()))
+    })
+}
+
+fn report_diagnostic(
+    file_manager: &FileManager,
+    diagnostic: noirc_errors::CustomDiagnostic,
+    compile_options: &CompileOptions,
+) {
+    noirc_errors::reporter::report_all(
+        file_manager.as_file_map(),
+        &[diagnostic],
+        compile_options.deny_warnings,
+        compile_options.silence_warnings,
+    );
+}
@@ -15,7 +15,9 @@ use nargo::ops::debug::{
 use nargo::ops::{TestStatus, check_crate_and_report_errors, test_status_program_compile_pass};
 use nargo::package::Package;
 use nargo::workspace::Workspace;
-use nargo_toml::{PackageSelection, get_package_manifest, resolve_workspace_from_toml};
+use nargo_toml::{
+    PackageSelection, ResolveOptions, get_package_manifest, resolve_workspace_from_toml,
+};
 use noir_artifact_cli::fs::inputs::read_inputs_from_file;
 use noir_debugger::{DebugExecutionResult, DebugProject, RunParams};
 use noirc_abi::Abi;
@@ -89,6 +91,7 @@ fn find_workspace(project_folder: &str, package: Option<&str>) -> Option<Workspa
         &toml_path,
         selection,
         Some(NOIR_ARTIFACT_VERSION_STRING.to_string()),
+        &ResolveOptions::default(),
     ) {
         Ok(workspace) => Some(workspace),
         Err(err) => {
@@ -126,7 +129,7 @@ fn compile_test(
     let (file_manager, mut parsed_files) = load_workspace_files(workspace);
 
     let (mut context, crate_id) =
-        prepare_package_for_debug(&file_manager, &mut parsed_files, package, workspace);
+        prepare_package_for_debug(&file_manager, &mut parsed_files, package, workspace, true);
 
     check_crate_and_report_errors(&mut context, crate_id, &compile_options)
         .map_err(|_| LoadError::Generic("Failed to compile project".into()))?;
@@ -198,6 +201,11 @@ fn loop_uninitialized_dap<R: Read, W: Write>(
                     supports_disassemble_request: Some(true),
                     supports_instruction_breakpoints: Some(true),
                     supports_stepping_granularity: Some(true),
+                    supports_step_back: Some(true),
+                    supports_conditional_breakpoints: Some(true),
+                    supports_hit_conditional_breakpoints: Some(true),
+                    supports_log_points: Some(true),
+                    supports_data_breakpoints: Some(true),
                     ..Default::default()
                 }));
                 server.respond(rsp)?;
@@ -264,6 +272,7 @@ fn loop_uninitialized_dap<R: Read, W: Write>(
                                 oracle_resolver_url,
                                 pedantic_solving,
                                 raw_source_printing: None,
+                                oracle_transcript: None,
                             },
                         )?;
 
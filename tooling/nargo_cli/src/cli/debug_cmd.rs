@@ -1,4 +1,4 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 
 use acvm::FieldElement;
@@ -6,6 +6,7 @@ use acvm::acir::native_types::{WitnessMap, WitnessStack};
 use clap::Args;
 use fm::FileManager;
 use nargo::constants::PROVER_INPUT_FILE;
+use nargo::foreign_calls::transcript::ReplayForeignCallExecutor;
 use nargo::ops::debug::{
     TestDefinition, compile_bin_package_for_debugging, compile_options_for_debugging,
     compile_test_fn_for_debugging, get_test_function_for_debug, load_workspace_files,
@@ -20,8 +21,8 @@ use nargo::workspace::Workspace;
 use nargo_toml::PackageSelection;
 use noir_artifact_cli::fs::inputs::read_inputs_from_file;
 use noir_artifact_cli::fs::witness::save_witness_to_dir;
-use noir_debugger::{DebugExecutionResult, DebugProject, RunParams};
-use noirc_abi::Abi;
+use noir_debugger::{DebugExecutionResult, DebugProject, FailureState, RunParams};
+use noirc_abi::{Abi, InputMap};
 use noirc_driver::{CompileOptions, CompiledProgram};
 use noirc_frontend::hir::Context;
 
@@ -67,6 +68,11 @@ pub(crate) struct DebugCommand {
     /// JSON RPC url to solve oracle calls
     #[clap(long)]
     oracle_resolver: Option<String>,
+
+    /// Reproduce a failure saved with the `save-state` debugger command, and start debugging
+    /// where execution failed
+    #[clap(long)]
+    load_state: Option<PathBuf>,
 }
 
 // TODO: find a better name
@@ -74,6 +80,7 @@ struct PackageParams<'a> {
     prover_name: String,
     witness_name: Option<String>,
     target_dir: &'a Path,
+    failure_state: Option<FailureState>,
 }
 
 impl WorkspaceCommand for DebugCommand {
@@ -95,15 +102,18 @@ pub(crate) fn run(args: DebugCommand, workspace: Workspace) -> Result<(), CliErr
     let acir_mode = args.acir_mode;
     let skip_instrumentation = args.skip_instrumentation.unwrap_or(acir_mode);
 
+    let failure_state = args.load_state.as_deref().map(FailureState::load).transpose()?;
     let package_params = PackageParams {
         prover_name: args.prover_name,
         witness_name: args.witness_name,
         target_dir: &workspace.target_directory_path(),
+        failure_state,
     };
     let run_params = RunParams {
         pedantic_solving: args.compile_options.pedantic_solving,
         raw_source_printing: args.raw_source_printing,
         oracle_resolver_url: args.oracle_resolver,
+        oracle_transcript: None,
     };
     let workspace_clone = workspace.clone();
 
@@ -175,6 +185,7 @@ fn debug_test_fn(
     TestResult::new(
         test.name.clone(),
         package.name.to_string(),
+        test.function.scope.clone(),
         test_status,
         String::new(),
         Duration::from_secs(1), // FIXME: hardcoded value
@@ -207,7 +218,7 @@ fn debug_test(
     let (file_manager, mut parsed_files) = load_workspace_files(&workspace);
 
     let (mut context, crate_id) =
-        prepare_package_for_debug(&file_manager, &mut parsed_files, package, &workspace);
+        prepare_package_for_debug(&file_manager, &mut parsed_files, package, &workspace, true);
 
     check_crate_and_report_errors(&mut context, crate_id, &compile_options)?;
 
@@ -235,13 +246,54 @@ fn run_async(
     run_params: RunParams,
     package_params: PackageParams,
 ) -> Result<DebugExecutionResult, CliError> {
+    let abi = &program.abi.clone();
+    let result = match package_params.failure_state {
+        Some(failure_state) => {
+            // The saved oracle results and witnesses only make sense for the program that failed.
+            failure_state.check_program_hash(program.hash)?;
+            let initial_witness = failure_state.initial_witness.clone();
+            run_post_mortem_debugger(
+                package,
+                program,
+                initial_witness,
+                workspace,
+                run_params,
+                Some(failure_state),
+            )
+        }
+        None => {
+            let initial_witness = parse_initial_witness(package, &package_params.prover_name, abi)?;
+            run_debugger(package, program, initial_witness, workspace, run_params)
+        }
+    };
+
+    if let DebugExecutionResult::Solved(ref witness_stack) = result {
+        println!("[{}] Circuit witness successfully solved", package.name);
+        decode_and_save_program_witness(
+            &package.name,
+            witness_stack,
+            abi,
+            package_params.witness_name,
+            package_params.target_dir,
+        )?;
+    }
+
+    Ok(result)
+}
+
+/// Run a program from the given initial witness in a debugger REPL session.
+pub(super) fn run_debugger(
+    package: &Package,
+    program: CompiledProgram,
+    initial_witness: WitnessMap<FieldElement>,
+    workspace: &Workspace,
+    run_params: RunParams,
+) -> DebugExecutionResult {
     use tokio::runtime::Builder;
     let runtime = Builder::new_current_thread().enable_all().build().unwrap();
-    let abi = &program.abi.clone();
 
     runtime.block_on(async {
         println!("[{}] Starting debugger", package.name);
-        let initial_witness = parse_initial_witness(package, &package_params.prover_name, abi)?;
 
         let project = DebugProject {
             compiled_program: program,
@@ -249,20 +301,107 @@ fn run_async(
             root_dir: workspace.root_dir.clone(),
             package_name: package.name.to_string(),
         };
-        let result = noir_debugger::run_repl_session(project, run_params);
-
-        if let DebugExecutionResult::Solved(ref witness_stack) = result {
-            println!("[{}] Circuit witness successfully solved", package.name);
-            decode_and_save_program_witness(
-                &package.name,
-                witness_stack,
-                abi,
-                package_params.witness_name,
-                package_params.target_dir,
-            )?;
-        }
+        noir_debugger::run_repl_session(project, run_params)
+    })
+}
+
+/// Run a program from the given initial witness up to the point where it fails, and start a
+/// debugger REPL session there.
+pub(super) fn run_post_mortem_debugger(
+    package: &Package,
+    program: CompiledProgram,
+    initial_witness: WitnessMap<FieldElement>,
+    workspace: &Workspace,
+    run_params: RunParams,
+    failure_state: Option<FailureState>,
+) -> DebugExecutionResult {
+    use tokio::runtime::Builder;
+    let runtime = Builder::new_current_thread().enable_all().build().unwrap();
+
+    runtime.block_on(async {
+        println!("[{}] Starting debugger at the failure", package.name);
+
+        let project = DebugProject {
+            compiled_program: program,
+            initial_witness,
+            root_dir: workspace.root_dir.clone(),
+            package_name: package.name.to_string(),
+        };
+        noir_debugger::run_post_mortem_repl_session(project, run_params, failure_state)
+    })
+}
+
+/// Compile a binary package for debugging, and start debugging it where executing it with the
+/// inputs of the given prover file fails, answering oracle calls from the transcript recorded
+/// when it failed, if any.
+pub(super) fn debug_package_failure(
+    workspace: &Workspace,
+    package: &Package,
+    prover_name: &str,
+    compile_options: CompileOptions,
+    oracle_resolver_url: Option<String>,
+    oracle_transcript: Option<&Path>,
+) -> Result<DebugExecutionResult, CliError> {
+    let run_params = failure_run_params(&compile_options, oracle_resolver_url, oracle_transcript)?;
+    let compile_options = compile_options_for_debugging(false, false, None, compile_options);
+    let program = compile_bin_package_for_debugging(workspace, package, &compile_options)?;
+    let initial_witness = parse_initial_witness(package, prover_name, &program.abi)?;
+
+    Ok(run_post_mortem_debugger(package, program, initial_witness, workspace, run_params, None))
+}
+
+/// Compile a test function for debugging, and start debugging it where it fails, answering
+/// oracle calls from the transcript recorded when it failed, if any.
+pub(super) fn debug_test_failure(
+    workspace: &Workspace,
+    package: &Package,
+    test_name: &str,
+    compile_options: CompileOptions,
+    oracle_resolver_url: Option<String>,
+    oracle_transcript: Option<&Path>,
+) -> Result<DebugExecutionResult, CliError> {
+    let run_params = failure_run_params(&compile_options, oracle_resolver_url, oracle_transcript)?;
+    let compile_options = compile_options_for_debugging(false, false, None, compile_options);
+    let (file_manager, mut parsed_files) = load_workspace_files(workspace);
+    let (mut context, crate_id) =
+        prepare_package_for_debug(&file_manager, &mut parsed_files, package, workspace, true);
+    check_crate_and_report_errors(&mut context, crate_id, &compile_options)?;
+
+    let test =
+        get_test_function_for_debug(crate_id, &context, test_name).map_err(CliError::Generic)?;
+    let program =
+        compile_test_fn_for_debugging(&test, &mut context, package, compile_options.clone())
+            .map_err(|error| {
+                noirc_errors::reporter::report_all(
+                    file_manager.as_file_map(),
+                    &[error.into()],
+                    compile_options.deny_warnings,
+                    compile_options.silence_warnings,
+                );
+                CliError::Generic(format!("Failed to compile {test_name}"))
+            })?;
+    // Tests don't take arguments
+    let initial_witness = program.abi.encode(&InputMap::new(), None)?;
+
+    Ok(run_post_mortem_debugger(package, program, initial_witness, workspace, run_params, None))
+}
 
-        Ok(result)
+/// The parameters to reproduce a failure with: calls that the transcript recorded when the
+/// failure happened are replayed instead of calling the resolver again.
+fn failure_run_params(
+    compile_options: &CompileOptions,
+    oracle_resolver_url: Option<String>,
+    oracle_transcript: Option<&Path>,
+) -> Result<RunParams, CliError> {
+    let oracle_transcript = oracle_transcript
+        .map(ReplayForeignCallExecutor::from_file)
+        .transpose()
+        .map_err(|error| CliError::Generic(format!("Failed to read oracle transcript: {error}")))?;
+    Ok(RunParams {
+        pedantic_solving: compile_options.pedantic_solving,
+        raw_source_printing: None,
+        oracle_resolver_url: if oracle_transcript.is_some() { None } else { oracle_resolver_url },
+        oracle_transcript,
     })
 }
 
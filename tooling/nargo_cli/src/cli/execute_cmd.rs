@@ -5,9 +5,11 @@ use clap::Args;
 use nargo::constants::PROVER_INPUT_FILE;
 use nargo::workspace::Workspace;
 use nargo_toml::PackageSelection;
+use noir_artifact_cli::errors::CliError as ArtifactCliError;
 use noirc_driver::CompileOptions;
 
 use super::compile_cmd::compile_workspace_full;
+use super::debug_cmd::debug_package_failure;
 use super::{LockType, PackageOptions, WorkspaceCommand};
 use crate::errors::CliError;
 
@@ -30,13 +32,26 @@ pub(crate) struct ExecuteCommand {
     #[clap(flatten)]
     compile_options: CompileOptions,
 
-    /// JSON RPC url to solve oracle calls
-    #[clap(long, conflicts_with = "oracle_file")]
+    /// JSON RPC url to solve oracle calls (`http://`, `stdio:<command>` or `unix:<socket path>`)
+    #[clap(long, conflicts_with = "replay_oracles")]
     oracle_resolver: Option<String>,
 
-    /// Path to the oracle transcript.
-    #[clap(long, conflicts_with = "oracle_resolver")]
-    oracle_file: Option<PathBuf>,
+    /// Record the foreign calls resolved during execution into an oracle transcript.
+    #[clap(long, conflicts_with = "replay_oracles")]
+    record_oracles: Option<PathBuf>,
+
+    /// Path to an oracle transcript to replay instead of calling an oracle resolver.
+    #[clap(long, alias = "oracle-file", conflicts_with = "oracle_resolver")]
+    replay_oracles: Option<PathBuf>,
+
+    /// Match foreign calls to the replayed oracle transcript by name and inputs, regardless of
+    /// their order, instead of requiring them to follow the transcript exactly.
+    #[clap(long, requires = "replay_oracles")]
+    lenient_oracles: bool,
+
+    /// Start the debugger where execution failed, if it fails
+    #[clap(long, conflicts_with = "replay_oracles")]
+    debug_on_failure: bool,
 }
 
 impl WorkspaceCommand for ExecuteCommand {
@@ -60,6 +75,14 @@ pub(crate) fn run(args: ExecuteCommand, workspace: Workspace) -> Result<(), CliE
         let program_artifact_path = workspace.package_build_path(package);
         let prover_file = package.root_dir.join(&args.prover_name).with_extension("toml");
 
+        // Record the oracle calls when debugging on failure, so the debugger can replay the
+        // calls of the failed execution.
+        let record_oracles = args.record_oracles.clone().or_else(|| {
+            args.debug_on_failure.then(|| {
+                workspace.target_directory_path().join(format!("{}-oracles.jsonl", package.name))
+            })
+        });
+
         let cmd = noir_artifact_cli::commands::execute_cmd::ExecuteCommand {
             artifact_path: program_artifact_path,
             prover_file,
@@ -68,14 +91,28 @@ pub(crate) fn run(args: ExecuteCommand, workspace: Workspace) -> Result<(), CliE
                 args.witness_name.clone().unwrap_or_else(|| package.name.to_string()),
             ),
             contract_fn: None,
-            oracle_file: args.oracle_file.clone(),
+            oracle_file: args.replay_oracles.clone(),
+            lenient_oracles: args.lenient_oracles,
+            record_oracles: record_oracles.clone(),
             oracle_resolver: args.oracle_resolver.clone(),
             oracle_root_dir: Some(workspace.root_dir.clone()),
             oracle_package_name: Some(package.name.to_string()),
             pedantic_solving: args.compile_options.pedantic_solving,
         };
 
-        noir_artifact_cli::commands::execute_cmd::run(cmd)?;
+        let result = noir_artifact_cli::commands::execute_cmd::run(cmd);
+        let execution_failed = matches!(result, Err(ArtifactCliError::CircuitExecutionError(_)));
+        if args.debug_on_failure && execution_failed {
+            debug_package_failure(
+                &workspace,
+                package,
+                &args.prover_name,
+                args.compile_options.clone(),
+                args.oracle_resolver.clone(),
+                record_oracles.as_deref(),
+            )?;
+        }
+        result?;
     }
     Ok(())
 }
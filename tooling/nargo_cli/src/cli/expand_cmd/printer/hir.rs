@@ -542,6 +542,16 @@ impl ItemPrinter<'_, '_> {
             HirStatement::Continue => {
                 self.push_str("continue;");
             }
+            HirStatement::Return(expr_id) => {
+                if let HirExpression::Literal(HirLiteral::Unit) = self.interner.expression(&expr_id)
+                {
+                    self.push_str("return;");
+                } else {
+                    self.push_str("return ");
+                    self.show_hir_expression_id(expr_id);
+                    self.push(';');
+                }
+            }
             HirStatement::Expression(expr_id) => {
                 self.show_hir_expression_id(expr_id);
             }
@@ -769,6 +779,7 @@ impl ItemPrinter<'_, '_> {
             }
             HirStatement::Break => false,
             HirStatement::Continue => false,
+            HirStatement::Return(expr_id) => self.expression_id_has_unsafe(*expr_id),
             HirStatement::Expression(expr_id) => self.expression_id_has_unsafe(*expr_id),
             HirStatement::Semi(expr_id) => self.expression_id_has_unsafe(*expr_id),
             HirStatement::Comptime(stmt_id) => self.statement_id_has_unsafe(*stmt_id),
@@ -1,4 +1,7 @@
-use std::{io::Write, path::PathBuf};
+use std::{
+    io::Write,
+    path::{Path, PathBuf},
+};
 
 use acvm::{BlackBoxFunctionSolver, FieldElement};
 use bn254_blackbox_solver::Bn254BlackBoxSolver;
@@ -6,15 +9,24 @@ use clap::Args;
 use fm::FileManager;
 use nargo::{
     FuzzExecutionConfig, FuzzFolderConfig,
+    errors::try_to_diagnose_runtime_error,
     foreign_calls::DefaultForeignCallBuilder,
     insert_all_files_for_workspace_into_file_manager,
-    ops::{FuzzingRunStatus, check_crate_and_report_errors},
+    ops::{
+        FuzzingRunStatus, check_crate_and_report_errors,
+        debug::{
+            compile_fuzzing_harness_for_debugging, compile_options_for_debugging,
+            load_workspace_files, prepare_package_for_debug,
+        },
+        fuzzing_crashes_dir, save_fuzzing_crash,
+    },
     package::{CrateName, Package},
-    parse_all, prepare_package,
+    parse_all, prepare_package_for_tests,
     workspace::Workspace,
 };
 use nargo_toml::PackageSelection;
-use noirc_abi::input_parser::{Format, json::serialize_to_json};
+use noir_debugger::{DebugExecutionResult, RunParams};
+use noirc_abi::input_parser::json::serialize_to_json;
 use noirc_driver::{CompileOptions, check_crate};
 use noirc_frontend::hir::{FunctionNameMatch, ParsedFiles};
 use rayon::prelude::{ParallelBridge, ParallelIterator};
@@ -22,8 +34,9 @@ use termcolor::{Color, ColorChoice, ColorSpec, StandardStream, WriteColor};
 
 use crate::errors::CliError;
 
+use super::debug_cmd::run_debugger;
 use super::{LockType, PackageOptions, WorkspaceCommand};
-use noir_artifact_cli::fs::inputs::write_inputs_to_file;
+use noir_artifact_cli::fs::inputs::read_inputs_from_file;
 
 /// Run the fuzzing harnesses for this program
 #[derive(Debug, Clone, Args)]
@@ -41,8 +54,14 @@ pub(crate) struct FuzzCommand {
     minimized_corpus_dir: Option<String>,
 
     /// If given, store the failing input in the given folder
+    /// (defaults to the `fuzz_crashes` folder of each package)
     #[arg(long)]
     fuzzing_failure_dir: Option<String>,
+
+    /// Rerun a single failing input saved by the fuzzer under the debugger, instead of fuzzing
+    #[arg(long, requires = "fuzzing_harness_name")]
+    replay: Option<PathBuf>,
+
     /// List all available harnesses that match the name
     #[clap(long)]
     list_all: bool,
@@ -131,6 +150,10 @@ fn list_harnesses_command(
 
 /// Run the fuzzing harnesses for this program
 pub(crate) fn run(args: FuzzCommand, workspace: Workspace) -> Result<(), CliError> {
+    if let Some(crash_file) = &args.replay {
+        return replay_in_debugger(&args, crash_file, &workspace);
+    }
+
     let mut file_manager = workspace.new_file_manager();
     insert_all_files_for_workspace_into_file_manager(&workspace, &mut file_manager);
     let parsed_files = parse_all(&file_manager);
@@ -216,6 +239,92 @@ pub(crate) fn run(args: FuzzCommand, workspace: Workspace) -> Result<(), CliErro
     }
 }
 
+/// Rerun a failing input of a fuzzing harness under the debugger
+fn replay_in_debugger(
+    args: &FuzzCommand,
+    crash_file: &Path,
+    workspace: &Workspace,
+) -> Result<(), CliError> {
+    let harness_name =
+        args.fuzzing_harness_name.as_deref().expect("--replay requires a fuzzing harness name");
+    let names = vec![harness_name.to_string()];
+    let pattern = if args.exact {
+        FunctionNameMatch::Exact(names)
+    } else {
+        FunctionNameMatch::Contains(names)
+    };
+
+    let compile_options =
+        compile_options_for_debugging(false, false, None, args.compile_options.clone());
+    let (file_manager, mut parsed_files) = load_workspace_files(workspace);
+
+    for package in workspace {
+        let (mut context, crate_id) =
+            prepare_package_for_debug(&file_manager, &mut parsed_files, package, workspace, true);
+        check_crate_and_report_errors(&mut context, crate_id, &compile_options)?;
+
+        let mut fuzzing_harnesses =
+            context.get_all_fuzzing_harnesses_in_crate_matching(&crate_id, &pattern);
+        if fuzzing_harnesses.is_empty() {
+            continue;
+        }
+        if fuzzing_harnesses.len() > 1 {
+            return Err(CliError::Generic(format!(
+                "`{harness_name}` matches with more than one fuzzing harness"
+            )));
+        }
+        let (fuzzing_harness_name, fuzzing_harness) = fuzzing_harnesses.remove(0);
+
+        let program = compile_fuzzing_harness_for_debugging(
+            &fuzzing_harness,
+            &mut context,
+            package,
+            compile_options.clone(),
+        )
+        .map_err(|err| {
+            noirc_errors::reporter::report_all(
+                file_manager.as_file_map(),
+                &[err.into()],
+                compile_options.deny_warnings,
+                compile_options.silence_warnings,
+            );
+            CliError::Generic(format!("Failed to compile {fuzzing_harness_name}"))
+        })?;
+
+        let (input_map, _) = read_inputs_from_file(crash_file, &program.abi)?;
+        let initial_witness = program.abi.encode(&input_map, None)?;
+        let abi = program.abi.clone();
+        let debug = program.debug.clone();
+
+        let run_params = RunParams {
+            pedantic_solving: args.compile_options.pedantic_solving,
+            raw_source_printing: None,
+            oracle_resolver_url: args.oracle_resolver.clone(),
+            oracle_transcript: None,
+        };
+        return match run_debugger(package, program, initial_witness, workspace, run_params) {
+            DebugExecutionResult::Solved(_) => {
+                println!("[{}] {fuzzing_harness_name} didn't fail on this input", package.name);
+                Ok(())
+            }
+            DebugExecutionResult::Error(error) => {
+                if let Some(diagnostic) = try_to_diagnose_runtime_error(&error, &abi, &debug) {
+                    noirc_errors::reporter::report_all(
+                        file_manager.as_file_map(),
+                        &[diagnostic],
+                        compile_options.deny_warnings,
+                        compile_options.silence_warnings,
+                    );
+                }
+                Err(error.into())
+            }
+            DebugExecutionResult::Incomplete => Ok(()),
+        };
+    }
+
+    Err(CliError::Generic(format!("Found 0 fuzzing harnesses matching '{harness_name}'.")))
+}
+
 fn list_harnesses(
     file_manager: &FileManager,
     parsed_files: &ParsedFiles,
@@ -273,8 +382,7 @@ fn run_fuzzers<S: BlackBoxFunctionSolver<FieldElement> + Default>(
         fuzzing_reports.push((fuzzing_harness_name, status));
         // Display the latest report
         display_fuzzing_report_and_store(
-            root_path.clone(),
-            fuzz_folder_config.fuzzing_failure_dir.clone(),
+            fuzz_folder_config.fuzzing_failure_dir.as_deref(),
             file_manager,
             package,
             compile_options,
@@ -302,7 +410,7 @@ fn run_fuzzing_harness<S: BlackBoxFunctionSolver<FieldElement> + Default>(
     // This is really hacky but we can't share `Context` or `S` across threads.
     // We then need to construct a separate copy for each test.
 
-    let (mut context, crate_id) = prepare_package(file_manager, parsed_files, package);
+    let (mut context, crate_id) = prepare_package_for_tests(file_manager, parsed_files, package);
     check_crate(&mut context, crate_id, compile_options)
         .expect("Any errors should have occurred when collecting fuzzing harnesses");
 
@@ -339,7 +447,7 @@ fn get_fuzzing_harnesses_in_package(
     fn_name: &FunctionNameMatch,
     options: &CompileOptions,
 ) -> Result<Vec<String>, CliError> {
-    let (mut context, crate_id) = prepare_package(file_manager, parsed_files, package);
+    let (mut context, crate_id) = prepare_package_for_tests(file_manager, parsed_files, package);
     check_crate_and_report_errors(&mut context, crate_id, options)?;
 
     Ok(context
@@ -350,8 +458,7 @@ fn get_fuzzing_harnesses_in_package(
 }
 
 fn display_fuzzing_report_and_store(
-    root_path: Option<PathBuf>,
-    fuzzing_failure_folder: Option<String>,
+    fuzzing_failure_folder: Option<&str>,
     file_manager: &FileManager,
     package: &Package,
     compile_options: &CompileOptions,
@@ -359,12 +466,6 @@ fn display_fuzzing_report_and_store(
 ) -> Result<(), CliError> {
     let writer = StandardStream::stderr(ColorChoice::Always);
     let mut writer = writer.lock();
-    let fuzzing_failure_path =
-        fuzzing_failure_folder.map(PathBuf::from).unwrap_or(root_path.clone().unwrap_or_default());
-    if !fuzzing_failure_path.exists() {
-        std::fs::create_dir_all(&fuzzing_failure_path)
-            .expect("Failed to create fuzzing failure directory");
-    }
 
     let (fuzzing_harness_name, status) = fuzzing_report;
     write!(writer, "[").expect("Failed to write to stderr");
@@ -446,26 +547,18 @@ fn display_fuzzing_report_and_store(
                 )
                 .expect("Failed to write to stderr");
                 writer.reset().expect("Failed to reset writer");
-                let file_name = "Prover-failing-".to_owned()
-                    + &package.name.to_string()
-                    + "-"
-                    + fuzzing_harness_name;
-                write_inputs_to_file(
-                    fuzzing_failure_path.clone(),
-                    &file_name,
-                    Format::Toml,
-                    abi,
-                    input_map,
-                )
-                .expect("Couldn't write toml file");
+                let crashes_dir =
+                    fuzzing_crashes_dir(package, fuzzing_failure_folder, fuzzing_harness_name);
+                let crash_path =
+                    save_fuzzing_crash(&crashes_dir, input_map, abi).map_err(|err| {
+                        CliError::Generic(format!("Couldn't save failing input: {err}"))
+                    })?;
                 writeln!(writer, "saved input to:").expect("Failed to write to stderr");
                 writer
                     .set_color(ColorSpec::new().set_fg(Some(Color::Yellow)))
                     .expect("Failed to set color");
                 // TODO(https://github.com/noir-lang/noir/issues/7796): Make the path shorter if possible
-                let mut full_path_of_example = fuzzing_failure_path.clone().join(file_name);
-                full_path_of_example.set_extension(PathBuf::from("toml"));
-                writeln!(writer, "\"{}\"", full_path_of_example.to_str().unwrap())
+                writeln!(writer, "\"{}\"", crash_path.display())
                     .expect("Failed to write to stderr");
                 writer.reset().expect("Failed to reset writer");
             }
@@ -2,8 +2,9 @@ use clap::{Args, Parser, Subcommand};
 use const_format::formatcp;
 use nargo::workspace::Workspace;
 use nargo_toml::{
-    ManifestError, NargoToml, PackageConfig, PackageMetadata, PackageSelection,
-    get_package_manifest, resolve_workspace_from_fixed_toml, resolve_workspace_from_toml,
+    LockfileMode, ManifestError, NargoToml, PackageConfig, PackageMetadata, PackageSelection,
+    ResolveOptions, get_package_manifest, read_package_name, resolve_workspace_from_fixed_toml,
+    resolve_workspace_from_toml,
 };
 use noirc_driver::{CrateName, NOIR_ARTIFACT_VERSION_STRING};
 use std::{
@@ -17,6 +18,7 @@ use color_eyre::eyre;
 
 use crate::errors::CliError;
 
+mod bench_cmd;
 mod check_cmd;
 pub mod compile_cmd;
 mod dap_cmd;
@@ -33,6 +35,8 @@ mod interpret_cmd;
 mod lsp_cmd;
 mod new_cmd;
 mod test_cmd;
+mod update_cmd;
+mod vendor_cmd;
 
 const GIT_HASH: &str = env!("GIT_COMMIT");
 const IS_DIRTY: &str = env!("GIT_DIRTY");
@@ -66,6 +70,41 @@ pub struct NargoConfig {
     /// Override the default target directory.
     #[arg(long, hide = true, global = true, value_parser = parse_path)]
     target_dir: Option<PathBuf>,
+
+    /// Require Nargo.lock to be up to date, failing instead of updating it
+    #[arg(long, global = true)]
+    locked: bool,
+
+    /// Resolve git dependencies only from the vendor directory or the local cache, without accessing the network
+    #[arg(long, global = true)]
+    offline: bool,
+
+    /// Equivalent to passing both `--locked` and `--offline`
+    #[arg(long, global = true)]
+    frozen: bool,
+
+    /// Registry index to resolve version requirements of dependencies against: a directory or a file:// URL
+    #[arg(long, global = true, env = "NARGO_REGISTRY")]
+    registry: Option<String>,
+
+    /// Comma separated list of features to enable on the workspace members, in addition to their default features
+    #[arg(long, global = true, value_delimiter = ',')]
+    features: Vec<String>,
+}
+
+impl NargoConfig {
+    /// Options for resolving the dependencies of the workspace,
+    /// based on the `--locked`, `--offline`, `--frozen`, `--registry` and `--features` flags.
+    fn resolve_options(&self) -> ResolveOptions {
+        let lockfile_mode =
+            if self.locked || self.frozen { LockfileMode::Locked } else { LockfileMode::Update };
+        ResolveOptions {
+            lockfile_mode,
+            offline: self.offline || self.frozen,
+            registry: self.registry.clone(),
+            features: self.features.clone(),
+        }
+    }
 }
 
 /// Options for commands that work on either workspace or package scope.
@@ -109,12 +148,15 @@ enum NargoCommand {
     Export(export_cmd::ExportCommand),
     Debug(debug_cmd::DebugCommand),
     Test(test_cmd::TestCommand),
+    Bench(bench_cmd::BenchCommand),
     Fuzz(fuzz_cmd::FuzzCommand),
     Info(info_cmd::InfoCommand),
     Lsp(lsp_cmd::LspCommand),
     #[command(hide = true)]
     Dap(dap_cmd::DapCommand),
     Expand(expand_cmd::ExpandCommand),
+    Update(update_cmd::UpdateCommand),
+    Vendor(vendor_cmd::VendorCommand),
     GenerateCompletionScript(generate_completion_script_cmd::GenerateCompletionScriptCommand),
 }
 
@@ -153,12 +195,15 @@ pub(crate) fn start_cli() -> eyre::Result<()> {
         NargoCommand::Execute(args) => with_workspace(args, config, execute_cmd::run),
         NargoCommand::Export(args) => with_workspace(args, config, export_cmd::run),
         NargoCommand::Test(args) => with_workspace(args, config, test_cmd::run),
+        NargoCommand::Bench(args) => with_workspace(args, config, bench_cmd::run),
         NargoCommand::Fuzz(args) => with_workspace(args, config, fuzz_cmd::run),
         NargoCommand::Info(args) => with_workspace(args, config, info_cmd::run),
         NargoCommand::Lsp(_) => lsp_cmd::run(),
         NargoCommand::Dap(args) => dap_cmd::run(args),
         NargoCommand::Fmt(args) => with_workspace(args, config, fmt_cmd::run),
         NargoCommand::Expand(args) => with_workspace(args, config, expand_cmd::run),
+        NargoCommand::Update(args) => update_cmd::run(args, config),
+        NargoCommand::Vendor(args) => vendor_cmd::run(args, config),
         NargoCommand::GenerateCompletionScript(args) => generate_completion_script_cmd::run(args),
     }?;
 
@@ -176,6 +221,7 @@ pub(crate) fn start_cli() -> eyre::Result<()> {
 fn read_workspace(
     program_dir: &Path,
     selection: PackageSelection,
+    options: &ResolveOptions,
 ) -> Result<Workspace, ManifestError> {
     let toml_path = get_package_manifest(program_dir)?;
 
@@ -183,6 +229,7 @@ fn read_workspace(
         &toml_path,
         selection,
         Some(NOIR_ARTIFACT_VERSION_STRING.to_owned()),
+        options,
     )?;
 
     Ok(workspace)
@@ -202,7 +249,9 @@ fn compile_with_maybe_dummy_workspace(
         package.name = Some(package_name.clone());
         package.package_type = Some("bin".into());
         let dependencies = BTreeMap::new();
-        let package_config = PackageConfig { package, dependencies };
+        let dev_dependencies = BTreeMap::new();
+        let features = BTreeMap::new();
+        let package_config = PackageConfig { package, dependencies, dev_dependencies, features };
         let config = nargo_toml::Config::Package { package_config };
         let nargo_toml = NargoToml { root_dir, config };
         let package_name =
@@ -213,6 +262,7 @@ fn compile_with_maybe_dummy_workspace(
             nargo_toml,
             selection,
             Some(NOIR_ARTIFACT_VERSION_STRING.to_owned()),
+            &config.resolve_options(),
         )?;
         compile_cmd::run(cmd, workspace)
     } else {
@@ -233,14 +283,14 @@ where
     // or a specific package; if that's the case then parse the package name to select it in the workspace.
     let selection = match cmd.package_selection() {
         PackageSelection::DefaultOrAll if workspace_dir != package_dir => {
-            let package = read_workspace(&package_dir, PackageSelection::DefaultOrAll)?;
-            let package = package.into_iter().next().expect("there should be exactly 1 package");
-            PackageSelection::Selected(package.name.clone())
+            // Only the name is needed here; dependencies are resolved (and locked) at the workspace level.
+            let package_name = read_package_name(&get_package_manifest(&package_dir)?)?;
+            PackageSelection::Selected(package_name)
         }
         other => other,
     };
     // Parse the top level workspace with the member selected.
-    let mut workspace = read_workspace(&workspace_dir, selection)?;
+    let mut workspace = read_workspace(&workspace_dir, selection, &config.resolve_options())?;
     // Optionally override the target directory. It's only done here because most commands like the LSP and DAP
     // don't read or write artifacts, so they don't use the target directory.
     workspace.target_dir = config.target_dir.clone();
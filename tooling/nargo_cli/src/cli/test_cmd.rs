@@ -2,8 +2,9 @@ use std::{
     cmp::max,
     collections::{BTreeMap, HashMap},
     fmt::Display,
+    fs::File,
     panic::{UnwindSafe, catch_unwind},
-    path::PathBuf,
+    path::{Path, PathBuf},
     sync::{
         Mutex,
         mpsc::{self, Sender},
@@ -16,22 +17,34 @@ use acvm::{BlackBoxFunctionSolver, FieldElement};
 use bn254_blackbox_solver::Bn254BlackBoxSolver;
 use clap::Args;
 use fm::FileManager;
-use formatters::{Formatter, JsonFormatter, PrettyFormatter, TerseFormatter};
+use formatters::{Formatter, JsonFormatter, JunitFormatter, PrettyFormatter, TerseFormatter};
 use nargo::{
     FuzzExecutionConfig, FuzzFolderConfig,
-    foreign_calls::DefaultForeignCallBuilder,
+    foreign_calls::{
+        DefaultForeignCallBuilder,
+        layers::{Either, Layer},
+        transcript::ReplayForeignCallExecutor,
+    },
     insert_all_files_for_workspace_into_file_manager,
-    ops::{FuzzConfig, TestStatus, check_crate_and_report_errors},
+    ops::{
+        FuzzConfig, PropertyTestConfig, SourceCoverage, TestStatus, TestWithArgumentsConfig,
+        check_crate_and_report_errors, cobertura_report, fuzzing_crashes, fuzzing_crashes_dir,
+        lcov_report,
+    },
     package::Package,
-    parse_all, prepare_package,
+    parse_all, prepare_package_for_tests,
     workspace::Workspace,
 };
 use nargo_toml::PackageSelection;
 use noirc_driver::{CompileOptions, check_crate};
-use noirc_frontend::hir::{FunctionNameMatch, ParsedFiles, def_map::TestFunction};
+use noirc_frontend::{
+    hir::{FunctionNameMatch, ParsedFiles, def_map::TestFunction},
+    token::TestScope,
+};
 
 use crate::errors::CliError;
 
+use super::debug_cmd::debug_test_failure;
 use super::{LockType, PackageOptions, WorkspaceCommand};
 
 pub(crate) mod formatters;
@@ -61,10 +74,38 @@ pub(crate) struct TestCommand {
     #[clap(flatten)]
     compile_options: CompileOptions,
 
-    /// JSON RPC url to solve oracle calls
-    #[clap(long)]
+    /// JSON RPC url to solve oracle calls (`http://`, `stdio:<command>` or `unix:<socket path>`)
+    #[clap(long, conflicts_with = "replay_oracles")]
     oracle_resolver: Option<String>,
 
+    /// Record the foreign calls resolved by each test into an oracle transcript in this directory.
+    ///
+    /// Transcripts are written to `<dir>/<package>/<test name>.jsonl`. Fuzz tests are not recorded.
+    #[clap(long, conflicts_with = "replay_oracles")]
+    record_oracles: Option<PathBuf>,
+
+    /// Replay the oracle transcripts recorded with `--record-oracles` in this directory,
+    /// instead of calling an oracle resolver.
+    #[clap(long, conflicts_with = "oracle_resolver")]
+    replay_oracles: Option<PathBuf>,
+
+    /// Match the foreign calls of each test to its oracle transcript by name and inputs,
+    /// regardless of their order, instead of requiring them to follow the transcript exactly.
+    #[clap(long, requires = "replay_oracles")]
+    lenient_oracles: bool,
+
+    /// Write a report of the source lines executed by the tests. Fuzz tests are not included.
+    #[clap(long)]
+    coverage: bool,
+
+    /// Directory to write the coverage report into (defaults to `target/coverage`)
+    #[clap(long, requires = "coverage")]
+    coverage_dir: Option<PathBuf>,
+
+    /// Format of the coverage report
+    #[clap(long, requires = "coverage", default_value_t = CoverageFormat::Lcov)]
+    coverage_format: CoverageFormat,
+
     /// Number of threads used for running tests in parallel
     #[clap(long, default_value_t = rayon::current_num_threads())]
     test_threads: usize,
@@ -85,6 +126,19 @@ pub(crate) struct TestCommand {
     #[clap(long, conflicts_with("no_fuzz"))]
     only_fuzz: bool,
 
+    /// Run tests that have arguments as property tests, with inputs generated from the types
+    /// of their arguments, instead of fuzzing them
+    #[arg(long)]
+    property: bool,
+
+    /// Number of generated inputs to run each property test with
+    #[arg(long, default_value_t = 256, requires("property"))]
+    property_cases: u32,
+
+    /// Seed for generating the inputs of property tests (defaults to a random seed)
+    #[arg(long, requires("property"))]
+    property_seed: Option<u64>,
+
     /// If given, load/store fuzzer corpus from this folder
     #[arg(long)]
     corpus_dir: Option<String>,
@@ -93,7 +147,8 @@ pub(crate) struct TestCommand {
     #[arg(long)]
     minimized_corpus_dir: Option<String>,
 
-    /// If given, store the failing input in the given folder
+    /// If given, store the failing input in the given folder, and replay the failing inputs
+    /// of fuzzing harnesses from it (defaults to the `fuzz_crashes` folder of each package)
     #[arg(long)]
     fuzzing_failure_dir: Option<String>,
 
@@ -108,6 +163,12 @@ pub(crate) struct TestCommand {
     /// Show progress of fuzzing (default: false)
     #[arg(long)]
     fuzz_show_progress: bool,
+
+    /// Start the debugger where each failed test failed, once all tests have run.
+    /// Tests with arguments can't be debugged, and neither can tests reported in the `json` or
+    /// `junit` formats, as the debugger would write to the same output.
+    #[clap(long, conflicts_with = "replay_oracles")]
+    debug_on_failure: bool,
 }
 
 impl WorkspaceCommand for TestCommand {
@@ -128,6 +189,8 @@ enum Format {
     Terse,
     /// Output a JSON Lines document
     Json,
+    /// Output a JUnit XML report
+    Junit,
 }
 
 impl Format {
@@ -136,6 +199,7 @@ impl Format {
             Format::Pretty => Box::new(PrettyFormatter),
             Format::Terse => Box::new(TerseFormatter),
             Format::Json => Box::new(JsonFormatter),
+            Format::Junit => Box::new(JunitFormatter::default()),
         }
     }
 }
@@ -146,6 +210,33 @@ impl Display for Format {
             Format::Pretty => write!(f, "pretty"),
             Format::Terse => write!(f, "terse"),
             Format::Json => write!(f, "json"),
+            Format::Junit => write!(f, "junit"),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, clap::ValueEnum)]
+enum CoverageFormat {
+    /// Write an lcov tracefile (`lcov.info`)
+    Lcov,
+    /// Write a Cobertura XML report (`cobertura.xml`)
+    Cobertura,
+}
+
+impl CoverageFormat {
+    fn file_name(&self) -> &'static str {
+        match self {
+            CoverageFormat::Lcov => "lcov.info",
+            CoverageFormat::Cobertura => "cobertura.xml",
+        }
+    }
+}
+
+impl Display for CoverageFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CoverageFormat::Lcov => write!(f, "lcov"),
+            CoverageFormat::Cobertura => write!(f, "cobertura"),
         }
     }
 }
@@ -154,12 +245,14 @@ struct Test<'a> {
     name: String,
     package_name: String,
     has_arguments: bool,
+    scope: TestScope,
     runner: Box<dyn FnOnce() -> (TestStatus, String) + Send + UnwindSafe + 'a>,
 }
 
 pub(crate) struct TestResult {
     name: String,
     package_name: String,
+    scope: TestScope,
     status: TestStatus,
     output: String,
     time_to_run: Duration,
@@ -169,17 +262,25 @@ impl TestResult {
     pub(crate) fn new(
         name: String,
         package_name: String,
+        scope: TestScope,
         status: TestStatus,
         output: String,
         time_to_run: Duration,
     ) -> Self {
-        TestResult { name, package_name, status, output, time_to_run }
+        TestResult { name, package_name, scope, status, output, time_to_run }
     }
 }
 
 const STACK_SIZE: usize = 4 * 1024 * 1024;
 
 pub(crate) fn run(args: TestCommand, workspace: Workspace) -> Result<(), CliError> {
+    // The debugger REPL writes to stdout, which machine-readable formats need for themselves.
+    if args.debug_on_failure && matches!(args.format, Some(Format::Json | Format::Junit)) {
+        return Err(CliError::Generic(
+            "--debug-on-failure can't be used with the json and junit formats".to_string(),
+        ));
+    }
+
     let mut file_manager = workspace.new_file_manager();
     insert_all_files_for_workspace_into_file_manager(&workspace, &mut file_manager);
     let parsed_files = parse_all(&file_manager);
@@ -200,6 +301,12 @@ pub(crate) fn run(args: TestCommand, workspace: Workspace) -> Result<(), CliErro
         Box::new(PrettyFormatter)
     };
 
+    let property_seed = args.property_seed.unwrap_or_else(rand::random);
+    if args.property {
+        // Printed to stderr to keep machine-readable formats on stdout intact.
+        eprintln!("Running property tests with seed {property_seed}");
+    }
+
     let runner = TestRunner {
         file_manager: &file_manager,
         parsed_files: &parsed_files,
@@ -208,6 +315,8 @@ pub(crate) fn run(args: TestCommand, workspace: Workspace) -> Result<(), CliErro
         pattern,
         num_threads: args.test_threads,
         formatter,
+        coverage: Mutex::default(),
+        property_seed,
     };
     runner.run()
 }
@@ -220,6 +329,10 @@ struct TestRunner<'a> {
     pattern: FunctionNameMatch,
     num_threads: usize,
     formatter: Box<dyn Formatter>,
+    /// The source lines executed by the tests of each package, if `--coverage` was given.
+    coverage: Mutex<BTreeMap<String, SourceCoverage>>,
+    /// The seed shared by all property tests, so a failing run can be reproduced with `--property-seed`.
+    property_seed: u64,
 }
 
 impl<'a> TestRunner<'a> {
@@ -247,7 +360,16 @@ impl<'a> TestRunner<'a> {
 
         // Now run all tests in parallel, but show output for each package sequentially
         let tests_count = tests.len();
-        let all_passed = self.run_all_tests(tests, &test_count_per_package);
+        let failed_tests = self.run_all_tests(tests, &test_count_per_package);
+        let all_passed = failed_tests.is_empty();
+
+        if self.args.coverage {
+            self.write_coverage_report()?;
+        }
+
+        if self.args.debug_on_failure {
+            self.debug_failed_tests(&failed_tests);
+        }
 
         if tests_count == 0 {
             match &self.pattern {
@@ -323,6 +445,7 @@ impl<'a> TestRunner<'a> {
             let test_result = TestResult {
                 name: test.name,
                 package_name: test.package_name,
+                scope: test.scope,
                 status,
                 output,
                 time_to_run,
@@ -344,13 +467,13 @@ impl<'a> TestRunner<'a> {
         }
     }
 
-    /// Runs all tests. Returns `true` if all tests passed, `false` otherwise.
+    /// Runs all tests. Returns the package and name of each test that failed.
     fn run_all_tests(
         &self,
         tests: Vec<Test<'a>>,
         test_count_per_package: &BTreeMap<String, usize>,
-    ) -> bool {
-        let mut all_passed = true;
+    ) -> Vec<(String, String)> {
+        let mut failed_tests = Vec::new();
 
         for (package_name, total_test_count) in test_count_per_package {
             self.formatter
@@ -360,11 +483,11 @@ impl<'a> TestRunner<'a> {
 
         let (sender, receiver) = mpsc::channel();
         let (standard_tests_finished_sender, standard_tests_finished_receiver) = mpsc::channel();
-        // Partition tests into standard and fuzz tests
+        // Partition tests into standard and fuzz tests. Property tests run like standard tests.
         let (iter_tests_without_arguments, iter_tests_with_arguments): (
             Vec<Test<'a>>,
             Vec<Test<'a>>,
-        ) = tests.into_iter().partition(|test| !test.has_arguments);
+        ) = tests.into_iter().partition(|test| !test.has_arguments || self.args.property);
 
         let iter_tests_without_arguments = &Mutex::new(iter_tests_without_arguments.into_iter());
         let iter_tests_with_arguments = &Mutex::new(iter_tests_with_arguments.into_iter());
@@ -445,7 +568,8 @@ impl<'a> TestRunner<'a> {
                 if current_test_count < total_test_count {
                     while let Ok(test_result) = receiver.recv() {
                         if test_result.status.failed() {
-                            all_passed = false;
+                            failed_tests
+                                .push((test_result.package_name.clone(), test_result.name.clone()));
                         }
 
                         // This is a test result from a different package: buffer it.
@@ -484,7 +608,37 @@ impl<'a> TestRunner<'a> {
             }
         });
 
-        all_passed
+        failed_tests
+    }
+
+    /// Starts the debugger where each of the given tests failed, one test after the other.
+    fn debug_failed_tests(&self, failed_tests: &[(String, String)]) {
+        for (package_name, test_name) in failed_tests {
+            let Some(package) = self
+                .workspace
+                .into_iter()
+                .find(|package| package.name.to_string() == *package_name)
+            else {
+                continue;
+            };
+            println!("[{package_name}] Debugging failed test {test_name}");
+            // Replay the oracle calls the test made when it failed, rather than calling the
+            // resolver again, which may answer differently.
+            let oracle_transcript = self
+                .oracle_record_dir()
+                .map(|dir| Self::oracle_transcript_path(&dir, package_name, test_name))
+                .filter(|path| path.exists());
+            if let Err(error) = debug_test_failure(
+                &self.workspace,
+                package,
+                test_name,
+                self.args.compile_options.clone(),
+                self.args.oracle_resolver.clone(),
+                oracle_transcript.as_deref(),
+            ) {
+                println!("[{package_name}] Could not debug {test_name}: {error}");
+            }
+        }
     }
 
     /// Compiles all packages in parallel and returns their tests
@@ -550,9 +704,9 @@ impl<'a> TestRunner<'a> {
         root_path: Option<PathBuf>,
         package_name: String,
     ) -> Result<Vec<Test<'a>>, CliError> {
-        let test_functions = self.get_tests_in_package(package)?;
+        let (test_functions, fuzzing_crashes) = self.get_tests_in_package(package)?;
 
-        let tests: Vec<Test> = test_functions
+        let mut tests: Vec<Test> = test_functions
             .into_iter()
             .map(|(test_name, test_function)| {
                 let test_name_copy = test_name.clone();
@@ -574,23 +728,70 @@ impl<'a> TestRunner<'a> {
                     package_name: package_name_clone2,
                     runner,
                     has_arguments: test_function.has_arguments,
+                    scope: test_function.scope,
                 }
             })
             .collect();
 
+        // The failing inputs saved for fuzzing harnesses are replayed as regression tests,
+        // one for each input, named after the harness and the input's file.
+        let crash_tests = fuzzing_crashes.into_iter().flat_map(|(harness_name, crash_files)| {
+            crash_files.into_iter().map(move |crash_file| (harness_name.clone(), crash_file))
+        });
+        tests.extend(crash_tests.map(|(harness_name, crash_file)| {
+            let file_stem = crash_file.file_stem().unwrap_or_default().to_string_lossy();
+            let test_name = format!("{harness_name}::{file_stem}");
+            let root_path = root_path.clone();
+            let package_name_clone = package_name.clone();
+            let runner = Box::new(move || {
+                self.replay_fuzzing_crashes::<S>(
+                    package,
+                    &harness_name,
+                    std::slice::from_ref(&crash_file),
+                    foreign_call_resolver_url,
+                    root_path,
+                    package_name_clone,
+                )
+            });
+            Test {
+                name: test_name,
+                package_name: package_name.clone(),
+                runner,
+                has_arguments: false,
+                scope: TestScope::None,
+            }
+        }));
+
         Ok(tests)
     }
 
-    /// Compiles a single package and returns all of its test names
+    /// Compiles a single package and returns all of its test names, together with the fuzzing
+    /// harnesses that have saved failing inputs to replay.
+    #[allow(clippy::type_complexity)]
     fn get_tests_in_package(
         &'a self,
         package: &'a Package,
-    ) -> Result<Vec<(String, TestFunction)>, CliError> {
+    ) -> Result<(Vec<(String, TestFunction)>, Vec<(String, Vec<PathBuf>)>), CliError> {
         let (mut context, crate_id) =
-            prepare_package(self.file_manager, self.parsed_files, package);
+            prepare_package_for_tests(self.file_manager, self.parsed_files, package);
         check_crate_and_report_errors(&mut context, crate_id, &self.args.compile_options)?;
 
-        Ok(context.get_all_test_functions_in_crate_matching(&crate_id, &self.pattern))
+        let tests = context.get_all_test_functions_in_crate_matching(&crate_id, &self.pattern);
+        let fuzzing_crashes = context
+            .get_all_fuzzing_harnesses_in_crate_matching(&crate_id, &self.pattern)
+            .into_iter()
+            .filter_map(|(harness_name, _)| {
+                let crashes_dir = fuzzing_crashes_dir(
+                    package,
+                    self.args.fuzzing_failure_dir.as_deref(),
+                    &harness_name,
+                );
+                let crash_files = fuzzing_crashes(&crashes_dir);
+                (!crash_files.is_empty()).then_some((harness_name, crash_files))
+            })
+            .collect();
+
+        Ok((tests, fuzzing_crashes))
     }
 
     /// Runs a single test and returns its status together with whatever was printed to stdout
@@ -612,7 +813,7 @@ impl<'a> TestRunner<'a> {
         // We then need to construct a separate copy for each test.
 
         let (mut context, crate_id) =
-            prepare_package(self.file_manager, self.parsed_files, package);
+            prepare_package_for_tests(self.file_manager, self.parsed_files, package);
         check_crate(&mut context, crate_id, &self.args.compile_options)
             .expect("Any errors should have occurred when collecting test functions");
 
@@ -623,20 +824,41 @@ impl<'a> TestRunner<'a> {
         let blackbox_solver = S::default();
         let mut output_buffer = Vec::new();
 
-        let fuzz_config = FuzzConfig {
-            folder_config: FuzzFolderConfig {
-                corpus_dir: self.args.corpus_dir.clone(),
-                minimized_corpus_dir: self.args.minimized_corpus_dir.clone(),
-                fuzzing_failure_dir: self.args.fuzzing_failure_dir.clone(),
-            },
-            execution_config: FuzzExecutionConfig {
-                num_threads: self.num_threads,
-                timeout: self.args.fuzz_timeout,
-                show_progress: self.args.fuzz_show_progress,
-                max_executions: self.args.fuzz_max_executions,
-            },
+        let arguments_config = if self.args.property {
+            TestWithArgumentsConfig::Property(PropertyTestConfig {
+                cases: self.args.property_cases,
+                seed: self.property_seed,
+            })
+        } else {
+            TestWithArgumentsConfig::Fuzz(FuzzConfig {
+                folder_config: FuzzFolderConfig {
+                    corpus_dir: self.args.corpus_dir.clone(),
+                    minimized_corpus_dir: self.args.minimized_corpus_dir.clone(),
+                    fuzzing_failure_dir: self.args.fuzzing_failure_dir.clone(),
+                },
+                execution_config: FuzzExecutionConfig {
+                    num_threads: self.num_threads,
+                    timeout: self.args.fuzz_timeout,
+                    show_progress: self.args.fuzz_show_progress,
+                    max_executions: self.args.fuzz_max_executions,
+                },
+            })
+        };
+
+        // Fuzz tests execute the program many times, so they don't have a single transcript.
+        let (recording, replay) = if has_arguments {
+            (None, None)
+        } else {
+            match self.oracle_transcripts(&package_name, fn_name) {
+                Ok(transcripts) => transcripts,
+                Err(message) => {
+                    return (TestStatus::Fail { message, error_diagnostic: None }, String::new());
+                }
+            }
         };
 
+        let mut coverage = self.args.coverage.then(SourceCoverage::default);
+
         let test_status = nargo::ops::run_or_fuzz_test(
             &blackbox_solver,
             &mut context,
@@ -644,25 +866,173 @@ impl<'a> TestRunner<'a> {
             &mut output_buffer,
             package_name.clone(),
             &self.args.compile_options,
-            fuzz_config,
+            arguments_config,
             |output, base| {
-                DefaultForeignCallBuilder {
+                let builder = DefaultForeignCallBuilder {
                     output,
                     enable_mocks: true,
                     resolver_url: foreign_call_resolver_url.map(|s| s.to_string()),
                     root_path: root_path.clone(),
                     package_name: Some(package_name.clone()),
+                };
+                match &recording {
+                    Some(transcript) => {
+                        let transcript =
+                            transcript.try_clone().expect("failed to clone transcript file");
+                        Either::Left(
+                            builder.build_with_transcript::<_, FieldElement, _>(base, transcript),
+                        )
+                    }
+                    None => {
+                        Either::Right(builder.build_with_base(Layer::new(replay.clone(), base)))
+                    }
                 }
-                .build_with_base(base)
             },
+            coverage.as_mut(),
         );
 
+        if let Some(coverage) = coverage {
+            self.coverage.lock().unwrap().entry(package_name).or_default().merge(coverage);
+        }
+
+        // A test which passed has to have made every call in its transcript.
+        let test_status = match (test_status, &replay) {
+            (TestStatus::Pass, Some(replay)) => match replay.finish() {
+                Ok(()) => TestStatus::Pass,
+                Err(error) => {
+                    TestStatus::Fail { message: error.to_string(), error_diagnostic: None }
+                }
+            },
+            (test_status, _) => test_status,
+        };
+
         let output_string =
             String::from_utf8(output_buffer).expect("output buffer should contain valid utf8");
 
         (test_status, output_string)
     }
 
+    /// Write the source lines executed by the tests of each package into a coverage report.
+    ///
+    /// Only the sources inside the workspace are reported, not those of the standard library
+    /// or of dependencies.
+    fn write_coverage_report(&self) -> Result<(), CliError> {
+        let root_dir = &self.workspace.root_dir;
+        let mut packages = std::mem::take(&mut *self.coverage.lock().unwrap());
+        for coverage in packages.values_mut() {
+            coverage.retain_files(|path| path.starts_with(root_dir));
+        }
+
+        let report = match self.args.coverage_format {
+            CoverageFormat::Lcov => lcov_report(&packages),
+            CoverageFormat::Cobertura => cobertura_report(&packages, root_dir),
+        };
+
+        let dir = self
+            .args
+            .coverage_dir
+            .clone()
+            .unwrap_or_else(|| self.workspace.target_directory_path().join("coverage"));
+        let path = dir.join(self.args.coverage_format.file_name());
+        std::fs::create_dir_all(&dir).and_then(|_| std::fs::write(&path, report)).map_err(|error| {
+            CliError::Generic(format!(
+                "Failed to write coverage report {}: {error}",
+                path.display()
+            ))
+        })
+    }
+
+    /// Replays the failing inputs saved for a fuzzing harness and returns the resulting status.
+    fn replay_fuzzing_crashes<S: BlackBoxFunctionSolver<FieldElement> + Default>(
+        &'a self,
+        package: &Package,
+        harness_name: &str,
+        crash_files: &[PathBuf],
+        foreign_call_resolver_url: Option<&str>,
+        root_path: Option<PathBuf>,
+        package_name: String,
+    ) -> (TestStatus, String) {
+        let (mut context, crate_id) =
+            prepare_package_for_tests(self.file_manager, self.parsed_files, package);
+        check_crate(&mut context, crate_id, &self.args.compile_options)
+            .expect("Any errors should have occurred when collecting test functions");
+
+        let pattern = FunctionNameMatch::Exact(vec![harness_name.to_string()]);
+        let fuzzing_harnesses =
+            context.get_all_fuzzing_harnesses_in_crate_matching(&crate_id, &pattern);
+        let (_, fuzzing_harness) = fuzzing_harnesses.first().expect("Fuzzing harness should exist");
+
+        let test_status = nargo::ops::replay_fuzzing_crashes(
+            &S::default(),
+            &mut context,
+            fuzzing_harness,
+            crash_files,
+            &self.args.compile_options,
+            |output, base| {
+                DefaultForeignCallBuilder {
+                    output,
+                    enable_mocks: true,
+                    resolver_url: foreign_call_resolver_url.map(|s| s.to_string()),
+                    root_path: root_path.clone(),
+                    package_name: Some(package_name.clone()),
+                }
+                .build_with_base(base)
+            },
+        );
+
+        (test_status, String::new())
+    }
+
+    /// Open the oracle transcript of a test for recording, or load it for replaying,
+    /// depending on which of `--record-oracles` and `--replay-oracles` was given.
+    fn oracle_transcripts(
+        &self,
+        package_name: &str,
+        fn_name: &str,
+    ) -> Result<(Option<File>, Option<ReplayForeignCallExecutor<FieldElement>>), String> {
+        if let Some(dir) = self.oracle_record_dir() {
+            let path = Self::oracle_transcript_path(&dir, package_name, fn_name);
+            let file = path
+                .parent()
+                .map_or(Ok(()), std::fs::create_dir_all)
+                .and_then(|_| File::create(&path))
+                .map_err(|error| {
+                    format!("Failed to create oracle transcript {}: {error}", path.display())
+                })?;
+            return Ok((Some(file), None));
+        }
+
+        if let Some(dir) = &self.args.replay_oracles {
+            // A test without a transcript made no foreign calls while it was recorded.
+            let path = Self::oracle_transcript_path(dir, package_name, fn_name);
+            let replay = if path.exists() {
+                ReplayForeignCallExecutor::from_file(&path).map_err(|error| {
+                    format!("Failed to read oracle transcript {}: {error}", path.display())
+                })?
+            } else {
+                ReplayForeignCallExecutor::default()
+            };
+            return Ok((None, Some(replay.with_lenient(self.args.lenient_oracles))));
+        }
+
+        Ok((None, None))
+    }
+
+    /// The directory to record oracle transcripts into: the one given with `--record-oracles`,
+    /// or one in the target directory when failed tests are to be debugged.
+    fn oracle_record_dir(&self) -> Option<PathBuf> {
+        self.args.record_oracles.clone().or_else(|| {
+            self.args
+                .debug_on_failure
+                .then(|| self.workspace.target_directory_path().join("oracles"))
+        })
+    }
+
+    /// The oracle transcript of a test, in a subdirectory for each package and module.
+    fn oracle_transcript_path(dir: &Path, package_name: &str, fn_name: &str) -> PathBuf {
+        dir.join(package_name).join(fn_name.replace("::", "/")).with_extension("jsonl")
+    }
+
     /// Display the status of a single test
     fn display_test_result(
         &'a self,
@@ -1,8 +1,10 @@
-use std::{io::Write, panic::RefUnwindSafe, time::Duration};
+use std::{fmt::Write as _, io::Write, panic::RefUnwindSafe, sync::Mutex, time::Duration};
 
 use fm::FileManager;
 use nargo::ops::TestStatus;
+use nargo::xml_escape;
 use noirc_errors::{CustomDiagnostic, reporter::stack_trace};
+use noirc_frontend::token::TestScope;
 use serde_json::{Map, json};
 use termcolor::{Color, ColorChoice, ColorSpec, StandardStream, StandardStreamLock, WriteColor};
 
@@ -508,6 +510,189 @@ impl Formatter for JsonFormatter {
     }
 }
 
+/// Outputs a JUnit XML report, with one `<testsuite>` per package.
+///
+/// The report is printed once the last package finishes, as the document can't be
+/// closed before then.
+#[derive(Default)]
+pub(super) struct JunitFormatter {
+    /// Number of packages that haven't finished yet.
+    pending_packages: Mutex<usize>,
+    /// The `<testsuite>` elements of the packages that have finished.
+    test_suites: Mutex<String>,
+}
+
+impl Formatter for JunitFormatter {
+    fn package_start_async(&self, _package_name: &str, _test_count: usize) -> std::io::Result<()> {
+        *self.pending_packages.lock().unwrap() += 1;
+        Ok(())
+    }
+
+    fn package_start_sync(&self, _package_name: &str, _test_count: usize) -> std::io::Result<()> {
+        Ok(())
+    }
+
+    fn test_start_async(&self, _name: &str, _package_name: &str) -> std::io::Result<()> {
+        Ok(())
+    }
+
+    fn test_end_async(
+        &self,
+        _test_result: &TestResult,
+        _file_manager: &FileManager,
+        _show_output: bool,
+        _deny_warnings: bool,
+        _silence_warnings: bool,
+    ) -> std::io::Result<()> {
+        Ok(())
+    }
+
+    fn test_end_sync(
+        &self,
+        _test_result: &TestResult,
+        _current_test_count: usize,
+        _total_test_count: usize,
+        _file_manager: &FileManager,
+        _show_output: bool,
+        _deny_warnings: bool,
+        _silence_warnings: bool,
+    ) -> std::io::Result<()> {
+        Ok(())
+    }
+
+    fn package_end(
+        &self,
+        package_name: &str,
+        test_results: &[TestResult],
+        file_manager: &FileManager,
+        _show_output: bool,
+        _deny_warnings: bool,
+        silence_warnings: bool,
+    ) -> std::io::Result<()> {
+        let mut test_suites = self.test_suites.lock().unwrap();
+        test_suites.push_str(&junit_test_suite(
+            package_name,
+            test_results,
+            file_manager,
+            silence_warnings,
+        ));
+
+        let mut pending_packages = self.pending_packages.lock().unwrap();
+        *pending_packages = pending_packages.saturating_sub(1);
+        if *pending_packages == 0 {
+            let writer = std::io::stdout();
+            let mut writer = writer.lock();
+            writeln!(writer, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+            writeln!(writer, "<testsuites>")?;
+            write!(writer, "{test_suites}")?;
+            writeln!(writer, "</testsuites>")?;
+            test_suites.clear();
+        }
+        Ok(())
+    }
+}
+
+fn junit_test_suite(
+    package_name: &str,
+    test_results: &[TestResult],
+    file_manager: &FileManager,
+    silence_warnings: bool,
+) -> String {
+    let count = |predicate: fn(&TestStatus) -> bool| {
+        test_results.iter().filter(|test_result| predicate(&test_result.status)).count()
+    };
+    let failures = count(|status| matches!(status, TestStatus::Fail { .. }));
+    let errors = count(|status| matches!(status, TestStatus::CompileError(_)));
+    let skipped = count(|status| matches!(status, TestStatus::Skipped));
+    let time: Duration = test_results.iter().map(|test_result| test_result.time_to_run).sum();
+
+    let mut xml = String::new();
+    writeln!(
+        xml,
+        r#"  <testsuite name="{}" tests="{}" failures="{failures}" errors="{errors}" skipped="{skipped}" time="{:.3}">"#,
+        xml_escape(package_name),
+        test_results.len(),
+        time.as_secs_f64(),
+    )
+    .unwrap();
+
+    for test_result in test_results {
+        writeln!(
+            xml,
+            r#"    <testcase name="{}" classname="{}" time="{:.3}">"#,
+            xml_escape(&test_result.name),
+            xml_escape(&test_result.package_name),
+            test_result.time_to_run.as_secs_f64(),
+        )
+        .unwrap();
+
+        let (should_fail, failure_reason) = match &test_result.scope {
+            TestScope::None => (false, None),
+            TestScope::ShouldFailWith { reason } => (true, reason.as_deref()),
+            TestScope::OnlyFailWith { reason } => (false, Some(reason.as_str())),
+        };
+        writeln!(xml, "      <properties>").unwrap();
+        writeln!(xml, r#"        <property name="should_fail" value="{should_fail}"/>"#).unwrap();
+        if let Some(failure_reason) = failure_reason {
+            writeln!(
+                xml,
+                r#"        <property name="failure_reason" value="{}"/>"#,
+                xml_escape(failure_reason)
+            )
+            .unwrap();
+        }
+        writeln!(xml, "      </properties>").unwrap();
+
+        let diagnostic_text = |diagnostic: &CustomDiagnostic| {
+            if diagnostic.is_warning() && silence_warnings {
+                None
+            } else {
+                Some(diagnostic_to_string(diagnostic, file_manager))
+            }
+        };
+        match &test_result.status {
+            TestStatus::Pass => (),
+            TestStatus::Fail { message, error_diagnostic } => {
+                let mut text = message.trim().to_string();
+                if let Some(diagnostic) = error_diagnostic.as_ref().and_then(diagnostic_text) {
+                    text.push('\n');
+                    text.push_str(&diagnostic);
+                }
+                let summary = message.trim().lines().next().unwrap_or_default();
+                writeln!(
+                    xml,
+                    r#"      <failure message="{}">{}</failure>"#,
+                    xml_escape(summary),
+                    xml_escape(&text)
+                )
+                .unwrap();
+            }
+            TestStatus::CompileError(diagnostic) => {
+                let text = diagnostic_text(diagnostic).unwrap_or_default();
+                writeln!(
+                    xml,
+                    r#"      <error message="{}">{}</error>"#,
+                    xml_escape(diagnostic.message.trim()),
+                    xml_escape(&text)
+                )
+                .unwrap();
+            }
+            TestStatus::Skipped => {
+                writeln!(xml, "      <skipped/>").unwrap();
+            }
+        }
+
+        if !test_result.output.is_empty() {
+            writeln!(xml, "      <system-out>{}</system-out>", xml_escape(&test_result.output))
+                .unwrap();
+        }
+        writeln!(xml, "    </testcase>").unwrap();
+    }
+
+    writeln!(xml, "  </testsuite>").unwrap();
+    xml
+}
+
 fn package_start(package_name: &str, test_count: usize) -> std::io::Result<()> {
     let plural = if test_count == 1 { "" } else { "s" };
     println!("[{package_name}] Running {test_count} test function{plural}");
@@ -544,3 +729,56 @@ pub(crate) fn diagnostic_to_string(
 fn stdout() -> StandardStream {
     StandardStream::stdout(ColorChoice::Always)
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{path::Path, time::Duration};
+
+    use fm::FileManager;
+    use nargo::ops::TestStatus;
+    use noirc_frontend::token::TestScope;
+
+    use super::{TestResult, junit_test_suite};
+
+    fn test_result(name: &str, scope: TestScope, status: TestStatus, output: &str) -> TestResult {
+        TestResult::new(
+            name.to_string(),
+            "foo".to_string(),
+            scope,
+            status,
+            output.to_string(),
+            Duration::from_millis(1500),
+        )
+    }
+
+    #[test]
+    fn renders_junit_test_suite() {
+        let file_manager = FileManager::new(Path::new("."));
+        let test_results = vec![
+            test_result("test_pass", TestScope::None, TestStatus::Pass, "hello <world>\n"),
+            test_result(
+                "test_fail",
+                TestScope::ShouldFailWith { reason: Some("oops".to_string()) },
+                TestStatus::Fail {
+                    message: "Test passed when it should have failed".to_string(),
+                    error_diagnostic: None,
+                },
+                "",
+            ),
+            test_result("test_skipped", TestScope::None, TestStatus::Skipped, ""),
+        ];
+
+        let xml = junit_test_suite("foo", &test_results, &file_manager, false);
+        assert!(xml.starts_with(
+            r#"  <testsuite name="foo" tests="3" failures="1" errors="0" skipped="1" time="4.500">"#
+        ));
+        assert!(xml.contains(r#"<testcase name="test_pass" classname="foo" time="1.500">"#));
+        assert!(xml.contains("<system-out>hello &lt;world&gt;\n</system-out>"));
+        assert!(xml.contains(r#"<property name="should_fail" value="true"/>"#));
+        assert!(xml.contains(r#"<property name="failure_reason" value="oops"/>"#));
+        assert!(xml.contains(
+            r#"<failure message="Test passed when it should have failed">Test passed when it should have failed</failure>"#
+        ));
+        assert!(xml.contains("<skipped/>"));
+    }
+}
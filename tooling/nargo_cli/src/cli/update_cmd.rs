@@ -0,0 +1,30 @@
+use clap::Args;
+use nargo_toml::{LockfileMode, PackageSelection, ResolveOptions, lockfile_path};
+
+use crate::errors::CliError;
+
+use super::{NargoConfig, read_workspace};
+
+/// Update the dependencies pinned in Nargo.lock to the commits their tags currently point to
+#[derive(Debug, Clone, Args)]
+pub(crate) struct UpdateCommand;
+
+pub(crate) fn run(_args: UpdateCommand, config: NargoConfig) -> Result<(), CliError> {
+    if config.locked || config.offline || config.frozen {
+        return Err(CliError::Generic(
+            "`nargo update` cannot be used together with `--locked`, `--offline` or `--frozen`"
+                .into(),
+        ));
+    }
+
+    let workspace_dir = nargo_toml::find_root(&config.program_dir, true)?;
+    let options =
+        ResolveOptions { lockfile_mode: LockfileMode::Refresh, ..config.resolve_options() };
+    read_workspace(&workspace_dir, PackageSelection::All, &options)?;
+
+    let lockfile = lockfile_path(&workspace_dir);
+    if lockfile.exists() {
+        println!("Updated {}", lockfile.display());
+    }
+    Ok(())
+}
@@ -0,0 +1,31 @@
+use clap::Args;
+use nargo_toml::{PackageSelection, VENDOR_DIR, vendor_workspace_dependencies};
+
+use crate::errors::CliError;
+
+use super::{NargoConfig, read_workspace};
+
+/// Copy all git dependencies of the workspace into its vendor directory,
+/// so that it can later be built with `--offline`
+#[derive(Debug, Clone, Args)]
+pub(crate) struct VendorCommand;
+
+pub(crate) fn run(_args: VendorCommand, config: NargoConfig) -> Result<(), CliError> {
+    let workspace_dir = nargo_toml::find_root(&config.program_dir, true)?;
+
+    // Resolve the workspace first, so that every dependency is downloaded and pinned in Nargo.lock.
+    let options = config.resolve_options();
+    read_workspace(&workspace_dir, PackageSelection::All, &options)?;
+
+    let vendored = vendor_workspace_dependencies(&workspace_dir, options.offline)?;
+    for dir in &vendored {
+        let dir = dir.strip_prefix(&workspace_dir).unwrap_or(dir);
+        println!("Vendored {}", dir.display());
+    }
+    println!(
+        "Vendored {} git dependencies into {}",
+        vendored.len(),
+        workspace_dir.join(VENDOR_DIR).display()
+    );
+    Ok(())
+}
@@ -1,6 +1,7 @@
 use acvm::FieldElement;
 use nargo::{NargoError, errors::CompileError};
 use nargo_toml::ManifestError;
+use noir_debugger::FailureStateError;
 use noir_debugger::errors::DapError;
 use noirc_abi::errors::AbiError;
 use std::path::PathBuf;
@@ -34,6 +35,10 @@ pub enum CliError {
     #[error(transparent)]
     DapError(#[from] DapError),
 
+    /// Error reading or writing a debugger failure state
+    #[error(transparent)]
+    FailureStateError(#[from] FailureStateError),
+
     /// Error from Nargo
     #[error(transparent)]
     NargoError(#[from] NargoError<FieldElement>),
@@ -0,0 +1,58 @@
+//! Checks that packages whose tests import `[dev-dependencies]` can still be compiled on their own.
+
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use std::process::Command;
+
+use assert_fs::prelude::{FileWriteStr, PathChild};
+
+#[test]
+fn compiles_package_whose_tests_use_dev_dependencies() {
+    let test_dir = assert_fs::TempDir::new().unwrap();
+
+    let helpers_dir = test_dir.child("helpers");
+    helpers_dir
+        .child("Nargo.toml")
+        .write_str("[package]\nname = \"helpers\"\ntype = \"lib\"\nauthors = [\"\"]\n")
+        .unwrap();
+    helpers_dir
+        .child("src/lib.nr")
+        .write_str("pub fn double(x: Field) -> Field {\n    x * 2\n}\n")
+        .unwrap();
+
+    let app_dir = test_dir.child("app");
+    app_dir
+        .child("Nargo.toml")
+        .write_str(
+            "[package]\nname = \"app\"\ntype = \"bin\"\nauthors = [\"\"]\n\n[dev-dependencies]\nhelpers = { path = \"../helpers\" }\n",
+        )
+        .unwrap();
+    app_dir
+        .child("src/main.nr")
+        .write_str(
+            r#"fn main(x: Field) {
+    assert(x != 0);
+}
+
+#[cfg(test)]
+mod tests {
+    use helpers::double;
+
+    #[test]
+    fn test_double() {
+        assert(double(2) == 4);
+    }
+}
+"#,
+        )
+        .unwrap();
+
+    // `nargo compile` leaves out the test module, and with it the import of the dev-dependency.
+    let mut cmd = Command::cargo_bin("nargo").unwrap();
+    cmd.arg("compile").arg("--program-dir").arg(app_dir.path());
+    cmd.assert().success();
+
+    let mut cmd = Command::cargo_bin("nargo").unwrap();
+    cmd.arg("test").arg("--program-dir").arg(app_dir.path());
+    cmd.assert().success().stdout(predicate::str::contains("1 test passed"));
+}
@@ -0,0 +1,45 @@
+//! Checks that `nargo test` replays the failing inputs saved for fuzzing harnesses.
+
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use std::process::Command;
+
+use assert_fs::prelude::{FileWriteStr, PathChild};
+
+#[test]
+fn replays_saved_fuzzing_crashes_as_tests() {
+    let test_dir = assert_fs::TempDir::new().unwrap();
+
+    let project_dir = test_dir.child("project");
+    project_dir
+        .child("Nargo.toml")
+        .write_str("[package]\nname = \"project\"\ntype = \"bin\"\nauthors = [\"\"]\n")
+        .unwrap();
+    project_dir
+        .child("src/main.nr")
+        .write_str(
+            r#"fn main(x: Field) {
+    assert(x != 0);
+}
+
+#[fuzz]
+fn fuzz_main(x: Field) {
+    assert(x != 42);
+}
+"#,
+        )
+        .unwrap();
+
+    let crashes_dir = project_dir.child("fuzz_crashes/fuzz_main");
+    crashes_dir.child("crash-failing.toml").write_str("x = \"42\"\n").unwrap();
+    crashes_dir.child("crash-passing.toml").write_str("x = \"1\"\n").unwrap();
+
+    // Each reproducer is its own test, named after the harness and the reproducer's file.
+    let mut cmd = Command::cargo_bin("nargo").unwrap();
+    cmd.arg("test").arg("--program-dir").arg(project_dir.path());
+    cmd.assert()
+        .failure()
+        .stdout(predicate::str::contains("fuzz_main::crash-failing"))
+        .stdout(predicate::str::contains("fuzz_main::crash-passing"))
+        .stdout(predicate::str::contains("1 test failed"));
+}
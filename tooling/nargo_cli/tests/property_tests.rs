@@ -0,0 +1,66 @@
+//! Checks that `nargo test --property` shrinks failing inputs and can be reproduced with a seed.
+
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use std::process::Command;
+
+use assert_fs::prelude::{FileWriteStr, PathChild};
+
+fn nargo_test_with_seed(program_dir: &std::path::Path, seed: u64) -> String {
+    let mut cmd = Command::cargo_bin("nargo").unwrap();
+    cmd.arg("test").arg("--program-dir").arg(program_dir);
+    cmd.arg("--property").arg("--property-seed").arg(seed.to_string());
+    let assert = cmd
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(format!("Running property tests with seed {seed}")))
+        .stdout(predicate::str::contains(format!("Seed: {seed}")));
+    String::from_utf8(assert.get_output().stdout.clone()).unwrap()
+}
+
+#[test]
+fn property_tests_shrink_failing_inputs_deterministically() {
+    let test_dir = assert_fs::TempDir::new().unwrap();
+
+    let project_dir = test_dir.child("project");
+    project_dir
+        .child("Nargo.toml")
+        .write_str("[package]\nname = \"project\"\ntype = \"lib\"\nauthors = [\"\"]\n")
+        .unwrap();
+    project_dir
+        .child("src/lib.nr")
+        .write_str(
+            r#"#[test]
+fn test_small(x: u32) {
+    assert(x < 1000);
+}
+
+#[test]
+fn test_even(x: u64) {
+    assert(x % 2 == 0);
+}
+"#,
+        )
+        .unwrap();
+
+    let output = nargo_test_with_seed(project_dir.path(), 7);
+
+    // `x < 1000` first fails on `x = 1000`, which is where shrinking stops.
+    assert!(output.contains(r#"Minimal failing input: {"x":"0x03e8"}"#), "{output}");
+
+    // The same seed generates, and shrinks to, the same inputs.
+    let minimal_inputs = |output: &str| -> Vec<String> {
+        output
+            .lines()
+            .filter(|line| line.contains("Minimal failing input"))
+            .map(String::from)
+            .collect()
+    };
+    let rerun_output = nargo_test_with_seed(project_dir.path(), 7);
+    let mut expected = minimal_inputs(&output);
+    let mut actual = minimal_inputs(&rerun_output);
+    expected.sort();
+    actual.sort();
+    assert_eq!(actual, expected);
+    assert_eq!(actual.len(), 2);
+}
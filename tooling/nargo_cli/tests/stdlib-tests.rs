@@ -6,7 +6,10 @@ use nargo::foreign_calls::DefaultForeignCallBuilder;
 use noirc_driver::{CompileOptions, check_crate, file_manager_with_stdlib};
 use noirc_frontend::hir::FunctionNameMatch;
 use std::io::Write;
-use std::{collections::BTreeMap, path::PathBuf};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::PathBuf,
+};
 
 use nargo::{
     ops::{TestStatus, report_errors, run_test},
@@ -63,6 +66,8 @@ fn run_stdlib_tests(force_brillig: bool, inliner_aggressiveness: i64) {
         entry_path: PathBuf::from("main.nr"),
         name: "stdlib".parse().unwrap(),
         dependencies: BTreeMap::new(),
+        dev_dependencies: BTreeMap::new(),
+        features: BTreeSet::new(),
         expression_width: None,
     };
 
@@ -1,6 +1,6 @@
 use noirc_frontend::token::{
-    Attribute, Attributes, FunctionAttribute, FunctionAttributeKind, FuzzingScope, MetaAttribute,
-    MetaAttributeName, SecondaryAttribute, SecondaryAttributeKind, TestScope, Token,
+    Attribute, Attributes, CfgPredicate, FunctionAttribute, FunctionAttributeKind, FuzzingScope,
+    MetaAttribute, MetaAttributeName, SecondaryAttribute, SecondaryAttributeKind, TestScope, Token,
 };
 
 use crate::chunks::ChunkGroup;
@@ -65,7 +65,8 @@ impl Formatter<'_> {
             }
             FunctionAttributeKind::Fold
             | FunctionAttributeKind::NoPredicates
-            | FunctionAttributeKind::InlineAlways => {
+            | FunctionAttributeKind::InlineAlways
+            | FunctionAttributeKind::Bench => {
                 self.format_no_args_attribute();
             }
         }
@@ -93,9 +94,14 @@ impl Formatter<'_> {
             }
             SecondaryAttributeKind::Field(_)
             | SecondaryAttributeKind::Abi(_)
-            | SecondaryAttributeKind::Allow(_) => {
+            | SecondaryAttributeKind::Allow(_)
+            | SecondaryAttributeKind::MaxAcirOpcodes(_)
+            | SecondaryAttributeKind::MaxBrilligBytecode(_) => {
                 self.format_one_arg_attribute();
             }
+            SecondaryAttributeKind::Cfg(predicate) => {
+                self.format_cfg_attribute(predicate);
+            }
             SecondaryAttributeKind::Tag(_) => {
                 self.write_and_skip_span_without_formatting(attribute.location.span);
             }
@@ -151,6 +157,29 @@ impl Formatter<'_> {
         self.write_right_bracket(); // ]
     }
 
+    fn format_cfg_attribute(&mut self, predicate: CfgPredicate) {
+        self.write_current_token_and_bump(); // #[
+        self.skip_comments_and_whitespace();
+        self.write_current_token_and_bump(); // cfg
+        self.write_left_paren(); // (
+        self.skip_comments_and_whitespace();
+        match predicate {
+            CfgPredicate::Feature(_) => {
+                self.write_current_token_and_bump(); // feature
+                self.write_space();
+                self.write_token(Token::Assign);
+                self.write_space();
+                self.skip_comments_and_whitespace();
+                self.write_current_token_and_bump(); // "name"
+            }
+            CfgPredicate::Test => {
+                self.write_current_token_and_bump(); // test
+            }
+        }
+        self.write_right_paren(); // )
+        self.write_right_bracket(); // ]
+    }
+
     fn format_fuzz_attribute(&mut self, fuzz_scope: FuzzingScope) {
         self.write_current_token_and_bump(); // #[
         self.skip_comments_and_whitespace();
@@ -295,6 +324,20 @@ mod tests {
         assert_format_attribute(src, expected);
     }
 
+    #[test]
+    fn format_cfg_feature() {
+        let src = "  #[ cfg ( feature  =  \"extra\" ) ] ";
+        let expected = "#[cfg(feature = \"extra\")]";
+        assert_format_attribute(src, expected);
+    }
+
+    #[test]
+    fn format_cfg_test() {
+        let src = "  #[ cfg ( test ) ] ";
+        let expected = "#[cfg(test)]";
+        assert_format_attribute(src, expected);
+    }
+
     #[test]
     fn format_varargs() {
         let src = "  #[ varargs ] ";
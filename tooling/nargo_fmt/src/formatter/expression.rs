@@ -110,6 +110,9 @@ impl ChunkFormatter<'_, '_> {
             ExpressionKind::TypePath(type_path) => {
                 group.group(self.format_type_path(*type_path));
             }
+            ExpressionKind::Try(expression) => {
+                group.group(self.format_try(*expression));
+            }
             ExpressionKind::Resolved(..)
             | ExpressionKind::Interned(..)
             | ExpressionKind::InternedStatement(..)
@@ -720,6 +723,15 @@ impl ChunkFormatter<'_, '_> {
         group
     }
 
+    fn format_try(&mut self, expression: Expression) -> ChunkGroup {
+        let mut group = ChunkGroup::new();
+        self.format_expression(expression, &mut group);
+        group.text(self.chunk(|formatter| {
+            formatter.write_token(Token::Question);
+        }));
+        group
+    }
+
     fn format_prefix(&mut self, prefix: PrefixExpression) -> ChunkGroup {
         let mut group = ChunkGroup::new();
         group.text(self.chunk(|formatter| {
@@ -1564,6 +1576,13 @@ global y = 1;
         assert_format(src, expected);
     }
 
+    #[test]
+    fn format_try() {
+        let src = "global x =  foo ( ) ? . bar ?  ;";
+        let expected = "global x = foo()?.bar?;\n";
+        assert_format(src, expected);
+    }
+
     #[test]
     fn format_variable() {
         let src = "global x =  y ;";
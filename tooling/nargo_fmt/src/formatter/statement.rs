@@ -96,6 +96,9 @@ impl ChunkFormatter<'_, '_> {
                     }
                 }));
             }
+            StatementKind::Return(expression) => {
+                group.group(self.format_return_statement(expression));
+            }
             StatementKind::Comptime(statement) => {
                 group.group(self.format_comptime_statement(*statement));
             }
@@ -356,6 +359,31 @@ impl ChunkFormatter<'_, '_> {
         group
     }
 
+    fn format_return_statement(&mut self, expression: Option<Expression>) -> ChunkGroup {
+        let mut group = ChunkGroup::new();
+
+        group.text(self.chunk(|formatter| {
+            formatter.write_keyword(Keyword::Return);
+            if expression.is_some() {
+                formatter.write_space();
+            }
+        }));
+
+        if let Some(expression) = expression {
+            self.format_expression(expression, &mut group);
+        }
+
+        group.text(self.chunk(|formatter| {
+            formatter.skip_comments_and_whitespace();
+        }));
+
+        if self.is_at(Token::Semicolon) {
+            group.semicolon(self);
+        }
+
+        group
+    }
+
     fn format_semi_statement(&mut self, expression: Expression) -> ChunkGroup {
         let mut group = ChunkGroup::new();
 
@@ -415,6 +443,29 @@ mod tests {
         assert_format(src, expected);
     }
 
+    #[test]
+    fn format_return_statement() {
+        let src = " fn foo() -> Field { if  true  { return  1 ; }  2 } ";
+        let expected = "fn foo() -> Field {
+    if true {
+        return 1;
+    }
+    2
+}
+";
+        assert_format(src, expected);
+    }
+
+    #[test]
+    fn format_return_statement_without_value() {
+        let src = " fn foo() { return  ; } ";
+        let expected = "fn foo() {
+    return;
+}
+";
+        assert_format(src, expected);
+    }
+
     #[test]
     fn format_let_statement_no_type() {
         let src = " fn foo() { let  x  =  1 ; } ";
@@ -47,6 +47,19 @@ pub enum ManifestError {
     #[error("{} found in {toml}", if name.is_empty() { "Empty dependency name".into() } else { format!("Invalid dependency name `{name}`") })]
     InvalidDependencyName { toml: PathBuf, name: String },
 
+    #[error(
+        "Dependency `{name}` is declared in both `[dependencies]` and `[dev-dependencies]` in {toml}"
+    )]
+    DuplicateDevDependency { toml: PathBuf, name: String },
+
+    #[error("Feature `{feature}` is not defined in {toml}")]
+    UnknownFeature { toml: PathBuf, feature: String },
+
+    #[error(
+        "Feature `{feature}` in {toml} refers to a dependency which is not declared in this package"
+    )]
+    UnknownFeatureDependency { toml: PathBuf, feature: String },
+
     #[error("Invalid directory path {directory} in {toml}: It must point to a subdirectory")]
     InvalidDirectory { toml: PathBuf, directory: PathBuf },
 
@@ -72,11 +85,44 @@ pub enum ManifestError {
     #[error(transparent)]
     SemverError(SemverError),
 
+    #[error(transparent)]
+    RegistryError(#[from] RegistryError),
+
     #[error("Cyclic package dependency found when processing {cycle}")]
     CyclicDependency { cycle: String },
 
     #[error("Failed to parse expression width with the following error: {0}")]
     ParseExpressionWidth(String),
+
+    #[error("Cannot write file {0}: {1}")]
+    WriteFailed(PathBuf, String),
+
+    /// Lockfile is unreadable.
+    #[error("{0} is badly formed, could not parse.\n\n {1}")]
+    MalformedLockfile(PathBuf, String),
+
+    #[error("{0} was written with an unsupported lockfile version {1}")]
+    UnsupportedLockfileVersion(PathBuf, u32),
+
+    #[error(
+        "The lockfile {0} needs to be updated but `--locked` or `--frozen` was passed to prevent this"
+    )]
+    LockfileOutOfDate(PathBuf),
+
+    #[error(
+        "Cannot download {git} at tag {tag} in offline mode: it is neither vendored nor in the local cache. Run the command without `--offline` or `--frozen` to fetch it"
+    )]
+    GitDependencyNotCached { git: String, tag: String },
+
+    #[error(
+        "{git} at tag {tag} is vendored but missing from Nargo.lock. Run `nargo vendor` to vendor it again"
+    )]
+    UnlockedVendoredDependency { git: String, tag: String },
+
+    #[error(
+        "Checksum mismatch for {git} at tag {tag}: Nargo.lock expects {expected} but the checked out tree hashes to {found}"
+    )]
+    ChecksumMismatch { git: String, tag: String, expected: String, found: String },
 }
 
 #[allow(clippy::enum_variant_names)]
@@ -103,3 +149,44 @@ pub enum SemverError {
     )]
     CouldNotParsePackageVersion { package_name: String, error: String },
 }
+
+/// Errors encountered while resolving version requirements against a registry index.
+#[derive(Error, Debug, PartialEq, Eq, Clone)]
+pub enum RegistryError {
+    #[error(
+        "Dependency `{package}` in {toml} is declared with a version requirement, but no registry index was configured. Pass `--registry` or set NARGO_REGISTRY"
+    )]
+    NoRegistry { package: String, toml: PathBuf },
+
+    #[error("Registry index location `{0}` is not a local directory or a file:// URL")]
+    UnsupportedIndexLocation(String),
+
+    #[error("Registry index {0} does not exist or is not a directory")]
+    MissingIndex(PathBuf),
+
+    #[error("Package `{0}` was not found in the registry index")]
+    UnknownPackage(String),
+
+    #[error("Registry index entry {file} is badly formed: {error}")]
+    MalformedIndexEntry { file: PathBuf, error: String },
+
+    #[error(
+        "Invalid version requirement `{requirement}` for `{package}` in {required_by}: {error}"
+    )]
+    InvalidVersionRequirement {
+        package: String,
+        requirement: String,
+        required_by: String,
+        error: String,
+    },
+
+    #[error(
+        "No version of `{package}` satisfies all of the following requirements:\n{requirements}"
+    )]
+    VersionConflict { package: String, requirements: String },
+
+    #[error(
+        "`{package}` requires `{dependency}` but the registry index doesn't list it as a dependency of that version"
+    )]
+    UndeclaredDependency { package: String, dependency: String },
+}
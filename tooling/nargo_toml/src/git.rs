@@ -1,6 +1,24 @@
-use std::path::PathBuf;
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    process::Command,
+};
 
-use crate::flock::FileLock;
+use sha2::{Digest, Sha256};
+
+use crate::{
+    ResolveOptions,
+    errors::ManifestError,
+    flock::FileLock,
+    lockfile::{
+        LockedGitDependency, LockedRegistryDependency, Lockfile, LockfileMode, lockfile_path,
+        read_lockfile, write_lockfile,
+    },
+};
+
+/// Name of the directory, next to the root `Nargo.toml` of a workspace, that `nargo vendor` copies
+/// git dependencies into.
+pub const VENDOR_DIR: &str = "vendor";
 
 /// Creates a unique folder name for a GitHub repo
 /// by using its URL and tag
@@ -25,30 +43,281 @@ fn git_dep_location(base: &url::Url, tag: &str) -> PathBuf {
     nargo_crates().join(folder_name)
 }
 
+/// Directory a dependency is vendored into, e.g.
+/// `<workspace>/vendor/github.com/noir-lang/noir-bignum/v0.1.2`
+fn vendor_dep_location(root_dir: &Path, base: &url::Url, tag: &str) -> PathBuf {
+    let folder_name = resolve_folder_name(base, tag);
+
+    root_dir.join(VENDOR_DIR).join(folder_name)
+}
+
 pub(crate) fn lock_git_deps() -> std::io::Result<FileLock> {
     FileLock::new(&nargo_crates().join(".package-cache"), "git dependencies cache")
 }
 
-/// XXX: I'd prefer to use a GitHub library however, there
-/// does not seem to be an easy way to download a repo at a specific
-/// tag
-/// github-rs looks promising, however it seems to require an API token
+/// Copies every git dependency pinned in the lockfile of the workspace rooted at `root_dir`
+/// into its `vendor` directory, replacing anything vendored before.
 ///
-/// One advantage of using "git clone" is that there is effectively no rate limit
-pub(crate) fn clone_git_repo(url: &str, tag: &str) -> Result<PathBuf, String> {
-    use std::process::Command;
+/// Dependencies are copied from the local cache, or kept from the existing `vendor` directory
+/// if they aren't cached. The new directory is only moved into place once every dependency
+/// was copied, so a failure leaves the previous one untouched.
+///
+/// The workspace is expected to have been resolved already, so that the lockfile is up to date.
+pub(crate) fn vendor_git_deps(
+    root_dir: &Path,
+    offline: bool,
+) -> Result<Vec<PathBuf>, ManifestError> {
+    let lockfile = read_lockfile(root_dir)?.unwrap_or_default();
+
+    let vendor_dir = root_dir.join(VENDOR_DIR);
+    let staging_dir = root_dir.join(format!(".{VENDOR_DIR}.tmp"));
+    let remove_dir = |dir: &Path| {
+        std::fs::remove_dir_all(dir)
+            .map_err(|err| ManifestError::WriteFailed(dir.to_path_buf(), err.to_string()))
+    };
+    if staging_dir.exists() {
+        remove_dir(&staging_dir)?;
+    }
 
-    let base = match url::Url::parse(url) {
-        Ok(base) => base,
-        Err(err) => return Err(err.to_string()),
+    let vendored = match stage_git_deps(root_dir, &lockfile, &staging_dir, offline) {
+        Ok(vendored) => vendored,
+        Err(err) => {
+            if staging_dir.exists() {
+                remove_dir(&staging_dir)?;
+            }
+            return Err(err);
+        }
     };
 
-    let loc = git_dep_location(&base, tag);
-    if loc.exists() {
-        return Ok(loc);
+    if vendor_dir.exists() {
+        remove_dir(&vendor_dir)?;
+    }
+    if staging_dir.exists() {
+        std::fs::rename(&staging_dir, &vendor_dir)
+            .map_err(|err| ManifestError::WriteFailed(vendor_dir.clone(), err.to_string()))?;
+    }
+    Ok(vendored)
+}
+
+/// Copies the dependencies pinned in `lockfile` into `staging_dir`, returning the directories
+/// they will end up in once `staging_dir` replaces the `vendor` directory.
+fn stage_git_deps(
+    root_dir: &Path,
+    lockfile: &Lockfile,
+    staging_dir: &Path,
+    offline: bool,
+) -> Result<Vec<PathBuf>, ManifestError> {
+    let mut vendored = Vec::new();
+    for dep in &lockfile.git_dependencies {
+        let base =
+            url::Url::parse(&dep.git).map_err(|err| ManifestError::GitError(err.to_string()))?;
+        let cached = git_dep_location(&base, &dep.tag);
+        let previously_vendored = vendor_dep_location(root_dir, &base, &dep.tag);
+        let destination = staging_dir.join(resolve_folder_name(&base, &dep.tag));
+
+        // Dependencies resolved from an earlier vendor directory might not be in the cache.
+        let source = if !cached.exists() && previously_vendored.exists() {
+            previously_vendored.clone()
+        } else {
+            let not_cached = || ManifestError::GitDependencyNotCached {
+                git: dep.git.clone(),
+                tag: dep.tag.clone(),
+            };
+            if !cached.exists() {
+                if offline {
+                    return Err(not_cached());
+                }
+                clone_git_repo(&base, &dep.tag, &cached)?;
+            }
+            if head_rev(&cached)? != dep.rev {
+                checkout_rev(&cached, &dep.rev, !offline)
+                    .map_err(|err| if offline { not_cached() } else { err })?;
+            }
+            cached
+        };
+
+        copy_dir(&source, &destination)
+            .map_err(|err| ManifestError::WriteFailed(destination.clone(), err.to_string()))?;
+
+        let checksum = checksum_dir(&destination)?;
+        if checksum != dep.checksum {
+            return Err(ManifestError::ChecksumMismatch {
+                git: dep.git.clone(),
+                tag: dep.tag.clone(),
+                expected: dep.checksum.clone(),
+                found: checksum,
+            });
+        }
+        vendored.push(previously_vendored);
+    }
+    Ok(vendored)
+}
+
+/// Recursively copies the contents of `source` into `destination`, skipping the `.git` directory.
+fn copy_dir(source: &Path, destination: &Path) -> std::io::Result<()> {
+    std::fs::create_dir_all(destination)?;
+    for entry in std::fs::read_dir(source)? {
+        let path = entry?.path();
+        let Some(name) = path.file_name() else {
+            continue;
+        };
+        if name == ".git" {
+            continue;
+        }
+        if path.is_dir() {
+            copy_dir(&path, &destination.join(name))?;
+        } else {
+            std::fs::copy(&path, destination.join(name))?;
+        }
+    }
+    Ok(())
+}
+
+/// Resolves git dependencies to checkouts in the local cache, honouring the commits
+/// pinned in the `Nargo.lock` of the workspace and recording the ones it checks out.
+///
+/// Dependencies which have been copied into the `vendor` directory of the workspace
+/// are used from there instead of the cache.
+///
+/// A workspace without any git or registry dependencies doesn't get a lockfile, but an existing
+/// one is still pruned.
+pub(crate) struct GitResolver {
+    mode: LockfileMode,
+    /// Whether fetching from the network is forbidden.
+    offline: bool,
+    /// Root directory of the workspace the lockfile belongs to.
+    root_dir: PathBuf,
+    /// Contents of the lockfile before resolution started, read on the first git dependency.
+    previous: Option<Lockfile>,
+    /// Dependencies resolved so far, keyed by URL and tag.
+    resolved: BTreeMap<(String, String), LockedGitDependency>,
+}
+
+impl GitResolver {
+    pub(crate) fn new(root_dir: &Path, options: &ResolveOptions) -> Self {
+        Self {
+            mode: options.lockfile_mode,
+            offline: options.offline,
+            root_dir: root_dir.to_path_buf(),
+            previous: None,
+            resolved: BTreeMap::new(),
+        }
+    }
+
+    fn previous(&mut self) -> Result<&Lockfile, ManifestError> {
+        if self.previous.is_none() {
+            self.previous = Some(read_lockfile(&self.root_dir)?.unwrap_or_default());
+        }
+        Ok(self.previous.as_ref().expect("previous lockfile was just read"))
+    }
+
+    /// Makes sure `url` is checked out at the commit pinned for `tag`, downloading it if necessary,
+    /// and returns the location of the checkout.
+    pub(crate) fn resolve(&mut self, url: &str, tag: &str) -> Result<PathBuf, ManifestError> {
+        let base = url::Url::parse(url).map_err(|err| ManifestError::GitError(err.to_string()))?;
+        let vendored_loc = vendor_dep_location(&self.root_dir, &base, tag);
+        let is_vendored = vendored_loc.exists();
+        let loc = if is_vendored { vendored_loc } else { git_dep_location(&base, tag) };
+
+        let key = (url.to_string(), tag.to_string());
+        if self.resolved.contains_key(&key) {
+            return Ok(loc);
+        }
+
+        let previously_locked = self.previous()?.find_git_dependency(url, tag).cloned();
+        let pinned = match self.mode {
+            LockfileMode::Refresh => None,
+            _ => previously_locked.clone(),
+        };
+        if pinned.is_none() && self.mode == LockfileMode::Locked {
+            return Err(ManifestError::LockfileOutOfDate(lockfile_path(&self.root_dir)));
+        }
+
+        let rev = if is_vendored {
+            // Vendored copies have no git history, so the commit can only come from the lockfile,
+            // even when refreshing it. The checksum is then taken from the vendored copy.
+            let Some(locked) = &previously_locked else {
+                return Err(ManifestError::UnlockedVendoredDependency {
+                    git: url.to_string(),
+                    tag: tag.to_string(),
+                });
+            };
+            locked.rev.clone()
+        } else {
+            let not_cached = || ManifestError::GitDependencyNotCached {
+                git: url.to_string(),
+                tag: tag.to_string(),
+            };
+            if !loc.exists() {
+                if self.offline {
+                    return Err(not_cached());
+                }
+                clone_git_repo(&base, tag, &loc)?;
+            } else if self.mode == LockfileMode::Refresh {
+                fetch_tag(&loc, tag)?;
+            }
+
+            if let Some(pinned) = &pinned {
+                if head_rev(&loc)? != pinned.rev {
+                    checkout_rev(&loc, &pinned.rev, !self.offline)
+                        .map_err(|err| if self.offline { not_cached() } else { err })?;
+                }
+            }
+            head_rev(&loc)?
+        };
+
+        let checksum = checksum_dir(&loc)?;
+
+        if let Some(pinned) = pinned {
+            if pinned.checksum != checksum {
+                return Err(ManifestError::ChecksumMismatch {
+                    git: url.to_string(),
+                    tag: tag.to_string(),
+                    expected: pinned.checksum,
+                    found: checksum,
+                });
+            }
+        }
+
+        let locked = LockedGitDependency { git: key.0.clone(), tag: key.1.clone(), rev, checksum };
+        self.resolved.insert(key, locked);
+
+        Ok(loc)
     }
 
-    Command::new("git")
+    /// Writes the dependencies resolved so far, along with the versions selected from
+    /// the registry, into the lockfile, pruning entries for dependencies that were removed,
+    /// unless nothing changed, or the mode requires the lockfile to be up to date already.
+    pub(crate) fn finish(
+        mut self,
+        registry_dependencies: Vec<LockedRegistryDependency>,
+    ) -> Result<(), ManifestError> {
+        if self.resolved.is_empty()
+            && registry_dependencies.is_empty()
+            && !lockfile_path(&self.root_dir).exists()
+        {
+            return Ok(());
+        }
+        let git_dependencies = std::mem::take(&mut self.resolved).into_values().collect();
+        let lockfile = Lockfile::new(git_dependencies, registry_dependencies);
+        if &lockfile == self.previous()? {
+            return Ok(());
+        }
+        if self.mode == LockfileMode::Locked {
+            return Err(ManifestError::LockfileOutOfDate(lockfile_path(&self.root_dir)));
+        }
+        write_lockfile(&self.root_dir, &lockfile)
+    }
+}
+
+/// XXX: I'd prefer to use a GitHub library however, there
+/// does not seem to be an easy way to download a repo at a specific
+/// tag
+/// github-rs looks promising, however it seems to require an API token
+///
+/// One advantage of using "git clone" is that there is effectively no rate limit
+fn clone_git_repo(base: &url::Url, tag: &str, loc: &Path) -> Result<(), ManifestError> {
+    let status = Command::new("git")
         .arg("-c")
         .arg("advice.detachedHead=false")
         .arg("clone")
@@ -57,11 +326,104 @@ pub(crate) fn clone_git_repo(url: &str, tag: &str) -> Result<PathBuf, String> {
         .arg("--branch")
         .arg(tag)
         .arg(base.as_str())
-        .arg(&loc)
+        .arg(loc)
         .status()
         .expect("git clone command failed to start");
 
-    Ok(loc)
+    if status.success() {
+        Ok(())
+    } else {
+        Err(ManifestError::GitError(format!("Failed to clone {base} at tag {tag}")))
+    }
+}
+
+/// Fetches the commit `tag` currently points to and checks it out.
+fn fetch_tag(loc: &Path, tag: &str) -> Result<(), ManifestError> {
+    run_git(loc, &["fetch", "--depth", "1", "--force", "origin", tag])?;
+    run_git(loc, &["-c", "advice.detachedHead=false", "checkout", "--detach", "FETCH_HEAD"])?;
+    Ok(())
+}
+
+/// Checks out `rev` in the repository at `loc`.
+///
+/// Checkouts are shallow, so unless `allow_fetch` is false the commit is fetched from the remote first.
+fn checkout_rev(loc: &Path, rev: &str, allow_fetch: bool) -> Result<(), ManifestError> {
+    if allow_fetch {
+        run_git(loc, &["fetch", "--depth", "1", "origin", rev])?;
+    }
+    run_git(loc, &["-c", "advice.detachedHead=false", "checkout", "--detach", rev])?;
+    Ok(())
+}
+
+/// Returns the commit hash currently checked out in the repository at `loc`.
+fn head_rev(loc: &Path) -> Result<String, ManifestError> {
+    run_git(loc, &["rev-parse", "HEAD"])
+}
+
+/// Runs a git command in `dir`, returning its trimmed standard output.
+fn run_git(dir: &Path, args: &[&str]) -> Result<String, ManifestError> {
+    let output = Command::new("git")
+        .current_dir(dir)
+        .args(args)
+        .output()
+        .expect("git command failed to start");
+
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    } else {
+        Err(ManifestError::GitError(format!(
+            "`git {}` failed in {}: {}",
+            args.join(" "),
+            dir.display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )))
+    }
+}
+
+/// Computes a SHA-256 hash over the relative paths and contents of every file under `dir`,
+/// ignoring the `.git` directory, so that the same tree always hashes to the same value.
+pub(crate) fn checksum_dir(dir: &Path) -> Result<String, ManifestError> {
+    fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) -> std::io::Result<()> {
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.file_name().is_some_and(|name| name == ".git") {
+                continue;
+            }
+            if path.is_dir() {
+                collect_files(&path, files)?;
+            } else {
+                files.push(path);
+            }
+        }
+        Ok(())
+    }
+
+    let read_error = |_| ManifestError::ReadFailed(dir.to_path_buf());
+
+    let mut files = Vec::new();
+    collect_files(dir, &mut files).map_err(read_error)?;
+
+    let mut relative_files: Vec<(String, PathBuf)> = files
+        .into_iter()
+        .map(|path| {
+            let relative = path.strip_prefix(dir).expect("file should be under dir");
+            let components: Vec<_> =
+                relative.components().map(|c| c.as_os_str().to_string_lossy()).collect();
+            (components.join("/"), path)
+        })
+        .collect();
+    relative_files.sort();
+
+    let mut hasher = Sha256::new();
+    for (relative, path) in relative_files {
+        let contents = std::fs::read(&path).map_err(read_error)?;
+        hasher.update(relative.as_bytes());
+        hasher.update([0]);
+        hasher.update((contents.len() as u64).to_le_bytes());
+        hasher.update(&contents);
+    }
+
+    Ok(hex::encode(hasher.finalize()))
 }
 
 #[cfg(test)]
@@ -69,7 +431,14 @@ mod tests {
     use test_case::test_case;
     use url::Url;
 
-    use super::resolve_folder_name;
+    use crate::{
+        ResolveOptions,
+        lockfile::{LockedGitDependency, Lockfile, LockfileMode, read_lockfile, write_lockfile},
+    };
+
+    use super::{
+        GitResolver, VENDOR_DIR, checksum_dir, copy_dir, resolve_folder_name, vendor_git_deps,
+    };
 
     #[test_case("https://github.com/noir-lang/noir-bignum/"; "with slash")]
     #[test_case("https://github.com/noir-lang/noir-bignum"; "without slash")]
@@ -78,4 +447,137 @@ mod tests {
         let dir = resolve_folder_name(&Url::parse(url).unwrap(), tag);
         assert_eq!(dir, "github.com/noir-lang/noir-bignum/v0.4.2");
     }
+
+    #[test]
+    fn test_copy_dir_preserves_checksum() {
+        let tmp = tempfile::tempdir().unwrap();
+        let source = tmp.path().join("source");
+        std::fs::create_dir_all(source.join("src")).unwrap();
+        std::fs::create_dir_all(source.join(".git")).unwrap();
+        std::fs::write(source.join("Nargo.toml"), "[package]").unwrap();
+        std::fs::write(source.join("src").join("lib.nr"), "fn foo() {}").unwrap();
+        std::fs::write(source.join(".git").join("HEAD"), "ref: refs/heads/main").unwrap();
+
+        let destination = tmp.path().join("vendor").join("dep");
+        copy_dir(&source, &destination).unwrap();
+
+        assert!(!destination.join(".git").exists());
+        assert_eq!(checksum_dir(&destination).unwrap(), checksum_dir(&source).unwrap());
+    }
+
+    #[test]
+    fn test_checksum_dir_ignores_git_directory() {
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(tmp.path().join("src")).unwrap();
+        std::fs::write(tmp.path().join("Nargo.toml"), "[package]").unwrap();
+        std::fs::write(tmp.path().join("src").join("lib.nr"), "fn foo() {}").unwrap();
+
+        let before = checksum_dir(tmp.path()).unwrap();
+
+        std::fs::create_dir_all(tmp.path().join(".git")).unwrap();
+        std::fs::write(tmp.path().join(".git").join("HEAD"), "ref: refs/heads/main").unwrap();
+        assert_eq!(checksum_dir(tmp.path()).unwrap(), before);
+
+        std::fs::write(tmp.path().join("src").join("lib.nr"), "fn bar() {}").unwrap();
+        assert_ne!(checksum_dir(tmp.path()).unwrap(), before);
+    }
+
+    fn stale_lockfile(root_dir: &std::path::Path) {
+        let dependency = LockedGitDependency {
+            git: "https://github.com/noir-lang/noir-bignum".to_string(),
+            tag: "v0.4.2".to_string(),
+            rev: "0123456789abcdef0123456789abcdef01234567".to_string(),
+            checksum: "00".repeat(32),
+        };
+        let lockfile = Lockfile::new(vec![dependency], Vec::new());
+        write_lockfile(root_dir, &lockfile).unwrap();
+    }
+
+    #[test]
+    fn test_finish_prunes_lockfile_without_git_dependencies() {
+        let tmp = tempfile::tempdir().unwrap();
+        stale_lockfile(tmp.path());
+
+        GitResolver::new(tmp.path(), &ResolveOptions::default()).finish(Vec::new()).unwrap();
+
+        let lockfile = read_lockfile(tmp.path()).unwrap().expect("lockfile should still exist");
+        assert!(lockfile.git_dependencies.is_empty());
+    }
+
+    #[test]
+    fn test_finish_rejects_stale_lockfile_when_locked() {
+        let tmp = tempfile::tempdir().unwrap();
+        stale_lockfile(tmp.path());
+
+        let options = ResolveOptions { lockfile_mode: LockfileMode::Locked, ..Default::default() };
+        assert!(GitResolver::new(tmp.path(), &options).finish(Vec::new()).is_err());
+    }
+
+    #[test]
+    fn test_finish_does_not_create_lockfile_without_git_dependencies() {
+        let tmp = tempfile::tempdir().unwrap();
+        GitResolver::new(tmp.path(), &ResolveOptions::default()).finish(Vec::new()).unwrap();
+        assert!(read_lockfile(tmp.path()).unwrap().is_none());
+    }
+
+    /// Vendors a dependency from a host nothing is ever cloned from, and pins it in the lockfile.
+    fn vendored_dependency(root_dir: &std::path::Path, tag: &str) -> LockedGitDependency {
+        let git = "https://nargo.invalid/noir-lang/vendored".to_string();
+        let dir = root_dir.join(VENDOR_DIR).join("nargo.invalid/noir-lang/vendored").join(tag);
+        std::fs::create_dir_all(dir.join("src")).unwrap();
+        std::fs::write(dir.join("Nargo.toml"), "[package]").unwrap();
+        std::fs::write(dir.join("src").join("lib.nr"), "fn foo() {}").unwrap();
+        LockedGitDependency {
+            git,
+            tag: tag.to_string(),
+            rev: "0123456789abcdef0123456789abcdef01234567".to_string(),
+            checksum: checksum_dir(&dir).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_vendor_keeps_vendored_dependencies_offline() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dependency = vendored_dependency(tmp.path(), "v0.1.0");
+        write_lockfile(tmp.path(), &Lockfile::new(vec![dependency], Vec::new())).unwrap();
+
+        let vendored = vendor_git_deps(tmp.path(), true).unwrap();
+
+        assert_eq!(vendored.len(), 1);
+        assert!(vendored[0].join("src").join("lib.nr").exists());
+    }
+
+    #[test]
+    fn test_vendor_failure_leaves_vendor_directory_untouched() {
+        let tmp = tempfile::tempdir().unwrap();
+        let vendored = vendored_dependency(tmp.path(), "v0.1.0");
+        let missing = LockedGitDependency {
+            git: "https://nargo.invalid/noir-lang/missing".to_string(),
+            ..vendored.clone()
+        };
+        write_lockfile(tmp.path(), &Lockfile::new(vec![vendored, missing], Vec::new())).unwrap();
+
+        let error = vendor_git_deps(tmp.path(), true).unwrap_err();
+
+        assert!(matches!(error, crate::ManifestError::GitDependencyNotCached { .. }));
+        let vendor_dir = tmp.path().join(VENDOR_DIR);
+        assert!(vendor_dir.join("nargo.invalid/noir-lang/vendored/v0.1.0/src/lib.nr").exists());
+        assert_eq!(std::fs::read_dir(tmp.path()).unwrap().count(), 2);
+    }
+
+    #[test]
+    fn test_refresh_relocks_vendored_dependency() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut dependency = vendored_dependency(tmp.path(), "v0.1.0");
+        let checksum = std::mem::replace(&mut dependency.checksum, "00".repeat(32));
+        write_lockfile(tmp.path(), &Lockfile::new(vec![dependency.clone()], Vec::new())).unwrap();
+
+        let options = ResolveOptions { lockfile_mode: LockfileMode::Refresh, ..Default::default() };
+        let mut resolver = GitResolver::new(tmp.path(), &options);
+        resolver.resolve(&dependency.git, &dependency.tag).unwrap();
+        resolver.finish(Vec::new()).unwrap();
+
+        let lockfile = read_lockfile(tmp.path()).unwrap().unwrap();
+        assert_eq!(lockfile.git_dependencies, vec![LockedGitDependency { checksum, ..dependency }]);
+    }
 }
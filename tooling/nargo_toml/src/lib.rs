@@ -4,7 +4,7 @@
 #![cfg_attr(not(test), warn(unused_crate_dependencies, unused_extern_crates))]
 
 use std::{
-    collections::BTreeMap,
+    collections::{BTreeMap, BTreeSet},
     path::{Component, Path, PathBuf},
 };
 
@@ -21,10 +21,19 @@ use serde::Deserialize;
 mod errors;
 mod flock;
 mod git;
+mod lockfile;
+mod registry;
 mod semver;
 
-pub use errors::ManifestError;
-use git::{clone_git_repo, lock_git_deps};
+pub use errors::{ManifestError, RegistryError};
+pub use git::VENDOR_DIR;
+use git::{GitResolver, lock_git_deps, vendor_git_deps};
+pub use lockfile::{
+    LOCKFILE_NAME, LockedGitDependency, LockedRegistryDependency, Lockfile, LockfileMode,
+    lockfile_path, read_lockfile,
+};
+pub use registry::REGISTRY_ENV;
+use registry::RegistryResolver;
 
 /// Searches for a `Nargo.toml` file in the current directory and all parent directories.
 /// For example, if the current directory is `/workspace/package/src`, then this function
@@ -150,14 +159,44 @@ pub struct PackageConfig {
     pub package: PackageMetadata,
     #[serde(default)]
     pub dependencies: BTreeMap<String, DependencyConfig>,
+    /// Dependencies which are only available when testing or fuzzing the package.
+    #[serde(default, rename = "dev-dependencies")]
+    pub dev_dependencies: BTreeMap<String, DependencyConfig>,
+    /// Features of the package, each listing the other features it enables.
+    /// These can also be features of a dependency, written as `dependency/feature`.
+    #[serde(default)]
+    pub features: BTreeMap<String, Vec<String>>,
+}
+
+/// The features a package is asked to enable, either by the package depending on it or,
+/// for workspace members, from the command line.
+#[derive(Debug, Clone)]
+struct FeatureSelection {
+    features: Vec<String>,
+    /// Whether the `default` feature of the package should be enabled, if it defines one.
+    default_features: bool,
+    /// Whether requesting a feature the package doesn't define is allowed.
+    /// Features given on the command line only apply to the workspace members defining them.
+    ignore_unknown: bool,
+}
+
+impl Default for FeatureSelection {
+    fn default() -> Self {
+        Self { features: Vec::new(), default_features: true, ignore_unknown: false }
+    }
 }
 
 impl PackageConfig {
+    #[allow(clippy::too_many_arguments)]
     fn resolve_to_package(
         &self,
         root_dir: &Path,
         processed: &mut Vec<String>,
+        git_resolver: &mut GitResolver,
+        registry_resolver: &mut RegistryResolver,
         assume_default_entry: bool, // assume that the 'default_entry_path' exists, e.g. src/main.nr
+        include_dev_dependencies: bool,
+        feature_selection: &FeatureSelection,
     ) -> Result<Package, ManifestError> {
         let name: CrateName = if let Some(name) = &self.package.name {
             name.parse().map_err(|_| ManifestError::InvalidPackageName {
@@ -168,16 +207,39 @@ impl PackageConfig {
             return Err(ManifestError::MissingNameField { toml: root_dir.join("Nargo.toml") });
         };
 
-        let mut dependencies: BTreeMap<CrateName, Dependency> = BTreeMap::new();
-        for (name, dep_config) in self.dependencies.iter() {
-            let name = name.parse().map_err(|_| ManifestError::InvalidDependencyName {
-                toml: root_dir.join("Nargo.toml"),
-                name: name.into(),
-            })?;
-            let resolved_dep = dep_config.resolve_to_dependency(root_dir, processed)?;
-
-            dependencies.insert(name, resolved_dep);
-        }
+        let (features, dependency_features) = self.resolve_features(root_dir, feature_selection)?;
+
+        let dependencies = resolve_dependencies(
+            &self.dependencies,
+            &dependency_features,
+            root_dir,
+            processed,
+            git_resolver,
+            registry_resolver,
+        )?;
+
+        // Dev-dependencies are only resolved for the packages being worked on, never for
+        // the packages they depend on.
+        let dev_dependencies = if include_dev_dependencies {
+            if let Some(name) =
+                self.dev_dependencies.keys().find(|name| self.dependencies.contains_key(*name))
+            {
+                return Err(ManifestError::DuplicateDevDependency {
+                    toml: root_dir.join("Nargo.toml"),
+                    name: name.clone(),
+                });
+            }
+            resolve_dependencies(
+                &self.dev_dependencies,
+                &dependency_features,
+                root_dir,
+                processed,
+                git_resolver,
+                registry_resolver,
+            )?
+        } else {
+            BTreeMap::new()
+        };
 
         let package_type = match self.package.package_type.as_deref() {
             Some("lib") => PackageType::Library,
@@ -251,9 +313,99 @@ impl PackageConfig {
             package_type,
             name,
             dependencies,
+            dev_dependencies,
+            features,
             expression_width,
         })
     }
+
+    /// Expands the selected features into every feature of this package they enable,
+    /// along with the features they enable on each dependency.
+    fn resolve_features(
+        &self,
+        root_dir: &Path,
+        feature_selection: &FeatureSelection,
+    ) -> Result<(BTreeSet<String>, BTreeMap<String, Vec<String>>), ManifestError> {
+        let mut features = BTreeSet::new();
+        let mut dependency_features: BTreeMap<String, Vec<String>> = BTreeMap::new();
+
+        let mut pending: Vec<&str> =
+            feature_selection.features.iter().map(String::as_str).collect();
+        if feature_selection.default_features && self.features.contains_key("default") {
+            pending.push("default");
+        }
+
+        while let Some(feature) = pending.pop() {
+            if let Some((dependency, dependency_feature)) = feature.split_once('/') {
+                if !self.dependencies.contains_key(dependency)
+                    && !self.dev_dependencies.contains_key(dependency)
+                {
+                    if feature_selection.ignore_unknown {
+                        continue;
+                    }
+                    return Err(ManifestError::UnknownFeatureDependency {
+                        toml: root_dir.join("Nargo.toml"),
+                        feature: feature.to_string(),
+                    });
+                }
+                dependency_features
+                    .entry(dependency.to_string())
+                    .or_default()
+                    .push(dependency_feature.to_string());
+                continue;
+            }
+
+            if features.contains(feature) {
+                continue;
+            }
+            let Some(enabled_features) = self.features.get(feature) else {
+                if feature_selection.ignore_unknown {
+                    continue;
+                }
+                return Err(ManifestError::UnknownFeature {
+                    toml: root_dir.join("Nargo.toml"),
+                    feature: feature.to_string(),
+                });
+            };
+            features.insert(feature.to_string());
+            pending.extend(enabled_features.iter().map(String::as_str));
+        }
+
+        Ok((features, dependency_features))
+    }
+}
+
+fn resolve_dependencies(
+    dependency_configs: &BTreeMap<String, DependencyConfig>,
+    dependency_features: &BTreeMap<String, Vec<String>>,
+    root_dir: &Path,
+    processed: &mut Vec<String>,
+    git_resolver: &mut GitResolver,
+    registry_resolver: &mut RegistryResolver,
+) -> Result<BTreeMap<CrateName, Dependency>, ManifestError> {
+    let mut dependencies: BTreeMap<CrateName, Dependency> = BTreeMap::new();
+    for (name, dep_config) in dependency_configs.iter() {
+        let mut feature_selection = dep_config.feature_selection();
+        if let Some(features) = dependency_features.get(name) {
+            feature_selection.features.extend(features.iter().cloned());
+        }
+
+        let name = name.parse().map_err(|_| ManifestError::InvalidDependencyName {
+            toml: root_dir.join("Nargo.toml"),
+            name: name.into(),
+        })?;
+        let resolved_dep = dep_config.resolve_to_dependency(
+            &name,
+            root_dir,
+            processed,
+            git_resolver,
+            registry_resolver,
+            &feature_selection,
+        )?;
+
+        dependencies.insert(name, resolved_dep);
+    }
+    Ok(dependencies)
 }
 
 /// Contains all the information about a package, as loaded from a `Nargo.toml`.
@@ -328,19 +480,65 @@ pub struct PackageMetadata {
 /// Enum representing the different types of ways to
 /// supply a source for the dependency
 pub enum DependencyConfig {
-    Github { git: String, tag: String, directory: Option<String> },
-    Path { path: String },
+    Github {
+        git: String,
+        tag: String,
+        directory: Option<String>,
+        #[serde(default)]
+        features: Vec<String>,
+        #[serde(default = "default_features_enabled", rename = "default-features")]
+        default_features: bool,
+    },
+    Path {
+        path: String,
+        #[serde(default)]
+        features: Vec<String>,
+        #[serde(default = "default_features_enabled", rename = "default-features")]
+        default_features: bool,
+    },
+    /// A package from the registry index, given as a table, e.g. `foo = { version = "0.3" }`.
+    Registry {
+        version: String,
+        #[serde(default)]
+        features: Vec<String>,
+        #[serde(default = "default_features_enabled", rename = "default-features")]
+        default_features: bool,
+    },
+    /// A package from the registry index, given as a semver requirement, e.g. `foo = "0.3"`.
+    Version(String),
+}
+
+fn default_features_enabled() -> bool {
+    true
 }
 
 impl DependencyConfig {
+    /// The features the dependent package asks this dependency to enable.
+    fn feature_selection(&self) -> FeatureSelection {
+        match self {
+            Self::Github { features, default_features, .. }
+            | Self::Path { features, default_features, .. }
+            | Self::Registry { features, default_features, .. } => FeatureSelection {
+                features: features.clone(),
+                default_features: *default_features,
+                ignore_unknown: false,
+            },
+            Self::Version(_) => FeatureSelection::default(),
+        }
+    }
+
     fn resolve_to_dependency(
         &self,
+        name: &CrateName,
         pkg_root: &Path,
         processed: &mut Vec<String>,
+        git_resolver: &mut GitResolver,
+        registry_resolver: &mut RegistryResolver,
+        feature_selection: &FeatureSelection,
     ) -> Result<Dependency, ManifestError> {
         let dep = match self {
-            Self::Github { git, tag, directory } => {
-                let dir_path = clone_git_repo(git, tag).map_err(ManifestError::GitError)?;
+            Self::Github { git, tag, directory, .. } => {
+                let dir_path = git_resolver.resolve(git, tag)?;
                 let project_path = if let Some(directory) = directory {
                     let internal_path = dir_path.join(directory).normalize();
                     if !internal_path.starts_with(&dir_path) {
@@ -354,15 +552,42 @@ impl DependencyConfig {
                     dir_path
                 };
                 let toml_path = project_path.join("Nargo.toml");
-                let package = resolve_package_from_toml(&toml_path, processed)?;
+                let package = resolve_package_from_toml(
+                    &toml_path,
+                    processed,
+                    git_resolver,
+                    registry_resolver,
+                    false,
+                    feature_selection,
+                )?;
                 Dependency::Remote { package }
             }
-            Self::Path { path } => {
+            Self::Path { path, .. } => {
                 let dir_path = pkg_root.join(path);
                 let toml_path = dir_path.join("Nargo.toml");
-                let package = resolve_package_from_toml(&toml_path, processed)?;
+                let package = resolve_package_from_toml(
+                    &toml_path,
+                    processed,
+                    git_resolver,
+                    registry_resolver,
+                    false,
+                    feature_selection,
+                )?;
                 Dependency::Local { package }
             }
+            Self::Version(version) | Self::Registry { version, .. } => {
+                let dir_path = registry_resolver.resolve(name, version, pkg_root, git_resolver)?;
+                let toml_path = dir_path.join("Nargo.toml");
+                let package = resolve_package_from_toml(
+                    &toml_path,
+                    processed,
+                    git_resolver,
+                    registry_resolver,
+                    false,
+                    feature_selection,
+                )?;
+                Dependency::Remote { package }
+            }
         };
 
         // Cannot depend on a binary
@@ -379,22 +604,64 @@ fn toml_to_workspace(
     nargo_toml: NargoToml,
     package_selection: PackageSelection,
     assume_default_entry: bool, // assume that the 'default_entry_path' exists, e.g. src/main.nr
+    options: &ResolveOptions,
 ) -> Result<Workspace, ManifestError> {
-    let mut resolved = Vec::new();
     let _lock = lock_git_deps().expect("Failed to lock git dependencies cache");
-    let workspace = match nargo_toml.config {
+    let locked_registry_dependencies = match options.lockfile_mode {
+        LockfileMode::Refresh => Vec::new(),
+        LockfileMode::Update | LockfileMode::Locked => read_lockfile(&nargo_toml.root_dir)?
+            .map(|lockfile| lockfile.registry_dependencies)
+            .unwrap_or_default(),
+    };
+    let mut registry_resolver =
+        RegistryResolver::new(options.registry.as_deref(), &locked_registry_dependencies)?;
+    loop {
+        let mut git_resolver = GitResolver::new(&nargo_toml.root_dir, options);
+        let workspace = resolve_workspace_members(
+            &nargo_toml,
+            &package_selection,
+            assume_default_entry,
+            &options.features,
+            &mut git_resolver,
+            &mut registry_resolver,
+        )?;
+        // Registry versions picked before all requirements were known might have to be revised,
+        // in which case the workspace is resolved again with the final selection.
+        if registry_resolver.finish_pass()? {
+            git_resolver.finish(registry_resolver.locked_dependencies())?;
+            return Ok(workspace);
+        }
+    }
+}
+
+fn resolve_workspace_members(
+    nargo_toml: &NargoToml,
+    package_selection: &PackageSelection,
+    assume_default_entry: bool,
+    features: &[String],
+    git_resolver: &mut GitResolver,
+    registry_resolver: &mut RegistryResolver,
+) -> Result<Workspace, ManifestError> {
+    let mut resolved = Vec::new();
+    let workspace = match &nargo_toml.config {
         Config::Package { package_config } => {
+            let feature_selection =
+                FeatureSelection { features: features.to_vec(), ..FeatureSelection::default() };
             let member = package_config.resolve_to_package(
                 &nargo_toml.root_dir,
                 &mut resolved,
+                git_resolver,
+                registry_resolver,
                 assume_default_entry,
+                true,
+                &feature_selection,
             )?;
-            match &package_selection {
+            match package_selection {
                 PackageSelection::Selected(selected_name) if selected_name != &member.name => {
                     return Err(ManifestError::MissingSelectedPackage(member.name));
                 }
                 _ => Workspace {
-                    root_dir: nargo_toml.root_dir,
+                    root_dir: nargo_toml.root_dir.clone(),
                     selected_package_index: Some(0),
                     members: vec![member],
                     is_assumed: false,
@@ -403,21 +670,34 @@ fn toml_to_workspace(
             }
         }
         Config::Workspace { workspace_config } => {
+            // Features given on the command line are enabled on every member which defines them.
+            let feature_selection = FeatureSelection {
+                features: features.to_vec(),
+                ignore_unknown: true,
+                ..FeatureSelection::default()
+            };
             let mut members = Vec::new();
             let mut selected_package_index = None;
-            for (index, member_path) in workspace_config.members.into_iter().enumerate() {
-                let package_root_dir = nargo_toml.root_dir.join(&member_path);
+            for (index, member_path) in workspace_config.members.iter().enumerate() {
+                let package_root_dir = nargo_toml.root_dir.join(member_path);
                 let package_toml_path = package_root_dir.join("Nargo.toml");
-                let member = resolve_package_from_toml(&package_toml_path, &mut resolved)?;
-
-                match &package_selection {
+                let member = resolve_package_from_toml(
+                    &package_toml_path,
+                    &mut resolved,
+                    git_resolver,
+                    registry_resolver,
+                    true,
+                    &feature_selection,
+                )?;
+
+                match package_selection {
                     PackageSelection::Selected(selected_name) => {
                         if &member.name == selected_name {
                             selected_package_index = Some(index);
                         }
                     }
                     PackageSelection::DefaultOrAll => {
-                        if Some(&member_path) == workspace_config.default_member.as_ref() {
+                        if Some(member_path) == workspace_config.default_member.as_ref() {
                             selected_package_index = Some(index);
                         }
                     }
@@ -432,13 +712,13 @@ fn toml_to_workspace(
             match package_selection {
                 PackageSelection::Selected(selected_name) => {
                     if selected_package_index.is_none() {
-                        return Err(ManifestError::MissingSelectedPackage(selected_name));
+                        return Err(ManifestError::MissingSelectedPackage(selected_name.clone()));
                     }
                 }
-                PackageSelection::DefaultOrAll => match workspace_config.default_member {
+                PackageSelection::DefaultOrAll => match &workspace_config.default_member {
                     // If `default-member` is specified but we don't have a selected_package_index, we need to fail
                     Some(default_path) if selected_package_index.is_none() => {
-                        return Err(ManifestError::MissingDefaultPackage(default_path));
+                        return Err(ManifestError::MissingDefaultPackage(default_path.clone()));
                     }
                     // However, if there wasn't a `default-member`, we select All, so no error is needed
                     _ => (),
@@ -446,8 +726,25 @@ fn toml_to_workspace(
                 PackageSelection::All => (),
             }
 
+            let defined_by_a_member = |feature: &String| {
+                members.iter().any(|member: &Package| match feature.split_once('/') {
+                    Some((dependency, _)) => member
+                        .dependencies
+                        .keys()
+                        .chain(member.dev_dependencies.keys())
+                        .any(|name| name.to_string() == dependency),
+                    None => member.features.contains(feature),
+                })
+            };
+            if let Some(feature) = features.iter().find(|feature| !defined_by_a_member(feature)) {
+                return Err(ManifestError::UnknownFeature {
+                    toml: nargo_toml.root_dir.join("Nargo.toml"),
+                    feature: feature.clone(),
+                });
+            }
+
             Workspace {
-                root_dir: nargo_toml.root_dir,
+                root_dir: nargo_toml.root_dir.clone(),
                 members,
                 selected_package_index,
                 is_assumed: false,
@@ -474,6 +771,10 @@ fn read_toml(toml_path: &Path) -> Result<NargoToml, ManifestError> {
 fn resolve_package_from_toml(
     toml_path: &Path,
     processed: &mut Vec<String>,
+    git_resolver: &mut GitResolver,
+    registry_resolver: &mut RegistryResolver,
+    include_dev_dependencies: bool,
+    feature_selection: &FeatureSelection,
 ) -> Result<Package, ManifestError> {
     // Checks for cyclic dependencies
     let str_path = toml_path.to_str().expect("ICE - path is empty");
@@ -499,7 +800,15 @@ fn resolve_package_from_toml(
     let result = match nargo_toml.config {
         Config::Package { package_config } => {
             let assume_default_entry = false;
-            package_config.resolve_to_package(&nargo_toml.root_dir, processed, assume_default_entry)
+            package_config.resolve_to_package(
+                &nargo_toml.root_dir,
+                processed,
+                git_resolver,
+                registry_resolver,
+                assume_default_entry,
+                include_dev_dependencies,
+                feature_selection,
+            )
         }
         Config::Workspace { .. } => {
             Err(ManifestError::UnexpectedWorkspace(toml_path.to_path_buf()))
@@ -518,16 +827,69 @@ pub enum PackageSelection {
     All,
 }
 
+/// Options controlling how the dependencies of a workspace are resolved.
+#[derive(Debug, Default, Clone)]
+pub struct ResolveOptions {
+    /// How to treat the `Nargo.lock` file of the workspace.
+    pub lockfile_mode: LockfileMode,
+    /// Resolve git dependencies only from the `vendor` directory of the workspace
+    /// or the local cache, failing instead of accessing the network.
+    pub offline: bool,
+    /// Location of the registry index that version requirements such as `foo = "0.3"`
+    /// are resolved against: a local directory or a `file://` URL.
+    ///
+    /// The `NARGO_REGISTRY` environment variable isn't read here: callers which want to honour it,
+    /// such as the CLI, should use it to fill in this option.
+    pub registry: Option<String>,
+    /// Features to enable on the workspace members, in addition to their default features.
+    pub features: Vec<String>,
+}
+
+/// Copies the git dependencies of the workspace rooted at `root_dir` into its `vendor` directory,
+/// returning the directories they were copied into.
+///
+/// The workspace should be resolved first, so that its lockfile pins every dependency.
+/// If `offline` is set, dependencies missing from the local cache and the `vendor` directory
+/// are not downloaded.
+pub fn vendor_workspace_dependencies(
+    root_dir: &Path,
+    offline: bool,
+) -> Result<Vec<PathBuf>, ManifestError> {
+    let _lock = lock_git_deps().expect("Failed to lock git dependencies cache");
+    vendor_git_deps(root_dir, offline)
+}
+
+/// Reads the name of the package defined by the Nargo.toml file, without resolving its dependencies.
+pub fn read_package_name(toml_path: &Path) -> Result<CrateName, ManifestError> {
+    let nargo_toml = read_toml(toml_path)?;
+    let Config::Package { package_config } = nargo_toml.config else {
+        return Err(ManifestError::UnexpectedWorkspace(toml_path.to_path_buf()));
+    };
+    let Some(name) = package_config.package.name else {
+        return Err(ManifestError::MissingNameField { toml: toml_path.to_path_buf() });
+    };
+    name.parse()
+        .map_err(|_| ManifestError::InvalidPackageName { toml: toml_path.to_path_buf(), name })
+}
+
 /// Resolves a Nargo.toml file into a `Workspace` struct as defined by our `nargo` core.
 ///
-/// As a side effect it downloads project dependencies as well.
+/// As a side effect it downloads project dependencies as well, pinning git dependencies
+/// to the commits recorded in the `Nargo.lock` next to the toml file, and updating it
+/// according to `options.lockfile_mode`.
 pub fn resolve_workspace_from_toml(
     toml_path: &Path,
     package_selection: PackageSelection,
     current_compiler_version: Option<String>,
+    options: &ResolveOptions,
 ) -> Result<Workspace, ManifestError> {
     let nargo_toml = read_toml(toml_path)?;
-    resolve_workspace_from_fixed_toml(nargo_toml, package_selection, current_compiler_version)
+    resolve_workspace_from_fixed_toml(
+        nargo_toml,
+        package_selection,
+        current_compiler_version,
+        options,
+    )
 }
 
 /// Resolves a Nargo.toml _ into a `Workspace` struct as defined by our `nargo` core.
@@ -537,9 +899,11 @@ pub fn resolve_workspace_from_fixed_toml(
     nargo_toml: NargoToml,
     package_selection: PackageSelection,
     current_compiler_version: Option<String>,
+    options: &ResolveOptions,
 ) -> Result<Workspace, ManifestError> {
     let assume_default_entry = true;
-    let workspace = toml_to_workspace(nargo_toml, package_selection, assume_default_entry)?;
+    let workspace =
+        toml_to_workspace(nargo_toml, package_selection, assume_default_entry, options)?;
     if let Some(current_compiler_version) = current_compiler_version {
         semver::semver_check_workspace(&workspace, current_compiler_version)?;
     }
@@ -549,13 +913,18 @@ pub fn resolve_workspace_from_fixed_toml(
 #[cfg(test)]
 mod tests {
     use std::{
+        collections::BTreeSet,
         path::{Path, PathBuf},
         str::FromStr,
     };
 
+    use nargo::workspace::Workspace;
     use test_case::test_matrix;
 
-    use crate::{Config, ManifestError, find_root};
+    use crate::{
+        Config, LockedRegistryDependency, LockfileMode, ManifestError, PackageSelection,
+        ResolveOptions, find_root, read_lockfile, resolve_workspace_from_toml,
+    };
 
     #[test]
     fn parse_standard_toml() {
@@ -627,6 +996,297 @@ mod tests {
         assert!(Config::try_from(src).is_ok());
     }
 
+    #[test]
+    fn parse_registry_dependencies_toml() {
+        let src = r#"
+        [package]
+        name = "test"
+        type = "bin"
+
+        [dependencies]
+        short = "0.3"
+        table = { version = ">=0.1, <0.5" }
+    "#;
+
+        let Config::Package { package_config } = Config::try_from(src).unwrap() else {
+            panic!("expected a package config");
+        };
+        assert!(
+            matches!(&package_config.dependencies["short"], crate::DependencyConfig::Version(v) if v == "0.3")
+        );
+        assert!(matches!(
+            &package_config.dependencies["table"],
+            crate::DependencyConfig::Registry { .. }
+        ));
+    }
+
+    #[test]
+    fn resolve_registry_dependencies_from_local_index() {
+        let tmp = tempfile::tempdir().unwrap();
+        let write = |path: &str, contents: &str| {
+            let path = tmp.path().join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, contents).unwrap();
+        };
+        let library = |name: &str, version: &str, dependencies: &str| {
+            format!(
+                "[package]\nname = \"{name}\"\nversion = \"{version}\"\ntype = \"lib\"\n\n[dependencies]\n{dependencies}"
+            )
+        };
+
+        write(
+            "index/a.toml",
+            "[[version]]\nversion = \"0.2.0\"\npath = \"a-0.2.0\"\n\n[[version]]\nversion = \"0.2.1\"\npath = \"a-0.2.1\"\n",
+        );
+        write("index/a-0.2.0/Nargo.toml", &library("a", "0.2.0", ""));
+        write("index/a-0.2.0/src/lib.nr", "");
+        write("index/a-0.2.1/Nargo.toml", &library("a", "0.2.1", ""));
+        write("index/a-0.2.1/src/lib.nr", "");
+        write(
+            "index/b.toml",
+            "[[version]]\nversion = \"1.0.0\"\npath = \"b-1.0.0\"\ndependencies = { a = \"=0.2.0\" }\n",
+        );
+        write("index/b-1.0.0/Nargo.toml", &library("b", "1.0.0", "a = \"=0.2.0\""));
+        write("index/b-1.0.0/src/lib.nr", "");
+
+        // `a` is required before `b`, so the resolver first hands out 0.2.1 and then has to revise it.
+        write(
+            "app/Nargo.toml",
+            "[package]\nname = \"app\"\ntype = \"bin\"\n\n[dependencies]\na = \"0.2\"\nb = \"1\"\n",
+        );
+        write("app/src/main.nr", "fn main() {}");
+
+        let options = ResolveOptions {
+            registry: Some(tmp.path().join("index").to_string_lossy().into_owned()),
+            ..Default::default()
+        };
+        let workspace = resolve_workspace_from_toml(
+            &tmp.path().join("app").join("Nargo.toml"),
+            PackageSelection::DefaultOrAll,
+            None,
+            &options,
+        )
+        .expect("should resolve against the local index");
+
+        let app = &workspace.members[0];
+        let version_of = |name: &str| {
+            let name: noirc_frontend::graph::CrateName = name.parse().unwrap();
+            let dependency = &app.dependencies[&name];
+            match dependency {
+                nargo::package::Dependency::Local { package }
+                | nargo::package::Dependency::Remote { package } => package.version.clone(),
+            }
+        };
+        assert_eq!(version_of("a").as_deref(), Some("0.2.0"));
+        assert_eq!(version_of("b").as_deref(), Some("1.0.0"));
+
+        let lockfile = read_lockfile(&tmp.path().join("app")).unwrap().expect("should be locked");
+        let locked = |name: &str, version: &str| LockedRegistryDependency {
+            name: name.to_string(),
+            version: version.to_string(),
+        };
+        assert_eq!(
+            lockfile.registry_dependencies,
+            vec![locked("a", "0.2.0"), locked("b", "1.0.0")]
+        );
+
+        // Without an index the version requirement can't be resolved.
+        let error = resolve_workspace_from_toml(
+            &tmp.path().join("app").join("Nargo.toml"),
+            PackageSelection::DefaultOrAll,
+            None,
+            &ResolveOptions::default(),
+        );
+        assert!(matches!(error, Err(ManifestError::RegistryError(_))));
+    }
+
+    #[test]
+    fn registry_versions_are_pinned_in_lockfile() {
+        let tmp = tempfile::tempdir().unwrap();
+        let write = |path: &str, contents: &str| {
+            let path = tmp.path().join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, contents).unwrap();
+        };
+        let publish = |versions: &[&str]| {
+            let mut index = String::new();
+            for version in versions {
+                index += &format!("[[version]]\nversion = \"{version}\"\npath = \"a-{version}\"\n");
+                write(
+                    &format!("index/a-{version}/Nargo.toml"),
+                    &format!("[package]\nname = \"a\"\nversion = \"{version}\"\ntype = \"lib\"\n"),
+                );
+                write(&format!("index/a-{version}/src/lib.nr"), "");
+            }
+            write("index/a.toml", &index);
+        };
+        write(
+            "app/Nargo.toml",
+            "[package]\nname = \"app\"\ntype = \"bin\"\n\n[dependencies]\na = \"0.2\"\n",
+        );
+        write("app/src/main.nr", "fn main() {}");
+
+        let resolve = |lockfile_mode: LockfileMode| -> Result<String, ManifestError> {
+            let options = ResolveOptions {
+                lockfile_mode,
+                registry: Some(tmp.path().join("index").to_string_lossy().into_owned()),
+                ..Default::default()
+            };
+            let workspace: Workspace = resolve_workspace_from_toml(
+                &tmp.path().join("app").join("Nargo.toml"),
+                PackageSelection::DefaultOrAll,
+                None,
+                &options,
+            )?;
+            let name: noirc_frontend::graph::CrateName = "a".parse().unwrap();
+            match &workspace.members[0].dependencies[&name] {
+                nargo::package::Dependency::Local { package }
+                | nargo::package::Dependency::Remote { package } => {
+                    Ok(package.version.clone().unwrap())
+                }
+            }
+        };
+
+        // Without a lockfile, `--locked` can't pin anything.
+        publish(&["0.2.0"]);
+        assert!(matches!(resolve(LockfileMode::Locked), Err(ManifestError::LockfileOutOfDate(_))));
+        assert_eq!(resolve(LockfileMode::Update).unwrap(), "0.2.0");
+
+        // A newer version is only picked up when the lockfile is refreshed.
+        publish(&["0.2.0", "0.2.1"]);
+        assert_eq!(resolve(LockfileMode::Locked).unwrap(), "0.2.0");
+        assert_eq!(resolve(LockfileMode::Update).unwrap(), "0.2.0");
+        assert_eq!(resolve(LockfileMode::Refresh).unwrap(), "0.2.1");
+        assert_eq!(resolve(LockfileMode::Locked).unwrap(), "0.2.1");
+
+        let lockfile = read_lockfile(&tmp.path().join("app")).unwrap().unwrap();
+        assert_eq!(lockfile.registry_dependencies[0].version, "0.2.1");
+    }
+
+    #[test]
+    fn resolve_dev_dependencies_only_for_workspace_members() {
+        let tmp = tempfile::tempdir().unwrap();
+        let write = |path: &str, contents: &str| {
+            let path = tmp.path().join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, contents).unwrap();
+        };
+        let library = |name: &str, dev_dependencies: &str| {
+            format!(
+                "[package]\nname = \"{name}\"\ntype = \"lib\"\n\n[dev-dependencies]\n{dev_dependencies}"
+            )
+        };
+
+        write("helpers/Nargo.toml", &library("helpers", ""));
+        write("helpers/src/lib.nr", "");
+        write("lib/Nargo.toml", &library("lib", "helpers = { path = \"../helpers\" }"));
+        write("lib/src/lib.nr", "");
+        write(
+            "app/Nargo.toml",
+            "[package]\nname = \"app\"\ntype = \"bin\"\n\n[dependencies]\nlib = { path = \"../lib\" }\n\n[dev-dependencies]\nhelpers = { path = \"../helpers\" }\n",
+        );
+        write("app/src/main.nr", "fn main() {}");
+
+        let workspace = resolve_workspace_from_toml(
+            &tmp.path().join("app").join("Nargo.toml"),
+            PackageSelection::DefaultOrAll,
+            None,
+            &ResolveOptions::default(),
+        )
+        .expect("should resolve dev-dependencies");
+
+        let app = &workspace.members[0];
+        let lib_name: noirc_frontend::graph::CrateName = "lib".parse().unwrap();
+        let helpers_name: noirc_frontend::graph::CrateName = "helpers".parse().unwrap();
+        assert!(app.dev_dependencies.contains_key(&helpers_name));
+        assert!(!app.dependencies.contains_key(&helpers_name));
+        let (nargo::package::Dependency::Local { package: lib }
+        | nargo::package::Dependency::Remote { package: lib }) = &app.dependencies[&lib_name];
+        assert!(lib.dev_dependencies.is_empty());
+
+        // A dependency can't be declared in both tables.
+        write(
+            "dup/Nargo.toml",
+            "[package]\nname = \"dup\"\ntype = \"bin\"\n\n[dependencies]\nhelpers = { path = \"../helpers\" }\n\n[dev-dependencies]\nhelpers = { path = \"../helpers\" }\n",
+        );
+        write("dup/src/main.nr", "fn main() {}");
+        let error = resolve_workspace_from_toml(
+            &tmp.path().join("dup").join("Nargo.toml"),
+            PackageSelection::DefaultOrAll,
+            None,
+            &ResolveOptions::default(),
+        );
+        assert!(matches!(error, Err(ManifestError::DuplicateDevDependency { .. })));
+    }
+
+    #[test]
+    fn resolve_features_of_packages_and_dependencies() {
+        let tmp = tempfile::tempdir().unwrap();
+        let write = |path: &str, contents: &str| {
+            let path = tmp.path().join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, contents).unwrap();
+        };
+
+        write(
+            "curves/Nargo.toml",
+            "[package]\nname = \"curves\"\ntype = \"lib\"\n\n[features]\ndefault = [\"bn254\"]\nbn254 = []\nbls12_381 = []\n",
+        );
+        write("curves/src/lib.nr", "");
+        write(
+            "app/Nargo.toml",
+            r#"[package]
+name = "app"
+type = "bin"
+
+[dependencies]
+curves = { path = "../curves", default-features = false }
+
+[features]
+default = ["fast"]
+fast = []
+extra = ["curves/bls12_381"]
+"#,
+        );
+        write("app/src/main.nr", "fn main() {}");
+
+        let resolve = |features: &[&str]| {
+            let options = ResolveOptions {
+                features: features.iter().map(|feature| feature.to_string()).collect(),
+                ..Default::default()
+            };
+            resolve_workspace_from_toml(
+                &tmp.path().join("app").join("Nargo.toml"),
+                PackageSelection::DefaultOrAll,
+                None,
+                &options,
+            )
+        };
+        let features_of = |workspace: &Workspace| {
+            let app = &workspace.members[0];
+            let curves_name: noirc_frontend::graph::CrateName = "curves".parse().unwrap();
+            let (nargo::package::Dependency::Local { package: curves }
+            | nargo::package::Dependency::Remote { package: curves }) =
+                &app.dependencies[&curves_name];
+            (app.features.clone(), curves.features.clone())
+        };
+        let set = |features: &[&str]| {
+            features.iter().map(|feature| feature.to_string()).collect::<BTreeSet<_>>()
+        };
+
+        let workspace = resolve(&[]).expect("should resolve with default features");
+        assert_eq!(features_of(&workspace), (set(&["default", "fast"]), set(&[])));
+
+        let workspace = resolve(&["extra"]).expect("should resolve with extra features");
+        assert_eq!(
+            features_of(&workspace),
+            (set(&["default", "extra", "fast"]), set(&["bls12_381"]))
+        );
+
+        let error = resolve(&["missing"]);
+        assert!(matches!(error, Err(ManifestError::UnknownFeature { .. })));
+    }
+
     /// Test that `find_root` handles all kinds of prefixes.
     /// (It dispatches based on `workspace` to methods which handle paths differently).
     #[test_matrix(
@@ -0,0 +1,178 @@
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::errors::ManifestError;
+
+/// Name of the lockfile written next to the root `Nargo.toml` of a workspace.
+pub const LOCKFILE_NAME: &str = "Nargo.lock";
+
+/// Version of the lockfile format written by this version of nargo.
+const LOCKFILE_VERSION: u32 = 1;
+
+const LOCKFILE_HEADER: &str =
+    "# This file is automatically @generated by Nargo.\n# It is not intended for manual editing.\n";
+
+/// How the `Nargo.lock` file of a workspace should be treated while resolving dependencies.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum LockfileMode {
+    /// Check out the commits pinned in the lockfile, pin any new dependencies
+    /// and write the lockfile back if it changed.
+    #[default]
+    Update,
+    /// Fail if the lockfile is missing an entry or would otherwise need to change.
+    Locked,
+    /// Ignore the pinned commits, re-resolve every tag to the commit it currently points to
+    /// and write the result to the lockfile.
+    Refresh,
+}
+
+/// Contents of a `Nargo.lock` file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Lockfile {
+    version: u32,
+    /// Every git dependency in the workspace, including transitive ones, sorted by URL and tag.
+    #[serde(default, rename = "git")]
+    pub git_dependencies: Vec<LockedGitDependency>,
+    /// Every package resolved from the registry index, sorted by name.
+    #[serde(default, rename = "registry")]
+    pub registry_dependencies: Vec<LockedRegistryDependency>,
+}
+
+impl Default for Lockfile {
+    fn default() -> Self {
+        Self {
+            version: LOCKFILE_VERSION,
+            git_dependencies: Vec::new(),
+            registry_dependencies: Vec::new(),
+        }
+    }
+}
+
+/// A git dependency pinned to the exact commit that was checked out for its tag.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct LockedGitDependency {
+    /// URL of the repository, as written in the `Nargo.toml` that declared it.
+    pub git: String,
+    /// Tag (or branch) requested in the `Nargo.toml`.
+    pub tag: String,
+    /// Commit hash the tag resolved to.
+    pub rev: String,
+    /// Hash of the contents of the checked out tree, excluding the `.git` directory.
+    pub checksum: String,
+}
+
+/// A package from the registry index, pinned to the version that was selected for it.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct LockedRegistryDependency {
+    /// Name of the package in the index.
+    pub name: String,
+    /// Version selected for it.
+    pub version: String,
+}
+
+impl Lockfile {
+    pub(crate) fn new(
+        mut git_dependencies: Vec<LockedGitDependency>,
+        mut registry_dependencies: Vec<LockedRegistryDependency>,
+    ) -> Self {
+        git_dependencies.sort();
+        registry_dependencies.sort();
+        Self { version: LOCKFILE_VERSION, git_dependencies, registry_dependencies }
+    }
+
+    /// Look up the entry pinning `git` at `tag`, if there is one.
+    pub fn find_git_dependency(&self, git: &str, tag: &str) -> Option<&LockedGitDependency> {
+        self.git_dependencies.iter().find(|dep| dep.git == git && dep.tag == tag)
+    }
+}
+
+/// Path of the lockfile belonging to the workspace rooted at `root_dir`.
+pub fn lockfile_path(root_dir: &Path) -> PathBuf {
+    root_dir.join(LOCKFILE_NAME)
+}
+
+/// Reads the lockfile of the workspace rooted at `root_dir`.
+///
+/// Returns `None` if the workspace doesn't have a lockfile yet.
+pub fn read_lockfile(root_dir: &Path) -> Result<Option<Lockfile>, ManifestError> {
+    let path = lockfile_path(root_dir);
+    if !path.exists() {
+        return Ok(None);
+    }
+    let contents =
+        std::fs::read_to_string(&path).map_err(|_| ManifestError::ReadFailed(path.clone()))?;
+    let lockfile: Lockfile = toml::from_str(&contents)
+        .map_err(|err| ManifestError::MalformedLockfile(path.clone(), err.to_string()))?;
+    if lockfile.version != LOCKFILE_VERSION {
+        return Err(ManifestError::UnsupportedLockfileVersion(path, lockfile.version));
+    }
+    Ok(Some(lockfile))
+}
+
+/// Writes the lockfile of the workspace rooted at `root_dir`.
+pub(crate) fn write_lockfile(root_dir: &Path, lockfile: &Lockfile) -> Result<(), ManifestError> {
+    let path = lockfile_path(root_dir);
+    let contents = toml::to_string_pretty(lockfile).expect("lockfile should serialize to TOML");
+    std::fs::write(&path, format!("{LOCKFILE_HEADER}{contents}"))
+        .map_err(|err| ManifestError::WriteFailed(path, err.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        LockedGitDependency, LockedRegistryDependency, Lockfile, read_lockfile, write_lockfile,
+    };
+
+    fn dependency(git: &str, tag: &str) -> LockedGitDependency {
+        LockedGitDependency {
+            git: git.to_string(),
+            tag: tag.to_string(),
+            rev: "0123456789abcdef0123456789abcdef01234567".to_string(),
+            checksum: "00".repeat(32),
+        }
+    }
+
+    #[test]
+    fn lockfile_roundtrip() {
+        let tmp = tempfile::tempdir().unwrap();
+        let lockfile = Lockfile::new(
+            vec![
+                dependency("https://github.com/noir-lang/noir-bignum", "v0.4.2"),
+                dependency("https://github.com/noir-lang/ec", "v0.1.0"),
+            ],
+            vec![
+                LockedRegistryDependency { name: "b".to_string(), version: "1.0.0".to_string() },
+                LockedRegistryDependency { name: "a".to_string(), version: "0.2.1".to_string() },
+            ],
+        );
+
+        write_lockfile(tmp.path(), &lockfile).unwrap();
+        let read = read_lockfile(tmp.path()).unwrap().expect("lockfile should exist");
+
+        assert_eq!(read, lockfile);
+        assert_eq!(read.git_dependencies[0].git, "https://github.com/noir-lang/ec");
+        assert_eq!(read.registry_dependencies[0].name, "a");
+        assert!(
+            read.find_git_dependency("https://github.com/noir-lang/noir-bignum", "v0.4.2")
+                .is_some()
+        );
+        assert!(
+            read.find_git_dependency("https://github.com/noir-lang/noir-bignum", "v0.4.3")
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn missing_lockfile_is_not_an_error() {
+        let tmp = tempfile::tempdir().unwrap();
+        assert!(read_lockfile(tmp.path()).unwrap().is_none());
+    }
+
+    #[test]
+    fn rejects_unknown_lockfile_version() {
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::write(tmp.path().join(super::LOCKFILE_NAME), "version = 999\n").unwrap();
+        assert!(read_lockfile(tmp.path()).is_err());
+    }
+}
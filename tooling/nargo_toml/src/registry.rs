@@ -0,0 +1,481 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::{Path, PathBuf},
+};
+
+use fm::NormalizePath;
+use noirc_frontend::graph::CrateName;
+use semver::{Version, VersionReq};
+use serde::Deserialize;
+
+use crate::{
+    errors::{ManifestError, RegistryError},
+    git::GitResolver,
+    lockfile::LockedRegistryDependency,
+};
+
+/// Environment variable holding the location of the registry index,
+/// used when no location is passed explicitly.
+pub const REGISTRY_ENV: &str = "NARGO_REGISTRY";
+
+/// Contents of `<index>/<package name>.toml`, which lists every published version of a package.
+#[derive(Debug, Deserialize)]
+struct IndexFile {
+    #[serde(default, rename = "version")]
+    versions: Vec<IndexEntry>,
+}
+
+/// A single published version of a package, and where to get its sources from.
+#[derive(Debug, Clone, Deserialize)]
+struct IndexEntry {
+    version: String,
+    /// Version requirements on other registry packages, which have to match
+    /// the `[dependencies]` in the `Nargo.toml` of this version.
+    #[serde(default)]
+    dependencies: BTreeMap<String, String>,
+    /// Directory holding the package, relative to the root of the index.
+    path: Option<PathBuf>,
+    /// Alternatively, a git repository and tag holding the package.
+    git: Option<String>,
+    tag: Option<String>,
+    directory: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+struct IndexVersion {
+    version: Version,
+    dependencies: Vec<(String, VersionReq)>,
+    entry: IndexEntry,
+}
+
+/// A registry index stored in a local directory.
+struct RegistryIndex {
+    root: PathBuf,
+    /// Versions of the packages loaded so far, from newest to oldest.
+    packages: HashMap<String, Vec<IndexVersion>>,
+}
+
+impl RegistryIndex {
+    fn open(location: &str) -> Result<Self, RegistryError> {
+        let root = if location.starts_with("file://") {
+            url::Url::parse(location)
+                .ok()
+                .and_then(|url| url.to_file_path().ok())
+                .ok_or_else(|| RegistryError::UnsupportedIndexLocation(location.to_string()))?
+        } else if location.contains("://") {
+            return Err(RegistryError::UnsupportedIndexLocation(location.to_string()));
+        } else {
+            PathBuf::from(location)
+        };
+
+        if !root.is_dir() {
+            return Err(RegistryError::MissingIndex(root));
+        }
+        Ok(Self { root, packages: HashMap::new() })
+    }
+
+    /// All published versions of a package, from newest to oldest.
+    fn versions(&mut self, package: &str) -> Result<&[IndexVersion], RegistryError> {
+        if !self.packages.contains_key(package) {
+            let versions = self.load(package)?;
+            self.packages.insert(package.to_string(), versions);
+        }
+        Ok(&self.packages[package])
+    }
+
+    fn load(&self, package: &str) -> Result<Vec<IndexVersion>, RegistryError> {
+        let file = self.root.join(package).with_extension("toml");
+        let Ok(contents) = std::fs::read_to_string(&file) else {
+            return Err(RegistryError::UnknownPackage(package.to_string()));
+        };
+        let malformed =
+            |error: String| RegistryError::MalformedIndexEntry { file: file.clone(), error };
+
+        let index_file: IndexFile =
+            toml::from_str(&contents).map_err(|err| malformed(err.to_string()))?;
+
+        let mut versions = Vec::new();
+        for entry in index_file.versions {
+            let version =
+                Version::parse(&entry.version).map_err(|err| malformed(err.to_string()))?;
+            if entry.path.is_none() == entry.git.is_none()
+                || entry.git.is_some() != entry.tag.is_some()
+            {
+                return Err(malformed(format!(
+                    "version {version} must specify either `path`, or `git` and `tag`"
+                )));
+            }
+            let mut dependencies = Vec::new();
+            for (name, requirement) in &entry.dependencies {
+                let req =
+                    VersionReq::parse(requirement).map_err(|err| malformed(err.to_string()))?;
+                dependencies.push((name.clone(), req));
+            }
+            versions.push(IndexVersion { version, dependencies, entry });
+        }
+        versions.sort_by(|a, b| b.version.cmp(&a.version));
+        Ok(versions)
+    }
+}
+
+/// A version requirement on a registry package, and who declared it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Requirement {
+    package: String,
+    req: VersionReq,
+    required_by: String,
+}
+
+/// Selects a single version of every registry package reachable from `requirements`,
+/// such that every requirement in the graph is satisfied, preferring the `locked` versions
+/// and then newer versions.
+fn solve(
+    index: &mut RegistryIndex,
+    requirements: &[Requirement],
+    locked: &HashMap<String, Version>,
+) -> Result<BTreeMap<String, Version>, RegistryError> {
+    let mut selected = BTreeMap::new();
+    let mut active = Vec::new();
+    let mut conflict = None;
+    let pending = requirements.to_vec();
+    if search(index, locked, &mut selected, &mut active, pending, &mut conflict)? {
+        Ok(selected)
+    } else {
+        Err(conflict.expect("a failed search should record a conflict"))
+    }
+}
+
+/// Depth-first search over the candidate versions of each pending requirement, backtracking on conflicts.
+///
+/// `active` holds the requirements on the current search path, so that conflicts can list all of them.
+fn search(
+    index: &mut RegistryIndex,
+    locked: &HashMap<String, Version>,
+    selected: &mut BTreeMap<String, Version>,
+    active: &mut Vec<Requirement>,
+    mut pending: Vec<Requirement>,
+    conflict: &mut Option<RegistryError>,
+) -> Result<bool, RegistryError> {
+    let Some(requirement) = pending.pop() else {
+        return Ok(true);
+    };
+    active.push(requirement.clone());
+
+    let found = if let Some(version) = selected.get(&requirement.package) {
+        // Only one version of each package can be used, so it has to satisfy every requirement.
+        requirement.req.matches(version)
+            && search(index, locked, selected, active, pending, conflict)?
+    } else {
+        let mut candidates: Vec<IndexVersion> = index
+            .versions(&requirement.package)?
+            .iter()
+            .filter(|candidate| requirement.req.matches(&candidate.version))
+            .cloned()
+            .collect();
+        // Sorting is stable, so the other candidates stay ordered from newest to oldest.
+        if let Some(locked_version) = locked.get(&requirement.package) {
+            candidates.sort_by_key(|candidate| &candidate.version != locked_version);
+        }
+
+        let mut found = false;
+        for candidate in candidates {
+            selected.insert(requirement.package.clone(), candidate.version.clone());
+            let mut next = pending.clone();
+            next.extend(candidate.dependencies.iter().map(|(package, req)| Requirement {
+                package: package.clone(),
+                req: req.clone(),
+                required_by: format!("{} {}", requirement.package, candidate.version),
+            }));
+            if search(index, locked, selected, active, next, conflict)? {
+                found = true;
+                break;
+            }
+            selected.remove(&requirement.package);
+        }
+        found
+    };
+
+    if !found && conflict.is_none() {
+        let requirements = active
+            .iter()
+            .filter(|req| req.package == requirement.package)
+            .map(|req| format!("  `{}` required by {}", req.req, req.required_by))
+            .collect::<Vec<_>>()
+            .join("\n");
+        *conflict = Some(RegistryError::VersionConflict {
+            package: requirement.package.clone(),
+            requirements,
+        });
+    }
+
+    active.pop();
+    Ok(found)
+}
+
+/// Resolves dependencies declared with a version requirement, e.g. `foo = "0.3"`, against a registry index.
+///
+/// Each registry package is unified to a single version across the dependency graph. Requirements are
+/// only known once the packages declaring them are read, so a version handed out early might be revised
+/// later on; [RegistryResolver::finish_pass] tells whether the workspace has to be resolved again.
+pub(crate) struct RegistryResolver {
+    index: Option<RegistryIndex>,
+    /// The versions pinned in the lockfile, which are kept as long as they satisfy the requirements.
+    locked: HashMap<String, Version>,
+    /// Requirements declared by packages which don't come from the registry themselves.
+    root_requirements: Vec<Requirement>,
+    /// The versions selected to satisfy the requirements seen so far.
+    solution: BTreeMap<String, Version>,
+    /// The versions handed out during the current pass.
+    used: BTreeMap<String, Version>,
+    /// Directories of the registry packages handed out during the current pass, with their name and version.
+    registry_packages: HashMap<PathBuf, String>,
+}
+
+impl RegistryResolver {
+    pub(crate) fn new(
+        location: Option<&str>,
+        locked: &[LockedRegistryDependency],
+    ) -> Result<Self, ManifestError> {
+        let index = location.map(RegistryIndex::open).transpose()?;
+        // Entries which aren't valid versions can't match anything, so they are simply not kept.
+        let locked = locked
+            .iter()
+            .filter_map(|dep| Some((dep.name.clone(), Version::parse(&dep.version).ok()?)))
+            .collect();
+        Ok(Self {
+            index,
+            locked,
+            root_requirements: Vec::new(),
+            solution: BTreeMap::new(),
+            used: BTreeMap::new(),
+            registry_packages: HashMap::new(),
+        })
+    }
+
+    /// Selects the version of `name` to use for a `requirement` declared by the package in `pkg_root`,
+    /// fetching it if necessary, and returns the directory containing it.
+    pub(crate) fn resolve(
+        &mut self,
+        name: &CrateName,
+        requirement: &str,
+        pkg_root: &Path,
+        git_resolver: &mut GitResolver,
+    ) -> Result<PathBuf, ManifestError> {
+        let package = name.to_string();
+        let toml = pkg_root.join("Nargo.toml");
+        let Some(index) = self.index.as_mut() else {
+            return Err(RegistryError::NoRegistry { package, toml }.into());
+        };
+
+        let declaring_package = self.registry_packages.get(pkg_root).cloned();
+        let required_by = declaring_package.clone().unwrap_or_else(|| toml.display().to_string());
+        let req = VersionReq::parse(requirement).map_err(|err| {
+            RegistryError::InvalidVersionRequirement {
+                package: package.clone(),
+                requirement: requirement.to_string(),
+                required_by: required_by.clone(),
+                error: err.to_string(),
+            }
+        })?;
+
+        if let Some(declaring_package) = declaring_package {
+            // The requirements of registry packages come from the index and are already part of the solution.
+            if !self.solution.get(&package).is_some_and(|version| req.matches(version)) {
+                return Err(RegistryError::UndeclaredDependency {
+                    package: declaring_package,
+                    dependency: package,
+                }
+                .into());
+            }
+        } else {
+            let requirement = Requirement { package: package.clone(), req, required_by };
+            let is_satisfied =
+                self.solution.get(&package).is_some_and(|version| requirement.req.matches(version));
+            if !self.root_requirements.contains(&requirement) {
+                self.root_requirements.push(requirement);
+            }
+            if !is_satisfied {
+                self.solution = solve(index, &self.root_requirements, &self.locked)?;
+            }
+        }
+
+        let version = self.solution[&package].clone();
+        let entry = index
+            .versions(&package)?
+            .iter()
+            .find(|candidate| candidate.version == version)
+            .expect("selected version should be in the index")
+            .entry
+            .clone();
+
+        let dir = if let Some(path) = &entry.path {
+            index.root.join(path).normalize()
+        } else {
+            let git = entry.git.as_ref().expect("index entries have either a path or a git source");
+            let tag = entry.tag.as_ref().expect("index entries with a git source have a tag");
+            let checkout = git_resolver.resolve(git, tag)?;
+            match &entry.directory {
+                Some(directory) => {
+                    let dir = checkout.join(directory).normalize();
+                    if !dir.starts_with(&checkout) {
+                        return Err(ManifestError::InvalidDirectory {
+                            toml: index.root.join(&package).with_extension("toml"),
+                            directory: directory.into(),
+                        });
+                    }
+                    dir
+                }
+                None => checkout,
+            }
+        };
+
+        self.used.insert(package.clone(), version.clone());
+        self.registry_packages.insert(dir.clone(), format!("{package} {version}"));
+        Ok(dir)
+    }
+
+    /// Ends a pass over the workspace, returning whether every registry package it used
+    /// is at its final version. If not, the solution is recomputed from all the requirements
+    /// seen so far, and the workspace needs to be resolved again.
+    pub(crate) fn finish_pass(&mut self) -> Result<bool, ManifestError> {
+        let is_stable = self.used.iter().all(|(package, version)| {
+            self.solution.get(package).is_some_and(|selected| selected == version)
+        });
+        self.used.clear();
+        self.registry_packages.clear();
+
+        if !is_stable {
+            let index = self.index.as_mut().expect("registry packages were used without an index");
+            self.solution = solve(index, &self.root_requirements, &self.locked)?;
+        }
+        Ok(is_stable)
+    }
+
+    /// The versions selected for every registry package, to be pinned in the lockfile.
+    pub(crate) fn locked_dependencies(&self) -> Vec<LockedRegistryDependency> {
+        self.solution
+            .iter()
+            .map(|(name, version)| LockedRegistryDependency {
+                name: name.clone(),
+                version: version.to_string(),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, path::Path};
+
+    use semver::{Version, VersionReq};
+
+    use crate::errors::RegistryError;
+
+    use super::{RegistryIndex, Requirement, solve};
+
+    /// Writes an index entry for `package` with the given `(version, dependencies)` pairs.
+    fn publish(index: &Path, package: &str, versions: &[(&str, &[(&str, &str)])]) {
+        let mut contents = String::new();
+        for (version, dependencies) in versions {
+            contents +=
+                &format!("[[version]]\nversion = \"{version}\"\npath = \"{package}-{version}\"\n");
+            if !dependencies.is_empty() {
+                contents += "[version.dependencies]\n";
+                for (name, req) in *dependencies {
+                    contents += &format!("{name} = \"{req}\"\n");
+                }
+            }
+        }
+        std::fs::write(index.join(format!("{package}.toml")), contents).unwrap();
+    }
+
+    fn requirement(package: &str, req: &str) -> Requirement {
+        Requirement {
+            package: package.to_string(),
+            req: VersionReq::parse(req).unwrap(),
+            required_by: "root".to_string(),
+        }
+    }
+
+    #[test]
+    fn selects_newest_compatible_versions() {
+        let tmp = tempfile::tempdir().unwrap();
+        publish(tmp.path(), "a", &[("0.1.0", &[]), ("0.2.0", &[]), ("0.2.3", &[]), ("0.3.0", &[])]);
+        publish(tmp.path(), "b", &[("1.0.0", &[("a", "0.2")])]);
+
+        let mut index = RegistryIndex::open(tmp.path().to_str().unwrap()).unwrap();
+        let solution = solve(&mut index, &[requirement("b", "1")], &HashMap::new()).unwrap();
+
+        assert_eq!(solution["a"], Version::new(0, 2, 3));
+        assert_eq!(solution["b"], Version::new(1, 0, 0));
+    }
+
+    #[test]
+    fn unifies_requirements_across_the_graph() {
+        let tmp = tempfile::tempdir().unwrap();
+        publish(tmp.path(), "a", &[("0.2.0", &[]), ("0.2.5", &[]), ("0.3.0", &[])]);
+        // The newest `b` requires an `a` that the root can't use, so the resolver has to backtrack.
+        publish(
+            tmp.path(),
+            "b",
+            &[("1.0.0", &[("a", ">=0.2.0, <0.2.5")]), ("1.1.0", &[("a", "0.3")])],
+        );
+
+        let mut index = RegistryIndex::open(tmp.path().to_str().unwrap()).unwrap();
+        let requirements = [requirement("a", "0.2"), requirement("b", "1")];
+        let solution = solve(&mut index, &requirements, &HashMap::new()).unwrap();
+
+        assert_eq!(solution["a"], Version::new(0, 2, 0));
+        assert_eq!(solution["b"], Version::new(1, 0, 0));
+    }
+
+    #[test]
+    fn prefers_locked_versions() {
+        let tmp = tempfile::tempdir().unwrap();
+        publish(tmp.path(), "a", &[("0.2.0", &[]), ("0.2.1", &[]), ("0.2.2", &[])]);
+
+        let mut index = RegistryIndex::open(tmp.path().to_str().unwrap()).unwrap();
+        let locked = HashMap::from([("a".to_string(), Version::new(0, 2, 1))]);
+        let solution = solve(&mut index, &[requirement("a", "0.2")], &locked).unwrap();
+        assert_eq!(solution["a"], Version::new(0, 2, 1));
+
+        // A locked version which no longer satisfies the requirements is replaced.
+        let solution = solve(&mut index, &[requirement("a", ">=0.2.2")], &locked).unwrap();
+        assert_eq!(solution["a"], Version::new(0, 2, 2));
+    }
+
+    #[test]
+    fn reports_conflicting_requirements() {
+        let tmp = tempfile::tempdir().unwrap();
+        publish(tmp.path(), "a", &[("0.2.0", &[]), ("0.3.0", &[])]);
+        publish(tmp.path(), "b", &[("1.0.0", &[("a", "0.3")])]);
+
+        let mut index = RegistryIndex::open(tmp.path().to_str().unwrap()).unwrap();
+        let requirements = [requirement("a", "0.2"), requirement("b", "1")];
+        let error = solve(&mut index, &requirements, &HashMap::new()).expect_err("should conflict");
+
+        let RegistryError::VersionConflict { package, requirements } = error else {
+            panic!("expected a version conflict, got {error:?}");
+        };
+        assert_eq!(package, "a");
+        assert!(requirements.contains("required by root"));
+        assert!(requirements.contains("required by b 1.0.0"));
+    }
+
+    #[test]
+    fn reports_unknown_packages() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut index = RegistryIndex::open(tmp.path().to_str().unwrap()).unwrap();
+        let error = solve(&mut index, &[requirement("missing", "1")], &HashMap::new())
+            .expect_err("should fail");
+        assert_eq!(error, RegistryError::UnknownPackage("missing".to_string()));
+    }
+
+    #[test]
+    fn opens_file_urls() {
+        let tmp = tempfile::tempdir().unwrap();
+        let url = url::Url::from_directory_path(tmp.path()).unwrap();
+        assert!(RegistryIndex::open(url.as_str()).is_ok());
+        assert!(RegistryIndex::open("https://example.com/index").is_err());
+    }
+}
@@ -53,7 +53,7 @@ fn semver_check_package(package: &Package, compiler_version: &Version) -> Result
     }
 
     // Check that all of this package's dependencies' compiler version requirements are satisfied
-    for dep in package.dependencies.values() {
+    for dep in package.dependencies.values().chain(package.dev_dependencies.values()) {
         match dep {
             Dependency::Local { package } | Dependency::Remote { package } => {
                 semver_check_package(package, compiler_version)?;
@@ -87,7 +87,11 @@ fn strip_build_meta_data(version: &Version) -> String {
 
 #[cfg(test)]
 mod tests {
-    use std::{collections::BTreeMap, path::PathBuf, str::FromStr};
+    use std::{
+        collections::{BTreeMap, BTreeSet},
+        path::PathBuf,
+        str::FromStr,
+    };
 
     use nargo::package::PackageType;
     use noirc_frontend::graph::CrateName;
@@ -105,6 +109,8 @@ mod tests {
             entry_path: PathBuf::new(),
             name: CrateName::from_str("test").unwrap(),
             dependencies: BTreeMap::new(),
+            dev_dependencies: BTreeMap::new(),
+            features: BTreeSet::new(),
             version: Some("1.0".to_string()),
             expression_width: None,
         };
@@ -141,6 +147,8 @@ mod tests {
             entry_path: PathBuf::new(),
             name: CrateName::from_str("test").unwrap(),
             dependencies: BTreeMap::new(),
+            dev_dependencies: BTreeMap::new(),
+            features: BTreeSet::new(),
             version: Some("1.0".to_string()),
             expression_width: None,
         };
@@ -152,6 +160,8 @@ mod tests {
             entry_path: PathBuf::new(),
             name: CrateName::from_str("good_dependency").unwrap(),
             dependencies: BTreeMap::new(),
+            dev_dependencies: BTreeMap::new(),
+            features: BTreeSet::new(),
             version: Some("1.0".to_string()),
             expression_width: None,
         };
@@ -162,6 +172,8 @@ mod tests {
             entry_path: PathBuf::new(),
             name: CrateName::from_str("bad_dependency").unwrap(),
             dependencies: BTreeMap::new(),
+            dev_dependencies: BTreeMap::new(),
+            features: BTreeSet::new(),
             version: Some("1.0".to_string()),
             expression_width: None,
         };
@@ -207,6 +219,8 @@ mod tests {
             entry_path: PathBuf::new(),
             name: CrateName::from_str("test").unwrap(),
             dependencies: BTreeMap::new(),
+            dev_dependencies: BTreeMap::new(),
+            features: BTreeSet::new(),
             version: Some("1.0".to_string()),
             expression_width: None,
         };
@@ -229,6 +243,8 @@ mod tests {
             entry_path: PathBuf::new(),
             name: CrateName::from_str("test").unwrap(),
             dependencies: BTreeMap::new(),
+            dev_dependencies: BTreeMap::new(),
+            features: BTreeSet::new(),
             version: Some("1.0".to_string()),
             expression_width: None,
         };
@@ -249,6 +265,8 @@ mod tests {
             entry_path: PathBuf::new(),
             name: CrateName::from_str("test").unwrap(),
             dependencies: BTreeMap::new(),
+            dev_dependencies: BTreeMap::new(),
+            features: BTreeSet::new(),
             version: Some("1.0".to_string()),
             expression_width: None,
         };
@@ -50,10 +50,14 @@ impl<F: AcirField> DebugVars<F> {
 
         let params: Vec<&str> =
             debug_fn.arg_names.iter().map(|arg_name| arg_name.as_str()).collect();
-        let vars: Vec<(&str, &PrintableValue<F>, &PrintableType)> = frame
-            .iter()
-            .filter_map(|(var_id, var_value)| {
-                self.lookup_var(*var_id).map(|(name, typ)| (name, var_value, typ))
+        // Variables are listed in the order they were declared, so a shadowed variable
+        // comes before the one shadowing it.
+        let mut var_ids: Vec<&DebugVarId> = frame.keys().collect();
+        var_ids.sort();
+        let vars: Vec<(&str, &PrintableValue<F>, &PrintableType)> = var_ids
+            .into_iter()
+            .filter_map(|var_id| {
+                self.lookup_var(*var_id).map(|(name, typ)| (name, &frame[var_id], typ))
             })
             .collect();
 
@@ -0,0 +1,168 @@
+//! Conversion between the [ForeignCallParam]s of a foreign call and [InputValue]s described by an [AbiType].
+//!
+//! Brillig passes each scalar argument of an oracle as a single value and each array or string as one
+//! array of flattened fields. Structs and tuples are not passed as a unit, each of their fields is passed
+//! as a separate parameter instead. Return values are laid out the same way.
+use acvm::{FieldElement, acir::brillig::ForeignCallParam};
+use noirc_abi::{AbiType, decode_value, input_parser::InputValue};
+
+/// Decode the next value of type `typ` from the foreign call parameters.
+///
+/// Returns `None` if the parameters don't have the shape required by `typ`.
+pub(crate) fn decode_param<'a>(
+    params: &mut impl Iterator<Item = &'a ForeignCallParam<FieldElement>>,
+    typ: &AbiType,
+) -> Option<InputValue> {
+    match typ {
+        AbiType::Field | AbiType::Integer { .. } | AbiType::Boolean => match params.next()? {
+            ForeignCallParam::Single(field) => {
+                let value = InputValue::Field(*field);
+                value.matches_abi(typ).then_some(value)
+            }
+            ForeignCallParam::Array(_) => None,
+        },
+        AbiType::Array { .. } | AbiType::String { .. } => match params.next()? {
+            ForeignCallParam::Array(fields) if fields.len() == typ.field_count() as usize => {
+                let value = decode_value(&mut fields.iter().copied(), typ).ok()?;
+                value.matches_abi(typ).then_some(value)
+            }
+            _ => None,
+        },
+        AbiType::Struct { fields, .. } => {
+            let mut values = std::collections::BTreeMap::new();
+            for (name, typ) in fields {
+                values.insert(name.clone(), decode_param(params, typ)?);
+            }
+            Some(InputValue::Struct(values))
+        }
+        AbiType::Tuple { fields } => {
+            let values =
+                fields.iter().map(|typ| decode_param(params, typ)).collect::<Option<_>>()?;
+            Some(InputValue::Vec(values))
+        }
+    }
+}
+
+/// Encode a value of type `typ` into foreign call parameters.
+///
+/// Returns `None` if the value doesn't match `typ`.
+pub(crate) fn encode_param(
+    value: InputValue,
+    typ: &AbiType,
+) -> Option<Vec<ForeignCallParam<FieldElement>>> {
+    if !value.matches_abi(typ) {
+        return None;
+    }
+    let mut params = Vec::new();
+    push_param(value, typ, &mut params);
+    Some(params)
+}
+
+/// Append the encoding of a value which has already been checked to match `typ`.
+fn push_param(value: InputValue, typ: &AbiType, params: &mut Vec<ForeignCallParam<FieldElement>>) {
+    match (value, typ) {
+        (InputValue::Field(field), _) => params.push(ForeignCallParam::Single(field)),
+        (value, AbiType::Array { .. } | AbiType::String { .. }) => {
+            let mut fields = Vec::new();
+            push_fields(value, typ, &mut fields);
+            params.push(ForeignCallParam::Array(fields));
+        }
+        (InputValue::Struct(mut values), AbiType::Struct { fields, .. }) => {
+            for (name, typ) in fields {
+                let value = values.remove(name).expect("value matches the ABI type");
+                push_param(value, typ, params);
+            }
+        }
+        (InputValue::Vec(values), AbiType::Tuple { fields }) => {
+            for (value, typ) in values.into_iter().zip(fields) {
+                push_param(value, typ, params);
+            }
+        }
+        _ => unreachable!("value should have already been checked to match abi type"),
+    }
+}
+
+/// Append the flattened fields of a value which has already been checked to match `typ`.
+fn push_fields(value: InputValue, typ: &AbiType, fields: &mut Vec<FieldElement>) {
+    match (value, typ) {
+        (InputValue::Field(field), _) => fields.push(field),
+        (InputValue::String(string), _) => {
+            fields.extend(string.bytes().map(|byte| FieldElement::from(u128::from(byte))));
+        }
+        (InputValue::Vec(values), AbiType::Array { typ, .. }) => {
+            for value in values {
+                push_fields(value, typ, fields);
+            }
+        }
+        (InputValue::Struct(mut values), AbiType::Struct { fields: field_types, .. }) => {
+            for (name, typ) in field_types {
+                let value = values.remove(name).expect("value matches the ABI type");
+                push_fields(value, typ, fields);
+            }
+        }
+        (InputValue::Vec(values), AbiType::Tuple { fields: field_types }) => {
+            for (value, typ) in values.into_iter().zip(field_types) {
+                push_fields(value, typ, fields);
+            }
+        }
+        _ => unreachable!("value should have already been checked to match abi type"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use acvm::{FieldElement, acir::brillig::ForeignCallParam};
+    use noirc_abi::{AbiType, Sign, input_parser::InputValue};
+
+    use super::{decode_param, encode_param};
+
+    fn point_type() -> AbiType {
+        AbiType::Struct {
+            path: "foo::Point".to_string(),
+            fields: vec![
+                ("x".to_string(), AbiType::Field),
+                ("y".to_string(), AbiType::Integer { sign: Sign::Unsigned, width: 8 }),
+            ],
+        }
+    }
+
+    #[test]
+    fn structs_are_passed_as_one_parameter_per_field() {
+        let params = vec![
+            ForeignCallParam::Single(FieldElement::from(1_u128)),
+            ForeignCallParam::Single(FieldElement::from(2_u128)),
+        ];
+        let value = decode_param(&mut params.iter(), &point_type()).unwrap();
+        assert_eq!(
+            value,
+            InputValue::Struct(
+                [
+                    ("x".to_string(), InputValue::Field(1_u128.into())),
+                    ("y".to_string(), InputValue::Field(2_u128.into())),
+                ]
+                .into()
+            )
+        );
+        assert_eq!(encode_param(value, &point_type()).unwrap(), params);
+    }
+
+    #[test]
+    fn arrays_of_structs_are_flattened_into_one_parameter() {
+        let typ = AbiType::Array { length: 2, typ: Box::new(point_type()) };
+        let fields = (1_u128..=4).map(FieldElement::from).collect::<Vec<_>>();
+        let params = vec![ForeignCallParam::Array(fields)];
+
+        let value = decode_param(&mut params.iter(), &typ).unwrap();
+        assert_eq!(encode_param(value, &typ).unwrap(), params);
+    }
+
+    #[test]
+    fn rejects_values_outside_of_the_type() {
+        let typ = AbiType::Integer { sign: Sign::Unsigned, width: 8 };
+        let params = vec![ForeignCallParam::Single(FieldElement::from(256_u128))];
+        assert!(decode_param(&mut params.iter(), &typ).is_none());
+
+        let params = vec![ForeignCallParam::Array(vec![FieldElement::from(1_u128)])];
+        assert!(decode_param(&mut params.iter(), &AbiType::Field).is_none());
+    }
+}
@@ -0,0 +1,361 @@
+//! A library for writing external resolvers for the foreign calls of Noir programs.
+//!
+//! Oracles are registered on an [OracleServer] by name, either as typed Rust functions
+//! (see [OracleHandler]) or as dynamic handlers with explicit [AbiType]s. The server
+//! decodes the foreign call parameters into values of the declared types, calls the
+//! handler, and encodes its return value back into a foreign call result.
+//!
+//! The server speaks the same `resolve_foreign_call` JSON-RPC protocol as the resolver
+//! client in `nargo`, and can be served over HTTP, stdio or a Unix domain socket, matching
+//! the `http://`, `stdio:` and `unix:` resolver URLs accepted by `--oracle-resolver`.
+#![forbid(unsafe_code)]
+#![cfg_attr(not(test), warn(unused_crate_dependencies, unused_extern_crates))]
+
+use std::collections::BTreeMap;
+use std::io::{BufRead, Write};
+use std::net::SocketAddr;
+
+use acvm::{
+    FieldElement,
+    acir::brillig::{ForeignCallParam, ForeignCallResult},
+    pwg::ForeignCallWaitInfo,
+};
+use jsonrpsee::{
+    server::{RpcModule, Server, ServerHandle},
+    types::{ErrorCode, ErrorObject, ErrorObjectOwned},
+};
+use noirc_abi::{AbiType, input_parser::InputValue};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+mod codec;
+mod value;
+
+pub use value::{OracleHandler, OracleReturn, OracleValue};
+
+/// The JSON-RPC method used to resolve foreign calls.
+pub const RESOLVE_FOREIGN_CALL: &str = "resolve_foreign_call";
+
+/// The JSON-RPC error code used when an oracle cannot be resolved.
+const ORACLE_ERROR_CODE: i32 = -32000;
+
+/// The parameters of a `resolve_foreign_call` request.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResolveForeignCallRequest {
+    /// A session ID which allows the server to link this foreign call request to other foreign calls
+    /// for the same program execution.
+    pub session_id: u64,
+
+    /// The foreign call which the server is to provide a response for.
+    #[serde(flatten)]
+    pub function_call: ForeignCallWaitInfo<FieldElement>,
+
+    /// Root path to the program or workspace in execution.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub root_path: Option<String>,
+
+    /// Name of the package in execution
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub package_name: Option<String>,
+}
+
+#[derive(Debug, Error)]
+pub enum OracleError {
+    #[error("No handler is registered for oracle `{0}`")]
+    UnknownOracle(String),
+
+    #[error("Arguments of oracle `{name}` do not match the expected types {expected:?}")]
+    ArgumentMismatch { name: String, expected: Vec<AbiType> },
+
+    #[error("Return value of oracle `{name}` does not match the expected type {expected:?}")]
+    ReturnTypeMismatch { name: String, expected: AbiType },
+
+    #[error("Oracle `{name}` failed: {message}")]
+    HandlerFailed { name: String, message: String },
+}
+
+type DynamicHandler = Box<dyn Fn(Vec<InputValue>) -> Result<InputValue, String> + Send + Sync>;
+
+struct RegisteredOracle {
+    parameter_types: Vec<AbiType>,
+    return_type: AbiType,
+    handler: DynamicHandler,
+}
+
+/// A set of oracle handlers, keyed by the name of the oracle.
+#[derive(Default)]
+pub struct OracleServer {
+    oracles: BTreeMap<String, RegisteredOracle>,
+}
+
+impl OracleServer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a typed handler for the oracle with the given name, replacing any previous handler.
+    ///
+    /// The parameter and return types of the oracle are derived from the signature of the handler.
+    pub fn register<Args, H>(self, name: &str, handler: H) -> Self
+    where
+        H: OracleHandler<Args>,
+    {
+        self.register_dynamic(name, H::parameter_types(), H::return_type(), move |arguments| {
+            handler.call(arguments)
+        })
+    }
+
+    /// Register a handler for the oracle with the given name, replacing any previous handler.
+    ///
+    /// The handler receives one value per entry in `parameter_types`, and has to return a value
+    /// of `return_type`. Use a tuple type to return multiple values.
+    pub fn register_dynamic<H>(
+        mut self,
+        name: &str,
+        parameter_types: Vec<AbiType>,
+        return_type: AbiType,
+        handler: H,
+    ) -> Self
+    where
+        H: Fn(Vec<InputValue>) -> Result<InputValue, String> + Send + Sync + 'static,
+    {
+        let oracle = RegisteredOracle { parameter_types, return_type, handler: Box::new(handler) };
+        self.oracles.insert(name.to_string(), oracle);
+        self
+    }
+
+    /// Resolve a single foreign call with the registered handlers.
+    pub fn resolve(
+        &self,
+        foreign_call: &ForeignCallWaitInfo<FieldElement>,
+    ) -> Result<ForeignCallResult<FieldElement>, OracleError> {
+        let name = &foreign_call.function;
+        let oracle =
+            self.oracles.get(name).ok_or_else(|| OracleError::UnknownOracle(name.clone()))?;
+
+        let argument_mismatch = || OracleError::ArgumentMismatch {
+            name: name.clone(),
+            expected: oracle.parameter_types.clone(),
+        };
+        let mut params = foreign_call.inputs.iter();
+        let arguments = oracle
+            .parameter_types
+            .iter()
+            .map(|typ| codec::decode_param(&mut params, typ))
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(argument_mismatch)?;
+        if params.next().is_some() {
+            return Err(argument_mismatch());
+        }
+
+        let return_value = (oracle.handler)(arguments)
+            .map_err(|message| OracleError::HandlerFailed { name: name.clone(), message })?;
+
+        let values: Vec<ForeignCallParam<FieldElement>> =
+            codec::encode_param(return_value, &oracle.return_type).ok_or_else(|| {
+                OracleError::ReturnTypeMismatch {
+                    name: name.clone(),
+                    expected: oracle.return_type.clone(),
+                }
+            })?;
+        Ok(values.into())
+    }
+
+    /// Turn the server into a JSON-RPC module exposing the `resolve_foreign_call` method.
+    pub fn into_rpc_module(self) -> RpcModule<OracleServer> {
+        let mut module = RpcModule::new(self);
+        module
+            .register_method(RESOLVE_FOREIGN_CALL, |params, server, _extensions| {
+                let request: ResolveForeignCallRequest = params.one()?;
+                server.resolve(&request.function_call).map_err(oracle_error_object)
+            })
+            .expect("method is only registered once");
+        module
+    }
+
+    /// Start serving JSON-RPC requests over HTTP, returning the address the server listens on.
+    ///
+    /// Binding to port 0 results in a random port being assigned.
+    pub async fn serve_http(self, addr: SocketAddr) -> std::io::Result<(SocketAddr, ServerHandle)> {
+        let server = Server::builder().build(addr).await?;
+        let addr = server.local_addr()?;
+        Ok((addr, server.start(self.into_rpc_module())))
+    }
+
+    /// Serve newline-delimited JSON-RPC requests from `reader` until it is closed, writing the responses to `writer`.
+    pub fn serve_stream(
+        &self,
+        reader: impl BufRead,
+        mut writer: impl Write,
+    ) -> std::io::Result<()> {
+        for line in reader.lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let response = self.handle_message(&line);
+            writeln!(writer, "{response}")?;
+            writer.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Serve requests over stdin and stdout, for resolvers spawned with a `stdio:` resolver URL.
+    pub fn serve_stdio(&self) -> std::io::Result<()> {
+        self.serve_stream(std::io::stdin().lock(), std::io::stdout().lock())
+    }
+
+    /// Serve requests from every connection accepted on the listener, for `unix:` resolver URLs.
+    #[cfg(unix)]
+    pub fn serve_unix(&self, listener: std::os::unix::net::UnixListener) -> std::io::Result<()> {
+        std::thread::scope(|scope| {
+            for stream in listener.incoming() {
+                let stream = stream?;
+                scope.spawn(move || -> std::io::Result<()> {
+                    let reader = std::io::BufReader::new(stream.try_clone()?);
+                    self.serve_stream(reader, stream)
+                });
+            }
+            Ok(())
+        })
+    }
+
+    /// Handle a single JSON-RPC message, returning the response to it.
+    fn handle_message(&self, message: &str) -> serde_json::Value {
+        #[derive(Deserialize)]
+        struct Request {
+            #[serde(default)]
+            id: serde_json::Value,
+            method: String,
+            #[serde(default)]
+            params: serde_json::Value,
+        }
+
+        let request: Request = match serde_json::from_str(message) {
+            Ok(request) => request,
+            Err(_) => {
+                return error_response(serde_json::Value::Null, ErrorCode::ParseError.into());
+            }
+        };
+        if request.method != RESOLVE_FOREIGN_CALL {
+            return error_response(request.id, ErrorCode::MethodNotFound.into());
+        }
+
+        // Parameters are sent as a single element array by `nargo`, but accept them by name as well.
+        let params = match request.params {
+            serde_json::Value::Array(mut params) if params.len() == 1 => params.remove(0),
+            params => params,
+        };
+        let Ok(params) = serde_json::from_value::<ResolveForeignCallRequest>(params) else {
+            return error_response(request.id, ErrorCode::InvalidParams.into());
+        };
+
+        match self.resolve(&params.function_call) {
+            Ok(result) => {
+                serde_json::json!({ "jsonrpc": "2.0", "id": request.id, "result": result })
+            }
+            Err(error) => error_response(request.id, oracle_error_object(error)),
+        }
+    }
+}
+
+fn oracle_error_object(error: OracleError) -> ErrorObjectOwned {
+    ErrorObject::owned(ORACLE_ERROR_CODE, error.to_string(), None::<()>)
+}
+
+fn error_response(id: serde_json::Value, error: ErrorObjectOwned) -> serde_json::Value {
+    serde_json::json!({ "jsonrpc": "2.0", "id": id, "error": error })
+}
+
+#[cfg(test)]
+mod tests {
+    use acvm::{FieldElement, acir::brillig::ForeignCallParam, pwg::ForeignCallWaitInfo};
+
+    use super::{OracleError, OracleServer};
+
+    fn foreign_call(
+        function: &str,
+        inputs: Vec<ForeignCallParam<FieldElement>>,
+    ) -> ForeignCallWaitInfo<FieldElement> {
+        ForeignCallWaitInfo { function: function.to_string(), inputs }
+    }
+
+    #[test]
+    fn resolves_typed_handlers() {
+        let server = OracleServer::new()
+            .register("add", |a: u32, b: u32| a + b)
+            .register("swap", |(a, b): (bool, FieldElement)| (b, a));
+
+        let result = server
+            .resolve(&foreign_call(
+                "add",
+                vec![FieldElement::from(1_u32).into(), FieldElement::from(2_u32).into()],
+            ))
+            .unwrap();
+        assert_eq!(result, FieldElement::from(3_u32).into());
+
+        // Tuple arguments and return values are flattened into one parameter per element.
+        let result = server
+            .resolve(&foreign_call(
+                "swap",
+                vec![FieldElement::from(true).into(), FieldElement::from(5_u32).into()],
+            ))
+            .unwrap();
+        assert_eq!(
+            result.values,
+            vec![FieldElement::from(5_u32).into(), FieldElement::from(true).into()]
+        );
+    }
+
+    #[test]
+    fn reports_mismatched_arguments() {
+        let server = OracleServer::new().register("double", |a: u8| a.wrapping_mul(2));
+
+        let error = server.resolve(&foreign_call("double", vec![])).unwrap_err();
+        assert!(matches!(error, OracleError::ArgumentMismatch { .. }));
+
+        let error = server
+            .resolve(&foreign_call("double", vec![FieldElement::from(300_u32).into()]))
+            .unwrap_err();
+        assert!(matches!(error, OracleError::ArgumentMismatch { .. }));
+
+        let error = server.resolve(&foreign_call("triple", vec![])).unwrap_err();
+        assert!(matches!(error, OracleError::UnknownOracle(_)));
+    }
+
+    #[test]
+    fn reports_handler_errors() {
+        let server = OracleServer::new()
+            .register("checked_div", |a: u32, b: u32| a.checked_div(b).ok_or("division by zero"));
+
+        let error = server
+            .resolve(&foreign_call(
+                "checked_div",
+                vec![FieldElement::from(1_u32).into(), FieldElement::from(0_u32).into()],
+            ))
+            .unwrap_err();
+        assert_eq!(error.to_string(), "Oracle `checked_div` failed: division by zero");
+    }
+
+    #[test]
+    fn handles_stream_messages() {
+        let server = OracleServer::new().register("one", || FieldElement::from(1_u32));
+
+        let requests = concat!(
+            r#"{"jsonrpc":"2.0","id":0,"method":"resolve_foreign_call","params":[{"session_id":1,"function":"one","inputs":[]}]}"#,
+            "\n",
+            r#"{"jsonrpc":"2.0","id":1,"method":"unknown","params":[]}"#,
+            "\n",
+        );
+        let mut output = Vec::new();
+        server.serve_stream(requests.as_bytes(), &mut output).unwrap();
+
+        let responses = String::from_utf8(output).unwrap();
+        let responses: Vec<serde_json::Value> =
+            responses.lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+        assert_eq!(responses[0]["id"], 0);
+        assert!(responses[0]["result"]["values"].is_array());
+        assert_eq!(responses[1]["id"], 1);
+        assert_eq!(responses[1]["error"]["code"], -32601);
+    }
+}
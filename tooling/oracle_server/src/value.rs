@@ -0,0 +1,238 @@
+//! Typed oracle handlers, converting between Rust values and [InputValue]s.
+use acvm::{AcirField, FieldElement};
+use noirc_abi::{AbiType, Sign, input_parser::InputValue};
+
+/// A Rust type which can be passed to, or returned from, an oracle.
+pub trait OracleValue: Sized {
+    /// The type of the value on the Noir side.
+    fn abi_type() -> AbiType;
+
+    /// Convert from a value which has already been checked to match [OracleValue::abi_type].
+    fn from_input_value(value: InputValue) -> Option<Self>;
+
+    fn into_input_value(self) -> InputValue;
+}
+
+impl OracleValue for FieldElement {
+    fn abi_type() -> AbiType {
+        AbiType::Field
+    }
+
+    fn from_input_value(value: InputValue) -> Option<Self> {
+        match value {
+            InputValue::Field(field) => Some(field),
+            _ => None,
+        }
+    }
+
+    fn into_input_value(self) -> InputValue {
+        InputValue::Field(self)
+    }
+}
+
+impl OracleValue for bool {
+    fn abi_type() -> AbiType {
+        AbiType::Boolean
+    }
+
+    fn from_input_value(value: InputValue) -> Option<Self> {
+        FieldElement::from_input_value(value).map(|field| field.is_one())
+    }
+
+    fn into_input_value(self) -> InputValue {
+        InputValue::Field(FieldElement::from(self))
+    }
+}
+
+macro_rules! impl_oracle_value_for_unsigned {
+    ($($typ:ty),*) => {
+        $(
+            impl OracleValue for $typ {
+                fn abi_type() -> AbiType {
+                    AbiType::Integer { sign: Sign::Unsigned, width: <$typ>::BITS }
+                }
+
+                fn from_input_value(value: InputValue) -> Option<Self> {
+                    FieldElement::from_input_value(value)
+                        .and_then(|field| <$typ>::try_from(field.to_u128()).ok())
+                }
+
+                fn into_input_value(self) -> InputValue {
+                    InputValue::Field(FieldElement::from(self as u128))
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! impl_oracle_value_for_signed {
+    ($($typ:ty => $unsigned:ty),*) => {
+        $(
+            impl OracleValue for $typ {
+                fn abi_type() -> AbiType {
+                    AbiType::Integer { sign: Sign::Signed, width: <$typ>::BITS }
+                }
+
+                /// Signed integers are passed in two's complement form.
+                fn from_input_value(value: InputValue) -> Option<Self> {
+                    FieldElement::from_input_value(value)
+                        .and_then(|field| <$unsigned>::try_from(field.to_u128()).ok())
+                        .map(|value| value as $typ)
+                }
+
+                fn into_input_value(self) -> InputValue {
+                    InputValue::Field(FieldElement::from(self as $unsigned as u128))
+                }
+            }
+        )*
+    };
+}
+
+impl_oracle_value_for_unsigned!(u8, u16, u32, u64);
+impl_oracle_value_for_signed!(i8 => u8, i16 => u16, i32 => u32, i64 => u64);
+
+impl<T: OracleValue, const N: usize> OracleValue for [T; N] {
+    fn abi_type() -> AbiType {
+        AbiType::Array { length: N as u32, typ: Box::new(T::abi_type()) }
+    }
+
+    fn from_input_value(value: InputValue) -> Option<Self> {
+        let InputValue::Vec(values) = value else {
+            return None;
+        };
+        let values = values.into_iter().map(T::from_input_value).collect::<Option<Vec<_>>>()?;
+        values.try_into().ok()
+    }
+
+    fn into_input_value(self) -> InputValue {
+        InputValue::Vec(self.into_iter().map(T::into_input_value).collect())
+    }
+}
+
+macro_rules! impl_oracle_value_for_tuple {
+    ($($name:ident),*) => {
+        impl<$($name: OracleValue),*> OracleValue for ($($name,)*) {
+            fn abi_type() -> AbiType {
+                AbiType::Tuple { fields: vec![$($name::abi_type()),*] }
+            }
+
+            #[allow(non_snake_case, unused_mut, unused_variables)]
+            fn from_input_value(value: InputValue) -> Option<Self> {
+                let InputValue::Vec(values) = value else {
+                    return None;
+                };
+                let mut values = values.into_iter();
+                $(let $name = $name::from_input_value(values.next()?)?;)*
+                Some(($($name,)*))
+            }
+
+            #[allow(non_snake_case)]
+            fn into_input_value(self) -> InputValue {
+                let ($($name,)*) = self;
+                InputValue::Vec(vec![$($name.into_input_value()),*])
+            }
+        }
+    };
+}
+
+impl_oracle_value_for_tuple!();
+impl_oracle_value_for_tuple!(A);
+impl_oracle_value_for_tuple!(A, B);
+impl_oracle_value_for_tuple!(A, B, C);
+impl_oracle_value_for_tuple!(A, B, C, D);
+
+/// The return value of a typed oracle handler, which is either an [OracleValue] or a `Result` of one.
+pub trait OracleReturn {
+    type Value: OracleValue;
+
+    fn into_result(self) -> Result<Self::Value, String>;
+}
+
+impl<T: OracleValue> OracleReturn for T {
+    type Value = T;
+
+    fn into_result(self) -> Result<T, String> {
+        Ok(self)
+    }
+}
+
+impl<T: OracleValue, E: std::fmt::Display> OracleReturn for Result<T, E> {
+    type Value = T;
+
+    fn into_result(self) -> Result<T, String> {
+        self.map_err(|error| error.to_string())
+    }
+}
+
+/// A function which can be registered as an oracle with [crate::OracleServer::register].
+///
+/// This is implemented for functions taking up to four [OracleValue] arguments and returning an [OracleReturn].
+pub trait OracleHandler<Args>: Send + Sync + 'static {
+    fn parameter_types() -> Vec<AbiType>;
+
+    fn return_type() -> AbiType;
+
+    /// Call the handler with arguments which have already been checked to match [OracleHandler::parameter_types].
+    fn call(&self, arguments: Vec<InputValue>) -> Result<InputValue, String>;
+}
+
+macro_rules! impl_oracle_handler {
+    ($($arg:ident),*) => {
+        impl<Func, Ret, $($arg),*> OracleHandler<($($arg,)*)> for Func
+        where
+            Func: Fn($($arg),*) -> Ret + Send + Sync + 'static,
+            Ret: OracleReturn,
+            $($arg: OracleValue,)*
+        {
+            fn parameter_types() -> Vec<AbiType> {
+                vec![$($arg::abi_type()),*]
+            }
+
+            fn return_type() -> AbiType {
+                Ret::Value::abi_type()
+            }
+
+            #[allow(non_snake_case, unused_mut, unused_variables)]
+            fn call(&self, arguments: Vec<InputValue>) -> Result<InputValue, String> {
+                let mut arguments = arguments.into_iter();
+                $(
+                    let $arg = arguments
+                        .next()
+                        .and_then($arg::from_input_value)
+                        .ok_or_else(|| "argument does not match the handler".to_string())?;
+                )*
+                self($($arg),*).into_result().map(OracleValue::into_input_value)
+            }
+        }
+    };
+}
+
+impl_oracle_handler!();
+impl_oracle_handler!(A);
+impl_oracle_handler!(A, B);
+impl_oracle_handler!(A, B, C);
+impl_oracle_handler!(A, B, C, D);
+
+#[cfg(test)]
+mod tests {
+    use acvm::FieldElement;
+    use noirc_abi::input_parser::InputValue;
+
+    use super::OracleValue;
+
+    #[test]
+    fn signed_integers_round_trip_through_twos_complement() {
+        let value = (-2_i8).into_input_value();
+        assert_eq!(value, InputValue::Field(FieldElement::from(254_u128)));
+        assert!(value.matches_abi(&i8::abi_type()));
+        assert_eq!(i8::from_input_value(value), Some(-2));
+    }
+
+    #[test]
+    fn arrays_and_tuples_round_trip() {
+        let value = ([1_u32, 2, 3], true);
+        let input_value = value.into_input_value();
+        assert!(input_value.matches_abi(&<([u32; 3], bool)>::abi_type()));
+        assert_eq!(<([u32; 3], bool)>::from_input_value(input_value), Some(value));
+    }
+}
@@ -0,0 +1,70 @@
+//! Resolve foreign calls with `nargo`'s own RPC client against an in-process [OracleServer].
+use acvm::{
+    FieldElement,
+    acir::brillig::{ForeignCallParam, ForeignCallResult},
+    pwg::ForeignCallWaitInfo,
+};
+use nargo::foreign_calls::{ForeignCallError, ForeignCallExecutor, rpc::RPCForeignCallExecutor};
+use noir_oracle_server::OracleServer;
+
+fn oracle_server() -> OracleServer {
+    OracleServer::new()
+        .register("sum", |values: [u32; 3]| values.iter().sum::<u32>())
+        .register("checked_div", |a: u32, b: u32| a.checked_div(b).ok_or("division by zero"))
+}
+
+fn sum_call() -> ForeignCallWaitInfo<FieldElement> {
+    let values = vec![1_u32, 2, 3].into_iter().map(FieldElement::from).collect::<Vec<_>>();
+    ForeignCallWaitInfo {
+        function: "sum".to_string(),
+        inputs: vec![ForeignCallParam::Array(values)],
+    }
+}
+
+/// Execute a foreign call with a fresh executor on a blocking thread,
+/// as the executor blocks on its own runtime.
+async fn execute(
+    url: String,
+    foreign_call: ForeignCallWaitInfo<FieldElement>,
+) -> Result<ForeignCallResult<FieldElement>, ForeignCallError> {
+    tokio::task::spawn_blocking(move || {
+        let mut executor = RPCForeignCallExecutor::new(&url, 1, None, None);
+        executor.execute(&foreign_call)
+    })
+    .await
+    .expect("executor should not panic")
+}
+
+#[tokio::test]
+async fn resolves_foreign_calls_over_http() {
+    let (addr, _handle) = oracle_server().serve_http("127.0.0.1:0".parse().unwrap()).await.unwrap();
+    let url = format!("http://{addr}");
+
+    let result = execute(url.clone(), sum_call()).await.unwrap();
+    assert_eq!(result, FieldElement::from(6_u32).into());
+
+    let foreign_call = ForeignCallWaitInfo {
+        function: "checked_div".to_string(),
+        inputs: vec![FieldElement::from(1_u32).into(), FieldElement::from(0_u32).into()],
+    };
+    let error = execute(url.clone(), foreign_call).await.unwrap_err();
+    assert!(error.to_string().contains("division by zero"));
+
+    let foreign_call = ForeignCallWaitInfo { function: "unknown".to_string(), inputs: vec![] };
+    let error = execute(url, foreign_call).await.unwrap_err();
+    assert!(error.to_string().contains("No handler is registered for oracle `unknown`"));
+}
+
+#[cfg(unix)]
+#[test]
+fn resolves_foreign_calls_over_unix_socket() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("oracle.sock");
+    let listener = std::os::unix::net::UnixListener::bind(&path).unwrap();
+    std::thread::spawn(move || oracle_server().serve_unix(listener));
+
+    let mut executor =
+        RPCForeignCallExecutor::new(&format!("unix:{}", path.display()), 2, None, None);
+    let result = executor.execute(&sum_call()).unwrap();
+    assert_eq!(result, FieldElement::from(6_u32).into());
+}
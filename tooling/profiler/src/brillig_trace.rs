@@ -0,0 +1,182 @@
+use std::time::{Duration, Instant};
+
+use acir::circuit::brillig::BrilligFunctionId;
+use acir::circuit::{OpcodeLocation, Program};
+use acir::native_types::WitnessMap;
+use acir::{AcirField, FieldElement};
+use acvm::BlackBoxFunctionSolver;
+use acvm::brillig_vm::MemoryValue;
+use acvm::pwg::{
+    ACVM, ACVMStatus, BrilligSolver, BrilligSolverStatus, ErrorLocation, OpcodeResolutionError,
+    StepResult,
+};
+use nargo::NargoError;
+use nargo::errors::{ResolvedOpcodeLocation, execution_error_from};
+use nargo::foreign_calls::ForeignCallExecutor;
+use noirc_evaluator::brillig::free_memory_pointer_address;
+
+/// A Brillig opcode executed by the VM, and what it cost.
+#[derive(Debug, Clone)]
+pub(crate) struct BrilligTraceStep {
+    /// The Brillig function the opcode belongs to.
+    pub(crate) brillig_function_id: BrilligFunctionId,
+    /// The location of the ACIR call to the Brillig function, followed by the Brillig call stack,
+    /// which ends with the executed opcode.
+    pub(crate) call_stack: Vec<OpcodeLocation>,
+    /// The wall-clock time it took to execute the opcode, including any foreign call it made.
+    pub(crate) time: Duration,
+    /// How many memory slots the opcode allocated on the heap.
+    pub(crate) heap_allocated: usize,
+    /// The name of the foreign call the opcode made, if it made one.
+    pub(crate) foreign_call: Option<String>,
+}
+
+/// Executes a program one Brillig opcode at a time, recording every opcode that gets executed.
+///
+/// ACIR opcodes are solved as usual, but aren't recorded. Programs made of more than one ACIR
+/// function aren't supported.
+pub(crate) fn trace_brillig_execution<B, E>(
+    program: &Program<FieldElement>,
+    initial_witness: WitnessMap<FieldElement>,
+    blackbox_solver: &B,
+    foreign_call_executor: &mut E,
+) -> Result<Vec<BrilligTraceStep>, NargoError<FieldElement>>
+where
+    B: BlackBoxFunctionSolver<FieldElement>,
+    E: ForeignCallExecutor<FieldElement>,
+{
+    let circuit = &program.functions[0];
+    let mut acvm = ACVM::new(
+        blackbox_solver,
+        &circuit.opcodes,
+        initial_witness,
+        &program.unconstrained_functions,
+        &circuit.assert_messages,
+    );
+
+    let mut steps = Vec::new();
+    loop {
+        let status = match acvm.step_into_brillig() {
+            StepResult::IntoBrillig(solver) => {
+                let acir_index = acvm.instruction_pointer();
+                let solver =
+                    trace_brillig_call(solver, acir_index, foreign_call_executor, &mut steps)?;
+                acvm.finish_brillig_with_solver(solver)
+            }
+            StepResult::Status(status) => status,
+        };
+
+        match status {
+            ACVMStatus::Solved => break,
+            ACVMStatus::InProgress => (),
+            ACVMStatus::Failure(error) => {
+                return Err(execution_error(error, acvm.instruction_pointer()));
+            }
+            ACVMStatus::RequiresForeignCall(foreign_call) => {
+                let foreign_call_result = foreign_call_executor.execute(&foreign_call)?;
+                acvm.resolve_pending_foreign_call(foreign_call_result);
+            }
+            ACVMStatus::RequiresAcirCall(_) => {
+                unreachable!("Programs with more than one ACIR function can't be traced")
+            }
+        }
+    }
+
+    Ok(steps)
+}
+
+/// Steps through a Brillig call until it finishes, returning the solver so the ACVM can
+/// write its outputs.
+fn trace_brillig_call<'a, B, E>(
+    mut solver: BrilligSolver<'a, FieldElement, B>,
+    acir_index: usize,
+    foreign_call_executor: &mut E,
+    steps: &mut Vec<BrilligTraceStep>,
+) -> Result<BrilligSolver<'a, FieldElement, B>, NargoError<FieldElement>>
+where
+    B: BlackBoxFunctionSolver<FieldElement>,
+    E: ForeignCallExecutor<FieldElement>,
+{
+    let brillig_function_id = solver.function_id;
+    loop {
+        let call_stack = std::iter::once(OpcodeLocation::Acir(acir_index))
+            .chain(
+                solver
+                    .get_call_stack()
+                    .into_iter()
+                    .map(|brillig_index| OpcodeLocation::Brillig { acir_index, brillig_index }),
+            )
+            .collect();
+        let free_memory_before = free_memory_pointer(solver.get_memory());
+
+        let start = Instant::now();
+        let mut foreign_call = None;
+        // The VM stops at a foreign call until it gets its result, and then executes the
+        // same opcode again, so both steps are recorded as a single one.
+        let status = loop {
+            match solver.step() {
+                Ok(BrilligSolverStatus::ForeignCallWait(foreign_call_wait_info)) => {
+                    let foreign_call_result =
+                        foreign_call_executor.execute(&foreign_call_wait_info)?;
+                    solver.resolve_pending_foreign_call(foreign_call_result);
+                    foreign_call = Some(foreign_call_wait_info.function);
+                }
+                status => break status,
+            }
+        };
+        let time = start.elapsed();
+
+        let free_memory_after = free_memory_pointer(solver.get_memory());
+        steps.push(BrilligTraceStep {
+            brillig_function_id,
+            call_stack,
+            time,
+            heap_allocated: free_memory_after.saturating_sub(free_memory_before),
+            foreign_call,
+        });
+
+        match status {
+            Ok(BrilligSolverStatus::InProgress) => (),
+            Ok(BrilligSolverStatus::Finished) => return Ok(solver),
+            Ok(BrilligSolverStatus::ForeignCallWait(_)) => {
+                unreachable!("Foreign calls are resolved above")
+            }
+            Err(error) => return Err(execution_error(error, acir_index)),
+        }
+    }
+}
+
+/// Reads the free memory pointer, which is bumped whenever memory is allocated on the heap.
+fn free_memory_pointer<F: AcirField>(memory: &[MemoryValue<F>]) -> usize {
+    match memory.get(free_memory_pointer_address().to_usize()) {
+        Some(MemoryValue::U32(pointer)) => *pointer as usize,
+        _ => 0,
+    }
+}
+
+fn execution_error(
+    error: OpcodeResolutionError<FieldElement>,
+    acir_index: usize,
+) -> NargoError<FieldElement> {
+    let call_stack: Vec<_> = match &error {
+        OpcodeResolutionError::UnsatisfiedConstrain {
+            opcode_location: ErrorLocation::Resolved(opcode_location),
+            ..
+        }
+        | OpcodeResolutionError::IndexOutOfBounds {
+            opcode_location: ErrorLocation::Resolved(opcode_location),
+            ..
+        }
+        | OpcodeResolutionError::InvalidInputBitSize {
+            opcode_location: ErrorLocation::Resolved(opcode_location),
+            ..
+        } => vec![*opcode_location],
+        OpcodeResolutionError::BrilligFunctionFailed { call_stack, .. } => call_stack.clone(),
+        _ => vec![OpcodeLocation::Acir(acir_index)],
+    };
+    let call_stack: Vec<_> = call_stack
+        .into_iter()
+        .map(|opcode_location| ResolvedOpcodeLocation { acir_function_index: 0, opcode_location })
+        .collect();
+    NargoError::ExecutionError(execution_error_from(error, &call_stack))
+}
@@ -11,7 +11,9 @@ use noir_artifact_cli::fs::inputs::read_inputs_from_file;
 use noirc_artifacts::program::ProgramArtifact;
 
 use crate::errors::{CliError, report_error};
-use crate::flamegraph::{BrilligExecutionSample, FlamegraphGenerator, InfernoFlamegraphGenerator};
+use crate::flamegraph::{
+    BrilligExecutionSample, FlamegraphGenerator, ProfileFormat, ProfileGenerator,
+};
 use crate::opcode_formatter::format_brillig_opcode;
 use bn254_blackbox_solver::Bn254BlackBoxSolver;
 use noirc_artifacts::debug::DebugArtifact;
@@ -27,10 +29,14 @@ pub(crate) struct ExecutionFlamegraphCommand {
     #[clap(long, short)]
     prover_toml_path: PathBuf,
 
-    /// The output folder for the flamegraph svg files
+    /// The output folder for the flamegraph files
     #[clap(long, short)]
     output: Option<PathBuf>,
 
+    /// The format of the generated profiles
+    #[clap(long, default_value_t = ProfileFormat::Svg)]
+    format: ProfileFormat,
+
     /// Use pedantic ACVM solving, i.e. double-check some black-box function
     /// assumptions when solving.
     /// This is disabled by default.
@@ -51,7 +57,7 @@ pub(crate) fn run(args: ExecutionFlamegraphCommand) -> eyre::Result<()> {
     run_with_generator(
         &args.artifact_path,
         &args.prover_toml_path,
-        &InfernoFlamegraphGenerator { count_name: "samples".to_string() },
+        &ProfileGenerator { count_name: "samples".to_string(), format: args.format },
         &args.output,
         args.pedantic_solving,
         args.sample_count,
@@ -159,7 +165,11 @@ fn run_with_generator(
         &debug_artifact,
         artifact_path.to_str().unwrap(),
         "main",
-        &Path::new(output_path).join(Path::new(&format!("{}_brillig_trace.svg", "main"))),
+        &Path::new(output_path).join(Path::new(&format!(
+            "{}_brillig_trace.{}",
+            "main",
+            flamegraph_generator.file_extension()
+        ))),
     )?;
 
     if verbose {
@@ -219,6 +229,10 @@ mod tests {
 
             Ok(())
         }
+
+        fn file_extension(&self) -> &'static str {
+            "svg"
+        }
     }
 
     #[test]
@@ -0,0 +1,177 @@
+use std::path::{Path, PathBuf};
+
+use clap::Args;
+use color_eyre::eyre::{self, Context};
+use nargo::errors::try_to_diagnose_runtime_error;
+use nargo::foreign_calls::DefaultForeignCallBuilder;
+use noir_artifact_cli::fs::artifact::read_program_from_file;
+use noir_artifact_cli::fs::inputs::read_inputs_from_file;
+use noirc_artifacts::debug::DebugArtifact;
+
+use crate::brillig_trace::trace_brillig_execution;
+use crate::errors::{CliError, report_error};
+use crate::trace_report::TraceResolver;
+use bn254_blackbox_solver::Bn254BlackBoxSolver;
+
+/// Generates a report of the time, heap allocations, array copies and foreign calls
+/// of each unconstrained function executed by a Noir program.
+#[derive(Debug, Clone, Args)]
+pub(crate) struct ExecutionTraceCommand {
+    /// The path to the artifact JSON file
+    #[clap(long, short)]
+    artifact_path: PathBuf,
+
+    /// The path to the Prover.toml file
+    #[clap(long, short)]
+    prover_toml_path: PathBuf,
+
+    /// The output folder for the JSON report and the speedscope profile
+    #[clap(long, short)]
+    output: PathBuf,
+
+    /// Use pedantic ACVM solving, i.e. double-check some black-box function
+    /// assumptions when solving.
+    /// This is disabled by default.
+    #[clap(long, default_value = "false")]
+    pedantic_solving: bool,
+
+    /// Enables additional logging
+    #[clap(long, default_value = "false")]
+    verbose: bool,
+}
+
+pub(crate) fn run(args: ExecutionTraceCommand) -> eyre::Result<()> {
+    run_trace(
+        &args.artifact_path,
+        &args.prover_toml_path,
+        &args.output,
+        args.pedantic_solving,
+        args.verbose,
+    )
+}
+
+fn run_trace(
+    artifact_path: &Path,
+    prover_toml_path: &Path,
+    output_path: &Path,
+    pedantic_solving: bool,
+    verbose: bool,
+) -> eyre::Result<()> {
+    let program =
+        read_program_from_file(artifact_path).context("Error reading program from file")?;
+
+    if program.bytecode.functions.len() != 1 {
+        return report_error("Command only supports programs made of a single ACIR function")
+            .map_err(Into::into);
+    }
+
+    let (inputs_map, _) =
+        read_inputs_from_file(&prover_toml_path.with_extension("toml"), &program.abi)?;
+
+    let initial_witness = program.abi.encode(&inputs_map, None)?;
+
+    if verbose {
+        println!("Executing...");
+    }
+
+    let steps = match trace_brillig_execution(
+        &program.bytecode,
+        initial_witness,
+        &Bn254BlackBoxSolver(pedantic_solving),
+        &mut DefaultForeignCallBuilder::default().with_output(std::io::stdout()).build(),
+    ) {
+        Ok(steps) => steps,
+        Err(err) => {
+            let debug_artifact = DebugArtifact {
+                debug_symbols: program.debug_symbols.debug_infos.clone(),
+                file_map: program.file_map.clone(),
+            };
+
+            if let Some(diagnostic) = try_to_diagnose_runtime_error(
+                &err,
+                &program.abi,
+                &program.debug_symbols.debug_infos,
+            ) {
+                diagnostic.report(&debug_artifact, false);
+            }
+
+            return Err(CliError::Generic.into());
+        }
+    };
+
+    if verbose {
+        println!("Executed");
+        // Mapping a large trace back to the source code can take some time.
+        println!("Generating report for {} Brillig opcodes...", steps.len());
+    }
+
+    let brillig_names = program.brillig_names.clone();
+    let debug_artifact: DebugArtifact = program.into();
+    if debug_artifact.debug_symbols[0].function_names.is_empty() {
+        println!(
+            "Note: functions are named after the code that calls them. Compile the program with `--record-function-names` to name them after their definitions."
+        );
+    }
+    let mut resolver =
+        TraceResolver::new(&debug_artifact.debug_symbols[0], &brillig_names, &debug_artifact);
+    let report = resolver.execution_report(&steps);
+    let profile = resolver.speedscope_profile(&steps, "main");
+
+    std::fs::create_dir_all(output_path)?;
+    let report_path = output_path.join("main_execution_trace.json");
+    std::fs::write(&report_path, serde_json::to_string_pretty(&report)?)?;
+    let profile_path = output_path.join("main_execution_trace.speedscope.json");
+    std::fs::write(&profile_path, serde_json::to_string(&profile)?)?;
+
+    if verbose {
+        println!("Wrote {} and {}", report_path.display(), profile_path.display());
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use acir::circuit::{Circuit, Program};
+    use noirc_artifacts::program::ProgramArtifact;
+    use noirc_driver::CrateName;
+    use noirc_errors::debug_info::{DebugInfo, ProgramDebugInfo};
+    use std::{collections::BTreeMap, str::FromStr};
+
+    #[test]
+    fn error_reporter_smoke_test() {
+        // This test purposefully uses an artifact made of more than one ACIR function.
+        // The goal is to see that our program fails gracefully and does not panic.
+        let temp_dir = tempfile::tempdir().unwrap();
+
+        let prover_toml_path = temp_dir.path().join("Prover.toml");
+
+        let artifact = ProgramArtifact {
+            noir_version: "0.0.0".to_string(),
+            hash: 27,
+            abi: noirc_abi::Abi::default(),
+            bytecode: Program {
+                functions: vec![Circuit::default(), Circuit::default()],
+                unconstrained_functions: Vec::new(),
+            },
+            debug_symbols: ProgramDebugInfo {
+                debug_infos: vec![DebugInfo::default(), DebugInfo::default()],
+            },
+            file_map: BTreeMap::default(),
+            names: vec!["main".to_string(), "foo".to_string()],
+            brillig_names: Vec::new(),
+        };
+
+        let artifact_path = noir_artifact_cli::fs::artifact::save_program_to_file(
+            &artifact,
+            &CrateName::from_str("test").unwrap(),
+            temp_dir.path(),
+        )
+        .unwrap();
+
+        assert!(
+            super::run_trace(&artifact_path, &prover_toml_path, temp_dir.path(), false, false)
+                .is_err()
+        );
+    }
+}
@@ -7,7 +7,7 @@ use color_eyre::eyre::{self, Context};
 use noir_artifact_cli::fs::artifact::read_program_from_file;
 use noirc_artifacts::debug::DebugArtifact;
 
-use crate::flamegraph::{CompilationSample, FlamegraphGenerator, InfernoFlamegraphGenerator};
+use crate::flamegraph::{CompilationSample, FlamegraphGenerator, ProfileFormat, ProfileGenerator};
 use crate::gates_provider::{BackendGatesProvider, GatesProvider};
 use crate::opcode_formatter::format_acir_opcode;
 
@@ -30,13 +30,17 @@ pub(crate) struct GatesFlamegraphCommand {
     #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
     backend_extra_args: Vec<String>,
 
-    /// The output folder for the flamegraph svg files
+    /// The output folder for the flamegraph files
     #[clap(long, short)]
     output: PathBuf,
 
-    /// The output name for the flamegraph svg files
+    /// The output name for the flamegraph files
     #[clap(long, short = 'f')]
     output_filename: Option<String>,
+
+    /// The format of the generated profiles
+    #[clap(long, default_value_t = ProfileFormat::Svg)]
+    format: ProfileFormat,
 }
 
 pub(crate) fn run(args: GatesFlamegraphCommand) -> eyre::Result<()> {
@@ -47,7 +51,7 @@ pub(crate) fn run(args: GatesFlamegraphCommand) -> eyre::Result<()> {
             gates_command: args.backend_gates_command,
             extra_args: args.backend_extra_args,
         },
-        &InfernoFlamegraphGenerator { count_name: "gates".to_string() },
+        &ProfileGenerator { count_name: "gates".to_string(), format: args.format },
         &args.output,
         args.output_filename,
     )
@@ -105,10 +109,12 @@ fn run_with_provider<Provider: GatesProvider, Generator: FlamegraphGenerator>(
             .collect();
 
         let output_filename = if let Some(output_filename) = &output_filename {
-            format!("{}_{}_gates.svg", output_filename, function_name)
+            format!("{}_{}_gates", output_filename, function_name)
         } else {
-            format!("{}_gates.svg", function_name)
+            format!("{}_gates", function_name)
         };
+        let output_filename =
+            format!("{}.{}", output_filename, flamegraph_generator.file_extension());
         flamegraph_generator.generate_flamegraph(
             samples,
             &debug_artifact.debug_symbols[func_idx],
@@ -173,6 +179,10 @@ mod tests {
 
             Ok(())
         }
+
+        fn file_extension(&self) -> &'static str {
+            "svg"
+        }
     }
 
     #[test]
@@ -3,6 +3,7 @@ use color_eyre::eyre::{self};
 use const_format::formatcp;
 
 mod execution_flamegraph_cmd;
+mod execution_trace_cmd;
 mod gates_flamegraph_cmd;
 mod opcodes_flamegraph_cmd;
 
@@ -23,6 +24,7 @@ enum ProfilerCommand {
     Gates(gates_flamegraph_cmd::GatesFlamegraphCommand),
     Opcodes(opcodes_flamegraph_cmd::OpcodesFlamegraphCommand),
     ExecutionOpcodes(execution_flamegraph_cmd::ExecutionFlamegraphCommand),
+    ExecutionTrace(execution_trace_cmd::ExecutionTraceCommand),
 }
 
 pub(crate) fn start_cli() -> eyre::Result<()> {
@@ -32,6 +34,7 @@ pub(crate) fn start_cli() -> eyre::Result<()> {
         ProfilerCommand::Gates(args) => gates_flamegraph_cmd::run(args),
         ProfilerCommand::Opcodes(args) => opcodes_flamegraph_cmd::run(args),
         ProfilerCommand::ExecutionOpcodes(args) => execution_flamegraph_cmd::run(args),
+        ProfilerCommand::ExecutionTrace(args) => execution_trace_cmd::run(args),
     }?;
 
     Ok(())
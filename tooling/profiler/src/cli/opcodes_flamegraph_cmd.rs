@@ -9,7 +9,7 @@ use color_eyre::eyre::{self, Context};
 use noir_artifact_cli::fs::artifact::read_program_from_file;
 use noirc_artifacts::debug::DebugArtifact;
 
-use crate::flamegraph::{CompilationSample, FlamegraphGenerator, InfernoFlamegraphGenerator};
+use crate::flamegraph::{CompilationSample, FlamegraphGenerator, ProfileFormat, ProfileGenerator};
 use crate::opcode_formatter::{format_acir_opcode, format_brillig_opcode};
 
 /// Generates a flamegraph mapping ACIR opcodes to their associated locations in the source code.
@@ -19,10 +19,14 @@ pub(crate) struct OpcodesFlamegraphCommand {
     #[clap(long, short)]
     artifact_path: PathBuf,
 
-    /// The output folder for the flamegraph svg files
+    /// The output folder for the flamegraph files
     #[clap(long, short)]
     output: PathBuf,
 
+    /// The format of the generated profiles
+    #[clap(long, default_value_t = ProfileFormat::Svg)]
+    format: ProfileFormat,
+
     /// Whether to skip brillig functions
     #[clap(long, short, action)]
     skip_brillig: bool,
@@ -31,7 +35,7 @@ pub(crate) struct OpcodesFlamegraphCommand {
 pub(crate) fn run(args: OpcodesFlamegraphCommand) -> eyre::Result<()> {
     run_with_generator(
         &args.artifact_path,
-        &InfernoFlamegraphGenerator { count_name: "opcodes".to_string() },
+        &ProfileGenerator { count_name: "opcodes".to_string(), format: args.format },
         &args.output,
         args.skip_brillig,
     )
@@ -82,8 +86,11 @@ fn run_with_generator<Generator: FlamegraphGenerator>(
             &debug_artifact,
             artifact_path.to_str().unwrap(),
             &function_name,
-            &Path::new(&output_path)
-                .join(Path::new(&format!("{}_acir_opcodes.svg", &function_name))),
+            &Path::new(&output_path).join(Path::new(&format!(
+                "{}_acir_opcodes.{}",
+                &function_name,
+                flamegraph_generator.file_extension()
+            ))),
         )?;
     }
 
@@ -127,8 +134,11 @@ fn run_with_generator<Generator: FlamegraphGenerator>(
             &debug_artifact,
             artifact_path.to_str().unwrap(),
             &function_name,
-            &Path::new(&output_path)
-                .join(Path::new(&format!("{}_brillig_opcodes.svg", function_name))),
+            &Path::new(&output_path).join(Path::new(&format!(
+                "{}_brillig_opcodes.{}",
+                function_name,
+                flamegraph_generator.file_extension()
+            ))),
         )?;
     }
 
@@ -188,6 +198,10 @@ mod tests {
 
             Ok(())
         }
+
+        fn file_extension(&self) -> &'static str {
+            "svg"
+        }
     }
 
     #[test]
@@ -1,3 +1,4 @@
+use std::fmt::Display;
 use std::path::Path;
 use std::{collections::BTreeMap, io::BufWriter};
 
@@ -12,6 +13,9 @@ use noirc_errors::debug_info::DebugInfo;
 use noirc_errors::reporter::line_and_column_from_span;
 use noirc_evaluator::brillig::ProcedureId;
 
+use crate::pprof::pprof_from_folded_lines;
+use crate::speedscope::speedscope_from_folded_lines;
+
 pub(crate) trait Sample {
     fn count(&self) -> usize;
 
@@ -89,13 +93,43 @@ pub(crate) trait FlamegraphGenerator {
         function_name: &str,
         output_path: &Path,
     ) -> eyre::Result<()>;
+
+    /// The extension of the files written by the generator.
+    fn file_extension(&self) -> &'static str;
 }
 
-pub(crate) struct InfernoFlamegraphGenerator {
+#[derive(Debug, Copy, Clone, Default, clap::ValueEnum)]
+pub(crate) enum ProfileFormat {
+    /// An interactive flamegraph (`.svg`)
+    #[default]
+    Svg,
+    /// A profile that can be opened in <https://www.speedscope.app> (`.speedscope.json`)
+    Speedscope,
+    /// A gzipped protobuf profile that can be opened with pprof (`.pb.gz`)
+    Pprof,
+    /// One line per stack, in the format read by inferno and flamegraph.pl (`.folded`)
+    Collapsed,
+}
+
+impl Display for ProfileFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ProfileFormat::Svg => write!(f, "svg"),
+            ProfileFormat::Speedscope => write!(f, "speedscope"),
+            ProfileFormat::Pprof => write!(f, "pprof"),
+            ProfileFormat::Collapsed => write!(f, "collapsed"),
+        }
+    }
+}
+
+/// Writes the samples in any of the supported formats. All of them are built from the same
+/// folded stacks, so they show the same data.
+pub(crate) struct ProfileGenerator {
     pub(crate) count_name: String,
+    pub(crate) format: ProfileFormat,
 }
 
-impl FlamegraphGenerator for InfernoFlamegraphGenerator {
+impl FlamegraphGenerator for ProfileGenerator {
     fn generate_flamegraph<'files, S: Sample>(
         &self,
         samples: Vec<S>,
@@ -106,27 +140,57 @@ impl FlamegraphGenerator for InfernoFlamegraphGenerator {
         output_path: &Path,
     ) -> eyre::Result<()> {
         let folded_lines = generate_folded_sorted_lines(samples, debug_symbols, files);
-        let flamegraph_file = std::fs::File::create(output_path)?;
-        let flamegraph_writer = BufWriter::new(flamegraph_file);
-
-        let mut options = Options::default();
-        options.hash = true;
-        options.deterministic = true;
-        options.title = format!("Artifact: {}, Function: {}", artifact_name, function_name);
-        options.frame_height = 24;
-        options.color_diffusion = true;
-        options.min_width = 0.0;
-        options.count_name = self.count_name.clone();
-        options.text_truncate_direction = TextTruncateDirection::Right;
-
-        from_lines(
-            &mut options,
-            folded_lines.iter().map(|as_string| as_string.as_str()),
-            flamegraph_writer,
-        )?;
+
+        match self.format {
+            ProfileFormat::Svg => {
+                let flamegraph_file = std::fs::File::create(output_path)?;
+                let flamegraph_writer = BufWriter::new(flamegraph_file);
+
+                let mut options = Options::default();
+                options.hash = true;
+                options.deterministic = true;
+                options.title = format!("Artifact: {}, Function: {}", artifact_name, function_name);
+                options.frame_height = 24;
+                options.color_diffusion = true;
+                options.min_width = 0.0;
+                options.count_name = self.count_name.clone();
+                options.text_truncate_direction = TextTruncateDirection::Right;
+
+                from_lines(
+                    &mut options,
+                    folded_lines.iter().map(|as_string| as_string.as_str()),
+                    flamegraph_writer,
+                )?;
+            }
+            ProfileFormat::Speedscope => {
+                let profile =
+                    speedscope_from_folded_lines(&folded_lines, function_name, &self.count_name);
+                std::fs::write(output_path, serde_json::to_string(&profile)?)?;
+            }
+            ProfileFormat::Pprof => {
+                std::fs::write(
+                    output_path,
+                    pprof_from_folded_lines(&folded_lines, &self.count_name)?,
+                )?;
+            }
+            ProfileFormat::Collapsed => {
+                let mut collapsed = folded_lines.join("\n");
+                collapsed.push('\n');
+                std::fs::write(output_path, collapsed)?;
+            }
+        }
 
         Ok(())
     }
+
+    fn file_extension(&self) -> &'static str {
+        match self.format {
+            ProfileFormat::Svg => "svg",
+            ProfileFormat::Speedscope => "speedscope.json",
+            ProfileFormat::Pprof => "pb.gz",
+            ProfileFormat::Collapsed => "folded",
+        }
+    }
 }
 
 fn generate_folded_sorted_lines<'files, S: Sample>(
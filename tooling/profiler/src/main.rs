@@ -3,11 +3,15 @@
 #![warn(clippy::semicolon_if_nothing_returned)]
 #![cfg_attr(not(test), warn(unused_crate_dependencies, unused_extern_crates))]
 
+mod brillig_trace;
 mod cli;
 mod errors;
 mod flamegraph;
 mod gates_provider;
 mod opcode_formatter;
+mod pprof;
+mod speedscope;
+mod trace_report;
 
 use std::env;
 
@@ -0,0 +1,152 @@
+//! Profiles in the protobuf format read by [pprof](https://github.com/google/pprof).
+//!
+//! Only the parts of `profile.proto` needed to describe stacks of source locations are included.
+
+use std::collections::HashMap;
+use std::io::Write;
+
+use flate2::Compression;
+use flate2::write::GzEncoder;
+use prost::Message;
+
+#[derive(Clone, PartialEq, Message)]
+struct Profile {
+    #[prost(message, repeated, tag = "1")]
+    sample_type: Vec<ValueType>,
+    #[prost(message, repeated, tag = "2")]
+    sample: Vec<Sample>,
+    #[prost(message, repeated, tag = "4")]
+    location: Vec<Location>,
+    #[prost(message, repeated, tag = "5")]
+    function: Vec<Function>,
+    /// Every string in the profile is an index into this table, whose first entry must be empty.
+    #[prost(string, repeated, tag = "6")]
+    string_table: Vec<String>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct ValueType {
+    #[prost(int64, tag = "1")]
+    r#type: i64,
+    #[prost(int64, tag = "2")]
+    unit: i64,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct Sample {
+    /// The leaf location comes first.
+    #[prost(uint64, repeated, tag = "1")]
+    location_id: Vec<u64>,
+    #[prost(int64, repeated, tag = "2")]
+    value: Vec<i64>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct Location {
+    #[prost(uint64, tag = "1")]
+    id: u64,
+    #[prost(message, repeated, tag = "4")]
+    line: Vec<Line>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct Line {
+    #[prost(uint64, tag = "1")]
+    function_id: u64,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct Function {
+    #[prost(uint64, tag = "1")]
+    id: u64,
+    #[prost(int64, tag = "2")]
+    name: i64,
+}
+
+#[derive(Default)]
+struct ProfileBuilder {
+    profile: Profile,
+    strings: HashMap<String, i64>,
+    locations: HashMap<String, u64>,
+}
+
+impl ProfileBuilder {
+    fn string(&mut self, string: &str) -> i64 {
+        if let Some(index) = self.strings.get(string) {
+            return *index;
+        }
+        let index = self.profile.string_table.len() as i64;
+        self.profile.string_table.push(string.to_string());
+        self.strings.insert(string.to_string(), index);
+        index
+    }
+
+    /// Each frame gets a function and a location with the same id, as frames have no addresses.
+    fn location(&mut self, frame: &str) -> u64 {
+        if let Some(id) = self.locations.get(frame) {
+            return *id;
+        }
+        let id = self.profile.location.len() as u64 + 1;
+        let name = self.string(frame);
+        self.profile.function.push(Function { id, name });
+        self.profile.location.push(Location { id, line: vec![Line { function_id: id }] });
+        self.locations.insert(frame.to_string(), id);
+        id
+    }
+}
+
+/// Builds a gzipped pprof profile out of stacks in the collapsed format, `frame;frame;frame count`.
+pub(crate) fn pprof_from_folded_lines(
+    folded_lines: &[String],
+    count_name: &str,
+) -> std::io::Result<Vec<u8>> {
+    let mut builder = ProfileBuilder::default();
+    builder.string("");
+    let sample_type =
+        ValueType { r#type: builder.string(count_name), unit: builder.string("count") };
+    builder.profile.sample_type.push(sample_type);
+
+    for line in folded_lines {
+        let Some((stack, count)) = line.rsplit_once(' ') else {
+            continue;
+        };
+        let count = count.parse().unwrap_or_default();
+        let mut location_id: Vec<u64> =
+            stack.split(';').map(|frame| builder.location(frame)).collect();
+        location_id.reverse();
+        builder.profile.sample.push(Sample { location_id, value: vec![count] });
+    }
+
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(&builder.profile.encode_to_vec())?;
+    encoder.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+
+    use flate2::read::GzDecoder;
+    use prost::Message;
+
+    use super::{Profile, pprof_from_folded_lines};
+
+    #[test]
+    fn shares_frames_between_samples() {
+        let folded_lines = vec!["main;foo 10".to_string(), "main;bar 20".to_string()];
+        let gzipped = pprof_from_folded_lines(&folded_lines, "opcodes").unwrap();
+
+        let mut encoded = Vec::new();
+        GzDecoder::new(gzipped.as_slice()).read_to_end(&mut encoded).unwrap();
+        let profile = Profile::decode(encoded.as_slice()).unwrap();
+
+        assert_eq!(profile.string_table, vec!["", "opcodes", "count", "main", "foo", "bar"]);
+        assert_eq!(profile.location.len(), 3);
+        let samples: Vec<_> = profile
+            .sample
+            .iter()
+            .map(|sample| (sample.location_id.clone(), sample.value.clone()))
+            .collect();
+        assert_eq!(samples, vec![(vec![2, 1], vec![10]), (vec![3, 1], vec![20])]);
+    }
+}
@@ -0,0 +1,151 @@
+//! Profiles in the file format of <https://www.speedscope.app>.
+
+use std::collections::HashMap;
+
+use serde::Serialize;
+
+/// A speedscope file, made of frames shared by one or more profiles.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct SpeedscopeFile {
+    #[serde(rename = "$schema")]
+    schema: &'static str,
+    exporter: &'static str,
+    name: String,
+    active_profile_index: usize,
+    shared: SpeedscopeShared,
+    profiles: Vec<SpeedscopeProfile>,
+}
+
+impl SpeedscopeFile {
+    pub(crate) fn new(
+        name: String,
+        frames: Vec<SpeedscopeFrame>,
+        profiles: Vec<SpeedscopeProfile>,
+    ) -> Self {
+        SpeedscopeFile {
+            schema: "https://www.speedscope.app/file-format-schema.json",
+            exporter: "noir-profiler",
+            name,
+            active_profile_index: 0,
+            shared: SpeedscopeShared { frames },
+            profiles,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct SpeedscopeShared {
+    frames: Vec<SpeedscopeFrame>,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct SpeedscopeFrame {
+    pub(crate) name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) file: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) line: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) col: Option<usize>,
+}
+
+impl SpeedscopeFrame {
+    pub(crate) fn named(name: String) -> Self {
+        SpeedscopeFrame { name, file: None, line: None, col: None }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct SpeedscopeProfile {
+    #[serde(rename = "type")]
+    profile_type: &'static str,
+    name: String,
+    unit: &'static str,
+    start_value: u64,
+    end_value: u64,
+    samples: Vec<Vec<usize>>,
+    weights: Vec<u64>,
+}
+
+impl SpeedscopeProfile {
+    /// A sampled profile, where each sample is a stack of indexes into the shared frames,
+    /// outermost first, weighted by the matching entry of `weights`.
+    pub(crate) fn sampled(
+        name: String,
+        unit: &'static str,
+        samples: Vec<Vec<usize>>,
+        weights: Vec<u64>,
+    ) -> Self {
+        SpeedscopeProfile {
+            profile_type: "sampled",
+            name,
+            unit,
+            start_value: 0,
+            end_value: weights.iter().sum(),
+            samples,
+            weights,
+        }
+    }
+}
+
+/// Builds a speedscope file out of stacks in the collapsed format, `frame;frame;frame count`,
+/// with one sample per stack.
+pub(crate) fn speedscope_from_folded_lines(
+    folded_lines: &[String],
+    name: &str,
+    count_name: &str,
+) -> SpeedscopeFile {
+    let mut frames = Vec::new();
+    let mut frame_indexes: HashMap<&str, usize> = HashMap::new();
+    let mut samples = Vec::with_capacity(folded_lines.len());
+    let mut weights = Vec::with_capacity(folded_lines.len());
+
+    for line in folded_lines {
+        let Some((stack, count)) = line.rsplit_once(' ') else {
+            continue;
+        };
+        let sample = stack
+            .split(';')
+            .map(|frame| {
+                *frame_indexes.entry(frame).or_insert_with(|| {
+                    frames.push(SpeedscopeFrame::named(frame.to_string()));
+                    frames.len() - 1
+                })
+            })
+            .collect();
+        samples.push(sample);
+        weights.push(count.parse().unwrap_or_default());
+    }
+
+    let profile =
+        SpeedscopeProfile::sampled(format!("{name}: {count_name}"), "none", samples, weights);
+    SpeedscopeFile::new(name.to_string(), frames, vec![profile])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::speedscope_from_folded_lines;
+
+    #[test]
+    fn shares_frames_between_samples() {
+        let folded_lines = vec!["main;foo 10".to_string(), "main;bar 20".to_string()];
+        let file = speedscope_from_folded_lines(&folded_lines, "main", "opcodes");
+
+        let json = serde_json::to_value(&file).unwrap();
+        let frames: Vec<_> = json["shared"]["frames"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|frame| &frame["name"])
+            .collect();
+        assert_eq!(frames, vec!["main", "foo", "bar"]);
+
+        let profile = &json["profiles"][0];
+        assert_eq!(profile["name"], "main: opcodes");
+        assert_eq!(profile["samples"], serde_json::json!([[0, 1], [0, 2]]));
+        assert_eq!(profile["weights"], serde_json::json!([10, 20]));
+        assert_eq!(profile["endValue"], 30);
+    }
+}
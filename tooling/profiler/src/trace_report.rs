@@ -0,0 +1,476 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use acir::circuit::AcirOpcodeLocation;
+use acir::circuit::OpcodeLocation;
+use acir::circuit::brillig::BrilligFunctionId;
+use fm::codespan_files::Files;
+use fxhash::FxHashMap as HashMap;
+use noirc_errors::Location;
+use noirc_errors::call_stack::CallStackId;
+use noirc_errors::debug_info::DebugInfo;
+use noirc_errors::reporter::line_and_column_from_span;
+use noirc_evaluator::brillig::ProcedureId;
+use serde::Serialize;
+
+use crate::brillig_trace::BrilligTraceStep;
+use crate::speedscope::{SpeedscopeFile, SpeedscopeFrame, SpeedscopeProfile};
+
+/// What the Brillig opcodes executed by a function, or by the whole program, cost.
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+pub(crate) struct ExecutionStats {
+    pub(crate) opcodes: usize,
+    pub(crate) time_ns: u64,
+    /// The number of memory slots allocated on the heap.
+    pub(crate) heap_allocated: usize,
+    /// The number of arrays copied because they were mutated while being shared.
+    pub(crate) array_copies: usize,
+    /// The number of calls made to each foreign function.
+    pub(crate) foreign_calls: BTreeMap<String, usize>,
+}
+
+impl ExecutionStats {
+    fn record(&mut self, step: &BrilligTraceStep, is_array_copy: bool) {
+        self.opcodes += 1;
+        self.time_ns += step.time.as_nanos() as u64;
+        self.heap_allocated += step.heap_allocated;
+        self.array_copies += usize::from(is_array_copy);
+        if let Some(foreign_call) = &step.foreign_call {
+            *self.foreign_calls.entry(foreign_call.clone()).or_default() += 1;
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub(crate) struct FunctionReport {
+    pub(crate) name: String,
+    /// Where the function was called from, as `file:line:column`.
+    pub(crate) call_sites: BTreeSet<String>,
+    /// The cost of the function's own body, not including the functions it calls.
+    #[serde(flatten)]
+    pub(crate) stats: ExecutionStats,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub(crate) struct ExecutionReport {
+    /// The cost of the whole execution.
+    #[serde(flatten)]
+    pub(crate) total: ExecutionStats,
+    /// The functions that executed Brillig opcodes, the most expensive first.
+    pub(crate) functions: Vec<FunctionReport>,
+}
+
+/// The function an opcode was executed by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum FunctionKey {
+    /// The Brillig function called from ACIR.
+    Entry(BrilligFunctionId),
+    /// A function called from Noir code, identified by the location of the call.
+    Call(Location),
+}
+
+/// Maps the opcodes of a trace back to the source code they were generated from.
+pub(crate) struct TraceResolver<'a, 'files, Fs: Files<'files, FileId = fm::FileId>> {
+    debug_symbols: &'a DebugInfo,
+    brillig_names: &'a [String],
+    files: &'files Fs,
+    cache: HashMap<OpcodeLocation, Vec<Location>>,
+}
+
+impl<'a, 'files, Fs: Files<'files, FileId = fm::FileId>> TraceResolver<'a, 'files, Fs> {
+    pub(crate) fn new(
+        debug_symbols: &'a DebugInfo,
+        brillig_names: &'a [String],
+        files: &'files Fs,
+    ) -> Self {
+        Self { debug_symbols, brillig_names, files, cache: HashMap::default() }
+    }
+
+    /// Sums up the cost of each function of the trace.
+    pub(crate) fn execution_report(&mut self, steps: &[BrilligTraceStep]) -> ExecutionReport {
+        let mut total = ExecutionStats::default();
+        let mut functions: BTreeMap<String, FunctionReport> = BTreeMap::new();
+
+        for step in steps {
+            let is_array_copy = self.is_array_copy(step);
+            total.record(step, is_array_copy);
+
+            let locations = self.source_call_stack(step);
+            let function_key = match locations.len() {
+                0 | 1 => FunctionKey::Entry(step.brillig_function_id),
+                length => FunctionKey::Call(locations[length - 2]),
+            };
+            let name = match function_key {
+                FunctionKey::Entry(_) => self.function_name(function_key),
+                // The opcode belongs to the function called at the call site, which is the one
+                // its innermost location is in.
+                FunctionKey::Call(_) => self
+                    .opcode_function_name(step)
+                    .unwrap_or_else(|| self.function_name(function_key)),
+            };
+            let function = functions.entry(name.clone()).or_insert_with(|| FunctionReport {
+                name,
+                call_sites: BTreeSet::new(),
+                stats: ExecutionStats::default(),
+            });
+            if let FunctionKey::Call(location) = function_key {
+                function.call_sites.insert(self.location_string(location));
+            }
+            function.stats.record(step, is_array_copy);
+        }
+
+        let mut functions: Vec<_> = functions.into_values().collect();
+        functions.sort_by(|a, b| b.stats.time_ns.cmp(&a.stats.time_ns));
+
+        ExecutionReport { total, functions }
+    }
+
+    /// Builds a profile of the trace with the time, opcodes and heap allocations of each call stack.
+    ///
+    /// Consecutive opcodes with the same call stack are merged into a single sample, so samples stay
+    /// in execution order.
+    pub(crate) fn speedscope_profile(
+        &mut self,
+        steps: &[BrilligTraceStep],
+        name: &str,
+    ) -> SpeedscopeFile {
+        let mut frames = Vec::new();
+        let mut frame_indexes: HashMap<FunctionKey, usize> = HashMap::default();
+        let mut samples: Vec<Vec<usize>> = Vec::new();
+        let mut weights: Vec<[u64; 3]> = Vec::new();
+
+        for step in steps {
+            let entry = FunctionKey::Entry(step.brillig_function_id);
+            let stack: Vec<usize> = std::iter::once(entry)
+                .chain(self.source_call_stack(step).into_iter().map(FunctionKey::Call))
+                .map(|key| {
+                    *frame_indexes.entry(key).or_insert_with(|| {
+                        frames.push(self.speedscope_frame(key));
+                        frames.len() - 1
+                    })
+                })
+                .collect();
+
+            let step_weights = [step.time.as_nanos() as u64, 1, step.heap_allocated as u64];
+            if samples.last() == Some(&stack) {
+                let last_weights = weights.last_mut().expect("There's a weight per sample");
+                for (weight, step_weight) in last_weights.iter_mut().zip(step_weights) {
+                    *weight += step_weight;
+                }
+            } else {
+                samples.push(stack);
+                weights.push(step_weights);
+            }
+        }
+
+        let metrics = [
+            ("Time", "nanoseconds"),
+            ("Brillig opcodes", "none"),
+            ("Heap allocations (memory slots)", "none"),
+        ];
+        let profiles = metrics
+            .into_iter()
+            .enumerate()
+            .map(|(index, (metric, unit))| {
+                let weights: Vec<u64> = weights.iter().map(|weights| weights[index]).collect();
+                SpeedscopeProfile::sampled(
+                    format!("{name}: {metric}"),
+                    unit,
+                    samples.clone(),
+                    weights,
+                )
+            })
+            .collect();
+
+        SpeedscopeFile::new(name.to_string(), frames, profiles)
+    }
+
+    /// The source locations of the call stack of a step, outermost first.
+    fn source_call_stack(&mut self, step: &BrilligTraceStep) -> Vec<Location> {
+        let debug_symbols = self.debug_symbols;
+        let mut locations = Vec::new();
+        for opcode_location in &step.call_stack {
+            let opcode_locations = self.cache.entry(*opcode_location).or_insert_with(|| {
+                call_stack_id(debug_symbols, step.brillig_function_id, opcode_location)
+                    .map(|call_stack| debug_symbols.location_tree.get_call_stack(call_stack))
+                    .unwrap_or_default()
+            });
+            locations.extend(opcode_locations.iter().copied());
+        }
+        locations
+    }
+
+    /// The name of the function the executed opcode of a step was generated from,
+    /// as recorded in the debug info.
+    fn opcode_function_name(&self, step: &BrilligTraceStep) -> Option<String> {
+        let opcode_location = step.call_stack.last()?;
+        let call_stack =
+            call_stack_id(self.debug_symbols, step.brillig_function_id, opcode_location)?;
+        self.debug_symbols.function_names.get(&call_stack).cloned()
+    }
+
+    /// Whether a step copied an array: it allocated memory inside the array copy procedure,
+    /// which only happens when the array is shared.
+    fn is_array_copy(&self, step: &BrilligTraceStep) -> bool {
+        if step.heap_allocated == 0 {
+            return false;
+        }
+        let Some(brillig_location) =
+            step.call_stack.last().and_then(|location| location.to_brillig_location())
+        else {
+            return false;
+        };
+        let Some(procedure_locations) =
+            self.debug_symbols.brillig_procedure_locs.get(&step.brillig_function_id)
+        else {
+            return false;
+        };
+        procedure_locations.iter().any(|(procedure, (start, end))| {
+            ProcedureId::from_debug_id(*procedure) == ProcedureId::ArrayCopy
+                && (*start..=*end).contains(&brillig_location.0)
+        })
+    }
+
+    fn function_name(&self, function_key: FunctionKey) -> String {
+        match function_key {
+            FunctionKey::Entry(brillig_function_id) => self
+                .brillig_names
+                .get(brillig_function_id.as_usize())
+                .cloned()
+                .unwrap_or_else(|| format!("brillig_function_{}", brillig_function_id.0)),
+            FunctionKey::Call(location) => {
+                self.code_slice(location).lines().next().unwrap_or_default().trim().to_string()
+            }
+        }
+    }
+
+    fn speedscope_frame(&self, function_key: FunctionKey) -> SpeedscopeFrame {
+        match function_key {
+            FunctionKey::Entry(_) => SpeedscopeFrame::named(self.function_name(function_key)),
+            FunctionKey::Call(location) => {
+                let (file, line, col) = self.file_line_and_column(location);
+                let name = self.function_name(function_key);
+                SpeedscopeFrame { name, file: Some(file), line: Some(line), col: Some(col) }
+            }
+        }
+    }
+
+    fn code_slice(&self, location: Location) -> String {
+        let Ok(source) = self.files.source(location.file) else {
+            return String::new();
+        };
+        let span = location.span;
+        source
+            .as_ref()
+            .get(span.start() as usize..span.end() as usize)
+            .unwrap_or_default()
+            .to_string()
+    }
+
+    fn file_line_and_column(&self, location: Location) -> (String, usize, usize) {
+        let file = self
+            .files
+            .name(location.file)
+            .map(|name| name.to_string())
+            .unwrap_or_else(|_| "invalid_path".to_string());
+        let (line, column) = self
+            .files
+            .source(location.file)
+            .map(|source| line_and_column_from_span(source.as_ref(), &location.span))
+            .unwrap_or_default();
+        (file, line as usize, column as usize)
+    }
+
+    fn location_string(&self, location: Location) -> String {
+        let (file, line, column) = self.file_line_and_column(location);
+        format!("{file}:{line}:{column}")
+    }
+}
+
+/// The call stack the debug info records for an opcode.
+fn call_stack_id(
+    debug_symbols: &DebugInfo,
+    brillig_function_id: BrilligFunctionId,
+    opcode_location: &OpcodeLocation,
+) -> Option<CallStackId> {
+    match opcode_location {
+        OpcodeLocation::Acir(index) => {
+            debug_symbols.acir_locations.get(&AcirOpcodeLocation::new(*index)).copied()
+        }
+        OpcodeLocation::Brillig { .. } => {
+            let brillig_location = opcode_location.to_brillig_location()?;
+            debug_symbols
+                .brillig_locations
+                .get(&brillig_function_id)?
+                .get(&brillig_location)
+                .copied()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::path::Path;
+    use std::time::Duration;
+
+    use acir::circuit::brillig::BrilligFunctionId;
+    use acir::circuit::{BrilligOpcodeLocation, OpcodeLocation};
+    use fm::FileManager;
+    use noirc_errors::call_stack::CallStackHelper;
+    use noirc_errors::debug_info::DebugInfo;
+    use noirc_errors::{Location, Span};
+
+    use crate::brillig_trace::BrilligTraceStep;
+
+    use super::TraceResolver;
+
+    fn step(brillig_index: usize) -> BrilligTraceStep {
+        BrilligTraceStep {
+            brillig_function_id: BrilligFunctionId(0),
+            call_stack: vec![
+                OpcodeLocation::Acir(0),
+                OpcodeLocation::Brillig { acir_index: 0, brillig_index },
+            ],
+            time: Duration::from_nanos(10),
+            heap_allocated: 0,
+            foreign_call: None,
+        }
+    }
+
+    #[test]
+    fn names_functions_after_the_debug_info() {
+        let source = "struct Foo {}
+
+impl Foo {
+    fn double(x: u32) -> u32 {
+        x * 2
+    }
+}
+
+unconstrained fn main(x: u32) -> u32 {
+    let y = Foo::double(x);
+    y + Foo::double(y)
+}
+";
+        let temp_dir = tempfile::tempdir().unwrap();
+        let mut file_manager = FileManager::new(temp_dir.path());
+        let file_id =
+            file_manager.add_file_with_source(Path::new("main.nr"), source.to_string()).unwrap();
+        let location_of = |code: &str, occurrence: usize| {
+            let start = source.match_indices(code).nth(occurrence).unwrap().0;
+            Location::new(Span::from(start as u32..(start + code.len()) as u32), file_id)
+        };
+
+        // Both calls are inlined into `main`, so their opcodes are only told apart by their call stacks.
+        let first_call = location_of("Foo::double(x)", 0);
+        let second_call = location_of("Foo::double(y)", 0);
+        let double_body = location_of("x * 2", 0);
+        let addition = location_of("y + Foo::double(y)", 0);
+
+        let mut call_stacks = CallStackHelper::default();
+        let first_double = call_stacks.get_or_insert_locations(&vec![first_call, double_body]);
+        let second_double = call_stacks.get_or_insert_locations(&vec![second_call, double_body]);
+        let main_body = call_stacks.get_or_insert_locations(&vec![addition]);
+
+        let debug_info = DebugInfo {
+            brillig_locations: BTreeMap::from([(
+                BrilligFunctionId(0),
+                BTreeMap::from([
+                    (BrilligOpcodeLocation(0), first_double),
+                    (BrilligOpcodeLocation(1), second_double),
+                    (BrilligOpcodeLocation(2), main_body),
+                ]),
+            )]),
+            location_tree: call_stacks.to_location_tree(),
+            function_names: BTreeMap::from([
+                (first_double, "Foo::double".to_string()),
+                (second_double, "Foo::double".to_string()),
+                (main_body, "main".to_string()),
+            ]),
+            ..DebugInfo::default()
+        };
+        let brillig_names = vec!["main".to_string()];
+
+        let mut resolver =
+            TraceResolver::new(&debug_info, &brillig_names, file_manager.as_file_map());
+        let report = resolver.execution_report(&[step(0), step(1), step(2)]);
+
+        let functions: BTreeMap<_, _> = report
+            .functions
+            .iter()
+            .map(|function| {
+                (function.name.as_str(), (function.stats.opcodes, &function.call_sites))
+            })
+            .collect();
+        assert_eq!(functions.len(), 2);
+        assert_eq!(functions["main"].0, 1);
+        // Both call sites are reported under the name of the function they call.
+        let (opcodes, call_sites) = functions["Foo::double"];
+        assert_eq!(opcodes, 2);
+        assert_eq!(call_sites.len(), 2);
+    }
+
+    #[test]
+    fn reports_methods_with_the_same_name_separately() {
+        let source = "struct Foo {}
+
+impl Foo {
+    fn new() -> u32 {
+        41
+    }
+}
+
+struct Bar {}
+
+impl Bar {
+    fn new() -> u32 {
+        42
+    }
+}
+
+unconstrained fn main() -> u32 {
+    Foo::new() + Bar::new()
+}
+";
+        let temp_dir = tempfile::tempdir().unwrap();
+        let mut file_manager = FileManager::new(temp_dir.path());
+        let file_id =
+            file_manager.add_file_with_source(Path::new("main.nr"), source.to_string()).unwrap();
+        let location_of = |code: &str| {
+            let start = source.find(code).unwrap();
+            Location::new(Span::from(start as u32..(start + code.len()) as u32), file_id)
+        };
+
+        let mut call_stacks = CallStackHelper::default();
+        let foo_new = call_stacks
+            .get_or_insert_locations(&vec![location_of("Foo::new()"), location_of("41")]);
+        let bar_new = call_stacks
+            .get_or_insert_locations(&vec![location_of("Bar::new()"), location_of("42")]);
+
+        let debug_info = DebugInfo {
+            brillig_locations: BTreeMap::from([(
+                BrilligFunctionId(0),
+                BTreeMap::from([
+                    (BrilligOpcodeLocation(0), foo_new),
+                    (BrilligOpcodeLocation(1), bar_new),
+                ]),
+            )]),
+            location_tree: call_stacks.to_location_tree(),
+            function_names: BTreeMap::from([
+                (foo_new, "Foo::new".to_string()),
+                (bar_new, "Bar::new".to_string()),
+            ]),
+            ..DebugInfo::default()
+        };
+        let brillig_names = vec!["main".to_string()];
+
+        let mut resolver =
+            TraceResolver::new(&debug_info, &brillig_names, file_manager.as_file_map());
+        let report = resolver.execution_report(&[step(0), step(1)]);
+
+        let mut names: Vec<_> =
+            report.functions.iter().map(|function| function.name.as_str()).collect();
+        names.sort_unstable();
+        assert_eq!(names, vec!["Bar::new", "Foo::new"]);
+    }
+}